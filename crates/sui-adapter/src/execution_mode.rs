@@ -236,6 +236,63 @@ impl ExecutionMode for DevInspect {
     }
 }
 
+/// Executes like `Normal`, and records the mutable references updated and the values returned by
+/// each command, to trace the execution. Values that can't be recorded are left out rather than
+/// failing the execution.
+#[derive(Copy, Clone)]
+pub struct Trace;
+
+impl ExecutionMode for Trace {
+    type ArgumentUpdates = Vec<(Argument, Vec<u8>, TypeTag)>;
+    type ExecutionResults = Vec<ExecutionResult>;
+
+    fn allow_arbitrary_function_calls() -> bool {
+        false
+    }
+
+    fn allow_arbitrary_values() -> bool {
+        false
+    }
+
+    fn packages_are_predefined() -> bool {
+        false
+    }
+
+    fn empty_arguments() -> Self::ArgumentUpdates {
+        vec![]
+    }
+
+    fn empty_results() -> Self::ExecutionResults {
+        vec![]
+    }
+
+    fn add_argument_update<S: StorageView>(
+        context: &mut ExecutionContext<S>,
+        acc: &mut Self::ArgumentUpdates,
+        arg: Argument,
+        new_value: &Value,
+    ) -> Result<(), ExecutionError> {
+        if let Ok((bytes, type_tag)) = value_to_bytes_and_tag(context, new_value) {
+            acc.push((arg, bytes, type_tag));
+        }
+        Ok(())
+    }
+
+    fn finish_command<S: StorageView>(
+        context: &mut ExecutionContext<S>,
+        acc: &mut Self::ExecutionResults,
+        argument_updates: Self::ArgumentUpdates,
+        command_result: &[Value],
+    ) -> Result<(), ExecutionError> {
+        let command_bytes = command_result
+            .iter()
+            .filter_map(|value| value_to_bytes_and_tag(context, value).ok())
+            .collect();
+        acc.push((argument_updates, command_bytes));
+        Ok(())
+    }
+}
+
 fn value_to_bytes_and_tag<S: StorageView>(
     context: &mut ExecutionContext<S>,
     value: &Value,
@@ -515,6 +515,10 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     indirect_objects_threshold: usize::MAX,
                     expensive_safety_check_config: Default::default(),
                     name_service_resolver_object_id: None,
+                    json_rpc_rate_limit_config: None,
+                    json_rpc_response_cache_size_bytes: None,
+                    checkpoint_archive_config: None,
+                    checkpoint_data_streaming_config: None,
                 }
             })
             .collect();
@@ -10,7 +10,9 @@ use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::net::SocketAddr;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::usize;
@@ -112,6 +114,25 @@ pub struct NodeConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name_service_resolver_object_id: Option<ObjectID>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_rate_limit_config: Option<JsonRpcRateLimitConfig>,
+
+    /// Size in bytes, in their JSON encoding, of the responses of immutable data (checkpointed
+    /// transactions, checkpoints, past object versions) the JSON-RPC server keeps in memory.
+    /// Response caching is disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_rpc_response_cache_size_bytes: Option<NonZeroUsize>,
+
+    /// Object store the checkpoints synced by the node are archived into, for other nodes to sync
+    /// from once pruned by their peers. Checkpoints are not archived if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_archive_config: Option<ObjectStoreConfig>,
+
+    /// Limits of the JSON-RPC subscriptions streaming the full content of executed checkpoints,
+    /// e.g. to indexers. The subscriptions are rejected if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_data_streaming_config: Option<CheckpointDataStreamingConfig>,
 }
 
 fn default_authority_store_pruning_config() -> AuthorityStorePruningConfig {
@@ -443,6 +464,71 @@ pub struct MetricsConfig {
     pub push_url: Option<String>,
 }
 
+/// Admission control of the JSON-RPC server. Limits are token buckets keyed by method name, the
+/// `*` entry applies to the methods that have no limit of their own.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct JsonRpcRateLimitConfig {
+    /// Per-method limits applied to each client IP, IPv6 clients being limited by /64 prefix.
+    /// The client IP is the peer address of the request, unless the request comes from one of
+    /// the `trusted-proxies`. Requests sent over websocket connections are limited as well.
+    #[serde(default)]
+    pub per_ip: BTreeMap<String, RateLimit>,
+    /// Per-method limits of each API key. Requests carrying a known API key are limited by the
+    /// quotas of the key instead of the per-IP limits.
+    #[serde(default)]
+    pub api_keys: BTreeMap<String, BTreeMap<String, RateLimit>>,
+    /// Header carrying the API key, defaults to `x-api-key`.
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+    /// Addresses of the proxies in front of the node, whose `X-Forwarded-For` header is trusted
+    /// to carry the client IP.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimit {
+    /// Number of tokens added to the bucket every second.
+    pub requests_per_second: u32,
+    /// Capacity of the bucket, i.e. the number of requests allowed in a burst.
+    pub burst: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CheckpointDataStreamingConfig {
+    /// Maximum number of concurrent subscriptions.
+    #[serde(default = "default_max_checkpoint_data_subscribers")]
+    pub max_subscribers: usize,
+    /// How far behind the latest executed checkpoint a subscription can start, as every
+    /// checkpoint before it is read back from the stores.
+    #[serde(default = "default_max_checkpoint_data_catch_up")]
+    pub max_catch_up_checkpoints: u64,
+}
+
+fn default_max_checkpoint_data_subscribers() -> usize {
+    4
+}
+
+fn default_max_checkpoint_data_catch_up() -> u64 {
+    10_000
+}
+
+impl Default for CheckpointDataStreamingConfig {
+    fn default() -> Self {
+        Self {
+            max_subscribers: default_max_checkpoint_data_subscribers(),
+            max_catch_up_checkpoints: default_max_checkpoint_data_catch_up(),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct DBCheckpointConfig {
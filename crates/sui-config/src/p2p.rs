@@ -4,6 +4,7 @@
 use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::multiaddr::Multiaddr;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -131,6 +132,19 @@ pub struct StateSyncConfig {
     /// If unspecified, this will default to no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub get_checkpoint_contents_rate_limit: Option<NonZeroU32>,
+
+    /// Object store of a checkpoint archive, used to sync the checkpoints which none of our peers
+    /// is able to serve anymore.
+    ///
+    /// If unspecified, checkpoints are only synced from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_object_store_config: Option<ObjectStoreConfig>,
+
+    /// How long the manifest of the checkpoint archive is cached before it is read again.
+    ///
+    /// If unspecified, this will default to `60,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_manifest_refresh_interval_ms: Option<u64>,
 }
 
 impl StateSyncConfig {
@@ -182,6 +196,14 @@ impl StateSyncConfig {
             .map(Duration::from_millis)
             .unwrap_or(DEFAULT_TIMEOUT)
     }
+
+    pub fn archive_manifest_refresh_interval(&self) -> Duration {
+        const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+        self.archive_manifest_refresh_interval_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL)
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -289,6 +289,11 @@ impl<'a> FullnodeConfigBuilder<'a> {
             // Copy the expensive safety check config from the first validator config.
             expensive_safety_check_config: validator_config.expensive_safety_check_config.clone(),
             name_service_resolver_object_id: None,
+            json_rpc_rate_limit_config: None,
+            json_rpc_response_cache_size_bytes: None,
+            checkpoint_archive_config: None,
+            // Local networks serve the indexers run against them.
+            checkpoint_data_streaming_config: Some(Default::default()),
         })
     }
 }
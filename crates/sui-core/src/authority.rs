@@ -47,12 +47,15 @@ use sui_config::node::{
 };
 use sui_framework::BuiltInFramework;
 use sui_json_rpc_types::{
-    Checkpoint, DevInspectResults, DryRunTransactionBlockResponse, EventFilter, SuiEvent,
-    SuiMoveValue, SuiObjectDataFilter, SuiTransactionBlockData, SuiTransactionBlockEvents,
+    Checkpoint, DevInspectResults, DryRunTransactionBlockResponse, EventFilter, EventPage, Filter,
+    Page, SuiEvent, SuiMoveValue, SuiObjectDataFilter, SuiTransactionBlockData,
+    SuiTransactionBlockEvents,
 };
 use sui_macros::{fail_point, fail_point_async, nondeterministic};
 use sui_protocol_config::SupportedProtocolVersions;
-use sui_storage::indexes::{CoinInfo, ObjectIndexChanges};
+use sui_storage::indexes::{
+    CoinInfo, LiveObjectSetIndexing, ObjectIndexChanges, MAX_COMPOSITE_EVENT_SCAN,
+};
 use sui_storage::IndexStore;
 use sui_types::committee::{EpochId, ProtocolVersion};
 use sui_types::crypto::{
@@ -96,18 +99,20 @@ use crate::authority::authority_per_epoch_store::{AuthorityPerEpochStore, CertTx
 use crate::authority::authority_per_epoch_store_pruner::AuthorityPerEpochStorePruner;
 use crate::authority::authority_store::{ExecutionLockReadGuard, InputKey, ObjectLockStatus};
 use crate::authority::authority_store_pruner::AuthorityStorePruner;
+use crate::authority::authority_store_tables::LiveObject;
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::checkpoints::checkpoint_data_streamer::CheckpointDataStreamer;
 use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::epoch::epoch_metrics::EpochMetrics;
-use crate::event_handler::EventHandler;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::signature_verifier::SignatureVerifierMetrics;
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::StateAccumulator;
+use crate::subscription_handler::SubscriptionHandler;
 use crate::{transaction_input_checker, transaction_manager::TransactionManager};
 
 #[cfg(test)]
@@ -476,7 +481,8 @@ pub struct AuthorityState {
 
     indexes: Option<Arc<IndexStore>>,
 
-    pub event_handler: Arc<EventHandler>,
+    pub subscription_handler: Arc<SubscriptionHandler>,
+    pub checkpoint_data_streamer: Arc<CheckpointDataStreamer>,
     pub(crate) checkpoint_store: Arc<CheckpointStore>,
 
     committee_store: Arc<CommitteeStore>,
@@ -612,6 +618,9 @@ impl AuthorityState {
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
+        // Signatures may use features which are not supported at the current protocol version.
+        transaction.check_version_supported(epoch_store.protocol_config())?;
+
         // Checks to see if the transaction has expired
         if match &transaction.inner().data().transaction_data().expiration() {
             TransactionExpiration::None => false,
@@ -1484,8 +1493,9 @@ impl AuthorityState {
 
             // Emit events
             if res.is_ok() {
-                self.event_handler
-                    .process_events(
+                self.subscription_handler
+                    .process_tx(
+                        certificate.data().transaction_data(),
                         &effects.clone().try_into()?,
                         &SuiTransactionBlockEvents::try_from(
                             events.clone(),
@@ -1647,7 +1657,6 @@ impl AuthorityState {
         checkpoint_store: Arc<CheckpointStore>,
         prometheus_registry: &Registry,
         pruning_config: AuthorityStorePruningConfig,
-        genesis_objects: &[Object],
         db_checkpoint_config: &DBCheckpointConfig,
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
     ) -> Arc<Self> {
@@ -1673,13 +1682,18 @@ impl AuthorityState {
             epoch_store.epoch_start_state().epoch_duration_ms(),
             prometheus_registry,
         );
+        let checkpoint_data_streamer = Arc::new(CheckpointDataStreamer::new(
+            checkpoint_store.clone(),
+            store.clone(),
+        ));
         let state = Arc::new(AuthorityState {
             name,
             secret,
             epoch_store: ArcSwap::new(epoch_store.clone()),
             database: store,
             indexes,
-            event_handler: Arc::new(EventHandler::default()),
+            subscription_handler: Arc::new(SubscriptionHandler::default()),
+            checkpoint_data_streamer,
             checkpoint_store,
             committee_store,
             transaction_manager,
@@ -1699,9 +1713,14 @@ impl AuthorityState {
             rx_execution_shutdown
         ));
 
-        state
-            .create_owner_index_if_empty(genesis_objects, &epoch_store)
-            .expect("Error indexing genesis objects.");
+        // Index the live object set in the background. Transactions are indexed meanwhile, and
+        // an interrupted indexing resumes from its progress on the next start.
+        let authority_state = state.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = authority_state.index_live_object_set() {
+                error!("Error indexing the live object set: {e:?}");
+            }
+        });
 
         state
     }
@@ -1760,7 +1779,7 @@ impl AuthorityState {
         let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
         let index_store = Some(Arc::new(IndexStore::new(path.join("indexes"))));
 
-        let state = AuthorityState::new(
+        AuthorityState::new(
             secret.public().into(),
             secret.clone(),
             SupportedProtocolVersions::SYSTEM_DEFAULT,
@@ -1771,18 +1790,10 @@ impl AuthorityState {
             checkpoint_store,
             &registry,
             AuthorityStorePruningConfig::default(),
-            genesis.objects(),
             &DBCheckpointConfig::default(),
             ExpensiveSafetyCheckConfig::new_enable_all(),
         )
-        .await;
-
-        let epoch_store = state.epoch_store_for_testing();
-        state
-            .create_owner_index_if_empty(genesis.objects(), &epoch_store)
-            .unwrap();
-
-        state
+        .await
     }
 
     pub fn transaction_manager(&self) -> &Arc<TransactionManager> {
@@ -1806,43 +1817,94 @@ impl AuthorityState {
             .enqueue_certificates(certs, epoch_store)
     }
 
-    fn create_owner_index_if_empty(
-        &self,
-        genesis_objects: &[Object],
-        epoch_store: &Arc<AuthorityPerEpochStore>,
-    ) -> SuiResult {
+    /// Indexes the live object set of the store, unless its objects were indexed from its genesis
+    /// transactions. Stores restored from a state snapshot only hold the live object set, their
+    /// transactions preceding the snapshot are never indexed.
+    fn index_live_object_set(&self) -> SuiResult {
+        const BATCH_SIZE: usize = 10_000;
         let Some(index_store) = &self.indexes else{
             return Ok(())
         };
-        if !index_store.is_empty() {
-            return Ok(());
-        }
+        let cursor = match index_store.live_object_set_indexing()? {
+            LiveObjectSetIndexing::NotStarted => None,
+            LiveObjectSetIndexing::Resume(cursor) => Some(cursor),
+            LiveObjectSetIndexing::Complete => return Ok(()),
+        };
+        info!(?cursor, "Indexing the live object set");
 
-        let mut new_owners = vec![];
-        let mut new_dynamic_fields = vec![];
-        for o in genesis_objects.iter() {
-            match o.owner {
-                Owner::AddressOwner(addr) => new_owners.push((
-                    (addr, o.id()),
-                    ObjectInfo::new(&o.compute_object_reference(), o),
-                )),
-                Owner::ObjectOwner(object_id) => {
-                    let id = o.id();
-                    let Some(info) = self.try_create_dynamic_field_info(o, epoch_store)? else{
-                        continue;
-                    };
-                    new_dynamic_fields.push(((ObjectID::from(object_id), id), info));
+        let mut objects = self
+            .database
+            .iter_live_object_set()
+            .filter_map(|live_object| match live_object {
+                LiveObject::Normal(object) => Some(object),
+                LiveObject::Wrapped(_) => None,
+            })
+            .skip_while(|object| cursor.map_or(false, |cursor| object.id() <= cursor))
+            .peekable();
+        let mut indexed = 0;
+        loop {
+            let batch: Vec<_> = objects.by_ref().take(BATCH_SIZE).collect();
+            let progress = match batch.last() {
+                Some(object) if objects.peek().is_some() => {
+                    LiveObjectSetIndexing::Resume(object.id())
                 }
-                _ => {}
+                _ => LiveObjectSetIndexing::Complete,
+            };
+
+            index_store.index_live_objects(
+                || {
+                    // Objects modified since they were read are indexed by the transactions
+                    // that modified them instead.
+                    let ids: Vec<_> = batch.iter().map(|o| o.id()).collect();
+                    let latest = self.database.get_objects(&ids)?;
+                    let objects: Vec<_> = batch
+                        .iter()
+                        .zip(latest)
+                        .filter(|(o, latest)| {
+                            latest.as_ref().map(|latest| latest.version()) == Some(o.version())
+                        })
+                        .map(|(o, _)| o.clone())
+                        .collect();
+
+                    let epoch_store = self.load_epoch_store_one_call_per_task();
+                    let mut new_owners = vec![];
+                    let mut new_dynamic_fields = vec![];
+                    for o in objects.iter() {
+                        match o.owner {
+                            Owner::AddressOwner(addr) => new_owners.push((
+                                (addr, o.id()),
+                                ObjectInfo::new(&o.compute_object_reference(), o),
+                            )),
+                            Owner::ObjectOwner(object_id) => {
+                                let id = o.id();
+                                let Some(info) = self.try_create_dynamic_field_info(o, &epoch_store)? else{
+                                    continue;
+                                };
+                                new_dynamic_fields.push(((ObjectID::from(object_id), id), info));
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    Ok((
+                        objects,
+                        ObjectIndexChanges {
+                            deleted_owners: vec![],
+                            deleted_dynamic_fields: vec![],
+                            new_owners,
+                            new_dynamic_fields,
+                        },
+                    ))
+                },
+                progress,
+            )?;
+            indexed += batch.len();
+            if progress == LiveObjectSetIndexing::Complete {
+                info!(indexed, "Indexed the live object set");
+                return Ok(());
             }
+            info!(indexed, ?progress, "Indexing the live object set");
         }
-
-        index_store.insert_genesis_objects(ObjectIndexChanges {
-            deleted_owners: vec![],
-            deleted_dynamic_fields: vec![],
-            new_owners,
-            new_dynamic_fields,
-        })
     }
 
     pub async fn reconfigure(
@@ -2245,6 +2307,33 @@ impl AuthorityState {
         }
     }
 
+    pub fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: String,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<(u128, usize), anyhow::Error> {
+        let latest = self.get_latest_checkpoint_sequence_number()?;
+        if checkpoint > latest {
+            return Err(anyhow!(
+                "Checkpoint {checkpoint} has not been executed yet, the latest executed checkpoint is {latest}"
+            ));
+        }
+        let contents = self.get_checkpoint_contents_by_sequence_number(checkpoint)?;
+        Ok(self.get_indexes()?.get_balance_at_checkpoint(
+            owner,
+            coin_type,
+            checkpoint,
+            contents.iter().map(|digests| digests.transaction),
+            &|digest| {
+                Ok(self
+                    .database
+                    .get_transaction_checkpoint(digest)?
+                    .map(|(_, checkpoint)| checkpoint))
+            },
+        )?)
+    }
+
     pub fn get_owned_coins_iterator_with_cursor(
         &self,
         owner: SuiAddress,
@@ -2422,6 +2511,9 @@ impl AuthorityState {
         }
     }
 
+    /// Returns up to `limit` transactions matching `filter`. Filters without any indexed
+    /// criterion stop scanning after `MAX_UNINDEXED_TX_SCAN` transactions, use
+    /// `get_transactions_page` to resume such scans.
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
@@ -2430,6 +2522,46 @@ impl AuthorityState {
         limit: Option<usize>,
         reverse: bool,
     ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        Ok(self
+            .get_transactions_with_scan_cursor(filter, cursor, limit, reverse)?
+            .0)
+    }
+
+    /// Returns a page of up to `limit` transactions matching `filter`. When the scan of a filter
+    /// without any indexed criterion stops before filling the page, the page ends at the last
+    /// scanned transaction, so that the next page resumes the scan from there.
+    pub fn get_transactions_page(
+        &self,
+        filter: Option<TransactionFilter>,
+        // If `Some`, the query will start from the next item after the specified cursor
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Page<TransactionDigest, TransactionDigest>, anyhow::Error> {
+        // Retrieve 1 extra item for next cursor
+        let (mut data, scan_cursor) =
+            self.get_transactions_with_scan_cursor(filter, cursor, Some(limit + 1), reverse)?;
+        let has_next_page = data.len() > limit || scan_cursor.is_some();
+        let next_cursor = if data.len() > limit {
+            data.truncate(limit);
+            data.last().cloned()
+        } else {
+            scan_cursor.or_else(|| data.last().cloned())
+        };
+        Ok(Page {
+            data,
+            next_cursor: next_cursor.or(cursor),
+            has_next_page,
+        })
+    }
+
+    fn get_transactions_with_scan_cursor(
+        &self,
+        filter: Option<TransactionFilter>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> Result<(Vec<TransactionDigest>, Option<TransactionDigest>), anyhow::Error> {
         if let Some(TransactionFilter::Checkpoint(sequence_number)) = filter {
             let checkpoint_contents =
                 self.get_checkpoint_contents_by_sequence_number(sequence_number)?;
@@ -2439,16 +2571,32 @@ impl AuthorityState {
                     .rev()
                     .skip_while(|d| cursor.is_some() && Some(*d) != cursor)
                     .skip(usize::from(cursor.is_some()));
-                return Ok(iter.take(limit.unwrap_or(usize::max_value())).collect());
+                return Ok((
+                    iter.take(limit.unwrap_or(usize::max_value())).collect(),
+                    None,
+                ));
             } else {
                 let iter = iter
                     .skip_while(|d| cursor.is_some() && Some(*d) != cursor)
                     .skip(usize::from(cursor.is_some()));
-                return Ok(iter.take(limit.unwrap_or(usize::max_value())).collect());
+                return Ok((
+                    iter.take(limit.unwrap_or(usize::max_value())).collect(),
+                    None,
+                ));
             }
         }
-        self.get_indexes()?
-            .get_transactions(filter, cursor, limit, reverse)
+        self.get_indexes()?.get_transactions_with_scan_cursor(
+            filter,
+            cursor,
+            limit,
+            reverse,
+            &|digest| {
+                Ok(self
+                    .database
+                    .get_transaction_checkpoint(digest)?
+                    .map(|(_, checkpoint)| checkpoint))
+            },
+        )
     }
 
     fn get_checkpoint_store(&self) -> Arc<CheckpointStore> {
@@ -2617,6 +2765,8 @@ impl AuthorityState {
         Ok(self.get_indexes()?.get_timestamp_ms(digest)?)
     }
 
+    /// Returns up to `limit` events matching `query`. Combined filters stop scanning after
+    /// `MAX_COMPOSITE_EVENT_SCAN` events, use `query_events_page` to resume such scans.
     pub fn query_events(
         &self,
         query: EventFilter,
@@ -2625,6 +2775,48 @@ impl AuthorityState {
         limit: usize,
         descending: bool,
     ) -> Result<Vec<SuiEvent>, anyhow::Error> {
+        Ok(self
+            .query_events_with_scan_cursor(query, cursor, limit, descending)?
+            .0)
+    }
+
+    /// Returns a page of up to `limit` events matching `query`. When a combined filter stops
+    /// scanning before filling the page, the page ends at the last scanned event, so that the
+    /// next page resumes the scan from there.
+    pub fn query_events_page(
+        &self,
+        query: EventFilter,
+        // If `Some`, the query will start from the next item after the specified cursor
+        cursor: Option<EventID>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<EventPage, anyhow::Error> {
+        // Retrieve 1 extra item for next cursor
+        let (mut data, scan_cursor) =
+            self.query_events_with_scan_cursor(query, cursor.clone(), limit + 1, descending)?;
+        let has_next_page = data.len() > limit || scan_cursor.is_some();
+        let next_cursor = if data.len() > limit {
+            data.truncate(limit);
+            data.last().map(|e| e.id.clone())
+        } else {
+            scan_cursor.or_else(|| data.last().map(|e| e.id.clone()))
+        };
+        Ok(EventPage {
+            data,
+            next_cursor: next_cursor.or(cursor),
+            has_next_page,
+        })
+    }
+
+    /// Returns the events matching `query`, together with the last scanned event if the scan of
+    /// a combined filter stopped before finding `limit` events.
+    fn query_events_with_scan_cursor(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: usize,
+        descending: bool,
+    ) -> Result<(Vec<SuiEvent>, Option<EventID>), anyhow::Error> {
         let index_store = self.get_indexes()?;
 
         //Get the tx_num from tx_digest
@@ -2641,48 +2833,185 @@ impl AuthorityState {
             (0, 0)
         };
 
-        let limit = limit + 1;
-        let mut event_keys = match query {
-            EventFilter::All(..) => index_store.all_events(tx_num, event_num, limit, descending)?,
+        let filters = match query {
+            EventFilter::All(filters) if !filters.is_empty() => filters,
+            EventFilter::And(f1, f2) => vec![*f1, *f2],
+            _ => {
+                let limit = limit + 1;
+                let mut event_keys = Self::event_keys_from_index(
+                    &index_store,
+                    &query,
+                    tx_num,
+                    event_num,
+                    limit,
+                    descending,
+                )?
+                .ok_or_else(|| anyhow!("This query type is not supported by the full node."))?;
+
+                // skip one event if exclusive cursor is provided,
+                // otherwise truncate to the original limit.
+                if cursor.is_some() {
+                    if !event_keys.is_empty() {
+                        event_keys.remove(0);
+                    }
+                } else {
+                    event_keys.truncate(limit - 1);
+                }
+                return Ok((self.load_events(event_keys)?, None));
+            }
+        };
+
+        self.query_events_by_composite_filter(
+            &index_store,
+            filters,
+            cursor,
+            (tx_num, event_num),
+            limit,
+            descending,
+        )
+    }
+
+    /// Reads the keys of events matching `filter` from its dedicated index, returns `None` if
+    /// the filter has no index.
+    fn event_keys_from_index(
+        index_store: &IndexStore,
+        filter: &EventFilter,
+        tx_num: TxSequenceNumber,
+        event_num: usize,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Option<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>>> {
+        Ok(Some(match filter {
+            EventFilter::All(filters) if filters.is_empty() => {
+                index_store.all_events(tx_num, event_num, limit, descending)?
+            }
             EventFilter::Transaction(digest) => {
-                index_store.events_by_transaction(&digest, tx_num, event_num, limit, descending)?
+                index_store.events_by_transaction(digest, tx_num, event_num, limit, descending)?
             }
             EventFilter::MoveModule { package, module } => {
-                let module_id = ModuleId::new(package.into(), module);
+                let module_id = ModuleId::new((*package).into(), module.clone());
                 index_store.events_by_module_id(&module_id, tx_num, event_num, limit, descending)?
             }
             EventFilter::MoveEventType(struct_name) => index_store
                 .events_by_move_event_struct_name(
-                    &struct_name,
+                    struct_name,
                     tx_num,
                     event_num,
                     limit,
                     descending,
                 )?,
             EventFilter::Sender(sender) => {
-                index_store.events_by_sender(&sender, tx_num, event_num, limit, descending)?
+                index_store.events_by_sender(sender, tx_num, event_num, limit, descending)?
             }
             EventFilter::TimeRange {
                 start_time,
                 end_time,
-            } => index_store
-                .event_iterator(start_time, end_time, tx_num, event_num, limit, descending)?,
-            _ => {
-                return Err(anyhow!(
-                    "This query type is not supported by the full node."
-                ))
-            }
-        };
+            } => index_store.event_iterator(
+                *start_time,
+                *end_time,
+                tx_num,
+                event_num,
+                limit,
+                descending,
+            )?,
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Evaluates a conjunction of event filters. Candidates are read page by page from the index
+    /// of the most selective filter that has one, and every candidate is checked against all the
+    /// filters. Conjunctions without any indexed filter are rejected rather than scanning all
+    /// events, and the scan stops after `MAX_COMPOSITE_EVENT_SCAN` candidates, returning the last
+    /// scanned event along with the events found so far.
+    fn query_events_by_composite_filter(
+        &self,
+        index_store: &IndexStore,
+        filters: Vec<EventFilter>,
+        cursor: Option<EventID>,
+        (mut tx_num, mut event_num): (TxSequenceNumber, usize),
+        limit: usize,
+        descending: bool,
+    ) -> Result<(Vec<SuiEvent>, Option<EventID>), anyhow::Error> {
+        let driver = filters
+            .iter()
+            .filter_map(|f| Self::event_index_selectivity(f).map(|rank| (rank, f)))
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, f)| f.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Combined event queries need at least one Transaction, Sender, \
+                     MoveEventType, MoveModule or TimeRange filter."
+                )
+            })?;
+        let query = EventFilter::All(filters);
 
-        // skip one event if exclusive cursor is provided,
-        // otherwise truncate to the original limit.
-        if cursor.is_some() {
-            if !event_keys.is_empty() {
-                event_keys.remove(0);
+        // The event the previous page ended on, index reads are inclusive of their start position.
+        let mut last_event = cursor.map(|c| (c.tx_digest, c.event_seq as usize));
+        let mut events = vec![];
+        let mut scanned = 0;
+        while events.len() < limit {
+            if scanned >= MAX_COMPOSITE_EVENT_SCAN {
+                let scan_cursor = last_event.map(|(tx_digest, event_seq)| EventID {
+                    tx_digest,
+                    event_seq: event_seq as u64,
+                });
+                return Ok((events, scan_cursor));
             }
-        } else {
-            event_keys.truncate(limit - 1);
+            let event_keys = Self::event_keys_from_index(
+                index_store,
+                &driver,
+                tx_num,
+                event_num,
+                limit + 1,
+                descending,
+            )?
+            .ok_or_else(|| anyhow!("This query type is not supported by the full node."))?;
+            let is_last_page = event_keys.len() <= limit;
+
+            let event_keys = event_keys
+                .into_iter()
+                .filter(|(_, tx_digest, event_seq, _)| last_event != Some((*tx_digest, *event_seq)))
+                .collect::<Vec<_>>();
+            let Some((_, tx_digest, event_seq, _)) = event_keys.last().cloned() else {
+                break;
+            };
+            tx_num = index_store
+                .get_transaction_seq(&tx_digest)?
+                .ok_or(SuiError::TransactionNotFound { digest: tx_digest })?;
+            event_num = event_seq;
+            last_event = Some((tx_digest, event_seq));
+            scanned += event_keys.len();
+
+            events.extend(
+                self.load_events(event_keys)?
+                    .into_iter()
+                    .filter(|e| query.matches(e)),
+            );
+            if is_last_page {
+                break;
+            }
+        }
+        events.truncate(limit);
+        Ok((events, None))
+    }
+
+    /// Ranks the event filters that have an index from the most to the least selective, returns
+    /// `None` for filters without an index.
+    fn event_index_selectivity(filter: &EventFilter) -> Option<u8> {
+        match filter {
+            EventFilter::Transaction(_) => Some(0),
+            EventFilter::Sender(_) => Some(1),
+            EventFilter::MoveEventType(_) => Some(2),
+            EventFilter::MoveModule { .. } => Some(3),
+            EventFilter::TimeRange { .. } => Some(4),
+            _ => None,
         }
+    }
+
+    fn load_events(
+        &self,
+        event_keys: Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>,
+    ) -> Result<Vec<SuiEvent>, anyhow::Error> {
         let keys = event_keys.iter().map(|(digest, _, seq, _)| (*digest, *seq));
 
         let stored_events = self
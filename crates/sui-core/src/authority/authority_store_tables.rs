@@ -4,6 +4,7 @@
 use super::*;
 use crate::authority::authority_store::LockDetailsWrapper;
 use rocksdb::Options;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
@@ -18,8 +19,8 @@ use typed_store::rocks::{
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
 use crate::authority::authority_store_types::{
-    try_construct_object, ObjectContentDigest, StoreData, StoreMoveObjectWrapper, StoreObject,
-    StoreObjectValue, StoreObjectWrapper,
+    get_store_object_pair, try_construct_object, ObjectContentDigest, StoreData,
+    StoreMoveObjectWrapper, StoreObject, StoreObjectValue, StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use typed_store_derive::DBMapUtils;
@@ -256,6 +257,37 @@ impl AuthorityPerpetualTables {
         Ok(())
     }
 
+    /// Inserts objects of a live object set, e.g. restored from a state snapshot, and initializes
+    /// the locks of the address owned objects. Objects are all stored inline, as tracking the
+    /// reference counts of indirect objects is only done when executing transactions.
+    pub fn bulk_insert_live_objects(&self, live_objects: &[LiveObject]) -> SuiResult {
+        let mut batch = self.objects.batch();
+        batch
+            .insert_batch(
+                &self.objects,
+                live_objects.iter().map(|live_object| match live_object {
+                    LiveObject::Normal(object) => (
+                        ObjectKey(object.id(), object.version()),
+                        get_store_object_pair(object.clone(), 0).0,
+                    ),
+                    LiveObject::Wrapped(key) => (*key, StoreObject::Wrapped.into()),
+                }),
+            )?
+            .insert_batch(
+                &self.owned_object_transaction_locks,
+                live_objects
+                    .iter()
+                    .filter_map(|live_object| match live_object {
+                        LiveObject::Normal(object) if object.is_address_owned() => {
+                            Some((object.compute_object_reference(), None))
+                        }
+                        _ => None,
+                    }),
+            )?;
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn database_is_empty(&self) -> SuiResult<bool> {
         Ok(self
             .objects
@@ -337,6 +369,7 @@ pub struct LiveSetIter<'a> {
     prev: Option<(ObjectKey, StoreObjectWrapper)>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LiveObject {
     Normal(Object),
     Wrapped(ObjectKey),
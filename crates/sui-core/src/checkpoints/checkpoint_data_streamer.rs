@@ -0,0 +1,287 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! CheckpointDataStreamer publishes the full content of every executed checkpoint, as a single
+//! `CheckpointData` bundle, to subscribers such as indexers. The CheckpointExecutor hands over the
+//! executed checkpoints in the order of their sequence number, and the bundles are loaded from the
+//! stores and published by a task of their own, off the execution path. Subscribers resuming from
+//! an earlier checkpoint, or falling behind, are caught up from the stores.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use futures::{stream, Stream};
+use mysten_metrics::spawn_monitored_task;
+use sui_types::error::{SuiError, SuiResult, UserInputError};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
+use tokio::sync::mpsc;
+use tracing::{debug, error};
+
+use crate::authority::AuthorityStore;
+use crate::checkpoints::CheckpointStore;
+
+#[cfg(test)]
+#[path = "../unit_tests/checkpoint_data_streamer_tests.rs"]
+mod checkpoint_data_streamer_tests;
+
+/// The number of checkpoints buffered for the subscribers, and of executed checkpoints waiting to
+/// be published. A subscriber falling further behind catches up by reading the checkpoints back
+/// from the stores, and checkpoints executed while the publisher is that far behind are dropped.
+pub const CHECKPOINT_DATA_BUFFER_SIZE: usize = 100;
+
+pub struct CheckpointDataStreamer {
+    sender: Sender<Arc<CheckpointData>>,
+    executed_sender: mpsc::Sender<VerifiedCheckpoint>,
+    reader: CheckpointDataReader,
+}
+
+impl CheckpointDataStreamer {
+    /// Creates the streamer and spawns the task publishing the executed checkpoints.
+    pub fn new(
+        checkpoint_store: Arc<CheckpointStore>,
+        authority_store: Arc<AuthorityStore>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(CHECKPOINT_DATA_BUFFER_SIZE);
+        let (executed_sender, executed_receiver) = mpsc::channel(CHECKPOINT_DATA_BUFFER_SIZE);
+        let reader = CheckpointDataReader {
+            checkpoint_store,
+            authority_store,
+        };
+        spawn_monitored_task!(Self::run_publisher(
+            reader.clone(),
+            sender.clone(),
+            executed_receiver
+        ));
+        Self {
+            sender,
+            executed_sender,
+            reader,
+        }
+    }
+
+    /// Hands over an executed checkpoint to be published to the current subscribers, without
+    /// waiting. Must be called in the order of checkpoint sequence number, once the checkpoint is
+    /// recorded as executed.
+    pub fn publish(&self, checkpoint: &VerifiedCheckpoint) {
+        // Nobody is listening, don't bother reading the checkpoint back.
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+
+        // Subscribers notice the gap and read the dropped checkpoint back from the stores.
+        if self.executed_sender.try_send(checkpoint.clone()).is_err() {
+            debug!(
+                sequence_number = checkpoint.sequence_number,
+                "Checkpoint data publisher is behind, dropping checkpoint"
+            );
+        }
+    }
+
+    async fn run_publisher(
+        reader: CheckpointDataReader,
+        sender: Sender<Arc<CheckpointData>>,
+        mut executed_receiver: mpsc::Receiver<VerifiedCheckpoint>,
+    ) {
+        while let Some(checkpoint) = executed_receiver.recv().await {
+            if sender.receiver_count() == 0 {
+                continue;
+            }
+
+            match reader.load_checkpoint_data(&checkpoint) {
+                Ok(data) => {
+                    debug!(
+                        sequence_number = checkpoint.sequence_number,
+                        "Publishing checkpoint data"
+                    );
+                    // Sending only fails when every subscriber is gone in the meantime.
+                    let _ = sender.send(Arc::new(data));
+                }
+                // Subscribers will notice the gap and fail when reading it back from the stores.
+                Err(err) => error!(
+                    sequence_number = checkpoint.sequence_number,
+                    "Failed to load checkpoint data: {err:?}"
+                ),
+            }
+        }
+    }
+
+    /// Returns the data of the checkpoint with the provided sequence number, or None if the
+    /// checkpoint has not been executed yet.
+    pub fn get_checkpoint_data(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointData>> {
+        self.reader.get_checkpoint_data(sequence_number)
+    }
+
+    /// Streams the data of every executed checkpoint starting from `start`, in order. The
+    /// checkpoints executed before the subscription are read from the stores, then the stream
+    /// follows the checkpoints as they are executed. The stream ends after the first error, which
+    /// happens when the objects of a checkpoint have been pruned.
+    pub fn subscribe(
+        self: Arc<Self>,
+        start: CheckpointSequenceNumber,
+    ) -> impl Stream<Item = SuiResult<Arc<CheckpointData>>> {
+        // Subscribe before reading the stores, so the checkpoints executed meanwhile are not missed.
+        let receiver = self.sender.subscribe();
+        stream::unfold(Some((self, receiver, start)), |state| async move {
+            let (streamer, mut receiver, next) = state?;
+            match streamer.next_checkpoint_data(&mut receiver, next).await? {
+                Ok(data) => Some((Ok(data), Some((streamer, receiver, next + 1)))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    async fn next_checkpoint_data(
+        &self,
+        receiver: &mut Receiver<Arc<CheckpointData>>,
+        next: CheckpointSequenceNumber,
+    ) -> Option<SuiResult<Arc<CheckpointData>>> {
+        loop {
+            // Catch up with the checkpoints executed already.
+            match self.get_checkpoint_data(next) {
+                Ok(Some(data)) => return Some(Ok(Arc::new(data))),
+                Ok(None) => (),
+                Err(err) => return Some(Err(err)),
+            }
+
+            match receiver.recv().await {
+                Ok(data) if data.sequence_number() == next => return Some(Ok(data)),
+                // Either a checkpoint already streamed from the stores, or one published after a
+                // gap which the stores will fill.
+                Ok(_) => continue,
+                Err(RecvError::Lagged(num_skipped)) => {
+                    debug!("Checkpoint data subscriber lagged by {num_skipped} checkpoints");
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Reads back the data of executed checkpoints from the stores.
+#[derive(Clone)]
+struct CheckpointDataReader {
+    checkpoint_store: Arc<CheckpointStore>,
+    authority_store: Arc<AuthorityStore>,
+}
+
+impl CheckpointDataReader {
+    fn get_checkpoint_data(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointData>> {
+        let highest_executed = self
+            .checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()?;
+        if highest_executed.map_or(true, |highest| sequence_number > highest) {
+            return Ok(None);
+        }
+
+        let checkpoint = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Executed checkpoint {sequence_number} not found"
+                ))
+            })?;
+        self.load_checkpoint_data(&checkpoint).map(Some)
+    }
+
+    fn load_checkpoint_data(&self, checkpoint: &VerifiedCheckpoint) -> SuiResult<CheckpointData> {
+        let checkpoint_contents = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Contents of checkpoint {} not found",
+                    checkpoint.sequence_number
+                ))
+            })?;
+
+        let tx_digests: Vec<_> = checkpoint_contents.iter().map(|d| d.transaction).collect();
+        let transactions = self
+            .authority_store
+            .multi_get_transaction_blocks(&tx_digests)?;
+        let effects = self
+            .authority_store
+            .multi_get_effects(checkpoint_contents.iter().map(|d| &d.effects))?;
+
+        let mut checkpoint_transactions = Vec::with_capacity(tx_digests.len());
+        for ((digest, transaction), effects) in tx_digests.iter().zip(transactions).zip(effects) {
+            let transaction =
+                transaction.ok_or(SuiError::TransactionNotFound { digest: *digest })?;
+            let effects = effects.ok_or_else(|| {
+                SuiError::GenericStorageError(format!("Effects of transaction {digest} not found"))
+            })?;
+
+            let events = match effects.events_digest() {
+                Some(events_digest) => {
+                    Some(self.authority_store.get_events(events_digest)?.ok_or(
+                        SuiError::TransactionEventsNotFound {
+                            digest: *events_digest,
+                        },
+                    )?)
+                }
+                None => None,
+            };
+
+            let input_object_keys: BTreeSet<_> = effects
+                .modified_at_versions()
+                .iter()
+                .map(|(id, version)| ObjectKey(*id, *version))
+                .chain(
+                    effects
+                        .shared_objects()
+                        .iter()
+                        .map(|(id, version, _)| ObjectKey(*id, *version)),
+                )
+                .collect();
+            let output_object_keys: Vec<_> = effects
+                .all_changed_objects()
+                .into_iter()
+                .map(|((id, version, _), _, _)| ObjectKey(*id, *version))
+                .collect();
+
+            checkpoint_transactions.push(CheckpointTransaction {
+                transaction: transaction.into_inner(),
+                events,
+                input_objects: self
+                    .get_objects(&input_object_keys.into_iter().collect::<Vec<_>>())?,
+                output_objects: self.get_objects(&output_object_keys)?,
+                effects,
+            });
+        }
+
+        Ok(CheckpointData {
+            checkpoint_summary: checkpoint.clone().into_inner(),
+            checkpoint_contents,
+            transactions: checkpoint_transactions,
+        })
+    }
+
+    fn get_objects(&self, object_keys: &[ObjectKey]) -> SuiResult<Vec<Object>> {
+        self.authority_store
+            .multi_get_object_by_key(object_keys)?
+            .into_iter()
+            .zip(object_keys)
+            .map(|(object, ObjectKey(object_id, version))| {
+                object.ok_or_else(|| {
+                    UserInputError::ObjectNotFound {
+                        object_id: *object_id,
+                        version: Some(*version),
+                    }
+                    .into()
+                })
+            })
+            .collect()
+    }
+}
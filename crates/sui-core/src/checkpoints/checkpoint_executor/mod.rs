@@ -50,6 +50,7 @@ use typed_store::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority::AuthorityStore;
+use crate::checkpoints::checkpoint_data_streamer::CheckpointDataStreamer;
 use crate::state_accumulator::StateAccumulator;
 use crate::transaction_manager::TransactionManager;
 use crate::{authority::EffectsNotifyRead, checkpoints::CheckpointStore};
@@ -71,6 +72,7 @@ pub struct CheckpointExecutor {
     authority_store: Arc<AuthorityStore>,
     tx_manager: Arc<TransactionManager>,
     accumulator: Arc<StateAccumulator>,
+    checkpoint_data_streamer: Option<Arc<CheckpointDataStreamer>>,
     config: CheckpointExecutorConfig,
     metrics: Arc<CheckpointExecutorMetrics>,
 }
@@ -82,6 +84,7 @@ impl CheckpointExecutor {
         authority_store: Arc<AuthorityStore>,
         tx_manager: Arc<TransactionManager>,
         accumulator: Arc<StateAccumulator>,
+        checkpoint_data_streamer: Arc<CheckpointDataStreamer>,
         config: CheckpointExecutorConfig,
         prometheus_registry: &Registry,
     ) -> Self {
@@ -91,6 +94,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            checkpoint_data_streamer: Some(checkpoint_data_streamer),
             config,
             metrics: CheckpointExecutorMetrics::new(prometheus_registry),
         }
@@ -109,6 +113,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            checkpoint_data_streamer: None,
             config: Default::default(),
             metrics: CheckpointExecutorMetrics::new_for_tests(),
         }
@@ -255,6 +260,10 @@ impl CheckpointExecutor {
             .update_highest_executed_checkpoint(checkpoint)
             .unwrap();
         self.metrics.last_executed_checkpoint.set(seq as i64);
+
+        if let Some(checkpoint_data_streamer) = &self.checkpoint_data_streamer {
+            checkpoint_data_streamer.publish(checkpoint);
+        }
     }
 
     async fn schedule_synced_checkpoints(
@@ -14,7 +14,6 @@ pub mod consensus_handler;
 pub mod consensus_validator;
 pub mod db_checkpoint_handler;
 pub mod epoch;
-pub mod event_handler;
 mod execution_driver;
 mod math;
 pub mod metrics;
@@ -25,8 +24,10 @@ pub mod safe_client;
 mod scoring_decision;
 mod stake_aggregator;
 pub mod state_accumulator;
+pub mod state_snapshot;
 pub mod storage;
 pub mod streamer;
+pub mod subscription_handler;
 #[cfg(feature = "test-utils")]
 pub mod test_utils;
 pub mod transaction_input_checker;
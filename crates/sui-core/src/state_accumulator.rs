@@ -50,6 +50,22 @@ impl WrappedObject {
     }
 }
 
+/// Inserts an object of the live object set into the accumulator, the same way its creation or
+/// wrapping was accumulated when executing checkpoints.
+pub fn accumulate_live_object(acc: &mut Accumulator, live_object: &LiveObject) {
+    match live_object {
+        LiveObject::Normal(object) => {
+            acc.insert(object.compute_object_reference().2);
+        }
+        LiveObject::Wrapped(key) => {
+            acc.insert(
+                bcs::to_bytes(&WrappedObject::new(key.0, key.1))
+                    .expect("Failed to serialize WrappedObject"),
+            );
+        }
+    }
+}
+
 impl StateAccumulator {
     pub fn new(authority_store: Arc<AuthorityStore>) -> Self {
         Self { authority_store }
@@ -303,17 +319,7 @@ impl StateAccumulator {
     pub fn accumulate_live_object_set(&self) -> Accumulator {
         let mut acc = Accumulator::default();
         for live_object in self.authority_store.iter_live_object_set() {
-            match live_object {
-                LiveObject::Normal(object) => {
-                    acc.insert(object.compute_object_reference().2);
-                }
-                LiveObject::Wrapped(key) => {
-                    acc.insert(
-                        bcs::to_bytes(&WrappedObject::new(key.0, key.1))
-                            .expect("Failed to serialize WrappedObject"),
-                    );
-                }
-            }
+            accumulate_live_object(&mut acc, &live_object);
         }
         acc
     }
@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! State snapshots hold the live object set at the end of an epoch, in a portable format on top
+//! of an object store, so that a node can be bootstrapped from them instead of replaying every
+//! checkpoint since genesis.
+//!
+//! The snapshot of epoch N is laid out under `epoch_<N>/`. The live objects, in the order of their
+//! ids, are partitioned into `<index>.obj` files, each starting with a magic number, the version
+//! of the format and the compression of the rest of the file, which holds the BCS encoding of the
+//! objects. The `MANIFEST` file lists the files along with their SHA3-256 checksum, and holds the
+//! certified last checkpoint of every epoch up to N. These let a node verify the snapshot from the
+//! genesis committee alone: the checkpoints chain the committees of the epochs, and the last one
+//! commits to the ECMH digest of the live object set at the end of epoch N. The manifest is only
+//! written once every file is, so that readers never observe partial snapshots.
+
+use anyhow::{anyhow, Context, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use sui_storage::compression::FileCompression;
+use sui_storage::object_store::util::put;
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointCommitment, CheckpointSummary, ECMHLiveObjectSetDigest,
+};
+
+use crate::authority::authority_store_tables::LiveObject;
+
+pub mod reader;
+pub mod writer;
+
+pub use reader::StateSnapshotReader;
+pub use writer::StateSnapshotWriter;
+
+#[cfg(test)]
+#[path = "../unit_tests/state_snapshot_tests.rs"]
+mod state_snapshot_tests;
+
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const OBJECT_FILE_SUFFIX: &str = "obj";
+pub const MANIFEST_FILE_MAGIC: u32 = 0x0053_4D4E;
+pub const OBJECT_FILE_MAGIC: u32 = 0x0053_4F42;
+pub const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+
+pub fn epoch_dir(epoch: EpochId) -> Path {
+    Path::from(format!("epoch_{epoch}"))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectFileMetadata {
+    pub index: u64,
+    pub num_objects: u64,
+    pub sha3_digest: [u8; 32],
+}
+
+impl ObjectFileMetadata {
+    pub fn file_path(&self, epoch: EpochId) -> Path {
+        epoch_dir(epoch).child(format!("{}.{OBJECT_FILE_SUFFIX}", self.index))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u8,
+    pub epoch: EpochId,
+    /// The certified last checkpoint of every epoch up to and including the snapshot epoch.
+    pub end_of_epoch_checkpoints: Vec<CertifiedCheckpointSummary>,
+    /// The files of the snapshot, in the order of the objects they hold.
+    pub files: Vec<ObjectFileMetadata>,
+}
+
+impl Manifest {
+    pub fn num_objects(&self) -> u64 {
+        self.files.iter().map(|file| file.num_objects).sum()
+    }
+}
+
+/// Returns the digest of the live object set committed to by the last checkpoint of an epoch, if
+/// the protocol version of the epoch commits to it.
+pub fn committed_root_state_digest(
+    checkpoint: &CheckpointSummary,
+) -> Option<ECMHLiveObjectSetDigest> {
+    checkpoint
+        .end_of_epoch_data
+        .as_ref()?
+        .epoch_commitments
+        .iter()
+        .find_map(|commitment| match commitment {
+            CheckpointCommitment::ECMHLiveObjectSetDigest(digest) => Some(digest.clone()),
+        })
+}
+
+pub fn encode_object_file(objects: &[LiveObject], compression: FileCompression) -> Result<Bytes> {
+    let payload = compression.compress(bcs::to_bytes(objects)?)?;
+    let mut bytes = BytesMut::with_capacity(payload.len() + 6);
+    bytes.put_u32(OBJECT_FILE_MAGIC);
+    bytes.put_u8(SNAPSHOT_FORMAT_VERSION);
+    bytes.put_u8(compression as u8);
+    bytes.put_slice(&payload);
+    Ok(bytes.freeze())
+}
+
+pub fn decode_object_file(bytes: &[u8]) -> Result<Vec<LiveObject>> {
+    if bytes.len() < 6 {
+        return Err(anyhow!("Object file is too short"));
+    }
+    let (header, payload) = bytes.split_at(6);
+    if header[..4] != OBJECT_FILE_MAGIC.to_be_bytes()[..] {
+        return Err(anyhow!("Unexpected magic number in object file"));
+    }
+    if header[4] != SNAPSHOT_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported object file version {}", header[4]));
+    }
+    let compression = FileCompression::try_from(header[5])?;
+    Ok(bcs::from_bytes(&compression.decompress(payload)?)?)
+}
+
+/// Encodes the manifest, followed by the checksum of the encoding.
+pub fn encode_manifest(manifest: &Manifest) -> Result<Bytes> {
+    let mut bytes = BytesMut::new();
+    bytes.put_u32(MANIFEST_FILE_MAGIC);
+    bytes.put_slice(&bcs::to_bytes(manifest)?);
+    let checksum = Sha3_256::digest(&bytes[..]).digest;
+    bytes.put_slice(&checksum);
+    Ok(bytes.freeze())
+}
+
+pub fn decode_manifest(bytes: &[u8]) -> Result<Manifest> {
+    if bytes.len() < 4 + 32 {
+        return Err(anyhow!("Manifest is too short"));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - 32);
+    if checksum != Sha3_256::digest(content).digest.as_slice() {
+        return Err(anyhow!("Manifest checksum does not match its content"));
+    }
+    let (magic, manifest) = content.split_at(4);
+    if magic != MANIFEST_FILE_MAGIC.to_be_bytes().as_slice() {
+        return Err(anyhow!("Unexpected magic number in manifest"));
+    }
+    let manifest: Manifest = bcs::from_bytes(manifest)?;
+    if manifest.version != SNAPSHOT_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported manifest version {}", manifest.version));
+    }
+    Ok(manifest)
+}
+
+/// Reads the manifest of the snapshot of an epoch, or None if there is no such snapshot.
+pub async fn read_manifest(store: Arc<DynObjectStore>, epoch: EpochId) -> Result<Option<Manifest>> {
+    match store.get(&epoch_dir(epoch).child(MANIFEST_FILENAME)).await {
+        Ok(result) => {
+            let bytes = result.bytes().await?;
+            decode_manifest(&bytes).map(Some)
+        }
+        Err(object_store::Error::NotFound { .. }) => Ok(None),
+        Err(err) => Err(err).context("Failed to read snapshot manifest"),
+    }
+}
+
+pub async fn write_manifest(store: Arc<DynObjectStore>, manifest: &Manifest) -> Result<()> {
+    put(
+        &epoch_dir(manifest.epoch).child(MANIFEST_FILENAME),
+        encode_manifest(manifest)?,
+        store,
+    )
+    .await
+    .context("Failed to write snapshot manifest")
+}
@@ -0,0 +1,232 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Context, Result};
+use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
+use object_store::DynObjectStore;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use sui_config::genesis::Genesis;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::accumulator::Accumulator;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::messages_checkpoint::{ECMHLiveObjectSetDigest, VerifiedCheckpoint};
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use tracing::info;
+use typed_store::Map;
+
+use super::{
+    committed_root_state_digest, decode_object_file, read_manifest, Manifest, ObjectFileMetadata,
+};
+use crate::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::checkpoints::CheckpointStore;
+use crate::epoch::committee_store::CommitteeStore;
+use crate::state_accumulator::accumulate_live_object;
+
+/// Reads back the state snapshots written by the `StateSnapshotWriter`, and restores nodes from
+/// them.
+#[derive(Clone)]
+pub struct StateSnapshotReader {
+    remote_object_store: Arc<DynObjectStore>,
+}
+
+impl StateSnapshotReader {
+    pub fn new(remote_object_store_config: &ObjectStoreConfig) -> Result<Self> {
+        Ok(StateSnapshotReader {
+            remote_object_store: remote_object_store_config.make()?,
+        })
+    }
+
+    pub async fn read_manifest(&self, epoch: EpochId) -> Result<Manifest> {
+        read_manifest(self.remote_object_store.clone(), epoch)
+            .await?
+            .ok_or_else(|| anyhow!("No snapshot of epoch {epoch}"))
+    }
+
+    /// Reads the objects of a snapshot file, after verifying the file against its checksum.
+    pub async fn read_object_file(
+        &self,
+        epoch: EpochId,
+        file_metadata: &ObjectFileMetadata,
+    ) -> Result<Vec<LiveObject>> {
+        let path = file_metadata.file_path(epoch);
+        let bytes = match self.remote_object_store.get(&path).await {
+            Ok(result) => result.bytes().await,
+            Err(err) => Err(err),
+        }
+        .with_context(|| format!("Failed to read snapshot file {path}"))?;
+        if Sha3_256::digest(&bytes).digest != file_metadata.sha3_digest {
+            return Err(anyhow!("Checksum of snapshot file {path} does not match"));
+        }
+        let objects = decode_object_file(&bytes)?;
+        if objects.len() as u64 != file_metadata.num_objects {
+            return Err(anyhow!(
+                "Snapshot file {path} holds {} objects instead of {}",
+                objects.len(),
+                file_metadata.num_objects
+            ));
+        }
+        Ok(objects)
+    }
+
+    /// Restores the stores of a node at `db_path` from the snapshot of an epoch, leaving the node
+    /// ready to execute the checkpoints of the next epoch once synced from its peers. The
+    /// snapshot is verified against the live object set digest committed to by the last
+    /// checkpoint of the epoch, itself verified from the genesis committee. Nothing is written at
+    /// `db_path` unless the snapshot is valid.
+    ///
+    /// No indexes are restored: full nodes index the live object set of the restored store when
+    /// started, see `AuthorityState::index_live_object_set`, and never index the transactions
+    /// preceding the snapshot.
+    pub async fn restore(&self, epoch: EpochId, genesis: &Genesis, db_path: &Path) -> Result<()> {
+        if db_path.exists() {
+            return Err(anyhow!(
+                "Cannot restore into existing directory {}",
+                db_path.display()
+            ));
+        }
+
+        let manifest = self.read_manifest(epoch).await?;
+        let (checkpoints, committees) =
+            verify_end_of_epoch_checkpoints(genesis.committee()?, &manifest)?;
+
+        let tmp_path = db_path.with_extension("tmp");
+        if tmp_path.exists() {
+            fs::remove_dir_all(&tmp_path)?;
+        }
+        match self
+            .restore_stores(&manifest, genesis, &checkpoints, &committees, &tmp_path)
+            .await
+        {
+            Ok(()) => {
+                fs::rename(&tmp_path, db_path)?;
+                info!(
+                    "Restored {} objects from the snapshot of epoch {epoch}",
+                    manifest.num_objects()
+                );
+                Ok(())
+            }
+            Err(err) => {
+                fs::remove_dir_all(&tmp_path)?;
+                Err(err)
+            }
+        }
+    }
+
+    async fn restore_stores(
+        &self,
+        manifest: &Manifest,
+        genesis: &Genesis,
+        checkpoints: &[VerifiedCheckpoint],
+        committees: &[Committee],
+        path: &Path,
+    ) -> Result<()> {
+        let epoch = manifest.epoch;
+        let last_checkpoint = checkpoints
+            .last()
+            .expect("Snapshot has the last checkpoint of its epoch");
+        let committed_digest = committed_root_state_digest(last_checkpoint).ok_or_else(|| {
+            anyhow!("Last checkpoint of epoch {epoch} does not commit to the live object set")
+        })?;
+
+        let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
+        let mut acc = Accumulator::default();
+        for file_metadata in &manifest.files {
+            let objects = self.read_object_file(epoch, file_metadata).await?;
+            for live_object in &objects {
+                accumulate_live_object(&mut acc, live_object);
+            }
+            perpetual_db.bulk_insert_live_objects(&objects)?;
+        }
+        let digest: ECMHLiveObjectSetDigest = acc.digest().into();
+        if digest != committed_digest {
+            return Err(anyhow!(
+                "Snapshot digest {digest:?} does not match the digest {committed_digest:?} \
+                committed to at the end of epoch {epoch}"
+            ));
+        }
+
+        // The epoch accumulator is the base for accumulating the next epoch, and the checkpoints
+        // of the past epochs are not available to the pruner.
+        perpetual_db
+            .root_state_hash_by_epoch
+            .insert(&epoch, &(last_checkpoint.sequence_number, acc))?;
+        let mut batch = perpetual_db.pruned_checkpoint.batch();
+        perpetual_db.set_highest_pruned_checkpoint(&mut batch, last_checkpoint.sequence_number)?;
+        batch.write()?;
+
+        let epoch_start_state = get_sui_system_state(&perpetual_db)?.into_epoch_start_state();
+        if epoch_start_state.epoch() != epoch + 1 {
+            return Err(anyhow!(
+                "Snapshot of epoch {epoch} holds the system state of epoch {}",
+                epoch_start_state.epoch()
+            ));
+        }
+        perpetual_db
+            .set_epoch_start_configuration(&EpochStartConfiguration::new(
+                epoch_start_state,
+                *last_checkpoint.digest(),
+            ))
+            .await?;
+
+        let committee_store = CommitteeStore::new(path.join("epochs"), &genesis.committee()?, None);
+        for committee in committees {
+            committee_store.insert_new_committee(committee)?;
+        }
+
+        let checkpoint_store = CheckpointStore::new(&path.join("checkpoints"));
+        checkpoint_store.insert_checkpoint_contents(genesis.checkpoint_contents().clone())?;
+        checkpoint_store.insert_verified_checkpoint(genesis.checkpoint())?;
+        for checkpoint in checkpoints {
+            checkpoint_store.insert_verified_checkpoint(checkpoint.clone())?;
+        }
+        checkpoint_store.update_highest_synced_checkpoint(last_checkpoint)?;
+        checkpoint_store.update_highest_executed_checkpoint(last_checkpoint)?;
+        Ok(())
+    }
+}
+
+/// Verifies the last checkpoints of the epochs up to the snapshot epoch, each against the
+/// committee chosen by the previous one, starting from the genesis committee. Returns the
+/// verified checkpoints along with the committees of the epochs following the genesis one.
+pub fn verify_end_of_epoch_checkpoints(
+    genesis_committee: Committee,
+    manifest: &Manifest,
+) -> Result<(Vec<VerifiedCheckpoint>, Vec<Committee>)> {
+    if manifest.end_of_epoch_checkpoints.len() as u64 != manifest.epoch + 1 {
+        return Err(anyhow!(
+            "Snapshot of epoch {} holds {} end of epoch checkpoints",
+            manifest.epoch,
+            manifest.end_of_epoch_checkpoints.len()
+        ));
+    }
+
+    let mut checkpoints = vec![];
+    let mut committees = vec![];
+    let mut committee = genesis_committee;
+    for (epoch, checkpoint) in manifest.end_of_epoch_checkpoints.iter().enumerate() {
+        if checkpoint.epoch() != epoch as EpochId {
+            return Err(anyhow!(
+                "Expected the last checkpoint of epoch {epoch}, got one of epoch {}",
+                checkpoint.epoch()
+            ));
+        }
+        let checkpoint = checkpoint.clone().verify(&committee)?;
+        let next_epoch_committee = checkpoint.next_epoch_committee().ok_or_else(|| {
+            anyhow!(
+                "Checkpoint {} is not the last checkpoint of epoch {epoch}",
+                checkpoint.sequence_number
+            )
+        })?;
+        committee = Committee::new(
+            checkpoint.epoch() + 1,
+            next_epoch_committee.iter().cloned().collect(),
+        );
+        committees.push(committee.clone());
+        checkpoints.push(checkpoint);
+    }
+    Ok((checkpoints, committees))
+}
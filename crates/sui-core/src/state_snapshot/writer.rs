@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
+use object_store::DynObjectStore;
+use std::sync::Arc;
+use sui_storage::compression::FileCompression;
+use sui_storage::object_store::util::put;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::accumulator::Accumulator;
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
+use tracing::info;
+
+use super::{
+    committed_root_state_digest, encode_object_file, write_manifest, Manifest, ObjectFileMetadata,
+    SNAPSHOT_FORMAT_VERSION,
+};
+use crate::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::accumulate_live_object;
+
+/// Writes the live object set of a node at the end of an epoch as a state snapshot.
+pub struct StateSnapshotWriter {
+    remote_object_store: Arc<DynObjectStore>,
+    /// Size above which the objects are split into another file, before compression
+    file_size_bytes: usize,
+}
+
+impl StateSnapshotWriter {
+    pub fn new(
+        remote_object_store_config: &ObjectStoreConfig,
+        file_size_bytes: usize,
+    ) -> Result<Self> {
+        Ok(StateSnapshotWriter {
+            remote_object_store: remote_object_store_config.make()?,
+            file_size_bytes,
+        })
+    }
+
+    /// Writes the snapshot of an epoch from the stores of a node whose last executed checkpoint is
+    /// the last one of the epoch, such as the database checkpoints taken at the end of epochs. The
+    /// snapshot is only written once its objects match the digest committed to on chain.
+    pub async fn write(
+        &self,
+        epoch: EpochId,
+        perpetual_db: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+    ) -> Result<Manifest> {
+        let last_checkpoint = checkpoint_store
+            .get_epoch_last_checkpoint(epoch)?
+            .ok_or_else(|| anyhow!("Last checkpoint of epoch {epoch} not found"))?;
+        let highest_executed = checkpoint_store.get_highest_executed_checkpoint_seq_number()?;
+        if highest_executed != Some(last_checkpoint.sequence_number) {
+            return Err(anyhow!(
+                "Live object set is not at the end of epoch {epoch}: highest executed checkpoint \
+                is {highest_executed:?} but the last checkpoint of the epoch is {}",
+                last_checkpoint.sequence_number
+            ));
+        }
+        let committed_digest = committed_root_state_digest(&last_checkpoint).ok_or_else(|| {
+            anyhow!("Last checkpoint of epoch {epoch} does not commit to the live object set")
+        })?;
+
+        let mut end_of_epoch_checkpoints = vec![];
+        for past_epoch in 0..=epoch {
+            let checkpoint = checkpoint_store
+                .get_epoch_last_checkpoint(past_epoch)?
+                .ok_or_else(|| anyhow!("Last checkpoint of epoch {past_epoch} not found"))?;
+            end_of_epoch_checkpoints.push(checkpoint.into_inner());
+        }
+
+        let mut acc = Accumulator::default();
+        let mut files = vec![];
+        let mut objects = vec![];
+        let mut size = 0;
+        for live_object in perpetual_db.iter_live_object_set() {
+            accumulate_live_object(&mut acc, &live_object);
+            size += bcs::serialized_size(&live_object)?;
+            objects.push(live_object);
+            if size >= self.file_size_bytes {
+                files.push(self.write_object_file(epoch, files.len(), &objects).await?);
+                objects.clear();
+                size = 0;
+            }
+        }
+        if !objects.is_empty() {
+            files.push(self.write_object_file(epoch, files.len(), &objects).await?);
+        }
+
+        let digest: ECMHLiveObjectSetDigest = acc.digest().into();
+        if digest != committed_digest {
+            return Err(anyhow!(
+                "Live object set digest {digest:?} does not match the digest {committed_digest:?} \
+                committed to at the end of epoch {epoch}"
+            ));
+        }
+
+        let manifest = Manifest {
+            version: SNAPSHOT_FORMAT_VERSION,
+            epoch,
+            end_of_epoch_checkpoints,
+            files,
+        };
+        write_manifest(self.remote_object_store.clone(), &manifest).await?;
+        info!(
+            "Wrote snapshot of epoch {epoch} with {} objects in {} files",
+            manifest.num_objects(),
+            manifest.files.len()
+        );
+        Ok(manifest)
+    }
+
+    async fn write_object_file(
+        &self,
+        epoch: EpochId,
+        index: usize,
+        objects: &[LiveObject],
+    ) -> Result<ObjectFileMetadata> {
+        let bytes = encode_object_file(objects, FileCompression::Snappy)?;
+        let file_metadata = ObjectFileMetadata {
+            index: index as u64,
+            num_objects: objects.len() as u64,
+            sha3_digest: Sha3_256::digest(&bytes).digest,
+        };
+        put(
+            &file_metadata.file_path(epoch),
+            bytes,
+            self.remote_object_store.clone(),
+        )
+        .await?;
+        Ok(file_metadata)
+    }
+}
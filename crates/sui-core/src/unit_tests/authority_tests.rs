@@ -49,7 +49,8 @@ use sui_types::{
     crypto::{AccountKeyPair, AuthorityKeyPair, KeypairTraits},
     messages::VerifiedTransaction,
     object::{Owner, GAS_VALUE_FOR_TESTING, OBJECT_START_VERSION},
-    MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
+    MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_OBJECT_ID,
+    SUI_SYSTEM_STATE_OBJECT_ID,
 };
 use sui_types::{SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION};
 
@@ -2872,7 +2873,6 @@ async fn test_authority_persist() {
             checkpoint_store,
             &registry,
             AuthorityStorePruningConfig::default(),
-            &[], // no genesis objects
             &DBCheckpointConfig::default(),
             ExpensiveSafetyCheckConfig::new_enable_all(),
         )
@@ -2926,6 +2926,67 @@ async fn test_authority_persist() {
     assert_eq!(obj2.owner, recipient);
 }
 
+#[tokio::test]
+async fn test_index_live_object_set() {
+    let seed = [1u8; 32];
+    let (genesis, authority_key) = init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let committee = genesis.committee().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    // GIVEN a store holding an object written without a transaction, as restored from a state
+    // snapshot
+    let store = AuthorityStore::open_with_committee_for_testing(
+        &dir.path().join("store"),
+        None,
+        &committee,
+        &genesis,
+        0,
+    )
+    .await
+    .unwrap();
+    let owner = dbg_addr(2);
+    let coin = Object::with_owner_for_testing(owner);
+    store
+        .perpetual_tables
+        .bulk_insert_live_objects(&[LiveObject::Normal(coin.clone())])
+        .unwrap();
+    drop(store);
+    // Wait for the background threads holding the db to release it, see test_authority_persist.
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    // WHEN starting an authority with an empty index store
+    let authority = AuthorityState::new_for_testing(
+        committee,
+        &authority_key,
+        Some(dir.path().to_path_buf()),
+        &genesis,
+    )
+    .await;
+
+    // THEN the live object set is indexed in the background, including the object and its coin
+    let index_store = authority.indexes.as_ref().unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        while index_store.live_object_set_indexing().unwrap() != LiveObjectSetIndexing::Complete {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("The live object set should be indexed");
+    let owned: Vec<_> = index_store
+        .get_owner_objects(owner, None, 10, None)
+        .unwrap()
+        .into_iter()
+        .map(|info| info.object_id)
+        .collect();
+    assert_eq!(owned, vec![coin.id()]);
+    let coins: Vec<_> = index_store
+        .get_owned_coins_iterator(owner, None)
+        .unwrap()
+        .map(|(_, object_id, coin_info)| (object_id, coin_info.version))
+        .collect();
+    assert_eq!(coins, vec![(coin.id(), coin.version())]);
+}
+
 #[tokio::test]
 async fn test_idempotent_reversed_confirmation() {
     // In this test we exercise the case where an authority first receive the certificate,
@@ -5548,3 +5609,174 @@ async fn test_publish_not_a_package_dependency() {
         failure,
     )
 }
+
+/// Indexes one event per sender, emitted at 1000, 2000, ... ms, and returns the digests of the
+/// transactions emitting them.
+#[cfg(test)]
+fn index_test_events(state: &AuthorityState, senders: &[SuiAddress]) -> Vec<TransactionDigest> {
+    let index_store = state.indexes.as_ref().unwrap();
+    senders
+        .iter()
+        .enumerate()
+        .map(|(i, sender)| {
+            let digest = TransactionDigest::random();
+            let id = ObjectID::random();
+            let events = TransactionEvents {
+                data: vec![Event {
+                    package_id: SUI_FRAMEWORK_OBJECT_ID,
+                    transaction_module: Identifier::new("object").unwrap(),
+                    sender: *sender,
+                    type_: StructTag {
+                        address: SUI_FRAMEWORK_ADDRESS,
+                        module: Identifier::new("object").unwrap(),
+                        name: Identifier::new("ID").unwrap(),
+                        type_params: vec![],
+                    },
+                    contents: bcs::to_bytes(&id).unwrap(),
+                }],
+            };
+            state
+                .database
+                .perpetual_tables
+                .events
+                .insert(&(events.digest(), 0), &events.data[0])
+                .unwrap();
+            index_store
+                .index_tx(
+                    *sender,
+                    std::iter::empty(),
+                    std::iter::empty(),
+                    std::iter::empty(),
+                    &events,
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &digest,
+                    1000 * (i as u64 + 1),
+                    None,
+                )
+                .unwrap();
+            digest
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_query_events_by_time_range() {
+    // GIVEN events emitted at 1000, 2000 and 3000 ms
+    let state = init_state().await;
+    let sender = SuiAddress::random_for_testing_only();
+    let digests = index_test_events(&state, &[sender, sender, sender]);
+    let time_range = EventFilter::TimeRange {
+        start_time: 1000,
+        end_time: 3000,
+    };
+    let tx_digests = |events: Vec<SuiEvent>| {
+        events
+            .into_iter()
+            .map(|e| e.id.tx_digest)
+            .collect::<Vec<_>>()
+    };
+
+    // WHEN querying a time range THEN its end time is exclusive, like `EventFilter::matches`
+    let events = state
+        .query_events(time_range.clone(), None, 10, false)
+        .unwrap();
+    assert!(events.iter().all(|e| time_range.matches(e)));
+    assert_eq!(tx_digests(events), digests[..2]);
+    let events = state
+        .query_events(time_range.clone(), None, 10, true)
+        .unwrap();
+    assert_eq!(tx_digests(events), [digests[1], digests[0]]);
+
+    // AND pages resume after their cursor, in both directions
+    let page = state
+        .query_events(time_range.clone(), None, 1, false)
+        .unwrap();
+    assert_eq!(tx_digests(page.clone()), [digests[0]]);
+    let page = state
+        .query_events(time_range.clone(), Some(page[0].id.clone()), 1, false)
+        .unwrap();
+    assert_eq!(tx_digests(page.clone()), [digests[1]]);
+    let page = state
+        .query_events(time_range.clone(), Some(page[0].id.clone()), 1, false)
+        .unwrap();
+    assert!(page.is_empty());
+
+    let page = state
+        .query_events(time_range.clone(), None, 1, true)
+        .unwrap();
+    assert_eq!(tx_digests(page.clone()), [digests[1]]);
+    let page = state
+        .query_events(time_range.clone(), Some(page[0].id.clone()), 1, true)
+        .unwrap();
+    assert_eq!(tx_digests(page.clone()), [digests[0]]);
+    let page = state
+        .query_events(time_range, Some(page[0].id.clone()), 1, true)
+        .unwrap();
+    assert!(page.is_empty());
+}
+
+#[tokio::test]
+async fn test_query_events_by_composite_filter() {
+    // GIVEN events of two senders, emitted alternately
+    let state = init_state().await;
+    let sender = SuiAddress::random_for_testing_only();
+    let other_sender = SuiAddress::random_for_testing_only();
+    let digests = index_test_events(&state, &[sender, other_sender, sender, other_sender]);
+    let filter = EventFilter::All(vec![
+        EventFilter::Sender(sender),
+        EventFilter::TimeRange {
+            start_time: 0,
+            end_time: 10_000,
+        },
+    ]);
+
+    // WHEN combining an indexed filter with another THEN only the events matching both are
+    // returned
+    let events = state.query_events(filter.clone(), None, 10, false).unwrap();
+    assert_eq!(
+        events.iter().map(|e| e.id.tx_digest).collect::<Vec<_>>(),
+        [digests[0], digests[2]]
+    );
+
+    // AND they are paged through one by one
+    let mut cursor = None;
+    let mut paged = vec![];
+    loop {
+        let page = state
+            .query_events(filter.clone(), cursor, 1, false)
+            .unwrap();
+        let Some(event) = page.first() else {
+            break;
+        };
+        assert_eq!(page.len(), 1);
+        paged.push(event.id.tx_digest);
+        cursor = Some(event.id.clone());
+    }
+    assert_eq!(paged, [digests[0], digests[2]]);
+
+    // AND in descending order
+    let events = state.query_events(filter, None, 10, true).unwrap();
+    assert_eq!(
+        events.iter().map(|e| e.id.tx_digest).collect::<Vec<_>>(),
+        [digests[2], digests[0]]
+    );
+
+    // BUT combinations without an indexed filter are rejected instead of scanning all events
+    for filter in [
+        EventFilter::All(vec![
+            EventFilter::Package(SUI_FRAMEWORK_OBJECT_ID),
+            EventFilter::All(vec![]),
+        ]),
+        EventFilter::And(
+            Box::new(EventFilter::Package(SUI_FRAMEWORK_OBJECT_ID)),
+            Box::new(EventFilter::All(vec![])),
+        ),
+    ] {
+        assert!(state.query_events(filter, None, 10, false).is_err());
+    }
+}
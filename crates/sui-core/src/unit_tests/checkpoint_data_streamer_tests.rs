@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::AuthorityState;
+use fastcrypto::traits::KeyPair;
+use futures::StreamExt;
+use std::time::Duration;
+use sui_network::state_sync::test_utils::{empty_contents, CommitteeFixture};
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn test_checkpoint_data_streamer() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let network_config = sui_config::builder::ConfigBuilder::new(&dir).build();
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let keypair = network_config.validator_configs[0]
+        .protocol_key_pair()
+        .copy();
+    let state = AuthorityState::new_for_testing(
+        committee.committee().clone(),
+        &keypair,
+        None,
+        &network_config.genesis,
+    )
+    .await;
+
+    let checkpoint_store = CheckpointStore::new(dir.path().join("checkpoints").as_path());
+    let streamer = Arc::new(CheckpointDataStreamer::new(
+        checkpoint_store.clone(),
+        state.database.clone(),
+    ));
+
+    // GIVEN five synced checkpoints, of which the first three have been executed
+    let (checkpoints, _, _) = committee.make_checkpoints(5, None);
+    checkpoint_store
+        .insert_checkpoint_contents(empty_contents().into_inner().into_checkpoint_contents())
+        .unwrap();
+    for checkpoint in &checkpoints {
+        checkpoint_store
+            .insert_verified_checkpoint(checkpoint.clone())
+            .unwrap();
+    }
+    for checkpoint in &checkpoints[..3] {
+        checkpoint_store
+            .update_highest_executed_checkpoint(checkpoint)
+            .unwrap();
+    }
+
+    // THEN only the executed checkpoints can be read
+    let data = streamer.get_checkpoint_data(2).unwrap().unwrap();
+    assert_eq!(data.sequence_number(), 2);
+    assert_eq!(data.checkpoint_summary.digest(), checkpoints[2].digest());
+    assert!(data.transactions.is_empty());
+    assert!(streamer.get_checkpoint_data(3).unwrap().is_none());
+
+    // WHEN subscribing from the second checkpoint
+    let mut stream = Box::pin(streamer.clone().subscribe(1));
+
+    // THEN the executed checkpoints are streamed from the stores
+    for sequence_number in 1..=2 {
+        let data = stream.next().await.unwrap().unwrap();
+        assert_eq!(data.sequence_number(), sequence_number);
+    }
+
+    // AND nothing more until the next checkpoint is executed
+    assert!(timeout(Duration::from_millis(100), stream.next())
+        .await
+        .is_err());
+
+    // WHEN the remaining checkpoints are executed and published
+    for checkpoint in &checkpoints[3..] {
+        checkpoint_store
+            .update_highest_executed_checkpoint(checkpoint)
+            .unwrap();
+        streamer.publish(checkpoint);
+    }
+
+    // THEN they are streamed in order
+    for sequence_number in 3..=4 {
+        let data = timeout(Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.sequence_number(), sequence_number);
+        assert_eq!(
+            data.checkpoint_summary.digest(),
+            checkpoints[sequence_number as usize].digest()
+        );
+    }
+}
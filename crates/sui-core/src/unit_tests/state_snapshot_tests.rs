@@ -0,0 +1,160 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::checkpoints::CheckpointStore;
+use crate::state_accumulator::accumulate_live_object;
+use fastcrypto::hash::MultisetHash;
+use sui_network::state_sync::test_utils::{empty_contents, CommitteeFixture};
+use sui_protocol_config::ProtocolVersion;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::messages_checkpoint::EndOfEpochData;
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+
+#[tokio::test]
+async fn test_write_and_read_state_snapshot() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let snapshot_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(snapshot_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    // GIVEN a live object set made of normal and wrapped objects
+    let live_objects: Vec<_> = (0..10)
+        .map(|_| {
+            LiveObject::Normal(Object::with_id_owner_for_testing(
+                ObjectID::random(),
+                SuiAddress::random_for_testing_only(),
+            ))
+        })
+        .chain(std::iter::once(LiveObject::Wrapped(ObjectKey(
+            ObjectID::random(),
+            SequenceNumber::from(3),
+        ))))
+        .collect();
+    let perpetual_db = AuthorityPerpetualTables::open(&db_dir.path().join("store"), None);
+    perpetual_db
+        .bulk_insert_live_objects(&live_objects)
+        .unwrap();
+    let mut acc = Accumulator::default();
+    for live_object in &live_objects {
+        accumulate_live_object(&mut acc, live_object);
+    }
+    let digest: ECMHLiveObjectSetDigest = acc.digest().into();
+
+    // AND an executed end of epoch checkpoint committing to its digest
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let next_committee = CommitteeFixture::generate(rand::rngs::OsRng, 1, 4);
+    let (checkpoints, _, _) = committee.make_checkpoints(1, None);
+    let (_, _, end_of_epoch_checkpoint) = committee.make_end_of_epoch_checkpoint(
+        checkpoints[0].clone(),
+        Some(EndOfEpochData {
+            next_epoch_committee: next_committee.committee().voting_rights.clone(),
+            next_epoch_protocol_version: ProtocolVersion::MIN,
+            epoch_commitments: vec![digest.clone().into()],
+        }),
+    );
+    let checkpoint_store = CheckpointStore::new(&db_dir.path().join("checkpoints"));
+    checkpoint_store
+        .insert_checkpoint_contents(empty_contents().into_inner().into_checkpoint_contents())
+        .unwrap();
+    for checkpoint in [&checkpoints[0], &end_of_epoch_checkpoint] {
+        checkpoint_store
+            .insert_verified_checkpoint(checkpoint.clone())
+            .unwrap();
+        checkpoint_store
+            .update_highest_executed_checkpoint(checkpoint)
+            .unwrap();
+    }
+
+    // WHEN writing the snapshot of the epoch, a few objects per file
+    let writer = StateSnapshotWriter::new(&snapshot_config, 1024).unwrap();
+    let manifest = writer
+        .write(0, &perpetual_db, &checkpoint_store)
+        .await
+        .unwrap();
+    assert!(manifest.files.len() > 1);
+    assert_eq!(manifest.num_objects(), live_objects.len() as u64);
+
+    // THEN the snapshot holds the live object set
+    let reader = StateSnapshotReader::new(&snapshot_config).unwrap();
+    let manifest = reader.read_manifest(0).await.unwrap();
+    let mut read_objects = vec![];
+    for file_metadata in &manifest.files {
+        read_objects.extend(reader.read_object_file(0, file_metadata).await.unwrap());
+    }
+    let mut expected: Vec<_> = live_objects
+        .iter()
+        .map(|live_object| (live_object.object_id(), live_object.version()))
+        .collect();
+    expected.sort();
+    let read: Vec<_> = read_objects
+        .iter()
+        .map(|live_object| (live_object.object_id(), live_object.version()))
+        .collect();
+    assert_eq!(read, expected);
+
+    // AND its checkpoints verify from the genesis committee, yielding the next committee
+    let (verified, committees) =
+        reader::verify_end_of_epoch_checkpoints(committee.committee().clone(), &manifest).unwrap();
+    assert_eq!(verified[0].digest(), end_of_epoch_checkpoint.digest());
+    assert_eq!(&committees[0], next_committee.committee());
+    assert_eq!(
+        committed_root_state_digest(&verified[0]),
+        Some(digest.clone())
+    );
+
+    // BUT not from another committee
+    assert!(
+        reader::verify_end_of_epoch_checkpoints(next_committee.committee().clone(), &manifest)
+            .is_err()
+    );
+
+    // AND not when checkpoints are missing
+    let mut truncated = manifest.clone();
+    truncated.end_of_epoch_checkpoints.clear();
+    assert!(
+        reader::verify_end_of_epoch_checkpoints(committee.committee().clone(), &truncated).is_err()
+    );
+
+    // AND a tampered file is rejected
+    let file_path = snapshot_dir.path().join("epoch_0").join("0.obj");
+    let mut bytes = std::fs::read(&file_path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    std::fs::write(&file_path, bytes).unwrap();
+    assert!(reader
+        .read_object_file(0, &manifest.files[0])
+        .await
+        .is_err());
+
+    // AND there is no snapshot of the next epoch
+    assert!(reader.read_manifest(1).await.is_err());
+}
+
+#[test]
+fn test_manifest_checksum() {
+    let manifest = Manifest {
+        version: SNAPSHOT_FORMAT_VERSION,
+        epoch: 0,
+        end_of_epoch_checkpoints: vec![],
+        files: vec![ObjectFileMetadata {
+            index: 0,
+            num_objects: 1,
+            sha3_digest: [0; 32],
+        }],
+    };
+    let bytes = encode_manifest(&manifest).unwrap();
+    assert_eq!(decode_manifest(&bytes).unwrap().files, manifest.files);
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[4] ^= 1;
+    assert!(decode_manifest(&corrupted).is_err());
+}
@@ -595,7 +595,7 @@ impl Faucet for SimpleFaucet {
 
 #[cfg(test)]
 mod tests {
-    use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+    use sui::client_commands::{Opts, SuiClientCommandResult, SuiClientCommands};
     use sui_json_rpc_types::SuiExecutionStatus;
     use test_utils::network::TestClusterBuilder;
 
@@ -728,8 +728,9 @@ mod tests {
         // Now we transfer one gas out
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
-            recipient: SuiAddress::random_for_testing_only(),
+            recipient: SuiAddress::random_for_testing_only().into(),
             gas_budget: 2_000_000,
+            opts: Opts::default(),
         }
         .execute(faucet.wallet_mut())
         .await
@@ -846,6 +847,7 @@ mod tests {
             gas_budget: 50000000,
             gas: None,
             count: None,
+            opts: Opts::default(),
         }
         .execute(&mut context)
         .await;
@@ -924,7 +926,7 @@ mod tests {
     async fn get_current_gases(address: SuiAddress, context: &mut WalletContext) -> Vec<GasCoin> {
         // Get the latest list of gas
         let results = SuiClientCommands::Gas {
-            address: Some(address),
+            address: Some(address.into()),
         }
         .execute(context)
         .await
@@ -13,6 +13,7 @@ use sui_json_rpc_types::{Balance, CoinPage, SuiCoinMetadata};
 use sui_open_rpc::Module;
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::BigInt;
 
 pub(crate) struct CoinReadApi {
     fullnode: HttpClient,
@@ -49,8 +50,13 @@ impl CoinReadApiServer for CoinReadApi {
         self.fullnode.get_all_coins(owner, cursor, limit).await
     }
 
-    fn get_balance(&self, owner: SuiAddress, coin_type: Option<String>) -> RpcResult<Balance> {
-        block_on(self.fullnode.get_balance(owner, coin_type))
+    fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<Balance> {
+        block_on(self.fullnode.get_balance(owner, coin_type, checkpoint))
     }
 
     fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
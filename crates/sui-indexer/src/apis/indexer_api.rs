@@ -9,11 +9,12 @@ use futures::executor::block_on;
 use futures::future::join_all;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 
 use move_core_types::identifier::Identifier;
-use sui_core::event_handler::EventHandler;
+use sui_core::subscription_handler::SubscriptionHandler;
 use sui_json_rpc::api::{
     validate_limit, IndexerApiClient, IndexerApiServer, QUERY_MAX_RESULT_LIMIT,
     QUERY_MAX_RESULT_LIMIT_OBJECTS,
@@ -31,6 +32,7 @@ use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
 use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 use crate::errors::IndexerError;
 use crate::store::IndexerStore;
@@ -38,7 +40,7 @@ use crate::store::IndexerStore;
 pub(crate) struct IndexerApi<S> {
     state: S,
     fullnode: HttpClient,
-    event_handler: Arc<EventHandler>,
+    subscription_handler: Arc<SubscriptionHandler>,
     migrated_methods: Vec<String>,
 }
 
@@ -46,13 +48,13 @@ impl<S: IndexerStore> IndexerApi<S> {
     pub fn new(
         state: S,
         fullnode_client: HttpClient,
-        event_handler: Arc<EventHandler>,
+        subscription_handler: Arc<SubscriptionHandler>,
         migrated_methods: Vec<String>,
     ) -> Self {
         Self {
             state,
             fullnode: fullnode_client,
-            event_handler,
+            subscription_handler,
             migrated_methods,
         }
     }
@@ -227,6 +229,16 @@ impl<S: IndexerStore> IndexerApi<S> {
                     )
                     .await
             }
+            Some(
+                filter @ (TransactionFilter::CheckpointRange { .. }
+                | TransactionFilter::TimeRange { .. }
+                | TransactionFilter::All(_)
+                | TransactionFilter::Any(_)
+                | TransactionFilter::Not(_)),
+            ) => Err(IndexerError::NotSupportedError(format!(
+                "Transaction filter {:?} is not supported by the indexer yet",
+                filter
+            ))),
         }?;
 
         let has_next_page = tx_vec_from_db.len() > limit;
@@ -445,7 +457,36 @@ where
     }
 
     fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.event_handler.subscribe(filter));
+        spawn_subscription(sink, self.subscription_handler.subscribe_events(filter));
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        if !filter.is_streamable() {
+            sink.reject(CallError::InvalidParams(anyhow!(
+                "Checkpoint and time range filters are not supported by transaction subscription"
+            )))?;
+            return Ok(());
+        }
+        spawn_subscription(
+            sink,
+            self.subscription_handler.subscribe_transactions(filter),
+        );
+        Ok(())
+    }
+
+    fn subscribe_checkpoint_data(
+        &self,
+        sink: SubscriptionSink,
+        _start: Option<BigInt<u64>>,
+    ) -> SubscriptionResult {
+        sink.reject(CallError::Failed(anyhow!(
+            "Checkpoint data subscription is not supported by the indexer"
+        )))?;
         Ok(())
     }
 
@@ -18,6 +18,7 @@ use sui_json_rpc_types::{
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::digests::TransactionDigest;
+use sui_types::event::EventID;
 use sui_types::sui_serde::BigInt;
 
 use crate::errors::IndexerError;
@@ -319,6 +320,17 @@ where
         cps_resp
     }
 
+    fn multi_get_checkpoints(&self, ids: Vec<CheckpointId>) -> RpcResult<Vec<Checkpoint>> {
+        let cps_guard = self
+            .state
+            .indexer_metrics()
+            .multi_get_checkpoints_latency
+            .start_timer();
+        let cps_resp = block_on(self.fullnode.multi_get_checkpoints(ids));
+        cps_guard.stop_and_record();
+        cps_resp
+    }
+
     fn get_checkpoints_deprecated_limit(
         &self,
         cursor: Option<BigInt<u64>>,
@@ -338,6 +350,17 @@ where
         events_guard.stop_and_record();
         events_resp
     }
+
+    fn multi_get_events(&self, event_ids: Vec<EventID>) -> RpcResult<Vec<SuiEvent>> {
+        let events_guard = self
+            .state
+            .indexer_metrics()
+            .multi_get_events_latency
+            .start_timer();
+        let events_resp = block_on(self.fullnode.multi_get_events(event_ids));
+        events_guard.stop_and_record();
+        events_resp
+    }
 }
 
 impl<S> SuiRpcModule for ReadApi<S>
@@ -1,13 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use fastcrypto::traits::ToFromBytes;
 use futures::future::join_all;
-use futures::FutureExt;
+use futures::{FutureExt, StreamExt};
+use jsonrpsee::core::client::Subscription;
 use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::ws_client::WsClient;
 use move_core_types::ident_str;
 use tokio::sync::{
     mpsc::{self, Receiver, Sender},
@@ -17,16 +19,21 @@ use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 
 use mysten_metrics::spawn_monitored_task;
-use sui_core::event_handler::EventHandler;
-use sui_json_rpc::api::{GovernanceReadApiClient, ReadApiClient};
+use sui_core::subscription_handler::SubscriptionHandler;
+use sui_json_rpc::api::{GovernanceReadApiClient, IndexerApiClient, ReadApiClient};
 use sui_json_rpc_types::{
-    OwnedObjectRef, SuiGetPastObjectRequest, SuiObjectData, SuiObjectDataOptions, SuiRawData,
+    Checkpoint as RpcCheckpoint, OwnedObjectRef, SuiCheckpointData, SuiGetPastObjectRequest,
+    SuiObjectData, SuiObjectDataOptions, SuiRawData, SuiTransactionBlock,
     SuiTransactionBlockDataAPI, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockEvents,
 };
 use sui_sdk::error::Error;
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::committee::EpochId;
+use sui_types::messages::{SenderSignedData, TransactionEffectsAPI};
 use sui_types::messages_checkpoint::{CheckpointCommitment, CheckpointSequenceNumber};
+use sui_types::object::Data;
+use sui_types::storage::WriteKind;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_types::SUI_SYSTEM_ADDRESS;
 
@@ -38,17 +45,19 @@ use crate::models::objects::{DeletedObject, Object, ObjectStatus};
 use crate::models::packages::Package;
 use crate::models::transactions::Transaction;
 use crate::store::{
-    CheckpointData, IndexerStore, TemporaryCheckpointStore, TemporaryEpochStore,
-    TransactionObjectChanges,
+    CheckpointData, CheckpointModuleResolver, IndexerStore, TemporaryCheckpointStore,
+    TemporaryEpochStore, TransactionObjectChanges,
 };
 use crate::types::{CheckpointTransactionBlockResponse, TemporaryTransactionBlockResponseStore};
 use crate::utils::multi_get_full_transactions;
-use crate::IndexerConfig;
+use crate::{get_ws_client, IndexerConfig};
 
 const MAX_PARALLEL_DOWNLOADS: usize = 24;
+// Stay well below the fullnode's limit of checkpoints streamed behind its latest one.
+const STREAM_CATCH_UP_CHECKPOINTS: u64 = 1_000;
+const MULTI_GET_CHUNK_SIZE: usize = 200;
 const DOWNLOAD_RETRY_INTERVAL_IN_SECS: u64 = 10;
 const DB_COMMIT_RETRY_INTERVAL_IN_MILLIS: u64 = 100;
-const MULTI_GET_CHUNK_SIZE: usize = 200;
 const CHECKPOINT_QUEUE_LIMIT: usize = 24;
 const EPOCH_QUEUE_LIMIT: usize = 2;
 
@@ -56,7 +65,7 @@ const EPOCH_QUEUE_LIMIT: usize = 2;
 pub struct CheckpointHandler<S> {
     state: S,
     http_client: HttpClient,
-    event_handler: Arc<EventHandler>,
+    subscription_handler: Arc<SubscriptionHandler>,
     metrics: IndexerMetrics,
     config: IndexerConfig,
     checkpoint_sender: Arc<Mutex<Sender<TemporaryCheckpointStore>>>,
@@ -75,7 +84,7 @@ where
     pub fn new(
         state: S,
         http_client: HttpClient,
-        event_handler: Arc<EventHandler>,
+        subscription_handler: Arc<SubscriptionHandler>,
         metrics: IndexerMetrics,
         config: &IndexerConfig,
     ) -> Self {
@@ -86,7 +95,7 @@ where
         Self {
             state,
             http_client,
-            event_handler,
+            subscription_handler,
             metrics,
             config: config.clone(),
             checkpoint_sender: Arc::new(Mutex::new(checkpoint_sender)),
@@ -117,26 +126,6 @@ where
             }
         });
 
-        let object_download_handler = self.clone();
-        spawn_monitored_task!(async move {
-            let mut object_download_index_res = object_download_handler
-                .start_download_and_index_object()
-                .await;
-            while let Err(e) = &object_download_index_res {
-                warn!(
-                    "Indexer object download & index failed with error: {:?}, retrying after {:?} secs...",
-                    e, DOWNLOAD_RETRY_INTERVAL_IN_SECS
-                );
-                tokio::time::sleep(std::time::Duration::from_secs(
-                    DOWNLOAD_RETRY_INTERVAL_IN_SECS,
-                ))
-                .await;
-                object_download_index_res = object_download_handler
-                    .start_download_and_index_object()
-                    .await;
-            }
-        });
-
         let checkpoint_commit_handler = self.clone();
         spawn_monitored_task!(async move {
             let mut checkpoint_commit_res =
@@ -190,91 +179,6 @@ where
         })
     }
 
-    // TODO(gegaowp): temp. solution for slow object commits, will be removed after.
-    async fn start_download_and_index_object(&self) -> Result<(), IndexerError> {
-        info!("Indexer object checkpoint download & index task started...");
-        // NOTE: important not to cast i64 to u64 here,
-        // because -1 will be returned when checkpoints table is empty.
-        let last_seq_from_db = self.state.get_latest_checkpoint_sequence_number().await?;
-        if last_seq_from_db > 0 {
-            info!("Resuming from checkpoint {last_seq_from_db}");
-        }
-        let mut next_cursor_sequence_number = last_seq_from_db + 1;
-        // NOTE: we will download checkpoints in parallel, but we will commit them sequentially.
-        // We will start with MAX_PARALLEL_DOWNLOADS, and adjust if no more checkpoints are available.
-        let mut current_parallel_downloads = MAX_PARALLEL_DOWNLOADS;
-        loop {
-            let download_futures = (next_cursor_sequence_number
-                ..next_cursor_sequence_number + current_parallel_downloads as i64)
-                .map(|seq_num| {
-                    self.download_checkpoint_data(seq_num as u64, /* skip objects */ false)
-                });
-            let download_results = join_all(download_futures).await;
-            let mut downloaded_checkpoints = vec![];
-            // NOTE: Push sequentially and if one of the downloads failed,
-            // we will discard all following checkpoints and retry, to avoid messing up the DB commit order.
-            for download_result in download_results {
-                if let Ok(checkpoint) = download_result {
-                    downloaded_checkpoints.push(checkpoint);
-                } else {
-                    if let Err(IndexerError::UnexpectedFullnodeResponseError(fn_e)) =
-                        download_result
-                    {
-                        warn!(
-                            "Unexpected response from fullnode for object checkpoints: {}",
-                            fn_e
-                        );
-                    }
-                    break;
-                }
-            }
-            next_cursor_sequence_number += downloaded_checkpoints.len() as i64;
-            // NOTE: with this line, we can make sure that:
-            // - when indexer is way behind and catching up, we download MAX_PARALLEL_DOWNLOADS checkpoints in parallel;
-            // - when indexer is up to date, we download at least one checkpoint at a time.
-            current_parallel_downloads =
-                std::cmp::min(downloaded_checkpoints.len() + 1, MAX_PARALLEL_DOWNLOADS);
-            if downloaded_checkpoints.is_empty() {
-                warn!("No object checkpoints downloaded, retrying in next iteration ...");
-                continue;
-            }
-
-            // Index checkpoint data
-            let indexed_checkpoint_epoch_vec = join_all(downloaded_checkpoints.iter().map(
-                |downloaded_checkpoint| async {
-                    self.index_checkpoint(downloaded_checkpoint).await
-                },
-            ))
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, IndexerError>>()
-            .map_err(|e| {
-                error!(
-                    "Failed to index checkpoints {:?} with error: {}",
-                    downloaded_checkpoints,
-                    e.to_string()
-                );
-                e
-            })?;
-            let (indexed_checkpoints, _indexed_epochs): (Vec<_>, Vec<_>) =
-                indexed_checkpoint_epoch_vec.into_iter().unzip();
-
-            let object_sender_guard = self.object_checkpoint_sender.lock().await;
-            // NOTE: when the channel is full, checkpoint_sender_guard will wait until the channel has space.
-            // Also add new checkpoint sequentially to stick to checkpoint order.
-            for indexed_checkpoint in indexed_checkpoints {
-                object_sender_guard
-                .send(indexed_checkpoint)
-                .await
-                .map_err(|e| {
-                    error!("Failed to send indexed checkpoint to checkpoint commit handler with error: {}", e.to_string());
-                    IndexerError::MpscChannelError(e.to_string())
-                })?;
-            }
-            drop(object_sender_guard);
-        }
-    }
-
     async fn start_download_and_index(&self) -> Result<(), IndexerError> {
         info!("Indexer checkpoint download & index task started...");
         // NOTE: important not to cast i64 to u64 here,
@@ -283,122 +187,164 @@ where
         if last_seq_from_db > 0 {
             info!("Resuming from checkpoint {last_seq_from_db}");
         }
-        let mut next_cursor_sequence_number = last_seq_from_db + 1;
-        // NOTE: we will download checkpoints in parallel, but we will commit them sequentially.
-        // We will start with MAX_PARALLEL_DOWNLOADS, and adjust if no more checkpoints are available.
-        let mut current_parallel_downloads = MAX_PARALLEL_DOWNLOADS;
+        let mut next_cursor_sequence_number = (last_seq_from_db + 1) as u64;
+        // NOTE: the fullnode only streams checkpoints from a bounded distance behind its latest
+        // one, so we download the checkpoints in parallel until we are close enough to subscribe.
+        // Checkpoints are downloaded or streamed in parallel, but we will commit them sequentially.
         loop {
+            let latest_fn_checkpoint_seq = self
+                .get_latest_fullnode_checkpoint_sequence_number()
+                .await?;
+            if latest_fn_checkpoint_seq < next_cursor_sequence_number + STREAM_CATCH_UP_CHECKPOINTS
+            {
+                break;
+            }
             let download_futures = (next_cursor_sequence_number
-                ..next_cursor_sequence_number + current_parallel_downloads as i64)
-                .map(|seq_num| {
-                    self.download_checkpoint_data(seq_num as u64, /* skip objects */ true)
-                });
+                ..next_cursor_sequence_number + MAX_PARALLEL_DOWNLOADS as u64)
+                .map(|seq_num| self.download_checkpoint_data(seq_num));
             let download_results = join_all(download_futures).await;
             let mut downloaded_checkpoints = vec![];
             // NOTE: Push sequentially and if one of the downloads failed,
             // we will discard all following checkpoints and retry, to avoid messing up the DB commit order.
             for download_result in download_results {
-                if let Ok(checkpoint) = download_result {
-                    downloaded_checkpoints.push(checkpoint);
-                } else {
-                    if let Err(IndexerError::UnexpectedFullnodeResponseError(fn_e)) =
-                        download_result
-                    {
-                        warn!(
-                            "Unexpected response from fullnode for checkpoints: {}",
-                            fn_e
-                        );
+                match download_result {
+                    Ok(checkpoint) => downloaded_checkpoints.push(checkpoint),
+                    Err(e) => {
+                        warn!("Failed to download checkpoint with error: {}", e);
+                        break;
                     }
-                    break;
                 }
             }
-
-            next_cursor_sequence_number += downloaded_checkpoints.len() as i64;
-            // NOTE: with this line, we can make sure that:
-            // - when indexer is way behind and catching up, we download MAX_PARALLEL_DOWNLOADS checkpoints in parallel;
-            // - when indexer is up to date, we download at least one checkpoint at a time.
-            current_parallel_downloads =
-                std::cmp::min(downloaded_checkpoints.len() + 1, MAX_PARALLEL_DOWNLOADS);
             if downloaded_checkpoints.is_empty() {
                 warn!("No checkpoints downloaded, retrying in next iteration ...");
                 continue;
             }
+            next_cursor_sequence_number += downloaded_checkpoints.len() as u64;
+            self.index_and_send_checkpoints(downloaded_checkpoints)
+                .await?;
+        }
 
-            // Index checkpoint data
-            let index_guard = self.metrics.checkpoint_index_latency.start_timer();
-            let indexed_checkpoint_epoch_vec = join_all(downloaded_checkpoints.iter().map(
-                |downloaded_checkpoint| async {
-                    self.index_checkpoint(downloaded_checkpoint).await
-                },
-            ))
+        info!("Streaming checkpoints from checkpoint {next_cursor_sequence_number}");
+        let (_ws_client, mut subscription) = self
+            .subscribe_checkpoint_data(next_cursor_sequence_number)
+            .await?;
+        // NOTE: checkpoints are streamed in order, we index the ones already received in
+        // parallel.
+        loop {
+            let downloaded_checkpoints = self.next_checkpoints(&mut subscription).await?;
+            self.index_and_send_checkpoints(downloaded_checkpoints)
+                .await?;
+        }
+    }
+
+    /// Index the downloaded checkpoints and send them to the commit tasks, in checkpoint order.
+    async fn index_and_send_checkpoints(
+        &self,
+        downloaded_checkpoints: Vec<CheckpointData>,
+    ) -> Result<(), IndexerError> {
+        // Index checkpoint data
+        let index_guard = self.metrics.checkpoint_index_latency.start_timer();
+        let indexed_checkpoint_epoch_vec = join_all(downloaded_checkpoints.iter().map(
+            |downloaded_checkpoint| async { self.index_checkpoint(downloaded_checkpoint).await },
+        ))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, IndexerError>>()
+        .map_err(|e| {
+            error!(
+                "Failed to index checkpoints {:?} with error: {}",
+                downloaded_checkpoints,
+                e.to_string()
+            );
+            e
+        })?;
+        let (indexed_checkpoints, indexed_epochs): (Vec<_>, Vec<_>) =
+            indexed_checkpoint_epoch_vec.into_iter().unzip();
+        index_guard.stop_and_record();
+
+        let checkpoint_sender_guard = self.checkpoint_sender.lock().await;
+        let object_sender_guard = self.object_checkpoint_sender.lock().await;
+        // NOTE: when the channel is full, checkpoint_sender_guard will wait until the channel has space.
+        // Also add new checkpoint sequentially to stick to checkpoint order.
+        for mut indexed_checkpoint in indexed_checkpoints {
+            // TODO(gegaowp): temp. solution for slow object commits, object changes are
+            // committed by their own task, will be removed after.
+            let object_checkpoint = TemporaryCheckpointStore {
+                checkpoint: indexed_checkpoint.checkpoint.clone(),
+                transactions: vec![],
+                events: vec![],
+                object_changes: std::mem::take(&mut indexed_checkpoint.object_changes),
+                addresses: vec![],
+                packages: vec![],
+                input_objects: vec![],
+                move_calls: vec![],
+                recipients: vec![],
+            };
+            checkpoint_sender_guard
+            .send(indexed_checkpoint)
             .await
-            .into_iter()
-            .collect::<Result<Vec<_>, IndexerError>>()
             .map_err(|e| {
-                error!(
-                    "Failed to index checkpoints {:?} with error: {}",
-                    downloaded_checkpoints,
-                    e.to_string()
-                );
-                e
+                error!("Failed to send indexed checkpoint to checkpoint commit handler with error: {}", e.to_string());
+                IndexerError::MpscChannelError(e.to_string())
             })?;
-            let (indexed_checkpoints, indexed_epochs): (Vec<_>, Vec<_>) =
-                indexed_checkpoint_epoch_vec.into_iter().unzip();
-            index_guard.stop_and_record();
-
-            let checkpoint_sender_guard = self.checkpoint_sender.lock().await;
-            // NOTE: when the channel is full, checkpoint_sender_guard will wait until the channel has space.
-            // Also add new checkpoint sequentially to stick to checkpoint order.
-            for indexed_checkpoint in indexed_checkpoints {
-                checkpoint_sender_guard
-                .send(indexed_checkpoint)
+            object_sender_guard
+                .send(object_checkpoint)
                 .await
                 .map_err(|e| {
-                    error!("Failed to send indexed checkpoint to checkpoint commit handler with error: {}", e.to_string());
+                    error!(
+                        "Failed to send indexed checkpoint to object commit handler with error: {}",
+                        e.to_string()
+                    );
                     IndexerError::MpscChannelError(e.to_string())
                 })?;
-            }
-            drop(checkpoint_sender_guard);
-
-            for epoch in indexed_epochs.into_iter().flatten() {
-                // for the first epoch, we need to store the epoch data first,
-                // otherwise send it to channel to be committed later.
-                if epoch.last_epoch.is_none() {
-                    let epoch_db_guard = self.metrics.epoch_db_commit_latency.start_timer();
-                    let mut persist_first_epoch_res = self.state.persist_epoch(&epoch).await;
-                    while persist_first_epoch_res.is_err() {
-                        warn!("Failed to persist first epoch, retrying...");
-                        persist_first_epoch_res = self.state.persist_epoch(&epoch).await;
-                    }
-                    self.state.persist_epoch(&epoch).await?;
-                    epoch_db_guard.stop_and_record();
-                    self.metrics.total_epoch_committed.inc();
-                } else {
-                    let epoch_sender_guard = self.epoch_sender.lock().await;
-                    // NOTE: when the channel is full, epoch_sender_guard will wait until the channel has space.
-                    epoch_sender_guard.send(epoch).await.map_err(|e| {
-                        error!(
-                            "Failed to send indexed epoch to epoch commit handler with error {}",
-                            e.to_string()
-                        );
-                        IndexerError::MpscChannelError(e.to_string())
-                    })?;
-                    drop(epoch_sender_guard);
+        }
+        drop(object_sender_guard);
+        drop(checkpoint_sender_guard);
+
+        for epoch in indexed_epochs.into_iter().flatten() {
+            // for the first epoch, we need to store the epoch data first,
+            // otherwise send it to channel to be committed later.
+            if epoch.last_epoch.is_none() {
+                let epoch_db_guard = self.metrics.epoch_db_commit_latency.start_timer();
+                let mut persist_first_epoch_res = self.state.persist_epoch(&epoch).await;
+                while persist_first_epoch_res.is_err() {
+                    warn!("Failed to persist first epoch, retrying...");
+                    persist_first_epoch_res = self.state.persist_epoch(&epoch).await;
                 }
+                self.state.persist_epoch(&epoch).await?;
+                epoch_db_guard.stop_and_record();
+                self.metrics.total_epoch_committed.inc();
+            } else {
+                let epoch_sender_guard = self.epoch_sender.lock().await;
+                // NOTE: when the channel is full, epoch_sender_guard will wait until the channel has space.
+                epoch_sender_guard.send(epoch).await.map_err(|e| {
+                    error!(
+                        "Failed to send indexed epoch to epoch commit handler with error {}",
+                        e.to_string()
+                    );
+                    IndexerError::MpscChannelError(e.to_string())
+                })?;
+                drop(epoch_sender_guard);
             }
+        }
 
-            // NOTE(gegaowp): today ws processing actually will block next checkpoint download,
-            // we can pipeline this as well in the future if needed
-            for checkpoint in &downloaded_checkpoints {
-                let ws_guard = self.metrics.subscription_process_latency.start_timer();
-                for tx in &checkpoint.transactions {
-                    self.event_handler
-                        .process_events(&tx.effects, &tx.events)
-                        .await?;
-                }
-                ws_guard.stop_and_record();
+        // NOTE(gegaowp): today ws processing actually will block next checkpoint download,
+        // we can pipeline this as well in the future if needed
+        for checkpoint in &downloaded_checkpoints {
+            let ws_guard = self.metrics.subscription_process_latency.start_timer();
+            for tx in &checkpoint.transactions {
+                let sender_signed_data: SenderSignedData = bcs::from_bytes(&tx.raw_transaction)?;
+                self.subscription_handler
+                    .process_tx(
+                        sender_signed_data.transaction_data(),
+                        &tx.effects,
+                        &tx.events,
+                    )
+                    .await?;
             }
+            ws_guard.stop_and_record();
         }
+        Ok(())
     }
 
     async fn start_checkpoint_commit(&self) -> Result<(), IndexerError> {
@@ -653,12 +599,9 @@ where
         }
     }
 
-    /// Download all the data we need for one checkpoint.
-    async fn download_checkpoint_data(
+    async fn get_latest_fullnode_checkpoint_sequence_number(
         &self,
-        seq: CheckpointSequenceNumber,
-        skip_object: bool,
-    ) -> Result<CheckpointData, IndexerError> {
+    ) -> Result<CheckpointSequenceNumber, IndexerError> {
         let latest_fn_checkpoint_seq = self
             .http_client
             .get_latest_checkpoint_sequence_number()
@@ -672,8 +615,19 @@ where
         self.metrics
             .latest_fullnode_checkpoint_sequence_number
             .set((*latest_fn_checkpoint_seq) as i64);
+        Ok(*latest_fn_checkpoint_seq)
+    }
 
-        let mut checkpoint = self
+    /// Download all the data we need for one checkpoint already executed by the fullnode.
+    async fn download_checkpoint_data(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData, IndexerError> {
+        let fn_checkpoint_guard = self
+            .metrics
+            .fullnode_checkpoint_wait_and_download_latency
+            .start_timer();
+        let checkpoint = self
             .http_client
             .get_checkpoint(seq.into())
             .await
@@ -682,29 +636,8 @@ where
                     "Failed to get checkpoint with sequence number {} and error {:?}",
                     seq, e
                 ))
-            });
-        let fn_checkpoint_guard = self
-            .metrics
-            .fullnode_checkpoint_wait_and_download_latency
-            .start_timer();
-        while checkpoint.is_err() {
-            // sleep for 0.1 second and retry if latest checkpoint is not available yet
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            // TODO(gegaowp): figure how to only measure successful checkpoint download time
-            checkpoint = self
-                .http_client
-                .get_checkpoint(seq.into())
-                .await
-                .map_err(|e| {
-                    IndexerError::FullNodeReadingError(format!(
-                        "Failed to get checkpoint with sequence number {} and error {:?}",
-                        seq, e
-                    ))
-                })
-        }
+            })?;
         fn_checkpoint_guard.stop_and_record();
-        // unwrap here is safe because we checked for error above
-        let checkpoint = checkpoint.unwrap();
 
         let fn_transaction_guard = self
             .metrics
@@ -721,14 +654,6 @@ where
         })?;
         fn_transaction_guard.stop_and_record();
 
-        if skip_object {
-            return Ok(CheckpointData {
-                checkpoint,
-                transactions,
-                changed_objects: vec![],
-            });
-        }
-
         let fn_object_guard = self.metrics.fullnode_object_download_latency.start_timer();
         let object_changes = transactions
             .iter()
@@ -745,6 +670,144 @@ where
         })
     }
 
+    /// Subscribe to the data of the checkpoints executed by the fullnode, starting from `start`.
+    /// The websocket client must be kept alive for as long as the subscription is used.
+    async fn subscribe_checkpoint_data(
+        &self,
+        start: CheckpointSequenceNumber,
+    ) -> Result<(WsClient, Subscription<SuiCheckpointData>), IndexerError> {
+        let ws_client = get_ws_client(&self.config.rpc_client_ws_url()).await?;
+        let subscription = ws_client
+            .subscribe_checkpoint_data(Some(start.into()))
+            .await
+            .map_err(|e| {
+                IndexerError::FullNodeReadingError(format!(
+                    "Failed to subscribe to checkpoint data from checkpoint {} with error {:?}",
+                    start, e
+                ))
+            })?;
+        Ok((ws_client, subscription))
+    }
+
+    /// Wait for the next checkpoint streamed by the fullnode, and take along the checkpoints
+    /// already received, up to MAX_PARALLEL_DOWNLOADS.
+    async fn next_checkpoints(
+        &self,
+        subscription: &mut Subscription<SuiCheckpointData>,
+    ) -> Result<Vec<CheckpointData>, IndexerError> {
+        let fn_checkpoint_guard = self
+            .metrics
+            .fullnode_checkpoint_wait_and_download_latency
+            .start_timer();
+        let mut streamed_checkpoints = vec![subscription.next().await];
+        fn_checkpoint_guard.stop_and_record();
+        while streamed_checkpoints.len() < MAX_PARALLEL_DOWNLOADS {
+            match subscription.next().now_or_never() {
+                Some(streamed_checkpoint) => streamed_checkpoints.push(streamed_checkpoint),
+                None => break,
+            }
+        }
+
+        let mut checkpoints = vec![];
+        for streamed_checkpoint in streamed_checkpoints {
+            let checkpoint = streamed_checkpoint
+                .ok_or_else(|| {
+                    IndexerError::FullNodeReadingError(
+                        "Checkpoint data subscription closed by the fullnode".to_string(),
+                    )
+                })?
+                .map_err(|e| {
+                    IndexerError::FullNodeReadingError(format!(
+                        "Failed to read checkpoint data from subscription with error {:?}",
+                        e
+                    ))
+                })?;
+            checkpoints.push(self.to_checkpoint_data(&checkpoint)?);
+        }
+
+        if let Some(checkpoint) = checkpoints.last() {
+            self.metrics
+                .latest_fullnode_checkpoint_sequence_number
+                .set(checkpoint.checkpoint.sequence_number as i64);
+        }
+        Ok(checkpoints)
+    }
+
+    /// Convert the data of a checkpoint streamed by the fullnode to the data we index.
+    fn to_checkpoint_data(
+        &self,
+        checkpoint_data: &SuiCheckpointData,
+    ) -> Result<CheckpointData, IndexerError> {
+        let checkpoint_data = checkpoint_data.checkpoint_data()?;
+        // Packages published in this checkpoint are not in the DB yet.
+        let module_cache =
+            CheckpointModuleResolver::new(&checkpoint_data, self.state.module_cache());
+        let summary = &checkpoint_data.checkpoint_summary;
+        let timestamp_ms = summary.timestamp_ms;
+
+        let transactions = checkpoint_data
+            .transactions
+            .iter()
+            .map(|tx| {
+                let digest = *tx.transaction.digest();
+                let sender_signed_data = tx.transaction.data();
+                Ok(CheckpointTransactionBlockResponse {
+                    digest,
+                    transaction: SuiTransactionBlock::try_from(
+                        sender_signed_data.clone(),
+                        &module_cache,
+                    )?,
+                    raw_transaction: bcs::to_bytes(sender_signed_data)?,
+                    effects: SuiTransactionBlockEffects::try_from(tx.effects.clone())?,
+                    events: SuiTransactionBlockEvents::try_from(
+                        tx.events.clone().unwrap_or_default(),
+                        digest,
+                        Some(timestamp_ms),
+                        &module_cache,
+                    )?,
+                    timestamp_ms,
+                    confirmed_local_execution: None,
+                    checkpoint: summary.sequence_number,
+                })
+            })
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+
+        let mut changed_objects = vec![];
+        for tx in &checkpoint_data.transactions {
+            let output_objects: HashMap<_, _> = tx
+                .output_objects
+                .iter()
+                .map(|o| ((o.id(), o.version()), o))
+                .collect();
+            for ((id, version, _), _, kind) in tx.effects.all_changed_objects() {
+                let object = output_objects.get(&(*id, *version)).ok_or_else(|| {
+                    IndexerError::UnexpectedFullnodeResponseError(format!(
+                        "Object {} of version {} is missing from the output of transaction {}",
+                        id,
+                        version,
+                        tx.effects.transaction_digest()
+                    ))
+                })?;
+                let status = match kind {
+                    WriteKind::Create => ObjectStatus::Created,
+                    WriteKind::Mutate => ObjectStatus::Mutated,
+                    WriteKind::Unwrap => ObjectStatus::Unwrapped,
+                };
+                changed_objects.push((status, to_sui_object_data(object)));
+            }
+        }
+
+        Ok(CheckpointData {
+            checkpoint: RpcCheckpoint::from((
+                summary.data().clone(),
+                checkpoint_data.checkpoint_contents.clone(),
+                summary.auth_sig().signature.clone(),
+            )),
+            transactions,
+            changed_objects,
+        })
+    }
+
     async fn index_checkpoint(
         &self,
         data: &CheckpointData,
@@ -1011,7 +1074,6 @@ where
     }
 }
 
-// TODO(gegaowp): re-orgnize object util functions below
 pub fn get_object_changes(
     effects: &SuiTransactionBlockEffects,
 ) -> Vec<(ObjectID, SequenceNumber, ObjectStatus)> {
@@ -1081,6 +1143,27 @@ pub async fn fetch_changed_objects(
     })
 }
 
+// TODO(gegaowp): re-orgnize object util functions below
+/// The object data with the content of `SuiObjectDataOptions::bcs_lossless`.
+fn to_sui_object_data(object: &sui_types::object::Object) -> SuiObjectData {
+    let bcs = match &object.data {
+        Data::Move(o) => SuiRawData::MoveObject(o.clone().into()),
+        Data::Package(p) => SuiRawData::Package(p.clone().into()),
+    };
+    SuiObjectData {
+        object_id: object.id(),
+        version: object.version(),
+        digest: object.digest(),
+        type_: Some(object.into()),
+        owner: Some(object.owner),
+        previous_transaction: Some(object.previous_transaction),
+        storage_rebate: Some(object.storage_rebate),
+        display: None,
+        content: None,
+        bcs: Some(bcs),
+    }
+}
+
 // TODO(gegaowp): temp. disable fast-path
 // pub fn to_changed_db_objects(
 //     changed_objects: Vec<(ObjectStatus, SuiObjectData)>,
@@ -49,7 +49,9 @@ pub struct IndexerMetrics {
     pub try_multi_get_past_objects_latency: Histogram,
     pub get_checkpoint_latency: Histogram,
     pub get_checkpoints_latency: Histogram,
+    pub multi_get_checkpoints_latency: Histogram,
     pub get_events_latency: Histogram,
+    pub multi_get_events_latency: Histogram,
     pub get_total_transaction_blocks_latency: Histogram,
     pub get_latest_checkpoint_sequence_number_latency: Histogram,
     // indexer.rs
@@ -257,6 +259,13 @@ impl IndexerMetrics {
                 registry
             )
             .unwrap(),
+            multi_get_checkpoints_latency: register_histogram_with_registry!(
+                "multi_get_checkpoints_latency",
+                "Time spent in multi_get_checkpoints on the fullnode behind.",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
             get_events_latency: register_histogram_with_registry!(
                 "get_events_latency",
                 "Time spent in get_events on the fullnode behind.",
@@ -264,6 +273,13 @@ impl IndexerMetrics {
                 registry
             )
             .unwrap(),
+            multi_get_events_latency: register_histogram_with_registry!(
+                "multi_get_events_latency",
+                "Time spent in multi_get_events on the fullnode behind.",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
             get_total_transaction_blocks_latency: register_histogram_with_registry!(
                 "get_total_transaction_blocks_latency",
                 "Time spent in get_total_transaction_blocks on the fullnode behind.",
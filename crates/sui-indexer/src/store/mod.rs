@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub use indexer_store::*;
+pub use module_resolver::CheckpointModuleResolver;
 pub use pg_indexer_store::PgIndexerStore;
 
 mod indexer_store;
@@ -1,14 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
 use diesel::pg::sql_types::Bytea;
 use diesel::sql_types::Text;
 use diesel::QueryableByName;
 use diesel::RunQueryDsl;
+use move_binary_format::file_format_common::VERSION_MAX;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::language_storage::ModuleId;
 use move_core_types::resolver::ModuleResolver;
 
 use sui_types::base_types::ObjectID;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::move_package::MovePackage;
 
 use crate::errors::{Context, IndexerError};
 use crate::store::diesel_marco::read_only_blocking;
@@ -54,3 +63,48 @@ impl ModuleResolver for IndexerModuleResolver {
         Ok(Some(module_bytes.data))
     }
 }
+
+/// Resolves the modules of the packages published by a checkpoint, which might not have been
+/// committed to the DB yet, before falling back to the module cache of the DB.
+pub struct CheckpointModuleResolver<'a, M> {
+    packages: BTreeMap<ObjectID, &'a MovePackage>,
+    module_cache: &'a M,
+}
+
+impl<'a, M> CheckpointModuleResolver<'a, M> {
+    pub fn new(checkpoint_data: &'a CheckpointData, module_cache: &'a M) -> Self {
+        let packages = checkpoint_data
+            .transactions
+            .iter()
+            .flat_map(|tx| &tx.output_objects)
+            .filter_map(|object| object.data.try_as_package())
+            .map(|package| (package.id(), package))
+            .collect();
+        Self {
+            packages,
+            module_cache,
+        }
+    }
+}
+
+impl<M> GetModule for CheckpointModuleResolver<'_, M>
+where
+    M: GetModule<Item = Arc<CompiledModule>>,
+{
+    type Error = IndexerError;
+    type Item = Arc<CompiledModule>;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        match self.packages.get(&ObjectID::from(*id.address())) {
+            // we are on the read path - it's OK to use VERSION_MAX of the supported Move
+            // binary format
+            Some(package) => Ok(Some(Arc::new(
+                package.deserialize_module(&id.name().to_owned(), VERSION_MAX)?,
+            ))),
+            None => self
+                .module_cache
+                .get_module_by_id(id)
+                .map_err(|e| anyhow!("Failed to resolve module {id}: {e:?}").into()),
+        }
+    }
+}
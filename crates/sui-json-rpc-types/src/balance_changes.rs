@@ -1,5 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::collections::BTreeMap;
+
 use move_core_types::language_storage::TypeTag;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -23,3 +25,28 @@ pub struct BalanceChange {
     #[serde_as(as = "DisplayFromStr")]
     pub amount: i128,
 }
+
+/// Folds the coins of a transaction into the net change of its balances, keyed by owner and coin
+/// type. `input_coins` are the coins before the transaction and are debited from their key,
+/// `output_coins` are the coins after it and are credited to theirs. Each entry holds the change
+/// of the balance and of the number of coins.
+pub fn coin_balance_deltas<K: Ord>(
+    input_coins: impl IntoIterator<Item = (K, u64)>,
+    output_coins: impl IntoIterator<Item = (K, u64)>,
+) -> BTreeMap<K, (i128, i64)> {
+    let mut deltas = BTreeMap::<K, (i128, i64)>::new();
+    for (key, amount, sign) in input_coins
+        .into_iter()
+        .map(|(key, amount)| (key, amount, -1))
+        .chain(
+            output_coins
+                .into_iter()
+                .map(|(key, amount)| (key, amount, 1)),
+        )
+    {
+        let (balance, count) = deltas.entry(key).or_default();
+        *balance += sign as i128 * amount as i128;
+        *count += sign;
+    }
+    deltas
+}
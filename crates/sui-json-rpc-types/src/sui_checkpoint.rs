@@ -10,6 +10,7 @@ use sui_types::base_types::TransactionDigest;
 use sui_types::committee::EpochId;
 use sui_types::crypto::AggregateAuthoritySignature;
 use sui_types::digests::CheckpointDigest;
+use sui_types::full_checkpoint_content::CheckpointData;
 use sui_types::gas::GasCostSummary;
 use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::{
@@ -132,3 +133,36 @@ impl From<CheckpointDigest> for CheckpointId {
         Self::Digest(digest)
     }
 }
+
+/// The full content of an executed checkpoint, as streamed to the subscribers of checkpoint data.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", rename = "CheckpointData")]
+pub struct SuiCheckpointData {
+    /// Checkpoint sequence number
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub sequence_number: CheckpointSequenceNumber,
+    /// BCS encoded `CheckpointData`, with the summary, contents, transactions, effects, events
+    /// and input and output objects of the checkpoint
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Base64")]
+    pub bcs: Vec<u8>,
+}
+
+impl SuiCheckpointData {
+    pub fn checkpoint_data(&self) -> Result<CheckpointData, bcs::Error> {
+        bcs::from_bytes(&self.bcs)
+    }
+}
+
+impl TryFrom<&CheckpointData> for SuiCheckpointData {
+    type Error = bcs::Error;
+
+    fn try_from(data: &CheckpointData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence_number: data.sequence_number(),
+            bcs: bcs::to_bytes(data)?,
+        })
+    }
+}
@@ -140,7 +140,7 @@ pub enum EventFilter {
         path: String,
         value: Value,
     },
-    /// Return events emitted in [start_time, end_time] interval
+    /// Return events emitted in [start_time, end_time) interval
     #[serde(rename_all = "camelCase")]
     TimeRange {
         /// left endpoint of time interval, milliseconds since epoch, inclusive
@@ -41,7 +41,7 @@ use sui_types::sui_serde::{
 
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
-use crate::{Page, SuiEvent, SuiMovePackage, SuiObjectRef};
+use crate::{Filter, Page, SuiEvent, SuiMovePackage, SuiObjectRef};
 
 // similar to EpochId of sui-types but BigInt
 pub type SuiEpochId = BigInt<u64>;
@@ -686,6 +686,65 @@ impl SuiTransactionBlockEvents {
     }
 }
 
+/// Effects of an executed transaction paired with the transaction data that produced them,
+/// used to evaluate a [TransactionFilter] against transactions as they are processed.
+#[derive(Debug, Clone)]
+pub struct EffectsWithInput {
+    pub effects: SuiTransactionBlockEffects,
+    pub input: TransactionData,
+}
+
+impl From<EffectsWithInput> for SuiTransactionBlockEffects {
+    fn from(e: EffectsWithInput) -> Self {
+        e.effects
+    }
+}
+
+impl Filter<EffectsWithInput> for TransactionFilter {
+    fn matches(&self, item: &EffectsWithInput) -> bool {
+        match self {
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => item.input.move_calls().into_iter().any(|(p, m, f)| {
+                p == package
+                    && module.as_ref().map(|x| m.as_str() == x).unwrap_or(true)
+                    && function.as_ref().map(|x| f.as_str() == x).unwrap_or(true)
+            }),
+            TransactionFilter::InputObject(o) => item
+                .input
+                .input_objects()
+                .map(|inputs| inputs.iter().any(|i| &i.object_id() == o))
+                .unwrap_or_default(),
+            TransactionFilter::ChangedObject(o) => item
+                .effects
+                .all_changed_objects()
+                .iter()
+                .any(|(oref, _)| &oref.object_id() == o),
+            TransactionFilter::FromAddress(a) => &item.input.sender() == a,
+            TransactionFilter::ToAddress(a) => item
+                .effects
+                .all_changed_objects()
+                .iter()
+                .any(|(oref, _)| oref.owner.get_owner_address().ok().as_ref() == Some(a)),
+            TransactionFilter::FromAndToAddress { from, to } => {
+                TransactionFilter::FromAddress(*from).matches(item)
+                    && TransactionFilter::ToAddress(*to).matches(item)
+            }
+            TransactionFilter::TransactionKind(kind) => item.input.kind().name() == kind,
+            TransactionFilter::All(filters) => filters.iter().all(|f| f.matches(item)),
+            TransactionFilter::Any(filters) => filters.iter().any(|f| f.matches(item)),
+            TransactionFilter::Not(filter) => !filter.matches(item),
+            // The checkpoint and index time of a transaction are not known at the time it is
+            // processed, see `TransactionFilter::is_streamable`.
+            TransactionFilter::Checkpoint(_)
+            | TransactionFilter::CheckpointRange { .. }
+            | TransactionFilter::TimeRange { .. } => false,
+        }
+    }
+}
+
 /// The response from processing a dev inspect transaction
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "DevInspectResults", rename_all = "camelCase")]
@@ -5,14 +5,17 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiCheckpointData, SuiEvent,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffects,
+    SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -71,6 +74,22 @@ pub trait IndexerApi {
         filter: EventFilter,
     );
 
+    /// Subscribe to a stream of Sui transaction effects
+    #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockEffects)]
+    fn subscribe_transaction(
+        &self,
+        /// the filter criteria of the transaction stream, checkpoint and time range filters are not supported.
+        filter: TransactionFilter,
+    );
+
+    /// Subscribe to a stream of the full content of executed checkpoints, in checkpoint order, served by the nodes enabling checkpoint data streaming
+    #[subscription(name = "subscribeCheckpointData", item = SuiCheckpointData)]
+    fn subscribe_checkpoint_data(
+        &self,
+        /// the sequence number of the first checkpoint to stream, to resume a previous stream. Default to the next checkpoint to be executed if not specified.
+        start: Option<BigInt<u64>>,
+    );
+
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields", blocking)]
     fn get_dynamic_fields(
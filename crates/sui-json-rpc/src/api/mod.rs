@@ -19,7 +19,10 @@ pub use indexer::IndexerApiServer;
 pub use move_utils::MoveUtilsClient;
 pub use move_utils::MoveUtilsOpenRpc;
 pub use move_utils::MoveUtilsServer;
-use prometheus::{register_int_counter_with_registry, IntCounter};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
+    IntCounterVec,
+};
 pub use read::ReadApiClient;
 pub use read::ReadApiOpenRpc;
 pub use read::ReadApiServer;
@@ -96,6 +99,9 @@ pub struct JsonRpcMetrics {
 
     pub get_stake_sui_result_size: Histogram,
     pub get_stake_sui_result_size_total: IntCounter,
+
+    pub response_cache_hits: IntCounterVec,
+    pub response_cache_misses: IntCounterVec,
 }
 
 impl JsonRpcMetrics {
@@ -240,6 +246,20 @@ impl JsonRpcMetrics {
                 registry
             )
             .unwrap(),
+            response_cache_hits: register_int_counter_vec_with_registry!(
+                "json_rpc_response_cache_hits",
+                "Number of responses served from the response cache, by method",
+                &["method"],
+                registry
+            )
+            .unwrap(),
+            response_cache_misses: register_int_counter_vec_with_registry!(
+                "json_rpc_response_cache_misses",
+                "Number of cacheable requests not found in the response cache, by method",
+                &["method"],
+                registry
+            )
+            .unwrap(),
         }
     }
 
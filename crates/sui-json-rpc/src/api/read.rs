@@ -11,6 +11,7 @@ use sui_json_rpc_types::{
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::event::EventID;
 use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "sui", tag = "Read API")]
@@ -106,6 +107,16 @@ pub trait ReadApi {
         descending_order: bool,
     ) -> RpcResult<CheckpointPage>;
 
+    /// Returns an ordered list of checkpoints
+    /// The method will throw an error if any of the checkpoints cannot be found or
+    /// the input size exceeds QUERY_MAX_RESULT_LIMIT_CHECKPOINTS
+    #[method(name = "multiGetCheckpoints", blocking)]
+    fn multi_get_checkpoints(
+        &self,
+        /// A list of checkpoint identifiers, each can be either a checkpoint digest or a checkpoint sequence number.
+        ids: Vec<CheckpointId>,
+    ) -> RpcResult<Vec<Checkpoint>>;
+
     #[method(name = "getCheckpoints", version <= "0.31", blocking)]
     fn get_checkpoints_deprecated_limit(
         &self,
@@ -125,6 +136,16 @@ pub trait ReadApi {
         transaction_digest: TransactionDigest,
     ) -> RpcResult<Vec<SuiEvent>>;
 
+    /// Returns an ordered list of events
+    /// The method will throw an error if any of the events cannot be found or
+    /// the input size exceeds QUERY_MAX_RESULT_LIMIT
+    #[method(name = "multiGetEvents", blocking)]
+    fn multi_get_events(
+        &self,
+        /// A list of event IDs, i.e. transaction digests and event sequence numbers.
+        event_ids: Vec<EventID>,
+    ) -> RpcResult<Vec<SuiEvent>>;
+
     /// Return the total number of transactions known to the server.
     #[method(name = "getTotalTransactionBlocks")]
     async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>>;
@@ -10,7 +10,7 @@ use move_core_types::language_storage::TypeTag;
 use tokio::sync::RwLock;
 
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_types::BalanceChange;
+use sui_json_rpc_types::{coin_balance_deltas, BalanceChange};
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
 use sui_types::coin::Coin;
 use sui_types::digests::ObjectDigest;
@@ -87,39 +87,25 @@ pub async fn get_balance_changes<P: ObjectProvider<Error = E>, E>(
     modified_at_version: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
     all_mutated: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
 ) -> Result<Vec<BalanceChange>, E> {
-    // 1. subtract all input coins
-    let balances = fetch_coins(object_provider, modified_at_version)
-        .await?
-        .into_iter()
-        .fold(
-            BTreeMap::<_, i128>::new(),
-            |mut acc, (owner, type_, amount)| {
-                *acc.entry((owner, type_)).or_default() -= amount as i128;
-                acc
-            },
-        );
-    // 2. add all mutated coins
-    let balances = fetch_coins(object_provider, all_mutated)
-        .await?
-        .into_iter()
-        .fold(balances, |mut acc, (owner, type_, amount)| {
-            *acc.entry((owner, type_)).or_default() += amount as i128;
-            acc
-        });
-
-    Ok(balances
-        .into_iter()
-        .filter_map(|((owner, coin_type), amount)| {
-            if amount == 0 {
-                return None;
-            }
-            Some(BalanceChange {
-                owner,
-                coin_type,
-                amount,
-            })
+    let input_coins = fetch_coins(object_provider, modified_at_version).await?;
+    let output_coins = fetch_coins(object_provider, all_mutated).await?;
+    let key = |(owner, type_, amount): (Owner, TypeTag, u64)| ((owner, type_), amount);
+    Ok(coin_balance_deltas(
+        input_coins.into_iter().map(key),
+        output_coins.into_iter().map(key),
+    )
+    .into_iter()
+    .filter_map(|((owner, coin_type), (amount, _))| {
+        if amount == 0 {
+            return None;
+        }
+        Some(BalanceChange {
+            owner,
+            coin_type,
+            amount,
         })
-        .collect())
+    })
+    .collect())
 }
 
 async fn fetch_coins<P: ObjectProvider<Error = E>, E>(
@@ -24,6 +24,7 @@ use sui_types::gas_coin::GAS;
 use sui_types::messages::TransactionEffectsAPI;
 use sui_types::object::{Object, Owner};
 use sui_types::parse_sui_struct_tag;
+use sui_types::sui_serde::BigInt;
 
 use crate::api::{cap_page_limit, CoinReadApiServer, JsonRpcMetrics};
 use crate::error::Error;
@@ -247,13 +248,32 @@ impl CoinReadApiServer for CoinReadApi {
         Ok(coins)
     }
 
-    fn get_balance(&self, owner: SuiAddress, coin_type: Option<String>) -> RpcResult<Balance> {
+    fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: Option<BigInt<u64>>,
+    ) -> RpcResult<Balance> {
         let coin_type_tag = TypeTag::Struct(Box::new(match coin_type {
             Some(c) => parse_sui_struct_tag(&c)?,
             None => GAS::type_(),
         }));
 
-        let balance = self.get_balance_iterator(owner, coin_type_tag.to_string())?;
+        let balance = match checkpoint {
+            Some(checkpoint) => {
+                let coin_type = coin_type_tag.to_string();
+                let (total_balance, coin_object_count) =
+                    self.state
+                        .get_balance_at_checkpoint(owner, coin_type.clone(), *checkpoint)?;
+                Balance {
+                    coin_type,
+                    coin_object_count,
+                    total_balance,
+                    locked_balance: HashMap::new(),
+                }
+            }
+            None => self.get_balance_iterator(owner, coin_type_tag.to_string())?,
+        };
 
         Ok(balance)
     }
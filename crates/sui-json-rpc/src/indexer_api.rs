@@ -1,24 +1,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{Stream, StreamExt, TryStream};
 use jsonrpsee::core::error::SubscriptionClosed;
 use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::{RpcModule, SubscriptionSink};
 use serde::Serialize;
+use tokio::sync::Semaphore;
 use tracing::{debug, warn};
 
 use move_core_types::language_storage::TypeTag;
 use mysten_metrics::spawn_monitored_task;
+use sui_config::node::CheckpointDataStreamingConfig;
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiMoveValue,
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiCheckpointData, SuiMoveValue,
     SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedMoveObject,
     SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
@@ -27,6 +31,8 @@ use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::query::TransactionFilter;
+use sui_types::sui_serde::BigInt;
 
 use crate::api::{
     cap_page_limit, validate_limit, IndexerApiServer, JsonRpcMetrics, ReadApiServer,
@@ -60,11 +66,35 @@ where
     });
 }
 
+/// Like `spawn_subscription`, but closes the subscription with the first error of the stream.
+pub fn spawn_try_subscription<S, T, E>(mut sink: SubscriptionSink, rx: S)
+where
+    S: TryStream<Ok = T, Error = E> + Unpin + Send + 'static,
+    T: Serialize,
+    E: Display,
+{
+    spawn_monitored_task!(async move {
+        match sink.pipe_from_try_stream(rx).await {
+            SubscriptionClosed::Success => {
+                sink.close(SubscriptionClosed::Success);
+            }
+            SubscriptionClosed::RemotePeerAborted => (),
+            SubscriptionClosed::Failed(err) => {
+                warn!(error = ?err, "Subscription closed.");
+                sink.close(err);
+            }
+        };
+    });
+}
+
 pub struct IndexerApi<R> {
     state: Arc<AuthorityState>,
     read_api: R,
     ns_resolver_id: Option<ObjectID>,
     pub metrics: Arc<JsonRpcMetrics>,
+    checkpoint_data_streaming_config: Option<CheckpointDataStreamingConfig>,
+    /// Permits of the checkpoint data subscriptions, each one holding a permit until it ends.
+    checkpoint_data_subscriptions: Arc<Semaphore>,
 }
 
 impl<R: ReadApiServer> IndexerApi<R> {
@@ -79,8 +109,17 @@ impl<R: ReadApiServer> IndexerApi<R> {
             read_api,
             ns_resolver_id,
             metrics,
+            checkpoint_data_streaming_config: None,
+            checkpoint_data_subscriptions: Arc::new(Semaphore::new(0)),
         }
     }
+
+    /// Accept checkpoint data subscriptions, within the limits of `config`.
+    pub fn with_checkpoint_data_streaming(mut self, config: CheckpointDataStreamingConfig) -> Self {
+        self.checkpoint_data_subscriptions = Arc::new(Semaphore::new(config.max_subscribers));
+        self.checkpoint_data_streaming_config = Some(config);
+        self
+    }
 }
 
 #[async_trait]
@@ -146,15 +185,13 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         let descending = descending_order.unwrap_or_default();
         let opts = query.options.unwrap_or_default();
 
-        // Retrieve 1 extra item for next cursor
-        let mut digests =
-            self.state
-                .get_transactions(query.filter, cursor, Some(limit + 1), descending)?;
-
-        // extract next cursor
-        let has_next_page = digests.len() > limit;
-        digests.truncate(limit);
-        let next_cursor = digests.last().cloned().map_or(cursor, Some);
+        let Page {
+            data: digests,
+            next_cursor,
+            has_next_page,
+        } = self
+            .state
+            .get_transactions_page(query.filter, cursor, limit, descending)?;
 
         let data: Vec<SuiTransactionBlockResponse> = if opts.only_digest() {
             digests
@@ -196,28 +233,101 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         let descending = descending_order.unwrap_or_default();
         let limit = cap_page_limit(limit);
         self.metrics.query_events_limit.report(limit as u64);
-        // Retrieve 1 extra item for next cursor
-        let mut data = self
+        let page = self
             .state
-            .query_events(query, cursor.clone(), limit + 1, descending)?;
-        let has_next_page = data.len() > limit;
-        data.truncate(limit);
-        let next_cursor = data.last().map_or(cursor, |e| Some(e.id.clone()));
+            .query_events_page(query, cursor, limit, descending)?;
         self.metrics
             .query_events_result_size
-            .report(data.len() as u64);
+            .report(page.data.len() as u64);
         self.metrics
             .query_events_result_size_total
-            .inc_by(data.len() as u64);
-        Ok(EventPage {
-            data,
-            next_cursor,
-            has_next_page,
-        })
+            .inc_by(page.data.len() as u64);
+        Ok(page)
     }
 
     fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.state.event_handler.subscribe(filter));
+        spawn_subscription(
+            sink,
+            self.state.subscription_handler.subscribe_events(filter),
+        );
+        Ok(())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        sink: SubscriptionSink,
+        filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        if !filter.is_streamable() {
+            sink.reject(CallError::InvalidParams(anyhow!(
+                "Checkpoint and time range filters are not supported by transaction subscription"
+            )))?;
+            return Ok(());
+        }
+        spawn_subscription(
+            sink,
+            self.state
+                .subscription_handler
+                .subscribe_transactions(filter),
+        );
+        Ok(())
+    }
+
+    fn subscribe_checkpoint_data(
+        &self,
+        sink: SubscriptionSink,
+        start: Option<BigInt<u64>>,
+    ) -> SubscriptionResult {
+        let config = match &self.checkpoint_data_streaming_config {
+            Some(config) => config,
+            None => {
+                sink.reject(CallError::Failed(anyhow!(
+                    "Checkpoint data subscription is not enabled on this node"
+                )))?;
+                return Ok(());
+            }
+        };
+        let permit = match self
+            .checkpoint_data_subscriptions
+            .clone()
+            .try_acquire_owned()
+        {
+            Ok(permit) => permit,
+            Err(_) => {
+                sink.reject(CallError::Failed(anyhow!(
+                    "Too many checkpoint data subscriptions, at most {} are allowed",
+                    config.max_subscribers
+                )))?;
+                return Ok(());
+            }
+        };
+
+        let next = self
+            .state
+            .get_latest_checkpoint_sequence_number()
+            .map_or(0, |latest| latest + 1);
+        let start = start.map_or(next, |start| *start);
+        // Checkpoints before the next one are read back from the stores, bound how many.
+        if next.saturating_sub(start) > config.max_catch_up_checkpoints {
+            sink.reject(CallError::InvalidParams(anyhow!(
+                "Cannot start streaming from checkpoint {start}, at most {} checkpoints before the \
+                next checkpoint {next} can be streamed",
+                config.max_catch_up_checkpoints
+            )))?;
+            return Ok(());
+        }
+
+        let stream = self
+            .state
+            .checkpoint_data_streamer
+            .clone()
+            .subscribe(start)
+            .map(move |data| {
+                // The permit is released once the subscription ends and the stream is dropped.
+                let _permit = &permit;
+                Ok::<_, anyhow::Error>(SuiCheckpointData::try_from(data?.as_ref())?)
+            });
+        spawn_try_subscription(sink, Box::pin(stream));
         Ok(())
     }
 
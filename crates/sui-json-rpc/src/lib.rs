@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::env;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 
 use hyper::header::HeaderName;
@@ -18,10 +18,12 @@ use tracing::{info, warn};
 
 pub use balance_changes::*;
 pub use object_changes::*;
+use sui_config::node::JsonRpcRateLimitConfig;
 use sui_open_rpc::{Module, Project};
 
 use crate::error::Error;
 use crate::metrics::MetricsLogger;
+use crate::rate_limit_layer::RateLimiter;
 use crate::routing_layer::RoutingLayer;
 
 pub mod api;
@@ -33,7 +35,9 @@ pub mod indexer_api;
 mod metrics;
 pub mod move_utils;
 mod object_changes;
+mod rate_limit_layer;
 pub mod read_api;
+pub mod response_cache;
 mod routing_layer;
 pub mod transaction_builder_api;
 pub mod transaction_execution_api;
@@ -59,6 +63,7 @@ pub struct JsonRpcServerBuilder {
     module: RpcModule<()>,
     rpc_doc: Project,
     registry: Registry,
+    rate_limit_config: Option<JsonRpcRateLimitConfig>,
 }
 
 pub fn sui_rpc_doc(version: &str) -> Project {
@@ -80,9 +85,14 @@ impl JsonRpcServerBuilder {
             module: RpcModule::new(()),
             rpc_doc: sui_rpc_doc(version),
             registry: prometheus_registry.clone(),
+            rate_limit_config: None,
         }
     }
 
+    pub fn set_rate_limit_config(&mut self, config: JsonRpcRateLimitConfig) {
+        self.rate_limit_config = Some(config);
+    }
+
     pub fn register_module<T: SuiRpcModule>(&mut self, module: T) -> Result<(), Error> {
         self.rpc_doc.add_module(T::rpc_doc_module());
         Ok(self.module.merge(module.rpc())?)
@@ -101,25 +111,35 @@ impl JsonRpcServerBuilder {
         };
         info!(?acl);
 
-        let cors = CorsLayer::new()
-            // Allow `POST` when accessing the resource
-            .allow_methods([Method::POST])
-            // Allow requests from any origin
-            .allow_origin(acl)
-            .allow_headers([
-                hyper::header::CONTENT_TYPE,
-                HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
-                HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
-                HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
-                HeaderName::from_static(APP_NAME_HEADER),
-            ]);
-
         let routing = self.rpc_doc.method_routing.clone();
 
         self.module
             .register_method("rpc.discover", move |_, _| Ok(self.rpc_doc.clone()))?;
         let methods_names = self.module.method_names().collect::<Vec<_>>();
 
+        let mut allow_headers = vec![
+            hyper::header::CONTENT_TYPE,
+            HeaderName::from_static(CLIENT_SDK_TYPE_HEADER),
+            HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
+            HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
+            HeaderName::from_static(APP_NAME_HEADER),
+        ];
+        let rate_limiter = match self.rate_limit_config.take() {
+            Some(config) => {
+                let limiter = RateLimiter::new(config, &self.registry, &methods_names)?;
+                allow_headers.push(limiter.api_key_header().clone());
+                Some(limiter)
+            }
+            None => None,
+        };
+
+        let cors = CorsLayer::new()
+            // Allow `POST` when accessing the resource
+            .allow_methods([Method::POST])
+            // Allow requests from any origin
+            .allow_origin(acl)
+            .allow_headers(allow_headers);
+
         let max_connection = env::var("RPC_MAX_CONNECTION")
             .ok()
             .and_then(|o| {
@@ -150,6 +170,12 @@ impl JsonRpcServerBuilder {
             .layer(cors)
             .layer(routing_layer);
 
+        // With rate limiting, the server is only reached through the rate limiter.
+        let server_address = match rate_limiter {
+            Some(_) => SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0),
+            None => listen_address,
+        };
+
         let server = ServerBuilder::default()
             .batch_requests_supported(false)
             .max_response_body_size(MAX_REQUEST_SIZE)
@@ -157,10 +183,16 @@ impl JsonRpcServerBuilder {
             .set_host_filtering(AllowHosts::Any)
             .set_middleware(middleware)
             .set_logger(metrics_logger)
-            .build(listen_address)
+            .build(server_address)
             .await?;
         let addr = server.local_addr()?;
         let handle = server.start(self.module)?;
+        let addr = match rate_limiter {
+            Some(limiter) => {
+                rate_limit_layer::serve(listen_address, addr, limiter, handle.clone())?
+            }
+            None => addr,
+        };
 
         info!(local_addr =? addr, "Sui JSON-RPC server listening on {addr}");
         info!("Available JSON-RPC methods : {:?}", methods_names);
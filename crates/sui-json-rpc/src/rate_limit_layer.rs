@@ -0,0 +1,915 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::Error;
+use crate::routing_layer::is_json;
+use crate::MAX_REQUEST_SIZE;
+use anyhow::anyhow;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use hyper::server::conn::AddrStream;
+use hyper::service::make_service_fn;
+use hyper::upgrade::Upgraded;
+use hyper::{Body, Client as HttpClient, Method, Request, Response, Server, StatusCode};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::error::GenericTransportError;
+use jsonrpsee::core::http_helpers::read_body;
+use jsonrpsee::server::ServerHandle;
+use jsonrpsee::types::Request as RpcRequest;
+use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use sui_config::node::{JsonRpcRateLimitConfig, RateLimit};
+use tokio::io::AsyncWriteExt;
+use tower::Service;
+use tracing::debug;
+
+/// JSON-RPC error code returned for requests rejected by the rate limiter.
+pub const RATE_LIMIT_EXCEEDED_CODE: i32 = -32029;
+
+const WILDCARD_METHOD: &str = "*";
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+const SPAM_LABEL: &str = "SPAM";
+/// Requests of new clients are rejected beyond this many buckets, until buckets that filled up
+/// again are dropped. Evicting buckets in use would hand their clients a full bucket.
+const MAX_BUCKETS: usize = 100_000;
+/// Minimum interval between two scans of a full bucket table for buckets to drop.
+const BUCKET_PURGE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+enum Client {
+    Ip(IpAddr),
+    ApiKey(String),
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit) -> Self {
+        Self {
+            limit: *limit,
+            tokens: limit.burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.requests_per_second as f64)
+            .min(self.limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// A full bucket is no different from a new one, so it can be dropped.
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.limit.burst as f64
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<(Client, String), TokenBucket>,
+    capacity: usize,
+    last_purge: Option<Instant>,
+}
+
+impl Buckets {
+    /// Returns the bucket of `key`, created with `limit` if there is room for it.
+    fn get_or_insert(
+        &mut self,
+        key: (Client, String),
+        limit: &RateLimit,
+        now: Instant,
+    ) -> Option<&mut TokenBucket> {
+        if !self.buckets.contains_key(&key) && self.buckets.len() >= self.capacity {
+            self.purge(now);
+            if self.buckets.len() >= self.capacity {
+                return None;
+            }
+        }
+        let bucket = self
+            .buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(limit));
+        // The limits of a bucket change along with the config, e.g. for a new API key.
+        bucket.limit = *limit;
+        Some(bucket)
+    }
+
+    fn purge(&mut self, now: Instant) {
+        if self.last_purge.map_or(false, |last| {
+            now.duration_since(last) < BUCKET_PURGE_INTERVAL
+        }) {
+            return;
+        }
+        self.last_purge = Some(now);
+        self.buckets.retain(|_, bucket| !bucket.is_full(now));
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: JsonRpcRateLimitConfig,
+    api_key_header: HeaderName,
+    buckets: Mutex<Buckets>,
+    method_whitelist: HashSet<String>,
+    rate_limited_requests: IntCounterVec,
+}
+
+impl RateLimiter {
+    pub fn new(
+        config: JsonRpcRateLimitConfig,
+        registry: &Registry,
+        method_whitelist: &[&str],
+    ) -> Result<Self, Error> {
+        let api_key_header = HeaderName::from_bytes(config.api_key_header.as_bytes())
+            .map_err(|e| anyhow!("Invalid API key header {}: {e}", config.api_key_header))?;
+        Ok(Self {
+            api_key_header,
+            config,
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                capacity: MAX_BUCKETS,
+                last_purge: None,
+            }),
+            method_whitelist: method_whitelist.iter().map(|s| (*s).into()).collect(),
+            rate_limited_requests: register_int_counter_vec_with_registry!(
+                "rpc_rate_limited_requests_by_route",
+                "Number of requests rejected by the rate limiter by route",
+                &["route"],
+                registry,
+            )
+            .unwrap(),
+        })
+    }
+
+    pub fn api_key_header(&self) -> &HeaderName {
+        &self.api_key_header
+    }
+
+    /// Resolves the IP of the client of a request received from `peer`. The `X-Forwarded-For`
+    /// header is only trusted from the configured proxies, each of which appends the address it
+    /// received the request from: the client is the last address not appended by a trusted
+    /// proxy.
+    fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let trusted_proxies = &self.config.trusted_proxies;
+        if !trusted_proxies.contains(&peer) {
+            return peer;
+        }
+        let forwarded: Vec<_> = headers
+            .get_all(FORWARDED_FOR_HEADER)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(','))
+            .collect();
+        forwarded
+            .into_iter()
+            .rev()
+            .map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .find(|ip| ip.map_or(true, |ip| !trusted_proxies.contains(&ip)))
+            .flatten()
+            .unwrap_or(peer)
+    }
+
+    /// Checks the JSON-RPC requests of `body`, a single request or a batch, against the limits
+    /// of the client. Returns the error response of the first request over the limit.
+    fn check_requests(&self, body: &[u8], ip: IpAddr, api_key: Option<&str>) -> Result<(), String> {
+        let is_batch = body
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .map_or(false, |b| *b == b'[');
+        let requests = if is_batch {
+            serde_json::from_slice::<Vec<RpcRequest>>(body).unwrap_or_default()
+        } else {
+            serde_json::from_slice::<RpcRequest>(body)
+                .map(|r| vec![r])
+                .unwrap_or_default()
+        };
+        let methods: Vec<&str> = requests.iter().map(|r| r.method.as_ref()).collect();
+        self.check(&methods, ip, api_key).map_err(|i| {
+            let request = &requests[i];
+            response::rate_limited_error(&request.method, request.id.clone())
+        })
+    }
+
+    /// Takes a token for each of `methods` from the buckets of the client. If the client is over
+    /// its limit for any of them, or has no bucket and there is no room for a new one, no token
+    /// is taken and the index of the first method over the limit is returned.
+    fn check(&self, methods: &[&str], ip: IpAddr, api_key: Option<&str>) -> Result<(), usize> {
+        let (client, limits) = match api_key.and_then(|key| self.config.api_keys.get_key_value(key))
+        {
+            Some((key, limits)) => (Client::ApiKey(key.clone()), limits),
+            None => (Client::Ip(ip_prefix(ip)), &self.config.per_ip),
+        };
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut needed: HashMap<&str, u32> = HashMap::new();
+        for (i, method) in methods.iter().enumerate() {
+            let Some(limit) = limits.get(*method).or_else(|| limits.get(WILDCARD_METHOD)) else {
+                continue;
+            };
+            // Unknown methods share a bucket, so that made up names cannot be used to get fresh
+            // buckets.
+            let route = if self.method_whitelist.contains(*method) {
+                *method
+            } else {
+                SPAM_LABEL
+            };
+            let count = needed.entry(route).or_default();
+            *count += 1;
+
+            let admitted =
+                match buckets.get_or_insert((client.clone(), route.to_string()), limit, now) {
+                    Some(bucket) => {
+                        bucket.refill(now);
+                        bucket.tokens >= *count as f64
+                    }
+                    None => false,
+                };
+            if !admitted {
+                self.rate_limited_requests.with_label_values(&[route]).inc();
+                return Err(i);
+            }
+        }
+        for (route, count) in needed {
+            if let Some(bucket) = buckets
+                .buckets
+                .get_mut(&(client.clone(), route.to_string()))
+            {
+                bucket.tokens -= count as f64;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Identifies clients by their IPv4 address, or by the /64 prefix of their IPv6 address, as a
+/// single host commonly gets a whole /64 to rotate its addresses in.
+fn ip_prefix(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => ip,
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(v6) & !(u64::MAX as u128))),
+        },
+    }
+}
+
+/// Serves the JSON-RPC server listening on `backend` at `listen_address`, admitting requests
+/// through the rate limiter. The jsonrpsee middleware is not given the peer address of the
+/// requests, so the limiter runs in a server of its own that forwards the admitted requests,
+/// with the peer address appended to their `X-Forwarded-For` header. The requests sent over
+/// upgraded websocket connections are limited along with those sent over HTTP. Stops along with
+/// the JSON-RPC server.
+pub(crate) fn serve(
+    listen_address: SocketAddr,
+    backend: SocketAddr,
+    limiter: RateLimiter,
+    handle: ServerHandle,
+) -> Result<SocketAddr, Error> {
+    let limiter = Arc::new(limiter);
+    let client = HttpClient::new();
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let service = RateLimitService {
+            inner: ForwardService {
+                client: client.clone(),
+                backend,
+            },
+            limiter: limiter.clone(),
+            peer: conn.remote_addr(),
+        };
+        async move { Ok::<_, Infallible>(service) }
+    });
+    let server = Server::try_bind(&listen_address)
+        .map_err(|e| anyhow!("Failed to bind {listen_address}: {e}"))?
+        .serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(async move {
+        if let Err(e) = server.with_graceful_shutdown(handle.stopped()).await {
+            tracing::error!("Rate limiting server failed: {e}");
+        }
+    });
+    Ok(addr)
+}
+
+#[derive(Debug, Clone)]
+struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    peer: SocketAddr,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+        let peer = self.peer;
+        let res_fut = async move {
+            let ip = limiter.client_ip(peer.ip(), req.headers());
+            let api_key = req
+                .headers()
+                .get(&limiter.api_key_header)
+                .and_then(|h| h.to_str().ok())
+                .map(|key| key.to_string());
+            // The server behind the limiter sees every request coming from the limiter.
+            req.headers_mut().append(
+                FORWARDED_FOR_HEADER,
+                HeaderValue::from_str(&peer.ip().to_string()).expect("IP is a valid header"),
+            );
+
+            let req = if req.method() == Method::POST && is_json(&req) {
+                let (parts, body) = req.into_parts();
+                let (body, _) = match read_body(&parts.headers, body, MAX_REQUEST_SIZE).await {
+                    Ok(r) => r,
+                    Err(GenericTransportError::TooLarge) => {
+                        return Ok(response::too_large(MAX_REQUEST_SIZE))
+                    }
+                    Err(GenericTransportError::Malformed) => return Ok(response::malformed()),
+                    Err(GenericTransportError::Inner(e)) => {
+                        tracing::error!("Internal error reading request body: {}", e);
+                        return Ok(response::internal_error());
+                    }
+                };
+                if let Err(error) = limiter.check_requests(&body, ip, api_key.as_deref()) {
+                    return Ok(response::rate_limited(error));
+                }
+                Request::from_parts(parts, Body::from(body))
+            } else {
+                req.extensions_mut().insert(WebSocketClient {
+                    limiter,
+                    ip,
+                    api_key,
+                });
+                req
+            };
+            inner.call(req).await.map_err(|err| err.into())
+        };
+        Box::pin(res_fut)
+    }
+}
+
+/// Client of a request that may upgrade to a websocket connection, whose messages are limited
+/// as the requests of the client are.
+#[derive(Clone)]
+struct WebSocketClient {
+    limiter: Arc<RateLimiter>,
+    ip: IpAddr,
+    api_key: Option<String>,
+}
+
+/// Forwards requests to the JSON-RPC server at `backend`.
+#[derive(Debug, Clone)]
+struct ForwardService {
+    client: HttpClient<HttpConnector>,
+    backend: SocketAddr,
+}
+
+impl Service<Request<Body>> for ForwardService {
+    type Response = Response<Body>;
+    type Error = hyper::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let client = self.client.clone();
+        let uri = format!(
+            "http://{}{}",
+            self.backend,
+            req.uri().path_and_query().map_or("/", |path| path.as_str())
+        );
+        *req.uri_mut() = uri.parse().expect("Backend URI is valid");
+        let websocket_client = req.extensions_mut().remove::<WebSocketClient>();
+        Box::pin(async move {
+            let upgrade = req
+                .headers()
+                .contains_key(hyper::header::UPGRADE)
+                .then(|| hyper::upgrade::on(&mut req));
+            let mut response = client.request(req).await?;
+            if let (StatusCode::SWITCHING_PROTOCOLS, Some(upgrade)) = (response.status(), upgrade) {
+                let backend_upgrade = hyper::upgrade::on(&mut response);
+                tokio::spawn(async move {
+                    match tokio::try_join!(upgrade, backend_upgrade) {
+                        Ok((mut client_io, mut backend_io)) => {
+                            let result = match websocket_client {
+                                Some(websocket_client) => {
+                                    forward_websocket(client_io, backend_io, websocket_client).await
+                                }
+                                None => {
+                                    tokio::io::copy_bidirectional(&mut client_io, &mut backend_io)
+                                        .await
+                                        .map(|_| ())
+                                }
+                            };
+                            if let Err(e) = result {
+                                debug!("Upgraded connection closed: {e}");
+                            }
+                        }
+                        Err(e) => debug!("Failed to upgrade connection: {e}"),
+                    }
+                });
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Forwards the frames of an upgraded websocket connection between the client and the backend,
+/// until either closes the connection. Messages of the client go through the rate limiter as
+/// requests sent over HTTP do, and those over the limit are answered with an error in place of
+/// being forwarded.
+async fn forward_websocket(
+    client_io: Upgraded,
+    backend_io: Upgraded,
+    client: WebSocketClient,
+) -> std::io::Result<()> {
+    let (mut client_reader, client_writer) = tokio::io::split(client_io);
+    let (mut backend_reader, mut backend_writer) = tokio::io::split(backend_io);
+    // Errors are written to the client in between the frames forwarded from the backend.
+    let client_writer = Arc::new(tokio::sync::Mutex::new(client_writer));
+
+    let to_client = {
+        let client_writer = client_writer.clone();
+        async move {
+            while let Some(frame) =
+                websocket::read_frame(&mut backend_reader, MAX_REQUEST_SIZE).await?
+            {
+                client_writer.lock().await.write_all(&frame.bytes).await?;
+            }
+            Ok::<_, std::io::Error>(())
+        }
+    };
+
+    let to_backend = async move {
+        let mut message = vec![];
+        let mut message_size = 0;
+        while let Some(frame) = websocket::read_frame(&mut client_reader, MAX_REQUEST_SIZE).await? {
+            // Control frames may come in between the frames of a fragmented message.
+            if frame.is_control() {
+                backend_writer.write_all(&frame.bytes).await?;
+                continue;
+            }
+            message_size += frame.payload.len();
+            if message_size > MAX_REQUEST_SIZE as usize {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Websocket message is too large",
+                ));
+            }
+            let fin = frame.fin;
+            message.push(frame);
+            if !fin {
+                continue;
+            }
+
+            let frames = std::mem::take(&mut message);
+            message_size = 0;
+            let payload: Vec<u8> = frames
+                .iter()
+                .flat_map(|frame| frame.payload.iter().copied())
+                .collect();
+            if let Err(error) =
+                client
+                    .limiter
+                    .check_requests(&payload, client.ip, client.api_key.as_deref())
+            {
+                client_writer
+                    .lock()
+                    .await
+                    .write_all(&websocket::text_frame(error.as_bytes()))
+                    .await?;
+                continue;
+            }
+            for frame in frames {
+                backend_writer.write_all(&frame.bytes).await?;
+            }
+        }
+        Ok(())
+    };
+
+    tokio::select! {
+        result = to_client => result,
+        result = to_backend => result,
+    }
+}
+
+/// Just enough of the websocket framing (RFC 6455) to read the messages of a connection.
+mod websocket {
+    use tokio::io::{AsyncRead, AsyncReadExt};
+
+    const OPCODE_TEXT: u8 = 0x1;
+    const FIN: u8 = 0x80;
+    const MASKED: u8 = 0x80;
+
+    pub(super) struct Frame {
+        /// The frame as read, to be forwarded as is.
+        pub bytes: Vec<u8>,
+        pub fin: bool,
+        pub opcode: u8,
+        /// The unmasked payload.
+        pub payload: Vec<u8>,
+    }
+
+    impl Frame {
+        pub fn is_control(&self) -> bool {
+            self.opcode & 0x8 != 0
+        }
+    }
+
+    /// Reads the next frame, or `None` once the connection is closed in between frames. Frames
+    /// whose payload is larger than `max_size` are rejected.
+    pub(super) async fn read_frame<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        max_size: u32,
+    ) -> std::io::Result<Option<Frame>> {
+        let mut header = [0u8; 2];
+        match reader.read_exact(&mut header).await {
+            Ok(_) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let mut bytes = header.to_vec();
+        let len = match header[1] & 0x7f {
+            126 => {
+                let len = reader.read_u16().await?;
+                bytes.extend_from_slice(&len.to_be_bytes());
+                len as u64
+            }
+            127 => {
+                let len = reader.read_u64().await?;
+                bytes.extend_from_slice(&len.to_be_bytes());
+                len
+            }
+            len => len as u64,
+        };
+        if len > max_size as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Websocket frame of {len} bytes is too large"),
+            ));
+        }
+        let mut mask = None;
+        if header[1] & MASKED != 0 {
+            let mut key = [0u8; 4];
+            reader.read_exact(&mut key).await?;
+            bytes.extend_from_slice(&key);
+            mask = Some(key);
+        }
+        let mut payload = vec![0u8; len as usize];
+        reader.read_exact(&mut payload).await?;
+        bytes.extend_from_slice(&payload);
+        if let Some(key) = mask {
+            for (i, b) in payload.iter_mut().enumerate() {
+                *b ^= key[i % 4];
+            }
+        }
+        Ok(Some(Frame {
+            bytes,
+            fin: header[0] & FIN != 0,
+            opcode: header[0] & 0x0f,
+            payload,
+        }))
+    }
+
+    /// Encodes an unfragmented text frame, as sent by servers: unmasked.
+    pub(super) fn text_frame(payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![FIN | OPCODE_TEXT];
+        match payload.len() {
+            len if len < 126 => bytes.push(len as u8),
+            len if len <= u16::MAX as usize => {
+                bytes.push(126);
+                bytes.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                bytes.push(127);
+                bytes.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+}
+
+mod response {
+    use super::RATE_LIMIT_EXCEEDED_CODE;
+    use jsonrpsee::core::__reexports::serde_json;
+    use jsonrpsee::types::error::ErrorObject;
+    use jsonrpsee::types::{ErrorResponse, Id};
+
+    pub(crate) use crate::routing_layer::response::{internal_error, malformed, too_large};
+
+    pub(crate) fn rate_limited_error(method: &str, id: Id) -> String {
+        serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::owned(
+                RATE_LIMIT_EXCEEDED_CODE,
+                format!("Rate limit exceeded for method {method}"),
+                None::<()>,
+            ),
+            id,
+        ))
+        .expect("built from known-good data; qed")
+    }
+
+    pub(crate) fn rate_limited(error: String) -> hyper::Response<hyper::Body> {
+        hyper::Response::builder()
+            .status(hyper::StatusCode::TOO_MANY_REQUESTS)
+            .header(
+                "content-type",
+                hyper::header::HeaderValue::from_static("application/json; charset=utf-8"),
+            )
+            .body(error.into())
+            .expect("Unable to parse response body for type conversion")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn limit(requests_per_second: u32, burst: u32) -> RateLimit {
+        RateLimit {
+            requests_per_second,
+            burst,
+        }
+    }
+
+    fn limiter() -> RateLimiter {
+        let config = JsonRpcRateLimitConfig {
+            per_ip: BTreeMap::from([
+                ("suix_queryEvents".to_string(), limit(0, 2)),
+                (WILDCARD_METHOD.to_string(), limit(0, 3)),
+            ]),
+            api_keys: BTreeMap::from([(
+                "key".to_string(),
+                BTreeMap::from([("suix_queryEvents".to_string(), limit(0, 4))]),
+            )]),
+            api_key_header: "x-api-key".to_string(),
+            trusted_proxies: vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+        };
+        RateLimiter::new(
+            config,
+            &Registry::new(),
+            &["suix_queryEvents", "sui_getObject"],
+        )
+        .unwrap()
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn test_per_method_limits() {
+        let limiter = limiter();
+        let ip = ip("1.2.3.4");
+        assert!(limiter.check(&["suix_queryEvents"], ip, None).is_ok());
+        assert!(limiter.check(&["suix_queryEvents"], ip, None).is_ok());
+        assert!(limiter.check(&["suix_queryEvents"], ip, None).is_err());
+
+        // Methods without a limit of their own share the wildcard limit, each in its own bucket.
+        for _ in 0..3 {
+            assert!(limiter.check(&["sui_getObject"], ip, None).is_ok());
+        }
+        assert!(limiter.check(&["sui_getObject"], ip, None).is_err());
+
+        // Unknown methods share a single bucket.
+        for i in 0..3 {
+            assert!(limiter
+                .check(&[format!("spam_{i}").as_str()], ip, None)
+                .is_ok());
+        }
+        assert!(limiter.check(&["spam_3"], ip, None).is_err());
+
+        // Other clients have their own buckets.
+        assert!(limiter
+            .check(&["suix_queryEvents"], self::ip("5.6.7.8"), None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_api_key_limits() {
+        let limiter = limiter();
+        let ip = ip("1.2.3.4");
+        for _ in 0..4 {
+            assert!(limiter
+                .check(&["suix_queryEvents"], ip, Some("key"))
+                .is_ok());
+        }
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip, Some("key"))
+            .is_err());
+        // Methods the key has no quota for are not limited.
+        for _ in 0..10 {
+            assert!(limiter.check(&["sui_getObject"], ip, Some("key")).is_ok());
+        }
+        // Unknown keys fall back to the per-IP limits.
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip, Some("unknown"))
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip, Some("unknown"))
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip, Some("unknown"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_rejected_batch_takes_no_tokens() {
+        let limiter = limiter();
+        let ip = ip("1.2.3.4");
+        assert_eq!(
+            limiter.check(
+                &[
+                    "sui_getObject",
+                    "suix_queryEvents",
+                    "suix_queryEvents",
+                    "suix_queryEvents"
+                ],
+                ip,
+                None
+            ),
+            Err(3)
+        );
+        // The whole quota is left after the rejected batch.
+        assert!(limiter
+            .check(&["suix_queryEvents", "suix_queryEvents"], ip, None)
+            .is_ok());
+        for _ in 0..3 {
+            assert!(limiter.check(&["sui_getObject"], ip, None).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let limiter = limiter();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            FORWARDED_FOR_HEADER,
+            HeaderValue::from_static("9.9.9.9, 1.2.3.4, 10.0.0.2"),
+        );
+        // Untrusted peers are identified by their address.
+        assert_eq!(limiter.client_ip(ip("5.6.7.8"), &headers), ip("5.6.7.8"));
+        // Trusted proxies are identified by the last address they did not append themselves.
+        assert_eq!(limiter.client_ip(ip("10.0.0.1"), &headers), ip("1.2.3.4"));
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &HeaderMap::new()),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_refill() {
+        let limit = limit(10, 1);
+        let mut bucket = TokenBucket::new(&limit);
+        let now = Instant::now();
+        bucket.tokens = 0.0;
+        bucket.last_refill = now;
+        bucket.refill(now + std::time::Duration::from_millis(50));
+        assert!(bucket.tokens < 1.0);
+        assert!(!bucket.is_full(now + std::time::Duration::from_millis(50)));
+        // Buckets do not fill up beyond their burst.
+        bucket.refill(now + std::time::Duration::from_millis(1000));
+        assert_eq!(bucket.tokens, 1.0);
+        assert!(bucket.is_full(now + std::time::Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn test_full_bucket_table() {
+        let limiter = limiter();
+        limiter.buckets.lock().unwrap().capacity = 2;
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("1.2.3.4"), None)
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("5.6.7.8"), None)
+            .is_ok());
+
+        // New clients are rejected rather than evicting the buckets in use, which do not refill.
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("9.9.9.9"), None)
+            .is_err());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("1.2.3.4"), None)
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("1.2.3.4"), None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ipv6_prefix() {
+        let limiter = limiter();
+        // Addresses of the same /64 share their buckets.
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("2001:db8::1"), None)
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("2001:db8::ffff:2"), None)
+            .is_ok());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("2001:db8::3"), None)
+            .is_err());
+        assert!(limiter
+            .check(&["suix_queryEvents"], ip("2001:db8:0:1::1"), None)
+            .is_ok());
+        assert_eq!(ip_prefix(ip("::ffff:1.2.3.4")), ip("1.2.3.4"));
+    }
+
+    #[test]
+    fn test_check_requests() {
+        let limiter = limiter();
+        let ip = ip("1.2.3.4");
+        let request = br#"{"jsonrpc":"2.0","id":7,"method":"suix_queryEvents","params":[]}"#;
+        assert!(limiter.check_requests(request, ip, None).is_ok());
+        assert!(limiter.check_requests(request, ip, None).is_ok());
+        let error = limiter.check_requests(request, ip, None).unwrap_err();
+        assert!(error.contains(&RATE_LIMIT_EXCEEDED_CODE.to_string()));
+        assert!(error.contains(r#""id":7"#));
+
+        let batch = br#" [{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":[]},
+            {"jsonrpc":"2.0","id":2,"method":"suix_queryEvents","params":[]}]"#;
+        assert!(limiter.check_requests(batch, ip, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_websocket_frames() {
+        // A masked frame from a client, with a 16 bit length.
+        let payload = vec![b'x'; 300];
+        let key = [1u8, 2, 3, 4];
+        let mut bytes = vec![0x81, 0x80 | 126];
+        bytes.extend_from_slice(&300u16.to_be_bytes());
+        bytes.extend_from_slice(&key);
+        bytes.extend(payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]));
+        // Followed by a ping.
+        bytes.extend_from_slice(&[0x89, 0x00]);
+
+        let mut reader = &bytes[..];
+        let frame = websocket::read_frame(&mut reader, 1024)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(frame.fin && !frame.is_control());
+        assert_eq!(frame.payload, payload);
+        assert_eq!(frame.bytes, &bytes[..bytes.len() - 2]);
+        let ping = websocket::read_frame(&mut reader, 1024)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(ping.is_control());
+        assert!(websocket::read_frame(&mut reader, 1024)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Frames written to clients are read back as is.
+        let frame = websocket::text_frame(&payload);
+        let read = websocket::read_frame(&mut &frame[..], 1024)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.payload, payload);
+        assert_eq!(read.bytes, frame);
+
+        // Frames over the size limit are rejected.
+        assert!(websocket::read_frame(&mut &frame[..], 100).await.is_err());
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
@@ -35,6 +36,7 @@ use sui_types::crypto::default_hash;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::display::DisplayVersionUpdatedEvent;
 use sui_types::error::{SuiObjectResponseError, UserInputError};
+use sui_types::event::EventID;
 use sui_types::messages::TransactionDataAPI;
 use sui_types::messages::{
     TransactionData, TransactionEffects, TransactionEffectsAPI, TransactionEvents,
@@ -97,6 +99,40 @@ impl ReadApi {
         Self { state, metrics }
     }
 
+    fn get_events_internal(
+        &self,
+        transaction_digest: TransactionDigest,
+    ) -> Result<Vec<SuiEvent>, Error> {
+        let store = self.state.load_epoch_store_one_call_per_task();
+        let effect = self.state.get_executed_effects(transaction_digest)?;
+        let events = if let Some(event_digest) = effect.events_digest() {
+            self.state
+                .get_transaction_events(event_digest)
+                .map_err(
+                    |e| {
+                        error!("Failed to get transaction events for event digest {event_digest:?} with error: {e:?}");
+                        Error::SuiError(e)
+                    })?
+                .data
+                .into_iter()
+                .enumerate()
+                .map(|(seq, e)| {
+                    SuiEvent::try_from(
+                        e,
+                        *effect.transaction_digest(),
+                        seq as u64,
+                        None,
+                        store.module_cache(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Error::SuiError)?
+        } else {
+            vec![]
+        };
+        Ok(events)
+    }
+
     fn get_checkpoint_internal(&self, id: CheckpointId) -> Result<Checkpoint, Error> {
         Ok(match id {
             CheckpointId::SequenceNumber(seq) => {
@@ -694,33 +730,32 @@ impl ReadApiServer for ReadApi {
     }
 
     fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
-        let store = self.state.load_epoch_store_one_call_per_task();
-        let effect = self.state.get_executed_effects(transaction_digest)?;
-        let events = if let Some(event_digest) = effect.events_digest() {
-            self.state
-                .get_transaction_events(event_digest)
-                .map_err(
-                    |e| {
-                        error!("Failed to get transaction events for event digest {event_digest:?} with error: {e:?}");
-                        Error::SuiError(e)
-                    })?
-                .data
-                .into_iter()
-                .enumerate()
-                .map(|(seq, e)| {
-                    SuiEvent::try_from(
-                        e,
-                        *effect.transaction_digest(),
-                        seq as u64,
-                        None,
-                        store.module_cache(),
-                    )
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(Error::SuiError)?
-        } else {
-            vec![]
-        };
+        Ok(self.get_events_internal(transaction_digest)?)
+    }
+
+    fn multi_get_events(&self, event_ids: Vec<EventID>) -> RpcResult<Vec<SuiEvent>> {
+        if event_ids.len() > QUERY_MAX_RESULT_LIMIT {
+            return Err(anyhow!(UserInputError::SizeLimitExceeded {
+                limit: "multi get events input limit".to_string(),
+                value: QUERY_MAX_RESULT_LIMIT.to_string()
+            })
+            .into());
+        }
+
+        // Events are loaded per transaction, cache them for ids sharing a transaction.
+        let mut tx_events: HashMap<TransactionDigest, Vec<SuiEvent>> = HashMap::new();
+        let mut events = Vec::with_capacity(event_ids.len());
+        for id in event_ids {
+            let events_of_tx = match tx_events.entry(id.tx_digest) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.get_events_internal(id.tx_digest)?),
+            };
+            let event = events_of_tx
+                .get(id.event_seq as usize)
+                .cloned()
+                .ok_or_else(|| anyhow!("Event {id:?} not found"))?;
+            events.push(event);
+        }
         Ok(events)
     }
 
@@ -738,6 +773,30 @@ impl ReadApiServer for ReadApi {
         Ok(self.get_checkpoint_internal(id)?)
     }
 
+    fn multi_get_checkpoints(&self, ids: Vec<CheckpointId>) -> RpcResult<Vec<Checkpoint>> {
+        if ids.len() > QUERY_MAX_RESULT_LIMIT_CHECKPOINTS {
+            return Err(anyhow!(UserInputError::SizeLimitExceeded {
+                limit: "multi get checkpoints input limit".to_string(),
+                value: QUERY_MAX_RESULT_LIMIT_CHECKPOINTS.to_string()
+            })
+            .into());
+        }
+        self.metrics.get_checkpoints_limit.report(ids.len() as u64);
+
+        let checkpoints = ids
+            .into_iter()
+            .map(|id| self.get_checkpoint_internal(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.metrics
+            .get_checkpoints_result_size
+            .report(checkpoints.len() as u64);
+        self.metrics
+            .get_checkpoints_result_size_total
+            .inc_by(checkpoints.len() as u64);
+        Ok(checkpoints)
+    }
+
     fn get_checkpoints(
         &self,
         // If `Some`, the query will start from the next item after the specified cursor
@@ -0,0 +1,428 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::RpcModule;
+use lru::LruCache;
+use serde::Serialize;
+
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, CheckpointPage, SuiEvent, SuiGetPastObjectRequest,
+    SuiObjectDataOptions, SuiObjectResponse, SuiPastObjectResponse, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::event::EventID;
+use sui_types::sui_serde::BigInt;
+
+use crate::api::{JsonRpcMetrics, ReadApiServer};
+use crate::read_api::ReadApi;
+use crate::SuiRpcModule;
+
+/// Methods whose responses may be cached. A method is only cacheable if its response can never
+/// change once it was served, callers must still check that the response they are about to
+/// cache is final (e.g. the transaction has been checkpointed).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum CacheableMethod {
+    GetTransactionBlock,
+    GetCheckpoint,
+    TryGetPastObject,
+}
+
+impl CacheableMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GetTransactionBlock => "sui_getTransactionBlock",
+            Self::GetCheckpoint => "sui_getCheckpoint",
+            Self::TryGetPastObject => "sui_tryGetPastObject",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CacheKey {
+    method: CacheableMethod,
+    params: String,
+}
+
+impl CacheKey {
+    /// Returns None if the params cannot be serialized, in which case the response is not cached.
+    pub fn new(method: CacheableMethod, params: &impl Serialize) -> Option<Self> {
+        Some(Self {
+            method,
+            params: serde_json::to_string(params).ok()?,
+        })
+    }
+
+    pub fn method(&self) -> CacheableMethod {
+        self.method
+    }
+}
+
+struct CachedResponse {
+    response: Arc<dyn Any + Send + Sync>,
+    size: usize,
+}
+
+struct Responses {
+    lru: LruCache<CacheKey, CachedResponse>,
+    size: usize,
+}
+
+/// LRU of rendered responses of cacheable methods, keyed by method and params, and bounded by the
+/// size of the responses in their JSON encoding.
+pub struct ResponseCache {
+    responses: Mutex<Responses>,
+    capacity: usize,
+}
+
+impl ResponseCache {
+    pub fn new(capacity_bytes: NonZeroUsize) -> Self {
+        Self {
+            responses: Mutex::new(Responses {
+                lru: LruCache::unbounded(),
+                size: 0,
+            }),
+            capacity: capacity_bytes.get(),
+        }
+    }
+
+    pub fn get<T: Clone + 'static>(&self, key: &CacheKey) -> Option<T> {
+        self.responses
+            .lock()
+            .unwrap()
+            .lru
+            .get(key)
+            .and_then(|cached| cached.response.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Caches the response, evicting the least recently used ones until the cache fits in its
+    /// capacity. Responses larger than the whole cache are not cached.
+    pub fn insert<T: Serialize + Clone + Send + Sync + 'static>(
+        &self,
+        key: CacheKey,
+        response: &T,
+    ) {
+        let Ok(encoded) = serde_json::to_vec(response) else {
+            return;
+        };
+        let size = key.params.len() + encoded.len();
+        if size > self.capacity {
+            return;
+        }
+
+        let mut responses = self.responses.lock().unwrap();
+        let cached = CachedResponse {
+            response: Arc::new(response.clone()),
+            size,
+        };
+        if let Some(replaced) = responses.lru.put(key, cached) {
+            responses.size -= replaced.size;
+        }
+        responses.size += size;
+        while responses.size > self.capacity {
+            let Some((_, evicted)) = responses.lru.pop_lru() else {
+                break;
+            };
+            responses.size -= evicted.size;
+        }
+    }
+}
+
+/// Read API serving the cacheable methods of `inner` from a [ResponseCache], once their
+/// responses are final. Other methods are passed through.
+pub struct CachedReadApi<R = ReadApi> {
+    inner: R,
+    cache: ResponseCache,
+    metrics: Arc<JsonRpcMetrics>,
+}
+
+impl<R: ReadApiServer> CachedReadApi<R> {
+    pub fn new(inner: R, capacity_bytes: NonZeroUsize, metrics: Arc<JsonRpcMetrics>) -> Self {
+        Self {
+            inner,
+            cache: ResponseCache::new(capacity_bytes),
+            metrics,
+        }
+    }
+
+    fn get_cached<T: Clone + 'static>(&self, key: &Option<CacheKey>) -> Option<T> {
+        let key = key.as_ref()?;
+        let response = self.cache.get(key);
+        let counter = if response.is_some() {
+            &self.metrics.response_cache_hits
+        } else {
+            &self.metrics.response_cache_misses
+        };
+        counter.with_label_values(&[key.method().name()]).inc();
+        response
+    }
+
+    fn cache_response<T: Serialize + Clone + Send + Sync + 'static>(
+        &self,
+        key: Option<CacheKey>,
+        response: &T,
+    ) {
+        if let Some(key) = key {
+            self.cache.insert(key, response);
+        }
+    }
+}
+
+#[async_trait]
+impl<R: ReadApiServer> ReadApiServer for CachedReadApi<R> {
+    async fn get_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        let key = CacheKey::new(CacheableMethod::GetTransactionBlock, &(digest, &options));
+        if let Some(response) = self.get_cached(&key) {
+            return Ok(response);
+        }
+        let response = self.inner.get_transaction_block(digest, options).await?;
+        // The checkpoint and timestamp of a transaction are only final once it is checkpointed.
+        if response.checkpoint.is_some() && response.errors.is_empty() {
+            self.cache_response(key, &response);
+        }
+        Ok(response)
+    }
+
+    fn multi_get_transaction_blocks(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>> {
+        self.inner.multi_get_transaction_blocks(digests, options)
+    }
+
+    fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiObjectResponse> {
+        self.inner.get_object(object_id, options)
+    }
+
+    fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>> {
+        self.inner.multi_get_objects(object_ids, options)
+    }
+
+    async fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let key = CacheKey::new(
+            CacheableMethod::TryGetPastObject,
+            &(object_id, version, &options),
+        );
+        if let Some(response) = self.get_cached(&key) {
+            return Ok(response);
+        }
+        // Display templates can be updated, only responses without display fields are final.
+        let show_display = options.as_ref().map_or(false, |o| o.show_display);
+        let response = self
+            .inner
+            .try_get_past_object(object_id, version, options)
+            .await?;
+        if matches!(response, SuiPastObjectResponse::VersionFound(_)) && !show_display {
+            self.cache_response(key, &response);
+        }
+        Ok(response)
+    }
+
+    fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        self.inner.try_multi_get_past_objects(past_objects, options)
+    }
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
+        let key = CacheKey::new(CacheableMethod::GetCheckpoint, &id);
+        if let Some(checkpoint) = self.get_cached(&key) {
+            return Ok(checkpoint);
+        }
+        let checkpoint = self.inner.get_checkpoint(id).await?;
+        self.cache_response(key, &checkpoint);
+        Ok(checkpoint)
+    }
+
+    fn get_checkpoints(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        self.inner.get_checkpoints(cursor, limit, descending_order)
+    }
+
+    fn multi_get_checkpoints(&self, ids: Vec<CheckpointId>) -> RpcResult<Vec<Checkpoint>> {
+        self.inner.multi_get_checkpoints(ids)
+    }
+
+    fn get_checkpoints_deprecated_limit(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<BigInt<u64>>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        self.inner
+            .get_checkpoints_deprecated_limit(cursor, limit, descending_order)
+    }
+
+    fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
+        self.inner.get_events(transaction_digest)
+    }
+
+    fn multi_get_events(&self, event_ids: Vec<EventID>) -> RpcResult<Vec<SuiEvent>> {
+        self.inner.multi_get_events(event_ids)
+    }
+
+    async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>> {
+        self.inner.get_total_transaction_blocks().await
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
+        self.inner.get_latest_checkpoint_sequence_number().await
+    }
+}
+
+impl<R: ReadApiServer> SuiRpcModule for CachedReadApi<R> {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::ReadApiOpenRpc::module_doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+    use sui_macros::sim_test;
+    use sui_types::SUI_FRAMEWORK_OBJECT_ID;
+    use test_utils::network::TestClusterBuilder;
+
+    #[test]
+    fn test_response_cache() {
+        let key = |method, params: u64| CacheKey::new(method, &params).unwrap();
+        let response = |c: char| c.to_string().repeat(10);
+        // Each entry takes 1 byte of params and 12 bytes of JSON encoded response.
+        let cache = ResponseCache::new(NonZeroUsize::new(30).unwrap());
+
+        cache.insert(key(CacheableMethod::GetCheckpoint, 1), &response('a'));
+        cache.insert(key(CacheableMethod::TryGetPastObject, 1), &response('b'));
+        assert_eq!(
+            cache.get::<String>(&key(CacheableMethod::GetCheckpoint, 1)),
+            Some(response('a'))
+        );
+        assert_eq!(
+            cache.get::<String>(&key(CacheableMethod::GetCheckpoint, 2)),
+            None
+        );
+
+        // The least recently used entry is evicted once the cache is full.
+        cache.insert(key(CacheableMethod::GetCheckpoint, 2), &response('c'));
+        assert_eq!(
+            cache.get::<String>(&key(CacheableMethod::TryGetPastObject, 1)),
+            None
+        );
+        assert!(cache
+            .get::<String>(&key(CacheableMethod::GetCheckpoint, 1))
+            .is_some());
+        assert!(cache
+            .get::<String>(&key(CacheableMethod::GetCheckpoint, 2))
+            .is_some());
+
+        // Responses larger than the cache are not cached, and evict nothing.
+        cache.insert(key(CacheableMethod::GetCheckpoint, 3), &"d".repeat(30));
+        assert_eq!(
+            cache.get::<String>(&key(CacheableMethod::GetCheckpoint, 3)),
+            None
+        );
+        assert!(cache
+            .get::<String>(&key(CacheableMethod::GetCheckpoint, 1))
+            .is_some());
+    }
+
+    #[sim_test]
+    async fn test_cached_read_api() {
+        let cluster = TestClusterBuilder::new().build().await.unwrap();
+        let metrics = Arc::new(JsonRpcMetrics::new(&Registry::new()));
+        let api = CachedReadApi::new(
+            ReadApi::new(cluster.fullnode_handle.sui_node.state(), metrics.clone()),
+            NonZeroUsize::new(1 << 20).unwrap(),
+            metrics.clone(),
+        );
+        let counts = |method: CacheableMethod| {
+            let label = [method.name()];
+            (
+                metrics.response_cache_hits.with_label_values(&label).get(),
+                metrics
+                    .response_cache_misses
+                    .with_label_values(&label)
+                    .get(),
+            )
+        };
+
+        // Checkpoints are read once and then served from the cache.
+        let id = CheckpointId::SequenceNumber(0);
+        let checkpoint = api.get_checkpoint(id.clone()).await.unwrap();
+        assert_eq!(api.get_checkpoint(id).await.unwrap(), checkpoint);
+        assert_eq!(counts(CacheableMethod::GetCheckpoint), (1, 1));
+
+        // So are checkpointed transactions, requests with other options are cached separately.
+        let digest = checkpoint.transactions[0];
+        let options = Some(
+            SuiTransactionBlockResponseOptions::new()
+                .with_input()
+                .with_effects(),
+        );
+        for _ in 0..2 {
+            let response = api.get_transaction_block(digest, None).await.unwrap();
+            assert_eq!(response.checkpoint, Some(0));
+            api.get_transaction_block(digest, options.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(counts(CacheableMethod::GetTransactionBlock), (2, 2));
+
+        // Past objects are cached, unless their display fields are requested.
+        let version = api
+            .get_object(SUI_FRAMEWORK_OBJECT_ID, None)
+            .unwrap()
+            .object()
+            .unwrap()
+            .version;
+        let display = Some(SuiObjectDataOptions::new().with_display());
+        for _ in 0..2 {
+            let response = api
+                .try_get_past_object(SUI_FRAMEWORK_OBJECT_ID, version, None)
+                .await
+                .unwrap();
+            assert!(matches!(response, SuiPastObjectResponse::VersionFound(_)));
+            api.try_get_past_object(SUI_FRAMEWORK_OBJECT_ID, version, display.clone())
+                .await
+                .unwrap();
+        }
+        assert_eq!(counts(CacheableMethod::TryGetPastObject), (1, 3));
+    }
+}
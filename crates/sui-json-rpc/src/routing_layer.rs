@@ -200,7 +200,7 @@ fn process_single_request(
 }
 
 // error responses borrowed from jsonrpsee
-mod response {
+pub(crate) mod response {
     use jsonrpsee::core::__reexports::serde_json;
     use jsonrpsee::types::error::{reject_too_big_request, ErrorCode};
     use jsonrpsee::types::{ErrorResponse, Id};
@@ -18,9 +18,10 @@ use sui_json::{call_args, type_args};
 use sui_json_rpc_types::ObjectChange;
 use sui_json_rpc_types::ObjectsPage;
 use sui_json_rpc_types::{
-    Balance, CoinPage, DelegatedStake, StakeStatus, SuiCoinMetadata, SuiExecutionStatus,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffectsAPI,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions, TransactionBlockBytes,
+    Balance, CheckpointId, CoinPage, DelegatedStake, StakeStatus, SuiCoinMetadata,
+    SuiExecutionStatus, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, TransactionBlockBytes,
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_macros::sim_test;
@@ -29,6 +30,7 @@ use sui_types::base_types::ObjectID;
 use sui_types::base_types::SequenceNumber;
 use sui_types::coin::{TreasuryCap, COIN_MODULE_NAME};
 use sui_types::digests::ObjectDigest;
+use sui_types::event::EventID;
 use sui_types::gas_coin::GAS;
 use sui_types::messages::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
@@ -67,6 +69,67 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_owned_objects_filtered_by_type() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.accounts.first().unwrap();
+
+    let all_ids = http_client
+        .get_owned_objects(*address, None, None, None)
+        .await?
+        .data
+        .iter()
+        .map(|o| o.object().unwrap().object_id)
+        .collect::<Vec<_>>();
+    assert_eq!(5, all_ids.len());
+
+    let coin_tag = parse_sui_struct_tag("0x2::coin::Coin")?;
+    let filters = [
+        SuiObjectDataFilter::gas_coin(),
+        SuiObjectDataFilter::StructType(coin_tag),
+        SuiObjectDataFilter::Package(ObjectID::from(SUI_FRAMEWORK_ADDRESS)),
+        SuiObjectDataFilter::MoveModule {
+            package: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+            module: COIN_MODULE_NAME.to_owned(),
+        },
+    ];
+    for filter in filters {
+        // Page through the filtered objects two at a time, pages must follow object id order.
+        let mut ids = vec![];
+        let mut cursor = None;
+        loop {
+            let page = http_client
+                .get_owned_objects(
+                    *address,
+                    Some(SuiObjectResponseQuery::new_with_filter(filter.clone())),
+                    cursor,
+                    Some(2),
+                )
+                .await?;
+            ids.extend(page.data.iter().map(|o| o.object().unwrap().object_id));
+            cursor = page.next_cursor;
+            if !page.has_next_page {
+                break;
+            }
+        }
+        assert_eq!(all_ids, ids);
+    }
+
+    let objects = http_client
+        .get_owned_objects(
+            *address,
+            Some(SuiObjectResponseQuery::new_with_filter(
+                SuiObjectDataFilter::StructType(parse_sui_struct_tag("0x2::coin::TreasuryCap")?),
+            )),
+            None,
+            None,
+        )
+        .await?;
+    assert!(objects.data.is_empty());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_get_package_with_display_should_not_fail() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
@@ -412,7 +475,7 @@ async fn test_get_balance() -> Result<(), anyhow::Error> {
     let http_client = cluster.rpc_client();
     let address = cluster.get_address_0();
 
-    let result: Balance = http_client.get_balance(address, None).await?;
+    let result: Balance = http_client.get_balance(address, None, None).await?;
     assert_eq!("0x2::sui::SUI", result.coin_type);
     assert_eq!(
         (DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT as u64 * DEFAULT_GAS_AMOUNT) as u128,
@@ -425,6 +488,156 @@ async fn test_get_balance() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_get_balance_at_checkpoint() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+    let recipient = cluster.get_address_1();
+
+    let checkpoint_before = *http_client.get_latest_checkpoint_sequence_number().await?;
+    let balance_before: Balance = http_client.get_balance(address, None, None).await?;
+
+    let objects = http_client
+        .get_owned_objects(address, None, None, None)
+        .await?
+        .data;
+    let obj = objects.first().unwrap().object().unwrap().object_id;
+    let gas = objects.last().unwrap().object().unwrap().object_id;
+    let transaction_bytes: TransactionBlockBytes = http_client
+        .transfer_object(address, obj, Some(gas), 10_000.into(), recipient)
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx =
+        to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(&address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let tx_response = http_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            None,
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    // Wait for the transfer to be included in an executed checkpoint.
+    let checkpoint_after = loop {
+        let checkpoint = http_client
+            .get_transaction_block(tx_response.digest, None)
+            .await?
+            .checkpoint;
+        if let Some(checkpoint) = checkpoint {
+            if *http_client.get_latest_checkpoint_sequence_number().await? >= checkpoint {
+                break checkpoint;
+            }
+        }
+        sleep(Duration::from_millis(500)).await;
+    };
+
+    let balance_after: Balance = http_client.get_balance(address, None, None).await?;
+    assert_eq!(
+        balance_before.coin_object_count - 1,
+        balance_after.coin_object_count
+    );
+
+    let result = http_client
+        .get_balance(address, None, Some(checkpoint_before.into()))
+        .await?;
+    assert_eq!(balance_before.total_balance, result.total_balance);
+    assert_eq!(balance_before.coin_object_count, result.coin_object_count);
+
+    let result = http_client
+        .get_balance(address, None, Some(checkpoint_after.into()))
+        .await?;
+    assert_eq!(balance_after.total_balance, result.total_balance);
+    assert_eq!(balance_after.coin_object_count, result.coin_object_count);
+
+    // Checkpoints that have not been executed yet are rejected.
+    assert!(http_client
+        .get_balance(address, None, Some((checkpoint_after + 1_000_000).into()))
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_multi_get_checkpoints_and_events() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let http_client = cluster.rpc_client();
+    let address = cluster.get_address_0();
+
+    while *http_client.get_latest_checkpoint_sequence_number().await? < 1 {
+        sleep(Duration::from_millis(500)).await;
+    }
+    let checkpoint_0 = http_client
+        .get_checkpoint(CheckpointId::SequenceNumber(0))
+        .await?;
+    let checkpoint_1 = http_client
+        .get_checkpoint(CheckpointId::SequenceNumber(1))
+        .await?;
+    let checkpoints = http_client
+        .multi_get_checkpoints(vec![
+            CheckpointId::SequenceNumber(1),
+            CheckpointId::Digest(checkpoint_0.digest),
+        ])
+        .await?;
+    assert_eq!(vec![checkpoint_1, checkpoint_0], checkpoints);
+    assert!(http_client
+        .multi_get_checkpoints(vec![CheckpointId::SequenceNumber(u64::MAX)])
+        .await
+        .is_err());
+
+    // Staking emits events.
+    let coin = http_client
+        .get_owned_objects(address, None, None, None)
+        .await?
+        .data[0]
+        .object()?
+        .object_id;
+    let validator = http_client
+        .get_latest_sui_system_state()
+        .await?
+        .active_validators[0]
+        .sui_address;
+    let transaction_bytes: TransactionBlockBytes = http_client
+        .request_add_stake(
+            address,
+            vec![coin],
+            Some(1000000000.into()),
+            validator,
+            None,
+            100_000_000.into(),
+        )
+        .await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx =
+        to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(&address)?);
+    let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
+    let response = http_client
+        .execute_transaction_block(
+            tx_bytes,
+            signatures,
+            None,
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+
+    let events = http_client.get_events(response.digest).await?;
+    assert!(!events.is_empty());
+    let ids = events.iter().rev().map(|e| e.id.clone()).collect();
+    let multi_events = http_client.multi_get_events(ids).await?;
+    assert_eq!(events.into_iter().rev().collect::<Vec<_>>(), multi_events);
+
+    let missing = EventID {
+        tx_digest: response.digest,
+        event_seq: 1_000,
+    };
+    assert!(http_client.multi_get_events(vec![missing]).await.is_err());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_get_metadata() -> Result<(), anyhow::Error> {
     let cluster = TestClusterBuilder::new().build().await?;
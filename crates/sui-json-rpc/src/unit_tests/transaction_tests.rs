@@ -1,17 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::str::FromStr;
+
 use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_json_rpc_types::SuiTransactionBlockResponseQuery;
 use sui_json_rpc_types::{
-    SuiObjectDataOptions, SuiObjectResponseQuery, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions, TransactionBlockBytes,
+    SuiObjectDataOptions, SuiObjectResponseQuery, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions, TransactionBlockBytes,
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_macros::sim_test;
-use sui_types::messages::{ExecuteTransactionRequestType, SenderSignedData};
+use sui_sdk::ptb_builder::MoveCallArg;
+use sui_types::gas_coin::GAS;
+use sui_types::messages::TransactionDataAPI;
+use sui_types::messages::{Argument, ExecuteTransactionRequestType, SenderSignedData};
 use sui_types::query::TransactionFilter;
 use sui_types::utils::to_sender_signed_transaction;
+use sui_types::SUI_FRAMEWORK_OBJECT_ID;
 use test_utils::network::TestClusterBuilder;
 
 use crate::api::{IndexerApiClient, TransactionBuilderClient, WriteApiClient};
@@ -327,3 +333,112 @@ async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[sim_test]
+async fn test_ptb_builder() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let address = cluster.accounts.first().unwrap();
+    let client = cluster.wallet.get_client().await?;
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(*address, None, None, None)
+        .await?
+        .data;
+    let coin_id = coins.first().unwrap().coin_object_id;
+
+    let mut ptb = client.ptb_builder(*address);
+    let coin = ptb.object(coin_id).await?;
+    ptb.move_call(
+        SUI_FRAMEWORK_OBJECT_ID,
+        "pay",
+        "split",
+        vec![GAS::type_tag()],
+        vec![
+            MoveCallArg::Argument(coin),
+            MoveCallArg::Json(sui_json::call_arg!(10u64)?),
+        ],
+    )
+    .await?;
+    let split = ptb.split_coins(Argument::GasCoin, vec![20])?;
+    ptb.transfer_objects(vec![split], *address)?;
+    let data = ptb.build().await?;
+
+    // The budget is estimated with a dry run and coins used as inputs don't pay for gas.
+    assert!(data.gas_budget() > 0);
+    assert!(data.gas().iter().all(|(id, _, _)| *id != coin_id));
+
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let tx = to_sender_signed_transaction(data, keystore.get_key(address)?);
+    let response = client
+        .quorum_driver()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let effects = response.effects.unwrap();
+    assert!(effects.status().is_ok());
+    assert_eq!(2, effects.created().len());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb_builder_split_gas() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let address = cluster.accounts.first().unwrap();
+    let client = cluster.wallet.get_client().await?;
+    let keystore_path = cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+
+    let coins = client
+        .coin_read_api()
+        .get_coins(*address, None, None, None)
+        .await?
+        .data;
+    let amount = coins.iter().map(|coin| coin.balance).max().unwrap();
+
+    // The gas coins cover both the budget and the amount split from the gas coin, which is more
+    // than a single coin holds.
+    let mut ptb = client.ptb_builder(*address);
+    let split = ptb.split_coins(ptb.gas(), vec![amount])?;
+    ptb.transfer_objects(vec![split], *address)?;
+    let data = ptb.build().await?;
+    let gas_balance: u128 = coins
+        .iter()
+        .filter(|coin| {
+            data.gas()
+                .iter()
+                .any(|(id, _, _)| *id == coin.coin_object_id)
+        })
+        .map(|coin| coin.balance as u128)
+        .sum();
+    assert!(gas_balance >= amount as u128 + data.gas_budget() as u128);
+
+    let tx = to_sender_signed_transaction(data, keystore.get_key(address)?);
+    let response = client
+        .quorum_driver()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    let effects = response.effects.unwrap();
+    assert!(effects.status().is_ok());
+    assert_eq!(1, effects.created().len());
+
+    // All the SUI coins pay for gas when the gas coin itself is transferred.
+    let coins = client
+        .coin_read_api()
+        .get_coins(*address, None, None, None)
+        .await?
+        .data;
+    let mut ptb = client.ptb_builder(*address);
+    ptb.transfer_objects(vec![ptb.gas()], *address)?;
+    let data = ptb.build().await?;
+    assert_eq!(coins.len(), data.gas().len());
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use jsonrpsee_proc_macros::rpc;
+use prometheus::Registry;
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use sui_config::node::{JsonRpcRateLimitConfig, RateLimit};
+use sui_config::utils::get_available_port;
+use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+
+const FOO_REQUEST: &str = r#"{"jsonrpc":"2.0","id":1,"method":"test_foo","params":[]}"#;
+
+async fn post(url: &str, body: String, forwarded_for: Option<&str>) -> reqwest::StatusCode {
+    let mut request = reqwest::Client::new()
+        .post(url)
+        .header("content-type", "application/json")
+        .body(body);
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.header("x-forwarded-for", forwarded_for);
+    }
+    request.send().await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_rate_limited_server() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+    builder.set_rate_limit_config(JsonRpcRateLimitConfig {
+        per_ip: BTreeMap::from([(
+            "*".to_string(),
+            RateLimit {
+                requests_per_second: 0,
+                burst: 3,
+            },
+        )]),
+        api_keys: BTreeMap::new(),
+        api_key_header: "x-api-key".to_string(),
+        trusted_proxies: vec![],
+    });
+
+    let port = get_available_port("127.0.0.1");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://127.0.0.1:{}", port);
+
+    // Requests within the limit are served.
+    assert_eq!(
+        post(&url, FOO_REQUEST.to_string(), None).await,
+        reqwest::StatusCode::OK
+    );
+
+    // A batch over the limit is rejected as a whole, without using up the quota.
+    let batch = format!("[{FOO_REQUEST},{FOO_REQUEST},{FOO_REQUEST}]");
+    assert_eq!(
+        post(&url, batch, None).await,
+        reqwest::StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        post(&url, FOO_REQUEST.to_string(), None).await,
+        reqwest::StatusCode::OK
+    );
+
+    // Clients of an untrusted peer cannot pick their identity with X-Forwarded-For.
+    assert_eq!(
+        post(&url, FOO_REQUEST.to_string(), Some("1.2.3.4")).await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        post(&url, FOO_REQUEST.to_string(), Some("5.6.7.8")).await,
+        reqwest::StatusCode::TOO_MANY_REQUESTS
+    );
+
+    handle.stop().unwrap()
+}
+
+#[open_rpc(namespace = "test")]
+#[rpc(server, client, namespace = "test")]
+trait TestApi {
+    #[method(name = "foo")]
+    async fn foo(&self) -> RpcResult<String>;
+}
+
+struct TestApiModule;
+
+#[async_trait]
+impl TestApiServer for TestApiModule {
+    async fn foo(&self) -> RpcResult<String> {
+        Ok("Some string".into())
+    }
+}
+
+impl SuiRpcModule for TestApiModule {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+    fn rpc_doc_module() -> Module {
+        TestApiOpenRpc::module_doc()
+    }
+}
@@ -8,7 +8,9 @@ use std::str::FromStr;
 use anyhow::{anyhow, bail};
 use fastcrypto::encoding::{Encoding, Hex};
 use move_binary_format::{
-    access::ModuleAccess, binary_views::BinaryIndexedView, file_format::SignatureToken,
+    access::ModuleAccess,
+    binary_views::BinaryIndexedView,
+    file_format::{CompiledModule, SignatureToken},
     file_format_common::VERSION_MAX,
 };
 use move_core_types::account_address::AccountAddress;
@@ -795,8 +797,66 @@ pub fn resolve_move_function_args(
     combined_args_json: Vec<SuiJsonValue>,
     allow_arbitrary_function_call: bool,
 ) -> Result<Vec<(ResolvedCallArg, SignatureToken)>, anyhow::Error> {
-    // Extract the expected function signature
     let module = package.deserialize_module(&module_ident, VERSION_MAX)?;
+    let parameters = function_parameters(
+        &module,
+        &module_ident,
+        &function,
+        combined_args_json.len(),
+        allow_arbitrary_function_call,
+    )?;
+    let view = BinaryIndexedView::Module(&module);
+    // Check that the args are valid and convert to the correct format
+    let call_args = resolve_call_args(&view, type_args, &combined_args_json, &parameters)?;
+    let tupled_call_args = call_args
+        .into_iter()
+        .zip(parameters.into_iter())
+        .collect::<Vec<_>>();
+    Ok(tupled_call_args)
+}
+
+/// Like [resolve_move_function_args], but only resolves the args that are given as JSON. Args that
+/// are `None` are left unresolved, e.g. because they are results of earlier commands of a
+/// programmable transaction, and are returned with their expected type only.
+pub fn resolve_move_function_partial_args(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    args: Vec<Option<SuiJsonValue>>,
+    allow_arbitrary_function_call: bool,
+) -> Result<Vec<(Option<ResolvedCallArg>, SignatureToken)>, anyhow::Error> {
+    let module = package.deserialize_module(&module_ident, VERSION_MAX)?;
+    let parameters = function_parameters(
+        &module,
+        &module_ident,
+        &function,
+        args.len(),
+        allow_arbitrary_function_call,
+    )?;
+    let view = BinaryIndexedView::Module(&module);
+    args.iter()
+        .zip(parameters.into_iter())
+        .enumerate()
+        .map(|(idx, (arg, param))| {
+            let arg = arg
+                .as_ref()
+                .map(|arg| resolve_call_arg(&view, type_args, idx, arg, &param))
+                .transpose()?;
+            Ok((arg, param))
+        })
+        .collect()
+}
+
+/// Returns the parameters of `function` the caller has to supply, i.e. without the TxContext.
+fn function_parameters(
+    module: &CompiledModule,
+    module_ident: &Identifier,
+    function: &Identifier,
+    num_args: usize,
+    allow_arbitrary_function_call: bool,
+) -> Result<Vec<SignatureToken>, anyhow::Error> {
+    // Extract the expected function signature
     let function_str = function.as_ident_str();
     let fdef = module
         .function_defs
@@ -822,28 +882,17 @@ pub fn resolve_move_function_args(
         )
     }
 
-    let view = BinaryIndexedView::Module(&module);
+    let view = BinaryIndexedView::Module(module);
 
     // Lengths have to match, less one, due to TxContext
     let expected_len = match parameters.last() {
         Some(param) if is_tx_context(&view, param) != TxContextKind::None => parameters.len() - 1,
         _ => parameters.len(),
     };
-    if combined_args_json.len() != expected_len {
-        bail!(
-            "Expected {} args, found {}",
-            expected_len,
-            combined_args_json.len()
-        );
+    if num_args != expected_len {
+        bail!("Expected {} args, found {}", expected_len, num_args);
     }
-    // Check that the args are valid and convert to the correct format
-    let call_args = resolve_call_args(&view, type_args, &combined_args_json, parameters)?;
-    let tupled_call_args = call_args
-        .into_iter()
-        .zip(parameters.iter())
-        .map(|(arg, expected_type)| (arg, expected_type.clone()))
-        .collect::<Vec<_>>();
-    Ok(tupled_call_args)
+    Ok(parameters[..expected_len].to_vec())
 }
 
 fn convert_string_to_u256(s: &str) -> Result<U256, anyhow::Error> {
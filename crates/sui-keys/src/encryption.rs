@@ -0,0 +1,180 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Passphrase based encryption of keystore files. Keys are encrypted with AES-256-GCM under a key
+//! derived from the passphrase with Argon2id.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, ensure};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Environment variable the keystore passphrase is read from instead of prompting for it.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+/// Environment variable the new passphrase is read from when changing the keystore passphrase.
+pub const SUI_KEYSTORE_NEW_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_NEW_PASSPHRASE";
+
+const ENCRYPTED_DATA_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// Argon2id parameters the encryption key was derived with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    /// Base64 encoded salt.
+    pub salt: String,
+    /// Memory size in KiB.
+    pub memory_cost: u32,
+    /// Number of iterations.
+    pub time_cost: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    fn new_with_random_salt() -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            salt: Base64::encode(salt),
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, anyhow::Error> {
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid KDF salt: {e}"))?;
+        let params = Params::new(
+            self.memory_cost,
+            self.time_cost,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .map_err(|e| anyhow!("Invalid KDF params: {e}"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| anyhow!("Cannot derive encryption key: {e}"))?;
+        Ok(key)
+    }
+}
+
+/// Encryption key derived from a passphrase, so that the passphrase itself does not need to be
+/// kept around to encrypt data again. The key is zeroed when dropped.
+pub struct EncryptionKey {
+    kdf: KdfParams,
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+}
+
+impl EncryptionKey {
+    /// Derives a key from the passphrase with a fresh salt.
+    pub fn new(passphrase: &str) -> Result<Self, anyhow::Error> {
+        let kdf = KdfParams::new_with_random_salt();
+        let key = kdf.derive_key(passphrase)?;
+        Ok(Self { kdf, key })
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key[..]))
+    }
+}
+
+/// Data encrypted under a passphrase, as stored in encrypted keystore files.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedData {
+    pub version: u8,
+    pub kdf: KdfParams,
+    /// Base64 encoded AES-GCM nonce.
+    pub nonce: String,
+    /// Base64 encoded ciphertext, including the authentication tag.
+    pub ciphertext: String,
+}
+
+impl EncryptedData {
+    /// Encrypts the plaintext under the key with a fresh nonce.
+    pub fn encrypt(plaintext: &[u8], key: &EncryptionKey) -> Result<Self, anyhow::Error> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = key
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &[ENCRYPTED_DATA_VERSION],
+                },
+            )
+            .map_err(|e| anyhow!("Cannot encrypt data: {e}"))?;
+        Ok(Self {
+            version: ENCRYPTED_DATA_VERSION,
+            kdf: key.kdf.clone(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the data, fails if the passphrase is wrong or the data was tampered with. Returns
+    /// the plaintext and the key derived from the passphrase, to encrypt data again with.
+    pub fn decrypt(&self, passphrase: &str) -> Result<(Vec<u8>, EncryptionKey), anyhow::Error> {
+        ensure!(
+            self.version == ENCRYPTED_DATA_VERSION,
+            "Unsupported encrypted data version {}",
+            self.version
+        );
+        let nonce = Base64::decode(&self.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid nonce length");
+        let ciphertext =
+            Base64::decode(&self.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        let key = EncryptionKey {
+            kdf: self.kdf.clone(),
+            key: self.kdf.derive_key(passphrase)?,
+        };
+        let plaintext = key
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &[self.version],
+                },
+            )
+            .map_err(|_| anyhow!("Cannot decrypt data, wrong passphrase or corrupted data"))?;
+        Ok((plaintext, key))
+    }
+}
+
+/// Reads the passphrase from [SUI_KEYSTORE_PASSPHRASE_ENV], or prompts for it if not set.
+pub fn read_passphrase(prompt: &str) -> Result<Zeroizing<String>, anyhow::Error> {
+    match std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+        Ok(passphrase) => Ok(Zeroizing::new(passphrase)),
+        Err(_) => Ok(Zeroizing::new(rpassword::prompt_password(prompt)?)),
+    }
+}
+
+/// Reads a new passphrase from the environment variable `env`, or prompts for it twice if not set.
+pub fn read_new_passphrase(env: &str) -> Result<Zeroizing<String>, anyhow::Error> {
+    let passphrase = match std::env::var(env) {
+        Ok(passphrase) => Zeroizing::new(passphrase),
+        Err(_) => {
+            let passphrase = Zeroizing::new(rpassword::prompt_password(
+                "Enter new keystore passphrase: ",
+            )?);
+            let confirmation = Zeroizing::new(rpassword::prompt_password(
+                "Confirm new keystore passphrase: ",
+            )?);
+            ensure!(passphrase == confirmation, "Passphrases do not match");
+            passphrase
+        }
+    };
+    ensure!(!passphrase.is_empty(), "Passphrase cannot be empty");
+    Ok(passphrase)
+}
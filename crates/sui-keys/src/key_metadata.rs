@@ -0,0 +1,157 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, ensure};
+use bip32::DerivationPath;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sui_types::base_types::SuiAddress;
+
+use crate::keystore::write_atomically;
+
+/// Metadata of a key in a keystore. Keystore files only hold keys, their metadata is stored
+/// next to them, see [metadata_path].
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// When the key was added to the keystore, in milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Path the key was derived from its mnemonic with, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+}
+
+impl KeyMetadata {
+    /// Metadata of a key added now.
+    pub fn new(derivation_path: Option<&DerivationPath>) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .ok();
+        Self {
+            alias: None,
+            created_at,
+            derivation_path: derivation_path.map(|path| path.to_string()),
+        }
+    }
+}
+
+/// A Sui address, or the alias of a key of the keystore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyIdentity {
+    Address(SuiAddress),
+    Alias(String),
+}
+
+impl FromStr for KeyIdentity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match SuiAddress::from_str(s) {
+            Ok(address) => Ok(Self::Address(address)),
+            Err(_) if s.starts_with("0x") => Err(anyhow!("Invalid address {s}")),
+            Err(_) => Ok(Self::Alias(s.to_string())),
+        }
+    }
+}
+
+impl Display for KeyIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Alias(alias) => write!(f, "{alias}"),
+        }
+    }
+}
+
+impl From<SuiAddress> for KeyIdentity {
+    fn from(address: SuiAddress) -> Self {
+        Self::Address(address)
+    }
+}
+
+/// Aliases start with a letter and only contain letters, digits, `-` and `_`, so they cannot be
+/// confused with addresses.
+pub fn validate_alias(alias: &str) -> Result<(), anyhow::Error> {
+    let mut chars = alias.chars();
+    ensure!(
+        chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            && SuiAddress::from_str(alias).is_err(),
+        "Invalid alias [{alias}], aliases must start with a letter and only contain letters, \
+        digits, '-' and '_'"
+    );
+    Ok(())
+}
+
+/// Sets or removes the alias of `address`, checking that no other key uses it.
+pub(crate) fn update_alias(
+    metadata: &mut BTreeMap<SuiAddress, KeyMetadata>,
+    address: &SuiAddress,
+    alias: Option<String>,
+) -> Result<(), anyhow::Error> {
+    if let Some(alias) = &alias {
+        validate_alias(alias)?;
+        if let Some((other, _)) = metadata
+            .iter()
+            .find(|(other, m)| *other != address && m.alias.as_ref() == Some(alias))
+        {
+            bail!("Alias [{alias}] is already used by [{other}]");
+        }
+    }
+    metadata.entry(*address).or_default().alias = alias;
+    Ok(())
+}
+
+/// Path of the file storing the metadata of the keys of the keystore file at `keystore_path`.
+pub fn metadata_path(keystore_path: &Path) -> PathBuf {
+    keystore_path.with_extension("metadata")
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyMetadataEntry {
+    address: SuiAddress,
+    #[serde(flatten)]
+    metadata: KeyMetadata,
+}
+
+pub(crate) fn read_metadata(
+    keystore_path: &Path,
+) -> Result<BTreeMap<SuiAddress, KeyMetadata>, anyhow::Error> {
+    let path = metadata_path(keystore_path);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let entries: Vec<KeyMetadataEntry> = serde_json::from_slice(&fs::read(&path)?)
+        .map_err(|e| anyhow!("Can't deserialize key metadata from {:?}: {e}", path))?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| (entry.address, entry.metadata))
+        .collect())
+}
+
+pub(crate) fn write_metadata(
+    keystore_path: &Path,
+    metadata: &BTreeMap<SuiAddress, KeyMetadata>,
+) -> Result<(), anyhow::Error> {
+    let path = metadata_path(keystore_path);
+    if metadata.is_empty() && !path.exists() {
+        return Ok(());
+    }
+    let entries: Vec<_> = metadata
+        .iter()
+        .map(|(address, metadata)| KeyMetadataEntry {
+            address: *address,
+            metadata: metadata.clone(),
+        })
+        .collect();
+    write_atomically(&path, serde_json::to_string_pretty(&entries)?.as_bytes())
+}
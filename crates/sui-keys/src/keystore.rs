@@ -1,10 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
-use anyhow::anyhow;
+use crate::encryption::{read_new_passphrase, read_passphrase, EncryptedData, EncryptionKey};
+use crate::key_derive::{derive_key_pair_from_path, generate_new_key, validate_path};
+use crate::key_metadata::{read_metadata, update_alias, write_metadata, KeyIdentity, KeyMetadata};
+use crate::remote_signer::{RemoteSignerEndpoint, RemoteSignerRequest};
+use anyhow::{anyhow, bail};
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::VerifyingKey;
 use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use shared_crypto::intent::{Intent, IntentMessage};
@@ -13,19 +18,44 @@ use std::fmt::Write;
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Write as _};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::get_key_pair_from_rng;
 use sui_types::crypto::{
     enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
+    SuiSignature, SuiSignatureInner,
 };
+use sui_types::error::{SuiError, SuiResult};
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileKeystore),
+    Remote(RemoteKeystore),
+}
+
+impl Keystore {
+    /// Path of the keystore file, `None` for keystores not stored in a file.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Keystore::File(keystore) => keystore.path(),
+            Keystore::Encrypted(keystore) => Some(keystore.path()),
+            Keystore::InMem(_) | Keystore::Remote(_) => None,
+        }
+    }
+
+    /// Opens the keystore file at `path`, prompting for the passphrase if it is encrypted.
+    pub fn from_path(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        Ok(if is_encrypted_keystore(path) {
+            Keystore::from(EncryptedFileKeystore::new(path)?)
+        } else {
+            Keystore::from(FileBasedKeystore::new(path)?)
+        })
+    }
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -47,15 +77,59 @@ pub trait AccountKeystore: Send + Sync {
         self.keys().iter().map(|k| k.into()).collect()
     }
 
+    /// Same as [Self::addresses], but fails if the keys cannot be listed rather than listing none.
+    fn try_addresses(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        Ok(self.addresses())
+    }
+
+    /// Adds the key along with its metadata, keystores not storing metadata drop it.
+    fn add_key_with_metadata(
+        &mut self,
+        keypair: SuiKeyPair,
+        _metadata: KeyMetadata,
+    ) -> Result<(), anyhow::Error> {
+        self.add_key(keypair)
+    }
+
+    fn get_metadata(&self, _address: &SuiAddress) -> Option<&KeyMetadata> {
+        None
+    }
+
+    /// Sets the alias of the key of `address`, or removes it if `alias` is None.
+    fn set_alias(
+        &mut self,
+        _address: &SuiAddress,
+        _alias: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        Err(anyhow!("Keystore does not support aliases"))
+    }
+
+    fn get_alias(&self, address: &SuiAddress) -> Option<&str> {
+        self.get_metadata(address)?.alias.as_deref()
+    }
+
+    /// Returns the address of `identity`, looking up aliases among the keys of this keystore.
+    fn get_address_by_identity(&self, identity: &KeyIdentity) -> Result<SuiAddress, anyhow::Error> {
+        match identity {
+            KeyIdentity::Address(address) => Ok(*address),
+            KeyIdentity::Alias(alias) => self
+                .addresses()
+                .into_iter()
+                .find(|address| self.get_alias(address) == Some(alias.as_str()))
+                .ok_or_else(|| anyhow!("Cannot find key with alias [{alias}]")),
+        }
+    }
+
     fn generate_and_add_new_key(
         &mut self,
         key_scheme: SignatureScheme,
         derivation_path: Option<DerivationPath>,
         word_length: Option<String>,
     ) -> Result<(SuiAddress, String, SignatureScheme), anyhow::Error> {
+        let path = validate_path(&key_scheme, derivation_path)?;
         let (address, kp, scheme, phrase) =
-            generate_new_key(key_scheme, derivation_path, word_length)?;
-        self.add_key(kp)?;
+            generate_new_key(key_scheme, Some(path.clone()), word_length)?;
+        self.add_key_with_metadata(kp, KeyMetadata::new(Some(&path)))?;
         Ok((address, phrase, scheme))
     }
 
@@ -68,9 +142,10 @@ pub trait AccountKeystore: Send + Sync {
         let mnemonic = Mnemonic::from_phrase(phrase, Language::English)
             .map_err(|e| anyhow::anyhow!("Invalid mnemonic phrase: {:?}", e))?;
         let seed = Seed::new(&mnemonic, "");
-        match derive_key_pair_from_path(seed.as_bytes(), derivation_path, &key_scheme) {
+        match derive_key_pair_from_path(seed.as_bytes(), derivation_path.clone(), &key_scheme) {
             Ok((address, kp)) => {
-                self.add_key(kp)?;
+                let path = validate_path(&key_scheme, derivation_path)?;
+                self.add_key_with_metadata(kp, KeyMetadata::new(Some(&path)))?;
                 Ok(address)
             }
             Err(e) => Err(anyhow!("error getting keypair {:?}", e)),
@@ -91,6 +166,16 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                write!(writer, "Keystore Path : {:?}", file.path)?;
+                write!(f, "{}", writer)
+            }
+            Keystore::Remote(remote) => {
+                writeln!(writer, "Keystore Type : Remote")?;
+                write!(writer, "Keystore Endpoint : {}", remote.endpoint)?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -98,6 +183,7 @@ impl Display for Keystore {
 #[derive(Default)]
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    metadata: BTreeMap<SuiAddress, KeyMetadata>,
     path: Option<PathBuf>,
 }
 
@@ -154,12 +240,37 @@ impl AccountKeystore for FileBasedKeystore {
     }
 
     fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.add_key_with_metadata(keypair, KeyMetadata::new(None))
+    }
+
+    fn add_key_with_metadata(
+        &mut self,
+        keypair: SuiKeyPair,
+        metadata: KeyMetadata,
+    ) -> Result<(), anyhow::Error> {
         let address: SuiAddress = (&keypair.public()).into();
         self.keys.insert(address, keypair);
+        self.metadata.entry(address).or_insert(metadata);
         self.save()?;
         Ok(())
     }
 
+    fn get_metadata(&self, address: &SuiAddress) -> Option<&KeyMetadata> {
+        self.metadata.get(address)
+    }
+
+    fn set_alias(
+        &mut self,
+        address: &SuiAddress,
+        alias: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        if !self.keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        update_alias(&mut self.metadata, address, alias)?;
+        self.save()
+    }
+
     fn keys(&self) -> Vec<PublicKey> {
         self.keys.values().map(|key| key.public()).collect()
     }
@@ -179,15 +290,15 @@ impl FileBasedKeystore {
                 File::open(path)
                     .map_err(|e| anyhow!("Can't open FileBasedKeystore from {:?}: {e}", path))?,
             );
+            if is_encrypted_keystore(path) {
+                bail!(
+                    "Keystore {:?} is encrypted, open it as an encrypted keystore",
+                    path
+                );
+            }
             let kp_strings: Vec<String> = serde_json::from_reader(reader)
                 .map_err(|e| anyhow!("Can't deserialize FileBasedKeystore from {:?}: {e}", path))?;
-            kp_strings
-                .iter()
-                .map(|kpstr| {
-                    let key = SuiKeyPair::decode_base64(kpstr);
-                    key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
-                })
-                .collect::<Result<BTreeMap<_, _>, _>>()
+            decode_key_pairs(&kp_strings)
                 .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?
         } else {
             BTreeMap::new()
@@ -195,6 +306,7 @@ impl FileBasedKeystore {
 
         Ok(Self {
             keys,
+            metadata: read_metadata(path)?,
             path: Some(path.to_path_buf()),
         })
     }
@@ -205,27 +317,392 @@ impl FileBasedKeystore {
 
     pub fn save(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
-            let store = serde_json::to_string_pretty(
-                &self
-                    .keys
-                    .values()
-                    .map(EncodeDecodeBase64::encode_base64)
-                    .collect::<Vec<_>>(),
-            )
-            .unwrap();
-            fs::write(path, store)?
+            let store = serde_json::to_string_pretty(&encode_key_pairs(&self.keys)).unwrap();
+            write_atomically(path, store.as_bytes())?;
+            write_metadata(path, &self.metadata)?;
         }
         Ok(())
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
+        self.keys.values().collect()
+    }
+}
+
+fn decode_key_pairs(
+    kp_strings: &[String],
+) -> Result<BTreeMap<SuiAddress, SuiKeyPair>, anyhow::Error> {
+    kp_strings
+        .iter()
+        .map(|kpstr| {
+            let key = SuiKeyPair::decode_base64(kpstr).map_err(|e| anyhow!("{e}"));
+            key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
+        })
+        .collect()
+}
+
+/// Replaces the contents of `path` through a temporary file of the same directory, synced to
+/// disk before being renamed over `path`, so that a crash while saving never leaves a truncated
+/// file behind. The permissions of an existing file are kept.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path)?;
+    // The rename is only durable once the directory itself is synced.
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+fn encode_key_pairs(keys: &BTreeMap<SuiAddress, SuiKeyPair>) -> Vec<String> {
+    keys.values()
+        .map(EncodeDecodeBase64::encode_base64)
+        .collect()
+}
+
+/// Returns true if the file at `path` is an encrypted keystore.
+pub fn is_encrypted_keystore(path: &Path) -> bool {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<EncryptedData>(&bytes).ok())
+        .is_some()
+}
+
+/// Keystore file whose keys are encrypted at rest under a passphrase, see [crate::encryption].
+pub struct EncryptedFileKeystore {
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    metadata: BTreeMap<SuiAddress, KeyMetadata>,
+    path: PathBuf,
+    /// Key derived from the passphrase, the passphrase itself is not kept in memory.
+    key: EncryptionKey,
+}
+
+impl Serialize for EncryptedFileKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(
+            msg,
+            self.keys.get(address).ok_or_else(|| {
+                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+            })?,
+        ))
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.keys.get(address).ok_or_else(|| {
+                signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+            })?,
+        ))
+    }
+
+    fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.add_key_with_metadata(keypair, KeyMetadata::new(None))
+    }
+
+    fn add_key_with_metadata(
+        &mut self,
+        keypair: SuiKeyPair,
+        metadata: KeyMetadata,
+    ) -> Result<(), anyhow::Error> {
+        let address: SuiAddress = (&keypair.public()).into();
+        self.keys.insert(address, keypair);
+        self.metadata.entry(address).or_insert(metadata);
+        self.save()?;
+        Ok(())
+    }
+
+    fn get_metadata(&self, address: &SuiAddress) -> Option<&KeyMetadata> {
+        self.metadata.get(address)
+    }
+
+    fn set_alias(
+        &mut self,
+        address: &SuiAddress,
+        alias: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        if !self.keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        update_alias(&mut self.metadata, address, alias)?;
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.keys.values().map(|key| key.public()).collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        match self.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+}
+
+impl EncryptedFileKeystore {
+    /// Opens the keystore at `path`. The passphrase is read from `SUI_KEYSTORE_PASSPHRASE` or
+    /// prompted for, a new passphrase is asked for if the keystore does not exist yet.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let passphrase = if path.exists() {
+            read_passphrase(&format!("Enter passphrase to unlock keystore {:?}: ", path))?
+        } else {
+            read_new_passphrase(crate::encryption::SUI_KEYSTORE_PASSPHRASE_ENV)?
+        };
+        Self::new_with_passphrase(path, &passphrase)
+    }
+
+    pub fn new_with_passphrase(path: &PathBuf, passphrase: &str) -> Result<Self, anyhow::Error> {
+        let (keys, key) = if path.exists() {
+            let reader =
+                BufReader::new(File::open(path).map_err(|e| {
+                    anyhow!("Can't open EncryptedFileKeystore from {:?}: {e}", path)
+                })?);
+            let data: EncryptedData = serde_json::from_reader(reader).map_err(|e| {
+                anyhow!(
+                    "Can't deserialize EncryptedFileKeystore from {:?}: {e}",
+                    path
+                )
+            })?;
+            let (plaintext, key) = data.decrypt(passphrase)?;
+            let kp_strings: Vec<String> = serde_json::from_slice(&plaintext)
+                .map_err(|e| anyhow!("Can't deserialize decrypted keys from {:?}: {e}", path))?;
+            let keys = decode_key_pairs(&kp_strings)
+                .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?;
+            (keys, key)
+        } else {
+            (BTreeMap::new(), EncryptionKey::new(passphrase)?)
+        };
+
+        Ok(Self {
+            keys,
+            metadata: read_metadata(path)?,
+            path: path.to_path_buf(),
+            key,
+        })
+    }
+
+    /// Encrypts the plaintext keystore at `path` in place.
+    pub fn encrypt_file_keystore(path: &PathBuf, passphrase: &str) -> Result<Self, anyhow::Error> {
+        let keystore = FileBasedKeystore::new(path)?;
+        let keystore = Self {
+            keys: keystore.keys,
+            metadata: keystore.metadata,
+            path: path.to_path_buf(),
+            key: EncryptionKey::new(passphrase)?,
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Re-encrypts the keystore under the new passphrase.
+    pub fn change_passphrase(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        self.key = EncryptionKey::new(passphrase)?;
+        self.save()
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let plaintext = serde_json::to_vec(&encode_key_pairs(&self.keys))?;
+        let store = serde_json::to_string_pretty(&EncryptedData::encrypt(&plaintext, &self.key)?)?;
+        write_atomically(&self.path, store.as_bytes())?;
+        write_metadata(&self.path, &self.metadata)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
         self.keys.values().collect()
     }
 }
 
+/// Keystore whose keys are held by a separate signer process, private keys never enter this
+/// process. See [crate::remote_signer] for the protocol. The signer is only connected to when
+/// its keys or signatures are needed.
+pub struct RemoteKeystore {
+    endpoint: RemoteSignerEndpoint,
+    keys: Mutex<Option<Vec<PublicKey>>>,
+}
+
+impl Serialize for RemoteKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.endpoint.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for RemoteKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let endpoint = String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)?;
+        Ok(RemoteKeystore::new(endpoint))
+    }
+}
+
+impl AccountKeystore for RemoteKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let signature = self
+            .endpoint
+            .sign(&RemoteSignerRequest::SignHashed {
+                address: *address,
+                message: Base64::encode(msg),
+            })
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        // Don't trust the signer to sign with the right key.
+        verify_hashed(&signature, msg, *address).map_err(|e| {
+            signature::Error::from_source(format!("Invalid signature from signer: {e}"))
+        })?;
+        Ok(signature)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?;
+        let signature = self
+            .endpoint
+            .sign(&RemoteSignerRequest::Sign {
+                address: *address,
+                message: Base64::encode(bytes),
+            })
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        // Don't trust the signer to sign with the right key.
+        signature
+            .verify_secure(&intent_msg, *address)
+            .map_err(|e| {
+                signature::Error::from_source(format!("Invalid signature from signer: {e}"))
+            })?;
+        Ok(signature)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Cannot add keys to a remote keystore, add them to the signer at {}",
+            self.endpoint
+        ))
+    }
+
+    /// Lists no keys if the signer cannot be reached, use [AccountKeystore::try_addresses] to get
+    /// the error.
+    fn keys(&self) -> Vec<PublicKey> {
+        self.fetch_keys().unwrap_or_default()
+    }
+
+    fn try_addresses(&self) -> Result<Vec<SuiAddress>, anyhow::Error> {
+        Ok(self.fetch_keys()?.iter().map(SuiAddress::from).collect())
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "Reading the private key of [{address}] is not supported with a remote signer, it is \
+             held by the signer at {}",
+            self.endpoint
+        ))
+    }
+}
+
+impl RemoteKeystore {
+    pub fn new(endpoint: RemoteSignerEndpoint) -> Self {
+        Self {
+            endpoint,
+            keys: Mutex::new(None),
+        }
+    }
+
+    /// Returns the keys of the signer, connecting to it to list them the first time.
+    pub fn fetch_keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        let mut keys = self.keys.lock().unwrap();
+        if keys.is_none() {
+            *keys = Some(self.endpoint.keys()?);
+        }
+        Ok(keys.clone().unwrap_or_default())
+    }
+
+    pub fn endpoint(&self) -> &RemoteSignerEndpoint {
+        &self.endpoint
+    }
+}
+
+/// Verifies that `signature` signs `msg` as is, as [Signature::new_hashed] does, with the key of
+/// `address`.
+fn verify_hashed(signature: &Signature, msg: &[u8], address: SuiAddress) -> SuiResult<()> {
+    fn verify<S: SuiSignatureInner>(
+        signature: &S,
+        msg: &[u8],
+        address: SuiAddress,
+    ) -> SuiResult<()> {
+        let (sig, pk) = signature.get_verification_inputs(address)?;
+        pk.verify(msg, &sig)
+            .map_err(|e| SuiError::InvalidSignature {
+                error: format!("Fail to verify user sig {e}"),
+            })
+    }
+    match signature {
+        Signature::Ed25519SuiSignature(signature) => verify(signature, msg, address),
+        Signature::Secp256k1SuiSignature(signature) => verify(signature, msg, address),
+        Signature::Secp256r1SuiSignature(signature) => verify(signature, msg, address),
+    }
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    #[serde(default)]
+    metadata: BTreeMap<SuiAddress, KeyMetadata>,
 }
 
 impl AccountKeystore for InMemKeystore {
@@ -255,11 +732,35 @@ impl AccountKeystore for InMemKeystore {
     }
 
     fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.add_key_with_metadata(keypair, KeyMetadata::new(None))
+    }
+
+    fn add_key_with_metadata(
+        &mut self,
+        keypair: SuiKeyPair,
+        metadata: KeyMetadata,
+    ) -> Result<(), anyhow::Error> {
         let address: SuiAddress = (&keypair.public()).into();
         self.keys.insert(address, keypair);
+        self.metadata.entry(address).or_insert(metadata);
         Ok(())
     }
 
+    fn get_metadata(&self, address: &SuiAddress) -> Option<&KeyMetadata> {
+        self.metadata.get(address)
+    }
+
+    fn set_alias(
+        &mut self,
+        address: &SuiAddress,
+        alias: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        if !self.keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        update_alias(&mut self.metadata, address, alias)
+    }
+
     fn keys(&self) -> Vec<PublicKey> {
         self.keys.values().map(|key| key.public()).collect()
     }
@@ -280,6 +781,9 @@ impl InMemKeystore {
             .map(|(ad, k)| (ad, SuiKeyPair::Ed25519(k)))
             .collect::<BTreeMap<SuiAddress, SuiKeyPair>>();
 
-        Self { keys }
+        Self {
+            keys,
+            metadata: BTreeMap::new(),
+        }
     }
 }
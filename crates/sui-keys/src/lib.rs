@@ -1,6 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encryption;
 pub mod key_derive;
+pub mod key_metadata;
 pub mod keypair_file;
 pub mod keystore;
+pub mod remote_signer;
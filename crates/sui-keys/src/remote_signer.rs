@@ -0,0 +1,198 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Protocol between [crate::keystore::RemoteKeystore] and a signer process holding the keys.
+//!
+//! The keystore connects to the signer over a Unix socket (`unix:<path>`) or a loopback TCP
+//! address (`tcp:<ip>:<port>`), writes one request as a line of JSON and reads one response
+//! line, then closes the connection. Requests are:
+//!
+//! - `{"method": "keys"}`: returns the Base64 encoded `flag || pk` public keys of the signer.
+//! - `{"method": "sign", "params": {"address": "0x..", "message": ".."}}`: signs the Base64
+//!   encoded BCS bytes of an `IntentMessage` with the key of `address`, as
+//!   `Signature::new_secure` does.
+//! - `{"method": "signHashed", "params": {"address": "0x..", "message": ".."}}`: signs the
+//!   Base64 encoded message as is, as `Signature::new_hashed` does.
+//!
+//! Signatures are returned Base64 encoded as `flag || sig || pk`. A response is either
+//! `{"result": ..}` or `{"error": "<message>"}`.
+
+use anyhow::{anyhow, bail, ensure};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, PublicKey, Signature};
+
+use crate::keystore::AccountKeystore;
+
+/// Signers may wait for an operator to approve a signature, so allow for slow responses.
+const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum RemoteSignerRequest {
+    Keys,
+    Sign {
+        address: SuiAddress,
+        message: String,
+    },
+    SignHashed {
+        address: SuiAddress,
+        message: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteSignerResponse<T> {
+    Result(T),
+    Error(String),
+}
+
+/// Address of a signer process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteSignerEndpoint {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(SocketAddr),
+}
+
+impl FromStr for RemoteSignerEndpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Self::Unix(PathBuf::from(path)));
+            #[cfg(not(unix))]
+            bail!("Unix socket {path} is not supported on this platform");
+        }
+        if let Some(addr) = s.strip_prefix("tcp:") {
+            let addr: SocketAddr = addr
+                .parse()
+                .map_err(|e| anyhow!("Invalid signer address {addr}: {e}"))?;
+            // Keys must not be requested over the network.
+            ensure!(
+                addr.ip().is_loopback(),
+                "Signer address {addr} is not a loopback address"
+            );
+            return Ok(Self::Tcp(addr));
+        }
+        bail!("Invalid signer endpoint {s}, expected unix:<path> or tcp:<ip>:<port>")
+    }
+}
+
+impl Display for RemoteSignerEndpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Tcp(addr) => write!(f, "tcp:{addr}"),
+        }
+    }
+}
+
+impl RemoteSignerEndpoint {
+    pub fn keys(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        self.call::<Vec<String>>(&RemoteSignerRequest::Keys)?
+            .iter()
+            .map(|key| {
+                PublicKey::decode_base64(key).map_err(|e| anyhow!("Invalid public key {key}: {e}"))
+            })
+            .collect()
+    }
+
+    pub fn sign(&self, request: &RemoteSignerRequest) -> Result<Signature, anyhow::Error> {
+        let signature = self.call::<String>(request)?;
+        Signature::decode_base64(&signature).map_err(|e| anyhow!("Invalid signature: {e}"))
+    }
+
+    fn call<T: DeserializeOwned>(&self, request: &RemoteSignerRequest) -> Result<T, anyhow::Error> {
+        let mut request = serde_json::to_vec(request)?;
+        request.push(b'\n');
+        let response = match self {
+            #[cfg(unix)]
+            Self::Unix(path) => {
+                let stream = UnixStream::connect(path)
+                    .map_err(|e| anyhow!("Cannot connect to signer at {self}: {e}"))?;
+                stream.set_read_timeout(Some(REMOTE_SIGNER_TIMEOUT))?;
+                exchange(stream, &request)?
+            }
+            Self::Tcp(addr) => {
+                let stream = TcpStream::connect_timeout(addr, REMOTE_SIGNER_TIMEOUT)
+                    .map_err(|e| anyhow!("Cannot connect to signer at {self}: {e}"))?;
+                stream.set_read_timeout(Some(REMOTE_SIGNER_TIMEOUT))?;
+                exchange(stream, &request)?
+            }
+        };
+        match serde_json::from_str(&response)
+            .map_err(|e| anyhow!("Invalid response from signer at {self}: {e}"))?
+        {
+            RemoteSignerResponse::Result(result) => Ok(result),
+            RemoteSignerResponse::Error(error) => bail!("Signer at {self} failed: {error}"),
+        }
+    }
+}
+
+fn exchange<S: Read + Write>(mut stream: S, request: &[u8]) -> std::io::Result<String> {
+    stream.write_all(request)?;
+    stream.flush()?;
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    Ok(response)
+}
+
+/// Answers one request of a [crate::keystore::RemoteKeystore] with the keys of `keystore`, a
+/// reference implementation of the signer side of the protocol.
+pub fn serve_request<S: Read + Write>(
+    stream: S,
+    keystore: &impl AccountKeystore,
+) -> Result<(), anyhow::Error> {
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let response = match handle_request(&request, keystore) {
+        Ok(result) => RemoteSignerResponse::Result(result),
+        Err(e) => RemoteSignerResponse::Error(e.to_string()),
+    };
+    let mut response = serde_json::to_vec(&response)?;
+    response.push(b'\n');
+    let stream = reader.get_mut();
+    stream.write_all(&response)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn handle_request(
+    request: &str,
+    keystore: &impl AccountKeystore,
+) -> Result<serde_json::Value, anyhow::Error> {
+    let (address, digest) = match serde_json::from_str(request)? {
+        RemoteSignerRequest::Keys => {
+            let keys: Vec<_> = keystore.keys().iter().map(|k| k.encode_base64()).collect();
+            return Ok(serde_json::to_value(keys)?);
+        }
+        RemoteSignerRequest::Sign { address, message } => {
+            let mut hasher = DefaultHash::default();
+            hasher.update(Base64::decode(&message).map_err(|e| anyhow!("{e}"))?);
+            (address, hasher.finalize().digest.to_vec())
+        }
+        RemoteSignerRequest::SignHashed { address, message } => (
+            address,
+            Base64::decode(&message).map_err(|e| anyhow!("{e}"))?,
+        ),
+    };
+    let signature = keystore.sign_hashed(&address, &digest)?;
+    Ok(serde_json::to_value(signature.encode_base64())?)
+}
@@ -1,12 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::fs;
 use std::str::FromStr;
 
 use fastcrypto::hash::HashFunction;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_keys::keystore::{
+    is_encrypted_keystore, AccountKeystore, EncryptedFileKeystore, FileBasedKeystore, Keystore,
+};
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, SignatureScheme, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +72,185 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn key_metadata_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    keystore.set_alias(&address, Some("deployer".to_string()))?;
+
+    // The keystore file is still a list of keys, metadata is stored next to it.
+    let keys: Vec<String> = serde_json::from_str(&fs::read_to_string(&keystore_path)?)?;
+    assert_eq!(keys.len(), 1);
+
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let metadata = keystore.get_metadata(&address).unwrap();
+    assert_eq!(metadata.alias.as_deref(), Some("deployer"));
+    assert_eq!(
+        metadata.derivation_path.as_deref(),
+        Some("m/44'/784'/0'/0'/0'")
+    );
+    assert!(metadata.created_at.is_some());
+    assert_eq!(
+        keystore.get_address_by_identity(&"deployer".parse()?)?,
+        address
+    );
+    assert_eq!(
+        keystore.get_address_by_identity(&address.to_string().parse()?)?,
+        address
+    );
+
+    // Aliases cannot be mistaken for addresses.
+    let mut keystore = keystore;
+    assert!(keystore
+        .set_alias(&address, Some("0xdeployer".to_string()))
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn keystore_save_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&keystore_path, fs::Permissions::from_mode(0o600))?;
+    }
+    keystore.set_alias(&address, Some("deployer".to_string()))?;
+
+    // Saving replaces the files without leaving temporary files behind.
+    let mut files = fs::read_dir(temp_dir.path())?
+        .map(|entry| Ok(entry?.file_name().into_string().unwrap()))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    files.sort();
+    assert_eq!(files, ["sui.keystore", "sui.metadata"]);
+    let reopened = FileBasedKeystore::new(&keystore_path)?;
+    assert_eq!(reopened.addresses(), vec![address]);
+
+    // And keeps the permissions of the keystore file.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&keystore_path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(EncryptedFileKeystore::new_with_passphrase(
+        &keystore_path,
+        "passphrase",
+    )?);
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+
+    // Keys are not stored in plaintext.
+    assert!(is_encrypted_keystore(&keystore_path));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    let key = keystore.get_key(&address)?.encode_base64();
+    assert!(!fs::read_to_string(&keystore_path)?.contains(&key));
+
+    let reopened = EncryptedFileKeystore::new_with_passphrase(&keystore_path, "passphrase")?;
+    assert_eq!(reopened.addresses(), vec![address]);
+    assert!(EncryptedFileKeystore::new_with_passphrase(&keystore_path, "wrong").is_err());
+    Ok(())
+}
+
+#[test]
+fn encrypt_file_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    assert!(!is_encrypted_keystore(&keystore_path));
+
+    let mut encrypted = EncryptedFileKeystore::encrypt_file_keystore(&keystore_path, "passphrase")?;
+    assert_eq!(encrypted.addresses(), vec![address]);
+    assert!(is_encrypted_keystore(&keystore_path));
+
+    encrypted.change_passphrase("new passphrase")?;
+    assert!(EncryptedFileKeystore::new_with_passphrase(&keystore_path, "passphrase").is_err());
+    let reopened = EncryptedFileKeystore::new_with_passphrase(&keystore_path, "new passphrase")?;
+    assert_eq!(reopened.addresses(), vec![address]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn remote_keystore_test() -> Result<(), anyhow::Error> {
+    use shared_crypto::intent::{Intent, IntentMessage};
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use sui_keys::keystore::{InMemKeystore, RemoteKeystore};
+    use sui_keys::remote_signer::{serve_request, RemoteSignerEndpoint};
+    use sui_types::crypto::SuiSignature;
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let endpoint: RemoteSignerEndpoint = format!("unix:{}", socket_path.display()).parse()?;
+
+    // The signer is not connected to until its keys are needed, failures to list them are
+    // reported by `try_addresses`.
+    let remote = RemoteKeystore::new(endpoint);
+    assert!(remote.fetch_keys().is_err());
+    let keystore = Keystore::from(remote);
+    assert!(keystore.try_addresses().is_err());
+
+    let listener = UnixListener::bind(&socket_path)?;
+    let signer = InMemKeystore::new_insecure_for_tests(2);
+    let addresses = signer.addresses();
+    // Keys, once, then sign_secure and two sign_hashed, and a signature by the wrong key.
+    let server = thread::spawn(move || {
+        let mut incoming = listener.incoming();
+        for stream in incoming.by_ref().take(4) {
+            serve_request(stream.unwrap(), &signer).unwrap();
+        }
+        let mut stream = incoming.next().unwrap().unwrap();
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request).unwrap();
+        let signature = signer
+            .sign_hashed(&signer.addresses()[0], &[0; 32])
+            .unwrap();
+        let response = serde_json::json!({ "result": signature.encode_base64() });
+        writeln!(stream, "{response}").unwrap();
+    });
+
+    assert_eq!(keystore.addresses(), addresses);
+    assert_eq!(keystore.addresses(), addresses);
+    assert!(keystore.get_key(&addresses[0]).is_err());
+
+    let intent_msg = IntentMessage::new(Intent::sui_transaction(), "message");
+    let signature = keystore.sign_secure(&addresses[1], &intent_msg.value, intent_msg.intent)?;
+    signature.verify_secure(&intent_msg, addresses[1])?;
+    keystore.sign_hashed(&addresses[0], &[0; 32])?;
+
+    // Unknown keys are reported by the signer.
+    assert!(keystore.sign_hashed(&SuiAddress::ZERO, &[0; 32]).is_err());
+
+    // Signatures by another key than the requested one are rejected.
+    assert!(keystore.sign_hashed(&addresses[1], &[0; 32]).is_err());
+    server.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn remote_signer_endpoint_test() {
+    use sui_keys::remote_signer::RemoteSignerEndpoint;
+
+    // Only loopback addresses are accepted.
+    assert!("tcp:127.0.0.1:9000".parse::<RemoteSignerEndpoint>().is_ok());
+    assert!("tcp:8.8.8.8:9000".parse::<RemoteSignerEndpoint>().is_err());
+}
@@ -8,6 +8,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 use sui_config::p2p::StateSyncConfig;
+use sui_storage::archive::ArchiveReader;
 use sui_types::{messages_checkpoint::VerifiedCheckpoint, storage::ReadStore};
 use tap::Pipe;
 use tokio::{
@@ -106,6 +107,13 @@ where
         let store = store.unwrap();
         let config = config.unwrap_or_default();
         let metrics = metrics.unwrap_or_else(Metrics::disabled);
+        let archive_reader = config
+            .archive_object_store_config
+            .as_ref()
+            .map(|archive_config| {
+                ArchiveReader::new(archive_config, config.archive_manifest_refresh_interval())
+                    .expect("Failed to create checkpoint archive reader")
+            });
 
         let (sender, mailbox) = mpsc::channel(config.mailbox_capacity());
         let (checkpoint_event_sender, _receiver) =
@@ -138,6 +146,7 @@ where
                 peer_heights,
                 checkpoint_event_sender,
                 metrics,
+                archive_reader,
             },
             server,
         )
@@ -152,6 +161,7 @@ pub struct UnstartedStateSync<S> {
     pub(super) peer_heights: Arc<RwLock<PeerHeights>>,
     pub(super) checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     pub(super) metrics: Metrics,
+    pub(super) archive_reader: Option<ArchiveReader>,
 }
 
 impl<S> UnstartedStateSync<S>
@@ -168,6 +178,7 @@ where
             peer_heights,
             checkpoint_event_sender,
             metrics,
+            archive_reader,
         } = self;
 
         (
@@ -183,6 +194,7 @@ where
                 checkpoint_event_sender,
                 network,
                 metrics,
+                archive_reader,
             },
             handle,
         )
@@ -38,6 +38,11 @@
 //! the appropriate committee (based on the epoch). As checkpoints are verified, the
 //! highest_synced_checkpoint watermark will be ratcheted up.
 //!
+//! If none of our peers is able to serve a checkpoint anymore, and a checkpoint archive is
+//! configured, the checkpoints are instead read from the archive and verified the same way, along
+//! with their contents. Likewise, the contents of verified checkpoints that none of our peers is
+//! able to serve are read from the archive.
+//!
 //! Once we've ratcheted up our highest_verified_checkpoint, and if it is higher than
 //! highest_synced_checkpoint, StateSync will then kick off a task to synchronize the contents of
 //! all of the checkpoints from highest_synced_checkpoint..=highest_verified_checkpoint. After the
@@ -56,8 +61,9 @@ use std::{
     time::{Duration, SystemTime},
 };
 use sui_config::p2p::StateSyncConfig;
+use sui_storage::archive::ArchiveReader;
 use sui_types::{
-    digests::{CheckpointContentsDigest, CheckpointDigest},
+    digests::CheckpointDigest,
     messages_checkpoint::{
         CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber, FullCheckpointContents,
         VerifiedCheckpoint, VerifiedCheckpointContents,
@@ -266,6 +272,7 @@ struct StateSyncEventLoop<S> {
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     network: anemo::Network,
     metrics: Metrics,
+    archive_reader: Option<ArchiveReader>,
 }
 
 impl<S> StateSyncEventLoop<S>
@@ -534,6 +541,7 @@ where
                 self.metrics.clone(),
                 self.config.checkpoint_header_download_concurrency(),
                 self.config.timeout(),
+                self.archive_reader.clone(),
                 // The if condition should ensure that this is Some
                 highest_known_checkpoint.unwrap(),
             )
@@ -565,13 +573,16 @@ where
 
         if highest_verified_checkpoint.sequence_number()
             > highest_synced_checkpoint.sequence_number()
-            // skip if we aren't connected to any peers that can help
-            && self
-                .peer_heights
-                .read()
-                .unwrap()
-                .highest_known_checkpoint_sequence_number()
-                > Some(*highest_synced_checkpoint.sequence_number())
+            // skip if neither the archive nor any of the peers we are connected to can help
+            && (self.archive_reader.as_ref().map_or(false, |archive_reader| {
+                archive_reader.may_hold_checkpoint(highest_synced_checkpoint.sequence_number() + 1)
+            })
+                || self
+                    .peer_heights
+                    .read()
+                    .unwrap()
+                    .highest_known_checkpoint_sequence_number()
+                    > Some(*highest_synced_checkpoint.sequence_number()))
         {
             let task = sync_checkpoint_contents(
                 self.network.clone(),
@@ -582,6 +593,7 @@ where
                 self.metrics.clone(),
                 self.config.checkpoint_content_download_concurrency(),
                 self.config.checkpoint_content_timeout(),
+                self.archive_reader.clone(),
                 highest_verified_checkpoint,
             );
 
@@ -774,6 +786,7 @@ async fn sync_to_checkpoint<S>(
     metrics: Metrics,
     checkpoint_header_download_concurrency: usize,
     timeout: Duration,
+    archive_reader: Option<ArchiveReader>,
     checkpoint: Checkpoint,
 ) -> Result<()>
 where
@@ -861,12 +874,26 @@ where
         .buffered(checkpoint_header_download_concurrency);
 
     while let Some((maybe_checkpoint, next, maybe_peer_id)) = request_stream.next().await {
+        // Skip the checkpoints which were already synced from the archive
+        if *current.sequence_number() >= next {
+            continue;
+        }
         debug_assert!(current.sequence_number().saturating_add(1) == next);
 
         // Verify the checkpoint
         let checkpoint = {
-            let checkpoint = maybe_checkpoint
-                .ok_or_else(|| anyhow::anyhow!("no peers were able to help sync"))?;
+            let checkpoint = match (maybe_checkpoint, &archive_reader) {
+                (Some(checkpoint), _) => checkpoint,
+                // None of our peers is able to serve the checkpoint anymore, fall back to the
+                // archive
+                (None, Some(archive_reader)) => {
+                    current =
+                        sync_checkpoints_from_archive(archive_reader, &store, &metrics, current)
+                            .await?;
+                    continue;
+                }
+                (None, None) => return Err(anyhow::anyhow!("no peers were able to help sync")),
+            };
             match verify_checkpoint(&current, &store, checkpoint) {
                 Ok(verified_checkpoint) => verified_checkpoint,
                 Err(checkpoint) => {
@@ -911,6 +938,49 @@ where
     Ok(())
 }
 
+/// Syncs the checkpoints following `current` from the archive, up to the end of the archived file
+/// holding them, and returns the highest checkpoint synced. Both the summaries and the contents of
+/// the checkpoints are inserted into the store, leaving the contents sync task to bump the highest
+/// synced checkpoint watermark.
+async fn sync_checkpoints_from_archive<S>(
+    archive_reader: &ArchiveReader,
+    store: S,
+    metrics: &Metrics,
+    mut current: VerifiedCheckpoint,
+) -> Result<VerifiedCheckpoint>
+where
+    S: WriteStore,
+    <S as ReadStore>::Error: std::error::Error,
+{
+    let archived_checkpoints = archive_reader
+        .read_checkpoints_from(current.sequence_number().saturating_add(1))
+        .await?;
+
+    for archived_checkpoint in archived_checkpoints {
+        let checkpoint = verify_checkpoint(&current, &store, archived_checkpoint.summary).map_err(
+            |checkpoint| anyhow::anyhow!("unable to verify archived checkpoint {checkpoint:?}"),
+        )?;
+        archived_checkpoint
+            .contents
+            .verify_digests(checkpoint.content_digest)?;
+
+        debug!(sequence_number = ?checkpoint.sequence_number(), "verified archived checkpoint");
+        // Insert the contents first, so that they are available once the checkpoint is verified.
+        store
+            .insert_checkpoint_contents(VerifiedCheckpointContents::new_unchecked(
+                archived_checkpoint.contents,
+            ))
+            .expect("store operation should not fail");
+        store
+            .insert_checkpoint(checkpoint.clone())
+            .expect("store operation should not fail");
+        metrics.set_highest_verified_checkpoint(*checkpoint.sequence_number());
+        current = checkpoint;
+    }
+
+    Ok(current)
+}
+
 fn verify_checkpoint<S>(
     current: &VerifiedCheckpoint,
     store: S,
@@ -979,6 +1049,7 @@ async fn sync_checkpoint_contents<S>(
     metrics: Metrics,
     checkpoint_content_download_concurrency: usize,
     timeout: Duration,
+    archive_reader: Option<ArchiveReader>,
     target_checkpoint: VerifiedCheckpoint,
 ) where
     S: WriteStore + Clone,
@@ -1005,6 +1076,7 @@ async fn sync_checkpoint_contents<S>(
                 &store,
                 peer_heights.clone(),
                 timeout,
+                archive_reader.as_ref(),
                 checkpoint,
             )
         })
@@ -1048,6 +1120,7 @@ async fn sync_one_checkpoint_contents<S>(
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
     timeout: Duration,
+    archive_reader: Option<&ArchiveReader>,
     checkpoint: VerifiedCheckpoint,
 ) -> Result<(VerifiedCheckpoint, u64)>
 where
@@ -1068,7 +1141,7 @@ where
         .collect::<Vec<_>>();
     rand::seq::SliceRandom::shuffle(peers.as_mut_slice(), &mut rng);
 
-    let Some(contents) = get_full_checkpoint_contents(&mut peers, &store, checkpoint.content_digest, timeout).await else {
+    let Some(contents) = get_full_checkpoint_contents(&mut peers, &store, archive_reader, &checkpoint, timeout).await else {
         return Err(anyhow!("unable to sync checkpoint contents for checkpoint {}", checkpoint.sequence_number()));
     };
 
@@ -1080,13 +1153,15 @@ where
 async fn get_full_checkpoint_contents<S>(
     peers: &mut [StateSyncClient<anemo::Peer>],
     store: S,
-    digest: CheckpointContentsDigest,
+    archive_reader: Option<&ArchiveReader>,
+    checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
 ) -> Option<FullCheckpointContents>
 where
     S: WriteStore,
     <S as ReadStore>::Error: std::error::Error,
 {
+    let digest = checkpoint.content_digest;
     if let Some(contents) = store
         .get_full_checkpoint_contents(&digest)
         .expect("store operation should not fail")
@@ -1116,5 +1191,63 @@ where
         }
     }
 
-    None
+    // None of our peers is able to serve the contents anymore, fall back to the archive
+    get_full_checkpoint_contents_from_archive(archive_reader?, store, checkpoint).await
+}
+
+/// Reads the contents of `checkpoint` from the archive. The contents of the following verified
+/// checkpoints held by the same archived file are inserted into the store as well, so that they
+/// don't need to be read again.
+async fn get_full_checkpoint_contents_from_archive<S>(
+    archive_reader: &ArchiveReader,
+    store: S,
+    checkpoint: &VerifiedCheckpoint,
+) -> Option<FullCheckpointContents>
+where
+    S: WriteStore,
+    <S as ReadStore>::Error: std::error::Error,
+{
+    // The contents may have been inserted from the archive while we were querying our peers
+    if let Some(contents) = store
+        .get_full_checkpoint_contents(&checkpoint.content_digest)
+        .expect("store operation should not fail")
+    {
+        return Some(contents);
+    }
+
+    let archived_checkpoints = archive_reader
+        .read_checkpoints_from(*checkpoint.sequence_number())
+        .await
+        .tap_err(|e| debug!("unable to read archived checkpoint contents: {e:?}"))
+        .ok()?;
+    let mut result = None;
+    for archived_checkpoint in archived_checkpoints {
+        // Contents can only be verified against a verified checkpoint
+        let Some(verified_checkpoint) = store
+            .get_checkpoint_by_sequence_number(*archived_checkpoint.summary.sequence_number())
+            .expect("store operation should not fail")
+        else {
+            break;
+        };
+        if archived_checkpoint
+            .contents
+            .verify_digests(verified_checkpoint.content_digest)
+            .is_err()
+        {
+            debug!(
+                sequence_number = verified_checkpoint.sequence_number(),
+                "archived checkpoint contents do not match the checkpoint"
+            );
+            break;
+        }
+        if verified_checkpoint.sequence_number() == checkpoint.sequence_number() {
+            result = Some(archived_checkpoint.contents.clone());
+        }
+        store
+            .insert_checkpoint_contents(VerifiedCheckpointContents::new_unchecked(
+                archived_checkpoint.contents,
+            ))
+            .expect("store operation should not fail");
+    }
+    result
 }
@@ -11,6 +11,9 @@ use crate::{
 };
 use anemo::{PeerId, Request};
 use std::{collections::HashMap, time::Duration};
+use sui_config::p2p::StateSyncConfig;
+use sui_storage::archive::{ArchiveReader, ArchiveWriter};
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_types::{
     messages_checkpoint::CheckpointDigest,
     storage::{ReadStore, SharedInMemoryStore, WriteStore},
@@ -253,6 +256,172 @@ async fn isolated_sync_job() {
     }
 }
 
+#[tokio::test]
+async fn sync_from_archive() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    // build mock data
+    let (ordered_checkpoints, sequence_number_to_digest, checkpoints) =
+        committee.make_checkpoints(100, None);
+
+    // Archive all the checkpoints
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(archive_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    {
+        let store = SharedInMemoryStore::default();
+        {
+            let mut inner = store.inner_mut();
+            inner.insert_genesis_state(
+                ordered_checkpoints.first().cloned().unwrap(),
+                empty_contents(),
+                committee.committee().to_owned(),
+            );
+            for checkpoint in ordered_checkpoints.clone() {
+                inner.insert_checkpoint(checkpoint);
+            }
+            inner.update_highest_synced_checkpoint(ordered_checkpoints.last().unwrap());
+        }
+
+        let mut writer =
+            ArchiveWriter::new(store, &archive_config, 10, Duration::from_secs(1)).unwrap();
+        writer.archive_synced_checkpoints().await.unwrap();
+    }
+
+    // Build and connect two nodes, of which only node 1 has access to the archive
+    let config = StateSyncConfig {
+        archive_object_store_config: Some(archive_config),
+        ..Default::default()
+    };
+    let (builder, server) = Builder::new()
+        .config(config)
+        .store(SharedInMemoryStore::default())
+        .build();
+    let network_1 = build_network(|router| router.add_rpc_service(server));
+    let (mut event_loop_1, _handle_1) = builder.build(network_1.clone());
+    let (builder, server) = Builder::new().store(SharedInMemoryStore::default()).build();
+    let network_2 = build_network(|router| router.add_rpc_service(server));
+    let (event_loop_2, _handle_2) = builder.build(network_2.clone());
+    network_1.connect(network_2.local_addr()).await.unwrap();
+
+    // Init the root committee in both nodes
+    event_loop_1.store.inner_mut().insert_genesis_state(
+        ordered_checkpoints.first().cloned().unwrap(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+    event_loop_2.store.inner_mut().insert_genesis_state(
+        ordered_checkpoints.first().cloned().unwrap(),
+        empty_contents(),
+        committee.committee().to_owned(),
+    );
+
+    // Node 1 believes that Node 2 has the data, which Node 2 is unable to serve
+    event_loop_1.peer_heights.write().unwrap().peers.insert(
+        network_2.peer_id(),
+        PeerStateSyncInfo {
+            genesis_checkpoint_digest: *ordered_checkpoints[0].digest(),
+            on_same_chain_as_us: true,
+            height: *ordered_checkpoints.last().unwrap().sequence_number(),
+        },
+    );
+    event_loop_1
+        .peer_heights
+        .write()
+        .unwrap()
+        .insert_checkpoint(ordered_checkpoints.last().cloned().unwrap().into_inner());
+
+    // Sync the data from the archive
+    event_loop_1.maybe_start_checkpoint_summary_sync_task();
+    event_loop_1.tasks.join_next().await.unwrap().unwrap();
+    assert_eq!(
+        ordered_checkpoints.last().map(|x| x.data()),
+        Some(
+            event_loop_1
+                .store
+                .get_highest_verified_checkpoint()
+                .unwrap()
+                .data()
+        )
+    );
+
+    {
+        let store = event_loop_1.store.inner();
+        let expected = checkpoints
+            .iter()
+            .map(|(key, value)| (key, value.data()))
+            .collect::<HashMap<_, _>>();
+        let actual = store
+            .checkpoints()
+            .iter()
+            .map(|(key, value)| (key, value.data()))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(actual, expected);
+        assert_eq!(
+            store.checkpoint_sequence_number_to_digest(),
+            &sequence_number_to_digest
+        );
+    }
+}
+
+#[tokio::test]
+async fn archive_writer_resumes_from_manifest() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _) = committee.make_checkpoints(50, None);
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(archive_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let store = SharedInMemoryStore::default();
+    {
+        let mut inner = store.inner_mut();
+        inner.insert_genesis_state(
+            ordered_checkpoints.first().cloned().unwrap(),
+            empty_contents(),
+            committee.committee().to_owned(),
+        );
+        for checkpoint in ordered_checkpoints.clone() {
+            inner.insert_checkpoint(checkpoint);
+        }
+        inner.update_highest_synced_checkpoint(&ordered_checkpoints[24]);
+    }
+    let reader = ArchiveReader::new(&archive_config, Duration::from_secs(60)).unwrap();
+
+    // Archive the first synced checkpoints, the last of which don't fill a file yet
+    let mut writer =
+        ArchiveWriter::new(store.clone(), &archive_config, 10, Duration::from_secs(1)).unwrap();
+    writer.archive_synced_checkpoints().await.unwrap();
+    let manifest = reader.read_manifest().await.unwrap();
+    assert_eq!(manifest.next_checkpoint_seq_num, 20);
+    assert_eq!(manifest.files.len(), 2);
+
+    // A new writer resumes from the manifest once more checkpoints are synced
+    store
+        .inner_mut()
+        .update_highest_synced_checkpoint(ordered_checkpoints.last().unwrap());
+    let mut writer =
+        ArchiveWriter::new(store, &archive_config, 10, Duration::from_secs(1)).unwrap();
+    writer.archive_synced_checkpoints().await.unwrap();
+    let manifest = reader.read_manifest().await.unwrap();
+    assert_eq!(manifest.next_checkpoint_seq_num, 50);
+    assert_eq!(
+        manifest
+            .files
+            .iter()
+            .map(|file| file.checkpoint_seq_range.clone())
+            .collect::<Vec<_>>(),
+        vec![0..10, 10..20, 20..30, 30..40, 40..50]
+    );
+    assert_eq!(reader.read_checkpoints_from(45).await.unwrap().len(), 5);
+    // The cached manifest tells which checkpoints the archive holds
+    assert!(reader.may_hold_checkpoint(45));
+    assert!(!reader.may_hold_checkpoint(50));
+}
+
 #[tokio::test]
 async fn sync_with_checkpoints_being_inserted() {
     telemetry_subscribers::init_for_testing();
@@ -28,7 +28,7 @@ use tokio::sync::oneshot;
 use tokio::sync::{watch, Mutex};
 use tokio::task::JoinHandle;
 use tower::ServiceBuilder;
-use tracing::{debug, warn};
+use tracing::{debug, error, warn};
 use tracing::{error_span, info, Instrument};
 
 use checkpoint_executor::CheckpointExecutor;
@@ -74,6 +74,7 @@ use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::indexer_api::IndexerApi;
 use sui_json_rpc::move_utils::MoveUtils;
 use sui_json_rpc::read_api::ReadApi;
+use sui_json_rpc::response_cache::CachedReadApi;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use sui_json_rpc::transaction_execution_api::TransactionExecutionApi;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
@@ -83,6 +84,7 @@ use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
+use sui_storage::archive::ArchiveWriter;
 use sui_storage::IndexStore;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::committee::Committee;
@@ -101,6 +103,9 @@ pub mod admin;
 mod handle;
 pub mod metrics;
 
+/// Maximum number of checkpoints archived in a single file of the checkpoint archive.
+const CHECKPOINTS_PER_ARCHIVE_FILE: u64 = 1000;
+
 pub struct ValidatorComponents {
     validator_server_handle: JoinHandle<Result<()>>,
     narwhal_manager: NarwhalManager,
@@ -135,6 +140,7 @@ pub struct SuiNode {
     trusted_peer_change_tx: watch::Sender<TrustedPeerChangeEvent>,
 
     _db_checkpoint_handle: Option<oneshot::Sender<()>>,
+    _checkpoint_archive_handle: Option<oneshot::Sender<()>>,
 
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
@@ -255,8 +261,30 @@ impl SuiNode {
             checkpoint_store.clone(),
         );
 
+        let checkpoint_archive_handle = match &config.checkpoint_archive_config {
+            Some(archive_config) => {
+                let writer = ArchiveWriter::new(
+                    state_sync_store.clone(),
+                    archive_config,
+                    CHECKPOINTS_PER_ARCHIVE_FILE,
+                    Duration::from_secs(60),
+                )?;
+                Some(writer.start())
+            }
+            None => None,
+        };
+
         let index_store = if is_full_node && config.enable_index_processing {
-            Some(Arc::new(IndexStore::new(config.db_path().join("indexes"))))
+            let index_store = Arc::new(IndexStore::new(config.db_path().join("indexes")));
+            let backfill = index_store.spawn_owner_type_index_backfill();
+            spawn_monitored_task!(async move {
+                match backfill.await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(err)) => error!("Failed to backfill the owner type index: {err:?}"),
+                    Err(err) => error!("Owner type index backfill task failed: {err:?}"),
+                }
+            });
+            Some(index_store)
         } else {
             None
         };
@@ -312,7 +340,6 @@ impl SuiNode {
             checkpoint_store.clone(),
             &prometheus_registry,
             config.authority_store_pruning_config,
-            genesis.objects(),
             &db_checkpoint_config,
             config.expensive_safety_check_config.clone(),
         )
@@ -428,6 +455,7 @@ impl SuiNode {
             trusted_peer_change_tx,
 
             _db_checkpoint_handle: db_checkpoint_handle,
+            _checkpoint_archive_handle: checkpoint_archive_handle,
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
             #[cfg(msim)]
@@ -890,6 +918,7 @@ impl SuiNode {
             self.state.database.clone(),
             self.state.transaction_manager().clone(),
             self.accumulator.clone(),
+            self.state.checkpoint_data_streamer.clone(),
             self.config.checkpoint_executor_config.clone(),
             &self.registry_service.default_registry(),
         );
@@ -1140,8 +1169,16 @@ pub async fn build_server(
     }
 
     let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
+    if let Some(rate_limit_config) = &config.json_rpc_rate_limit_config {
+        server.set_rate_limit_config(rate_limit_config.clone());
+    }
     let metrics = Arc::new(JsonRpcMetrics::new(prometheus_registry));
-    server.register_module(ReadApi::new(state.clone(), metrics.clone()))?;
+    let read_api = ReadApi::new(state.clone(), metrics.clone());
+    if let Some(cache_size) = config.json_rpc_response_cache_size_bytes {
+        server.register_module(CachedReadApi::new(read_api, cache_size, metrics.clone()))?;
+    } else {
+        server.register_module(read_api)?;
+    }
     server.register_module(CoinReadApi::new(state.clone(), metrics.clone()))?;
     server.register_module(TransactionBuilderApi::new(state.clone()))?;
     server.register_module(GovernanceReadApi::new(state.clone(), metrics.clone()))?;
@@ -1153,12 +1190,16 @@ pub async fn build_server(
         ))?;
     }
 
-    server.register_module(IndexerApi::new(
+    let mut indexer_api = IndexerApi::new(
         state.clone(),
         ReadApi::new(state.clone(), metrics.clone()),
         config.name_service_resolver_object_id,
         metrics.clone(),
-    ))?;
+    );
+    if let Some(streaming_config) = &config.checkpoint_data_streaming_config {
+        indexer_api = indexer_api.with_checkpoint_data_streaming(streaming_config.clone());
+    }
+    server.register_module(indexer_api)?;
     server.register_module(MoveUtils::new(state.clone()))?;
 
     let rpc_server_handle = server.start(config.json_rpc_address).await?;
@@ -509,3 +509,191 @@ impl Fold for CheckArithmetic {
         parse2(expr).unwrap()
     }
 }
+
+/// Derives `MoveStructType`, `MoveTypeTagTrait` and `MoveTypeLayoutTrait` from `sui_types` for a
+/// Rust struct mirroring a Move struct, so that Move objects of that type can be decoded with
+/// `MoveStructType::from_bcs_bytes`. The Move struct is named by the `move_struct` attribute, its
+/// name defaults to the name of the Rust struct. Type parameters of the Rust struct are the type
+/// parameters of the Move struct, `PhantomData` fields are left out of the layout. Fields of type
+/// `String` and `Option<T>` mirror `0x1::string::String` and `0x1::option::Option<T>`.
+///
+/// ```ignore
+/// #[derive(Deserialize, MoveStruct)]
+/// #[move_struct(address = "0x2", module = "coin")]
+/// struct Coin<T> {
+///     id: UID,
+///     balance: Balance<T>,
+/// }
+///
+/// #[derive(Deserialize, MoveStruct)]
+/// #[move_struct(address = "0x2", module = "balance")]
+/// struct Balance<T> {
+///     value: u64,
+///     #[move_struct(skip)]
+///     _phantom: PhantomData<T>,
+/// }
+/// ```
+#[proc_macro_derive(MoveStruct, attributes(move_struct))]
+pub fn derive_move_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match move_struct_impl(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn move_struct_impl(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let mut address = None;
+    let mut module = None;
+    let mut name = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("move_struct"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let value = meta.value()?.parse::<syn::LitStr>()?;
+            if meta.path.is_ident("address") {
+                address = Some(value);
+            } else if meta.path.is_ident("module") {
+                module = Some(value);
+            } else if meta.path.is_ident("name") {
+                name = Some(value);
+            } else {
+                return Err(meta.error("expected `address`, `module` or `name`"));
+            }
+            Ok(())
+        })?;
+    }
+    let address = address.ok_or_else(|| {
+        syn::Error::new_spanned(ident, "missing `#[move_struct(address = \"0x..\")]`")
+    })?;
+    if !address.value().starts_with("0x") {
+        return Err(syn::Error::new_spanned(
+            &address,
+            "the address must be a hex literal starting with 0x",
+        ));
+    }
+    let module = module.ok_or_else(|| {
+        syn::Error::new_spanned(ident, "missing `#[move_struct(module = \"..\")]`")
+    })?;
+    let name = name.unwrap_or_else(|| syn::LitStr::new(&ident.to_string(), ident.span()));
+
+    let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "MoveStruct can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut field_names = vec![];
+    let mut field_types = vec![];
+    for field in &fields.named {
+        let mut skip = false;
+        let mut rename = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("move_struct"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<syn::LitStr>()?);
+                } else {
+                    return Err(meta.error("expected `skip` or `rename`"));
+                }
+                Ok(())
+            })?;
+        }
+        if skip {
+            continue;
+        }
+        // Field idents are always present for named fields.
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_name = rename.unwrap_or_else(|| {
+            let field_name = field_ident.to_string();
+            syn::LitStr::new(
+                field_name.strip_prefix("r#").unwrap_or(&field_name),
+                field_ident.span(),
+            )
+        });
+        field_names.push(field_name);
+        field_types.push(&field.ty);
+    }
+
+    let type_params = input
+        .generics
+        .type_params()
+        .map(|p| &p.ident)
+        .collect::<Vec<_>>();
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for param in &type_params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#param: ::sui_types::MoveTypeTagTrait));
+    }
+    // The layouts of generic fields depend on the type params.
+    if !type_params.is_empty() {
+        for ty in &field_types {
+            where_clause
+                .predicates
+                .push(syn::parse_quote!(#ty: ::sui_types::move_struct::MoveTypeLayoutTrait));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::sui_types::move_struct::MoveStructType for #ident #ty_generics #where_clause {
+            fn type_() -> ::sui_types::move_struct::StructTag {
+                ::sui_types::move_struct::StructTag {
+                    address: ::sui_types::move_struct::AccountAddress::from_hex_literal(#address)
+                        .expect("Invalid Move struct address"),
+                    module: ::sui_types::Identifier::new(#module)
+                        .expect("Invalid Move module name"),
+                    name: ::sui_types::Identifier::new(#name)
+                        .expect("Invalid Move struct name"),
+                    type_params: vec![
+                        #(<#type_params as ::sui_types::MoveTypeTagTrait>::get_type_tag()),*
+                    ],
+                }
+            }
+
+            fn layout() -> ::sui_types::move_struct::MoveStructLayout {
+                ::sui_types::move_struct::MoveStructLayout::WithTypes {
+                    type_: <Self as ::sui_types::move_struct::MoveStructType>::type_(),
+                    fields: vec![
+                        #(::sui_types::move_struct::MoveFieldLayout::new(
+                            ::sui_types::Identifier::new(#field_names)
+                                .expect("Invalid Move field name"),
+                            <#field_types as ::sui_types::move_struct::MoveTypeLayoutTrait>::get_layout(),
+                        )),*
+                    ],
+                }
+            }
+        }
+
+        impl #impl_generics ::sui_types::MoveTypeTagTrait for #ident #ty_generics #where_clause {
+            fn get_type_tag() -> ::sui_types::TypeTag {
+                ::sui_types::TypeTag::Struct(Box::new(
+                    <Self as ::sui_types::move_struct::MoveStructType>::type_(),
+                ))
+            }
+        }
+
+        impl #impl_generics ::sui_types::move_struct::MoveTypeLayoutTrait for #ident #ty_generics #where_clause {
+            fn get_layout() -> ::sui_types::move_struct::MoveTypeLayout {
+                ::sui_types::move_struct::MoveTypeLayout::Struct(
+                    <Self as ::sui_types::move_struct::MoveStructType>::layout(),
+                )
+            }
+        }
+    })
+}
@@ -10,7 +10,7 @@ use tracing::{info, warn};
 
 /// The minimum and maximum protocol versions supported by this build.
 const MIN_PROTOCOL_VERSION: u64 = 1;
-const MAX_PROTOCOL_VERSION: u64 = 5;
+const MAX_PROTOCOL_VERSION: u64 = 6;
 
 // Record history of protocol version allocations here:
 //
@@ -22,6 +22,7 @@ const MAX_PROTOCOL_VERSION: u64 = 5;
 // Version 4: New reward slashing rate. Framework changes to skip stake susbidy when the epoch
 //            length is short.
 // Version 5: Package upgrade compatibility error fix. New gas cost table.
+// Version 6: Nested MultiSig members and MultiSig with more than 10 signers.
 
 #[derive(Copy, Clone, Debug, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ProtocolVersion(u64);
@@ -135,6 +136,10 @@ struct FeatureFlags {
     // compatibility error.
     #[serde(skip_serializing_if = "is_false")]
     missing_type_is_compatibility_error: bool,
+    // If true, a MultiSig may have other MultiSigs as members and more than
+    // `MAX_SIGNER_IN_MULTISIG` signers.
+    #[serde(skip_serializing_if = "is_false")]
+    nested_multisig: bool,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -610,6 +615,17 @@ impl ProtocolConfig {
     pub fn missing_type_is_compatibility_error(&self) -> bool {
         self.feature_flags.missing_type_is_compatibility_error
     }
+
+    pub fn check_nested_multisig_supported(&self) -> Result<(), Error> {
+        if self.feature_flags.nested_multisig {
+            Ok(())
+        } else {
+            Err(Error(format!(
+                "nested and large multisigs are not supported at {:?}",
+                self.version
+            )))
+        }
+    }
 }
 
 // Special getters
@@ -994,6 +1010,11 @@ impl ProtocolConfig {
                 cfg.gas_model_version = Some(4);
                 cfg
             }
+            6 => {
+                let mut cfg = Self::get_for_version_impl(version - 1);
+                cfg.feature_flags.nested_multisig = true;
+                cfg
+            }
             // Use this template when making changes:
             //
             //     // modify an existing constant.
@@ -1039,6 +1060,9 @@ impl ProtocolConfig {
     pub fn set_package_upgrades_for_testing(&mut self, val: bool) {
         self.feature_flags.package_upgrades = val
     }
+    pub fn set_nested_multisig_for_testing(&mut self, val: bool) {
+        self.feature_flags.nested_multisig = val
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
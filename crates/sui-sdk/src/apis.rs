@@ -11,6 +11,7 @@ use futures::stream;
 use futures::StreamExt;
 use futures_core::Stream;
 use jsonrpsee::core::client::Subscription;
+use serde::de::DeserializeOwned;
 
 use sui_json_rpc::api::GovernanceReadApiClient;
 use sui_json_rpc::api::{
@@ -21,7 +22,8 @@ use sui_json_rpc_types::{
     DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage,
     SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest, SuiMoveNormalizedModule,
     SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
-    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    SuiRawData, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
     SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_types::balance::Supply;
@@ -31,6 +33,8 @@ use sui_types::error::TRANSACTION_NOT_FOUND_MSG_PREFIX;
 use sui_types::event::EventID;
 use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, VerifiedTransaction};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::move_struct::MoveStructType;
+use sui_types::query::TransactionFilter;
 use sui_types::sui_serde::BigInt;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
@@ -107,6 +111,23 @@ impl ReadApi {
         Ok(self.api.http.get_object(object_id, Some(options)).await?)
     }
 
+    /// Returns the Move object decoded as `T`. Fails if the object is not of type `T`.
+    pub async fn get_object_as<T: MoveStructType + DeserializeOwned>(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiRpcResult<T> {
+        let object = self
+            .get_object_with_options(object_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()
+            .map_err(|e| Error::DataError(e.to_string()))?;
+        let Some(SuiRawData::MoveObject(object)) = object.bcs else {
+            return Err(Error::DataError(format!("Object {object_id} is not a Move object")));
+        };
+        T::from_bcs_bytes(&object.type_, &object.bcs_bytes)
+            .map_err(|e| Error::DataError(format!("Cannot decode object {object_id}: {e}")))
+    }
+
     pub async fn multi_get_object_with_options(
         &self,
         object_ids: Vec<ObjectID>,
@@ -173,6 +194,19 @@ impl ReadApi {
         Ok(self.api.http.get_checkpoint(id).await?)
     }
 
+    /// Return the checkpoints with the given ids, in the same order
+    pub async fn multi_get_checkpoints(
+        &self,
+        ids: Vec<CheckpointId>,
+    ) -> SuiRpcResult<Vec<Checkpoint>> {
+        Ok(self.api.http.multi_get_checkpoints(ids).await?)
+    }
+
+    /// Return the events with the given ids, in the same order
+    pub async fn multi_get_events(&self, event_ids: Vec<EventID>) -> SuiRpcResult<Vec<SuiEvent>> {
+        Ok(self.api.http.multi_get_events(event_ids).await?)
+    }
+
     /// Return the sequence number of the latest checkpoint that has been executed
     pub async fn get_latest_checkpoint_sequence_number(
         &self,
@@ -184,6 +218,22 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn subscribe_transaction(
+        &self,
+        filter: TransactionFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiTransactionBlockEffects>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<SuiTransactionBlockEffects> =
+                    c.subscribe_transaction(filter).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
     pub fn get_transactions_stream(
         &self,
         query: SuiTransactionBlockResponseQuery,
@@ -339,7 +389,21 @@ impl CoinReadApi {
         owner: SuiAddress,
         coin_type: Option<String>,
     ) -> SuiRpcResult<Balance> {
-        Ok(self.api.http.get_balance(owner, coin_type).await?)
+        Ok(self.api.http.get_balance(owner, coin_type, None).await?)
+    }
+
+    /// Return the total balance of `coin_type` owned by `owner` as of the end of `checkpoint`.
+    pub async fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<Balance> {
+        Ok(self
+            .api
+            .http
+            .get_balance(owner, coin_type, Some(checkpoint.into()))
+            .await?)
     }
 
     pub async fn get_all_balances(&self, owner: SuiAddress) -> SuiRpcResult<Vec<Balance>> {
@@ -30,9 +30,11 @@ use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriver, ReadApi};
 use crate::error::{Error, SuiRpcResult};
+use crate::ptb_builder::PtbBuilder;
 
 pub mod apis;
 pub mod error;
+pub mod ptb_builder;
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
 const WAIT_FOR_TX_TIMEOUT_SEC: u64 = 60;
 
@@ -248,6 +250,9 @@ impl SuiClient {
     pub fn governance_api(&self) -> &GovernanceApi {
         &self.governance_api
     }
+    pub fn ptb_builder(&self, sender: SuiAddress) -> PtbBuilder<'_> {
+        PtbBuilder::new(self, sender)
+    }
 }
 
 #[async_trait]
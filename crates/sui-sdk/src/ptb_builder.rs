@@ -0,0 +1,335 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future;
+
+use anyhow::{anyhow, bail};
+use futures::StreamExt;
+use move_core_types::language_storage::TypeTag;
+use serde::Serialize;
+
+use sui_json_rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
+pub use sui_transaction_builder::MoveCallArg;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::messages::{
+    Argument, CallArg, Command, InputObjectKind, ProgrammableTransaction, TransactionData,
+    TransactionKind,
+};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::SuiClient;
+
+/// Gas budget used to dry run transactions whose budget is not set, capped by the balance of the
+/// sender's gas coins.
+pub const DEFAULT_MAX_GAS_BUDGET: u64 = 10_000_000_000;
+/// Computation units added on top of the dry run cost, in case the transaction costs more when it
+/// is executed than when it was dry run.
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// Builder of programmable transaction blocks. Objects are given by ID and resolved into owned,
+/// shared or immutable inputs, pure args of move calls are type checked against the function
+/// signature. Unless set, the gas budget is estimated with a dry run and gas coins are selected
+/// from the sender's SUI coins, to cover the budget and the amounts split from the gas coin. All
+/// the sender's SUI coins are used for gas when the gas coin is used otherwise, e.g. transferred.
+///
+/// ```ignore
+/// let mut ptb = client.ptb_builder(sender);
+/// let coin = ptb.split_coins(ptb.gas(), vec![1000])?;
+/// ptb.transfer_objects(vec![coin], recipient)?;
+/// let tx_data = ptb.build().await?;
+/// ```
+pub struct PtbBuilder<'a> {
+    client: &'a SuiClient,
+    sender: SuiAddress,
+    builder: ProgrammableTransactionBuilder,
+    gas_budget: Option<u64>,
+    gas_price: Option<u64>,
+    max_gas_budget: u64,
+}
+
+impl<'a> PtbBuilder<'a> {
+    pub(crate) fn new(client: &'a SuiClient, sender: SuiAddress) -> Self {
+        Self {
+            client,
+            sender,
+            builder: ProgrammableTransactionBuilder::new(),
+            gas_budget: None,
+            gas_price: None,
+            max_gas_budget: DEFAULT_MAX_GAS_BUDGET,
+        }
+    }
+
+    /// Sets the gas budget instead of estimating it with a dry run.
+    pub fn gas_budget(&mut self, gas_budget: u64) -> &mut Self {
+        self.gas_budget = Some(gas_budget);
+        self
+    }
+
+    /// Sets the gas price, defaults to the reference gas price.
+    pub fn gas_price(&mut self, gas_price: u64) -> &mut Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Sets the budget the transaction is dry run with when the gas budget is not set.
+    pub fn max_gas_budget(&mut self, max_gas_budget: u64) -> &mut Self {
+        self.max_gas_budget = max_gas_budget;
+        self
+    }
+
+    /// The gas coin of the transaction.
+    pub fn gas(&self) -> Argument {
+        Argument::GasCoin
+    }
+
+    pub fn pure<T: Serialize>(&mut self, value: T) -> anyhow::Result<Argument> {
+        self.builder.pure(value)
+    }
+
+    /// Adds the object as an input. Shared objects are used mutably, use them as args of
+    /// [Self::move_call] to infer their mutability from the function signature.
+    pub async fn object(&mut self, object_id: ObjectID) -> anyhow::Result<Argument> {
+        let object_arg = self
+            .client
+            .transaction_builder()
+            .resolve_object_arg(object_id, true)
+            .await?;
+        self.builder.obj(object_arg)
+    }
+
+    pub async fn move_call(
+        &mut self,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<TypeTag>,
+        call_args: Vec<MoveCallArg>,
+    ) -> anyhow::Result<Argument> {
+        self.client
+            .transaction_builder()
+            .programmable_move_call(
+                &mut self.builder,
+                package,
+                module,
+                function,
+                type_args,
+                call_args,
+            )
+            .await
+    }
+
+    pub fn split_coins(&mut self, coin: Argument, amounts: Vec<u64>) -> anyhow::Result<Argument> {
+        let amounts = amounts
+            .into_iter()
+            .map(|amount| self.builder.pure(amount))
+            .collect::<Result<_, _>>()?;
+        Ok(self.builder.command(Command::SplitCoins(coin, amounts)))
+    }
+
+    pub fn merge_coins(&mut self, coin: Argument, coins: Vec<Argument>) -> Argument {
+        self.builder.command(Command::MergeCoins(coin, coins))
+    }
+
+    pub fn transfer_objects(
+        &mut self,
+        objects: Vec<Argument>,
+        recipient: SuiAddress,
+    ) -> anyhow::Result<Argument> {
+        let recipient = self.builder.pure(recipient)?;
+        Ok(self
+            .builder
+            .command(Command::TransferObjects(objects, recipient)))
+    }
+
+    pub fn make_move_vec(&mut self, type_: Option<TypeTag>, elements: Vec<Argument>) -> Argument {
+        self.builder.command(Command::MakeMoveVec(type_, elements))
+    }
+
+    /// Adds any other command.
+    pub fn command(&mut self, command: Command) -> Argument {
+        self.builder.command(command)
+    }
+
+    /// Selects the gas coins and, unless set, the gas budget of the transaction.
+    pub async fn build(self) -> anyhow::Result<TransactionData> {
+        let pt = self.builder.finish();
+        // Coins used as inputs cannot pay for gas.
+        let input_objects = pt
+            .input_objects()?
+            .into_iter()
+            .filter_map(|input| match input {
+                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let gas_coin_usage = gas_coin_usage(&pt)?;
+        let gas_price = match self.gas_price {
+            Some(gas_price) => gas_price,
+            None => self.client.read_api().get_reference_gas_price().await?,
+        };
+
+        let gas_budget = match self.gas_budget {
+            Some(gas_budget) => gas_budget,
+            None => {
+                estimate_gas_budget(
+                    self.client,
+                    self.sender,
+                    &pt,
+                    &input_objects,
+                    gas_coin_usage,
+                    self.max_gas_budget,
+                    gas_price,
+                )
+                .await?
+            }
+        };
+        let gas = match gas_coin_usage {
+            Some(amount) => self
+                .client
+                .coin_read_api()
+                .select_coins(
+                    self.sender,
+                    None,
+                    gas_budget as u128 + amount,
+                    None,
+                    input_objects,
+                )
+                .await?
+                .iter()
+                .map(|coin| coin.object_ref())
+                .collect(),
+            None => {
+                let gas = gas_coins(self.client, self.sender, &input_objects, u128::MAX).await;
+                if gas.is_empty() {
+                    bail!("Cannot find gas coins for sender [{}]", self.sender);
+                }
+                gas.into_iter().map(|(object_ref, _)| object_ref).collect()
+            }
+        };
+
+        Ok(TransactionData::new_with_gas_coins(
+            TransactionKind::programmable(pt),
+            self.sender,
+            gas,
+            gas_budget,
+            gas_price,
+        ))
+    }
+}
+
+/// Returns the amount of SUI the commands of `pt` take from the gas coin, or `None` if it is not
+/// known before execution, i.e. when the gas coin is used otherwise than split by pure amounts or
+/// merged into.
+fn gas_coin_usage(pt: &ProgrammableTransaction) -> anyhow::Result<Option<u128>> {
+    let mut usage = 0u128;
+    for command in &pt.commands {
+        let args = match command {
+            Command::SplitCoins(Argument::GasCoin, amounts) => {
+                for amount in amounts {
+                    let Argument::Input(i) = amount else {
+                        return Ok(None);
+                    };
+                    let Some(CallArg::Pure(bytes)) = pt.inputs.get(*i as usize) else {
+                        return Ok(None);
+                    };
+                    usage += bcs::from_bytes::<u64>(bytes)? as u128;
+                }
+                continue;
+            }
+            Command::MergeCoins(Argument::GasCoin, coins) => coins.clone(),
+            Command::MoveCall(call) => call.arguments.clone(),
+            Command::TransferObjects(objects, recipient) => {
+                let mut args = objects.clone();
+                args.push(*recipient);
+                args
+            }
+            Command::SplitCoins(coin, amounts) | Command::MergeCoins(coin, amounts) => {
+                let mut args = amounts.clone();
+                args.push(*coin);
+                args
+            }
+            Command::MakeMoveVec(_, elements) => elements.clone(),
+            Command::Upgrade(_, _, _, ticket) => vec![*ticket],
+            Command::Publish(_, _) => vec![],
+        };
+        if args.contains(&Argument::GasCoin) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(usage))
+}
+
+/// Returns the sender's SUI coins that are not inputs of the transaction, until their balance
+/// reaches `amount`.
+async fn gas_coins(
+    client: &SuiClient,
+    sender: SuiAddress,
+    input_objects: &[ObjectID],
+    amount: u128,
+) -> Vec<(ObjectRef, u64)> {
+    let mut balance = 0u128;
+    client
+        .coin_read_api()
+        .get_coins_stream(sender, None)
+        .filter(|coin| future::ready(!input_objects.contains(&coin.coin_object_id)))
+        .take_while(|coin| {
+            let take = balance < amount;
+            balance = balance.saturating_add(coin.balance as u128);
+            future::ready(take)
+        })
+        .map(|coin| (coin.object_ref(), coin.balance))
+        .collect()
+        .await
+}
+
+async fn estimate_gas_budget(
+    client: &SuiClient,
+    sender: SuiAddress,
+    pt: &ProgrammableTransaction,
+    input_objects: &[ObjectID],
+    gas_coin_usage: Option<u128>,
+    max_gas_budget: u64,
+    gas_price: u64,
+) -> anyhow::Result<u64> {
+    // Dry run with as much of the max budget as the sender's gas coins cover, on top of the
+    // amounts taken from the gas coin.
+    let gas_coins = gas_coins(
+        client,
+        sender,
+        input_objects,
+        gas_coin_usage.map_or(u128::MAX, |usage| usage + max_gas_budget as u128),
+    )
+    .await;
+    if gas_coins.is_empty() {
+        bail!("Cannot find gas coins for sender [{sender}]");
+    }
+    let balance: u128 = gas_coins.iter().map(|(_, balance)| *balance as u128).sum();
+    let budget = balance
+        .saturating_sub(gas_coin_usage.unwrap_or_default())
+        .min(max_gas_budget as u128) as u64;
+    let gas = gas_coins
+        .into_iter()
+        .map(|(object_ref, _)| object_ref)
+        .collect();
+
+    let tx = TransactionData::new_with_gas_coins(
+        TransactionKind::programmable(pt.clone()),
+        sender,
+        gas,
+        budget,
+        gas_price,
+    );
+    let effects = client
+        .read_api()
+        .dry_run_transaction_block(tx)
+        .await?
+        .effects;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        return Err(anyhow!("Dry run of the transaction failed: {error}"));
+    }
+
+    let cost = effects.gas_cost_summary();
+    let computation_cost = cost.computation_cost + GAS_SAFE_OVERHEAD * gas_price;
+    Ok(computation_cost
+        .max((computation_cost + cost.storage_cost).saturating_sub(cost.storage_rebate)))
+}
@@ -1,10 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use serde::Deserialize;
+use std::marker::PhantomData;
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::SUI_COIN_TYPE;
 use sui_types::crypto::Ed25519SuiSignature;
 use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
+use sui_types::id::UID;
+use sui_types::move_struct::MoveStruct;
+use test_utils::network::TestClusterBuilder;
 #[test]
 fn mnemonic_test() {
     let temp_dir = TempDir::new().unwrap();
@@ -32,3 +38,65 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[derive(Deserialize, MoveStruct)]
+#[move_struct(address = "0x2", module = "sui", name = "SUI")]
+struct Sui {}
+
+#[derive(Deserialize, MoveStruct)]
+#[move_struct(address = "0x1", module = "ascii", name = "String")]
+struct AsciiString {
+    bytes: Vec<u8>,
+}
+
+#[derive(Deserialize, MoveStruct)]
+#[move_struct(address = "0x2", module = "url")]
+struct Url {
+    url: AsciiString,
+}
+
+#[derive(Deserialize, MoveStruct)]
+#[move_struct(address = "0x2", module = "coin")]
+struct CoinMetadata<T> {
+    id: UID,
+    decimals: u8,
+    name: String,
+    symbol: AsciiString,
+    description: String,
+    icon_url: Option<Url>,
+    #[move_struct(skip)]
+    _phantom: PhantomData<T>,
+}
+
+#[tokio::test]
+async fn get_object_as_test() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await?;
+    let client = cluster.sui_client();
+    let expected = client
+        .coin_read_api()
+        .get_coin_metadata(SUI_COIN_TYPE.to_string())
+        .await?;
+    let id = expected.id.unwrap();
+
+    // The metadata of SUI is decoded from the object created at genesis.
+    let metadata: CoinMetadata<Sui> = client.read_api().get_object_as(id).await?;
+    assert_eq!(metadata.id.id.bytes, id);
+    assert_eq!(metadata.decimals, expected.decimals);
+    assert_eq!(metadata.name, expected.name);
+    assert_eq!(metadata.symbol.bytes, expected.symbol.into_bytes());
+    assert_eq!(metadata.description, expected.description);
+    assert_eq!(
+        metadata
+            .icon_url
+            .map(|url| String::from_utf8(url.url.bytes).unwrap()),
+        expected.icon_url
+    );
+
+    // Objects of another type are not decoded.
+    assert!(client
+        .read_api()
+        .get_object_as::<CoinMetadata<Url>>(id)
+        .await
+        .is_err());
+    Ok(())
+}
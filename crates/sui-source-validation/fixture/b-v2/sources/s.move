@@ -0,0 +1,12 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module b::s {
+    struct S has store, drop {
+        value: u64,
+    }
+
+    public fun value(s: &S): u64 {
+        s.value
+    }
+}
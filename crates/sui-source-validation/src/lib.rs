@@ -4,21 +4,30 @@
 use core::fmt;
 use futures::future;
 use move_binary_format::access::ModuleAccess;
-use move_binary_format::CompiledModule;
-use std::{collections::HashMap, fmt::Debug};
+use move_binary_format::{normalized, CompiledModule};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
 use sui_framework_build::compiled_package::CompiledPackage;
 use sui_types::error::SuiObjectResponseError;
+use sui_types::move_package::{TypeOrigin, UpgradeInfo};
 use thiserror::Error;
 
 use move_compiler::compiled_unit::{CompiledUnitEnum, NamedCompiledModule};
 use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
 use move_package::compilation::compiled_package::CompiledPackage as MoveCompiledPackage;
 use move_symbol_pool::Symbol;
 use sui_sdk::apis::ReadApi;
 use sui_sdk::error::Error;
 
-use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiRawData, SuiRawMoveObject, SuiRawMovePackage};
-use sui_types::base_types::ObjectID;
+use sui_sdk::rpc_types::{
+    SuiCommand, SuiObjectDataOptions, SuiRawData, SuiRawMoveObject, SuiRawMovePackage,
+    SuiTransactionBlockDataAPI, SuiTransactionBlockKind, SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber};
 
 #[cfg(test)]
 mod tests;
@@ -58,6 +67,42 @@ pub enum SourceVerificationError {
         module: Symbol,
     },
 
+    #[error(
+        "On-chain package links dependency {package} at {linked}, but it is published at \
+         {published_at} locally"
+    )]
+    LinkageMismatch {
+        package: Symbol,
+        linked: ObjectID,
+        published_at: ObjectID,
+    },
+
+    #[error(
+        "Local type {address}::{module}::{name} did not match its definition in version {origin} \
+         of the package"
+    )]
+    TypeOriginMismatch {
+        address: AccountAddress,
+        module: Symbol,
+        name: Symbol,
+        origin: ObjectID,
+    },
+
+    #[error(
+        "Could not read the transaction that upgraded package {package} to {upgraded}: {error:?}"
+    )]
+    UpgradeTransactionReadFailure {
+        package: AccountAddress,
+        upgraded: ObjectID,
+        error: Error,
+    },
+
+    #[error("Could not find the version of package {package} that was upgraded to {upgraded}")]
+    PreviousVersionNotFound {
+        package: AccountAddress,
+        upgraded: ObjectID,
+    },
+
     #[error("On-chain address cannot be zero")]
     ZeroOnChainAddresSpecifiedFailure,
 
@@ -65,19 +110,26 @@ pub enum SourceVerificationError {
     InvalidModuleFailure { name: String, message: String },
 }
 
+/// The errors found during a verification, along with the report of the packages that verified
+/// despite them.
 #[derive(Debug, Error)]
-pub struct AggregateSourceVerificationError(Vec<SourceVerificationError>);
+pub struct AggregateSourceVerificationError {
+    pub errors: Vec<SourceVerificationError>,
+    pub report: VerificationReport,
+}
 
 impl From<SourceVerificationError> for AggregateSourceVerificationError {
     fn from(error: SourceVerificationError) -> Self {
-        AggregateSourceVerificationError(vec![error])
+        AggregateSourceVerificationError {
+            errors: vec![error],
+            report: VerificationReport::default(),
+        }
     }
 }
 
 impl fmt::Display for AggregateSourceVerificationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let AggregateSourceVerificationError(errors) = self;
-        match &errors[..] {
+        match &self.errors[..] {
             [] => unreachable!("Aggregate error with no errors"),
             [error] => write!(f, "{}", error)?,
             errors => {
@@ -92,6 +144,63 @@ impl fmt::Display for AggregateSourceVerificationError {
     }
 }
 
+/// Packages whose modules matched their on-chain versions during a verification.
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    pub packages: Vec<PackageVerification>,
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for package in &self.packages {
+            writeln!(
+                f,
+                "Verified {} at {} (modules: {})",
+                package.package,
+                ObjectID::from(package.storage_id),
+                package.modules.join(", ")
+            )?;
+            for previous in &package.previous_versions {
+                write!(
+                    f,
+                    "  Verified previous version {} at {}",
+                    previous.version, previous.storage_id
+                )?;
+                if previous.types.is_empty() {
+                    writeln!(f, " (no types defined)")?;
+                } else {
+                    writeln!(f, " (types: {})", previous.types.join(", "))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVerification {
+    pub package: String,
+    /// ID of the first version of the package, which its modules are addressed by.
+    pub runtime_id: AccountAddress,
+    /// ID of the on-chain version of the package the modules were verified against.
+    pub storage_id: AccountAddress,
+    pub modules: Vec<String>,
+    /// The previous versions of the package, oldest first, whose types matched the verified
+    /// modules.
+    pub previous_versions: Vec<PreviousVersion>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousVersion {
+    pub storage_id: ObjectID,
+    pub version: SequenceNumber,
+    /// The types first defined by this version of the package, as `module::name`.
+    pub types: Vec<String>,
+}
+
 /// How to handle package source during bytecode verification.
 #[derive(PartialEq, Eq)]
 pub enum SourceMode {
@@ -126,7 +235,7 @@ impl<'a> BytecodeSourceVerifier<'a> {
         &self,
         compiled_package: &CompiledPackage,
         root_on_chain_address: AccountAddress,
-    ) -> Result<(), AggregateSourceVerificationError> {
+    ) -> Result<VerificationReport, AggregateSourceVerificationError> {
         self.verify_package(
             compiled_package,
             /* verify_deps */ true,
@@ -141,7 +250,7 @@ impl<'a> BytecodeSourceVerifier<'a> {
         &self,
         compiled_package: &CompiledPackage,
         root_on_chain_address: AccountAddress,
-    ) -> Result<(), AggregateSourceVerificationError> {
+    ) -> Result<VerificationReport, AggregateSourceVerificationError> {
         self.verify_package(
             compiled_package,
             /* verify_deps */ false,
@@ -155,7 +264,7 @@ impl<'a> BytecodeSourceVerifier<'a> {
     pub async fn verify_package_deps(
         &self,
         compiled_package: &CompiledPackage,
-    ) -> Result<(), AggregateSourceVerificationError> {
+    ) -> Result<VerificationReport, AggregateSourceVerificationError> {
         self.verify_package(
             compiled_package,
             /* verify_deps */ true,
@@ -168,67 +277,124 @@ impl<'a> BytecodeSourceVerifier<'a> {
     /// at the address specified on the Sui network we are publishing to.  If `verify_deps` is true,
     /// the dependencies are verified.  If `root_on_chain_address` is specified, the root is
     /// verified against a package at `root_on_chain_address`.
+    ///
+    /// When the root is verified along with its dependencies, the dependencies are verified
+    /// against the versions the on-chain root links to, according to its linkage table. The types
+    /// of verified upgraded packages are also verified against each previous version of the
+    /// package that defined them, following the chain of upgrades back to the first version.
     pub async fn verify_package(
         &self,
         compiled_package: &CompiledPackage,
         verify_deps: bool,
         source_mode: SourceMode,
-    ) -> Result<(), AggregateSourceVerificationError> {
-        let mut on_chain_pkgs = vec![];
-        match &source_mode {
-            SourceMode::Skip => (),
+    ) -> Result<VerificationReport, AggregateSourceVerificationError> {
+        let root_on_chain_address = match &source_mode {
+            SourceMode::Skip => None,
             // On-chain address for matching root package cannot be zero
             SourceMode::VerifyAt(AccountAddress::ZERO) => {
                 return Err(SourceVerificationError::ZeroOnChainAddresSpecifiedFailure.into())
             }
-            SourceMode::VerifyAt(root_address) => on_chain_pkgs.push(*root_address),
-            SourceMode::Verify => {
-                on_chain_pkgs.extend(compiled_package.published_at.as_ref().map(|id| **id))
-            }
+            SourceMode::VerifyAt(root_address) => Some(*root_address),
+            SourceMode::Verify => compiled_package.published_at.as_ref().ok().map(|id| **id),
         };
 
+        let mut on_chain_pkgs = vec![];
+        if let Some(root_address) = root_on_chain_address {
+            on_chain_pkgs.push((root_address, self.pkg_for_address(root_address).await?));
+        }
+
+        let mut errors = Vec::new();
         if verify_deps {
-            on_chain_pkgs.extend(
-                compiled_package
-                    .dependency_ids
-                    .published
-                    .values()
-                    .map(|id| **id),
-            );
+            let linkage_table = on_chain_pkgs.first().map(|(_, pkg)| &pkg.linkage_table);
+            let runtime_ids = dependency_runtime_ids(&compiled_package.package);
+            let mut dep_addresses = vec![];
+            for (package, published_at) in &compiled_package.dependency_ids.published {
+                let linked = runtime_ids
+                    .get(package)
+                    .and_then(|runtime_id| linkage_table?.get(&ObjectID::from(*runtime_id)));
+                match linked {
+                    Some(UpgradeInfo { upgraded_id, .. }) if upgraded_id != published_at => {
+                        errors.push(SourceVerificationError::LinkageMismatch {
+                            package: *package,
+                            linked: *upgraded_id,
+                            published_at: *published_at,
+                        });
+                        dep_addresses.push(**upgraded_id);
+                    }
+                    _ => dep_addresses.push(**published_at),
+                }
+            }
+            let deps = future::join_all(dep_addresses.iter().map(|a| self.pkg_for_address(*a)))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            on_chain_pkgs.extend(dep_addresses.into_iter().zip(deps));
         }
 
         let local_modules = local_modules(&compiled_package.package, verify_deps, source_mode)?;
-        let mut on_chain_modules = self.on_chain_modules(on_chain_pkgs.into_iter()).await?;
+        let (mut on_chain_modules, storage_ids) = on_chain_modules(&on_chain_pkgs)?;
 
-        let mut errors = Vec::new();
-        for ((address, module), (package, local_module)) in local_modules {
-            let Some(on_chain_module) = on_chain_modules.remove(&(address, module)) else {
+        let mut report = BTreeMap::new();
+        for ((address, module), (package, local_module)) in &local_modules {
+            let Some(on_chain_module) = on_chain_modules.remove(&(*address, *module)) else {
                 errors.push(SourceVerificationError::OnChainDependencyNotFound {
-                    package, module,
+                    package: *package, module: *module,
                 });
-		continue;
+                continue;
             };
 
             // compare local bytecode to on-chain bytecode to ensure integrity of our
             // dependencies
-            if local_module != on_chain_module {
+            if *local_module != on_chain_module {
                 errors.push(SourceVerificationError::ModuleBytecodeMismatch {
-                    address,
-                    package,
-                    module,
+                    address: *address,
+                    package: *package,
+                    module: *module,
                 });
+                continue;
             }
+
+            report
+                .entry(*address)
+                .or_insert_with(|| PackageVerification {
+                    package: package.to_string(),
+                    runtime_id: *address,
+                    storage_id: storage_ids[address],
+                    modules: vec![],
+                    previous_versions: vec![],
+                })
+                .modules
+                .push(module.to_string());
         }
 
         if let Some(((address, module), _)) = on_chain_modules.into_iter().next() {
             errors.push(SourceVerificationError::LocalDependencyNotFound { address, module });
         }
 
-        if !errors.is_empty() {
-            return Err(AggregateSourceVerificationError(errors));
+        for (_, pkg) in &on_chain_pkgs {
+            let Some(runtime_id) = storage_ids
+                .iter()
+                .find_map(|(runtime_id, storage_id)| (*storage_id == *pkg.id).then_some(*runtime_id))
+            else {
+                continue;
+            };
+            let Some(verification) = report.get_mut(&runtime_id) else {
+                continue;
+            };
+            verification.previous_versions = self
+                .verify_type_origins(runtime_id, pkg, &local_modules, &mut errors)
+                .await?;
         }
 
-        Ok(())
+        let mut packages: Vec<_> = report.into_values().collect();
+        for verification in &mut packages {
+            verification.modules.sort();
+        }
+        let report = VerificationReport { packages };
+        if !errors.is_empty() {
+            return Err(AggregateSourceVerificationError { errors, report });
+        }
+        Ok(report)
     }
 
     async fn pkg_for_address(
@@ -266,36 +432,215 @@ impl<'a> BytecodeSourceVerifier<'a> {
         }
     }
 
-    async fn on_chain_modules(
+    /// Find the version of package `runtime_id` that was upgraded to `upgraded`, by looking for
+    /// it among the packages upgraded by the transaction that published `upgraded`.
+    async fn previous_version(
         &self,
-        addresses: impl Iterator<Item = AccountAddress> + Clone,
-    ) -> Result<OnChainModules, AggregateSourceVerificationError> {
-        let resp = future::join_all(addresses.clone().map(|addr| self.pkg_for_address(addr))).await;
-        let mut map = OnChainModules::new();
-        let mut err = vec![];
-
-        for (storage_id, pkg) in addresses.zip(resp) {
-            let SuiRawMovePackage { module_map, .. } = pkg?;
-            for (name, bytes) in module_map {
-                let Ok(module) = CompiledModule::deserialize(&bytes) else {
-                    err.push(SourceVerificationError::OnChainDependencyDeserializationError {
-                        address: storage_id,
-                        module: name.into(),
-                    });
+        runtime_id: AccountAddress,
+        upgraded: ObjectID,
+    ) -> Result<SuiRawMovePackage, SourceVerificationError> {
+        let read_failure = |error| SourceVerificationError::UpgradeTransactionReadFailure {
+            package: runtime_id,
+            upgraded,
+            error,
+        };
+        let not_found = || SourceVerificationError::PreviousVersionNotFound {
+            package: runtime_id,
+            upgraded,
+        };
+
+        let digest = self
+            .rpc_client
+            .get_object_with_options(
+                upgraded,
+                SuiObjectDataOptions::new().with_previous_transaction(),
+            )
+            .await
+            .map_err(SourceVerificationError::DependencyObjectReadFailure)?
+            .into_object()
+            .map_err(SourceVerificationError::SuiObjectRefFailure)?
+            .previous_transaction
+            .ok_or_else(not_found)?;
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_options(
+                digest,
+                SuiTransactionBlockResponseOptions::new().with_input(),
+            )
+            .await
+            .map_err(read_failure)?
+            .transaction
+            .ok_or_else(not_found)?;
+        let SuiTransactionBlockKind::ProgrammableTransaction(pt) = transaction.data.transaction() else {
+            return Err(not_found());
+        };
+
+        for command in &pt.commands {
+            let SuiCommand::Upgrade(_, _, current_package_id, _) = command else {
+                continue;
+            };
+            let previous = self.pkg_for_address(**current_package_id).await?;
+            if package_runtime_id(&previous) == Some(runtime_id) {
+                return Ok(previous);
+            }
+        }
+        Err(not_found())
+    }
+
+    /// Verify the types of the local modules of package `runtime_id` against each previous
+    /// version of the package, walking the chain of upgrades from its on-chain version `pkg` back
+    /// to the first version. The types are verified against the versions that first defined them,
+    /// according to the type origin table of `pkg`. Returns the previous versions whose types all
+    /// matched, oldest first.
+    async fn verify_type_origins(
+        &self,
+        runtime_id: AccountAddress,
+        pkg: &SuiRawMovePackage,
+        local_modules: &LocalModules,
+        errors: &mut Vec<SourceVerificationError>,
+    ) -> Result<Vec<PreviousVersion>, SourceVerificationError> {
+        let mut previous_versions = vec![];
+        let mut upgraded = pkg.id;
+        while AccountAddress::from(upgraded) != runtime_id {
+            let previous = self.previous_version(runtime_id, upgraded).await?;
+            upgraded = previous.id;
+            previous_versions.push(previous);
+        }
+        previous_versions.reverse();
+
+        for TypeOrigin {
+            module_name,
+            struct_name,
+            package,
+        } in &pkg.type_origin_table
+        {
+            if *package != pkg.id && !previous_versions.iter().any(|p| p.id == *package) {
+                errors.push(SourceVerificationError::TypeOriginMismatch {
+                    address: runtime_id,
+                    module: module_name.as_str().into(),
+                    name: struct_name.as_str().into(),
+                    origin: *package,
+                });
+            }
+        }
+
+        let mut verified = vec![];
+        for previous in &previous_versions {
+            let mut matched = true;
+            let mut types = vec![];
+            for TypeOrigin {
+                module_name,
+                struct_name,
+                package,
+            } in &pkg.type_origin_table
+            {
+                if *package != previous.id {
+                    continue;
+                }
+                let Some((_, local_module)) =
+                    local_modules.get(&(runtime_id, Symbol::from(module_name.as_str())))
+                else {
                     continue;
                 };
 
-                let runtime_id = *module.self_id().address();
-                map.insert((runtime_id, Symbol::from(name)), module);
+                let local_struct = normalized_struct(local_module, struct_name);
+                let origin_struct = previous
+                    .module_map
+                    .get(module_name)
+                    .and_then(|bytes| CompiledModule::deserialize(bytes).ok())
+                    .filter(|module| *module.self_id().address() == runtime_id)
+                    .and_then(|module| normalized_struct(&module, struct_name));
+                if local_struct.is_none() || local_struct != origin_struct {
+                    matched = false;
+                    errors.push(SourceVerificationError::TypeOriginMismatch {
+                        address: runtime_id,
+                        module: module_name.as_str().into(),
+                        name: struct_name.as_str().into(),
+                        origin: previous.id,
+                    });
+                } else {
+                    types.push(format!("{module_name}::{struct_name}"));
+                }
+            }
+            if matched {
+                types.sort();
+                verified.push(PreviousVersion {
+                    storage_id: previous.id,
+                    version: previous.version,
+                    types,
+                });
             }
         }
 
-        if !err.is_empty() {
-            return Err(AggregateSourceVerificationError(err));
+        Ok(verified)
+    }
+}
+
+/// The runtime ID of an on-chain package: the address of its modules.
+fn package_runtime_id(pkg: &SuiRawMovePackage) -> Option<AccountAddress> {
+    let bytes = pkg.module_map.values().next()?;
+    let module = CompiledModule::deserialize(bytes).ok()?;
+    Some(*module.self_id().address())
+}
+
+/// Map the runtime IDs and names of the modules of the on-chain packages to their bytecode, along
+/// with the storage IDs of the packages by their runtime IDs.
+fn on_chain_modules(
+    pkgs: &[(AccountAddress, SuiRawMovePackage)],
+) -> Result<
+    (OnChainModules, BTreeMap<AccountAddress, AccountAddress>),
+    AggregateSourceVerificationError,
+> {
+    let mut map = OnChainModules::new();
+    let mut storage_ids = BTreeMap::new();
+    let mut err = vec![];
+
+    for (storage_id, pkg) in pkgs {
+        for (name, bytes) in &pkg.module_map {
+            let Ok(module) = CompiledModule::deserialize(bytes) else {
+                err.push(SourceVerificationError::OnChainDependencyDeserializationError {
+                    address: *storage_id,
+                    module: name.as_str().into(),
+                });
+                continue;
+            };
+
+            let runtime_id = *module.self_id().address();
+            storage_ids.insert(runtime_id, *storage_id);
+            map.insert((runtime_id, Symbol::from(name.as_str())), module);
         }
+    }
 
-        Ok(map)
+    if !err.is_empty() {
+        return Err(AggregateSourceVerificationError {
+            errors: err,
+            report: VerificationReport::default(),
+        });
     }
+
+    Ok((map, storage_ids))
+}
+
+fn normalized_struct(module: &CompiledModule, name: &str) -> Option<normalized::Struct> {
+    let name = Identifier::new(name).ok()?;
+    normalized::Module::new(module).structs.remove(&name)
+}
+
+/// Runtime IDs of the published dependencies of the package, by package name.
+fn dependency_runtime_ids(
+    compiled_package: &MoveCompiledPackage,
+) -> HashMap<Symbol, AccountAddress> {
+    compiled_package
+        .deps_compiled_units
+        .iter()
+        .filter_map(|(package, local_unit)| {
+            let CompiledUnitEnum::Module(m) = &local_unit.unit else {
+                return None;
+            };
+            let address = m.address.into_inner();
+            (address != AccountAddress::ZERO).then_some((*package, address))
+        })
+        .collect()
 }
 
 fn substitute_root_address(
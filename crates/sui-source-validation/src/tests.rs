@@ -16,7 +16,9 @@ use sui_types::{
 use test_utils::network::TestClusterBuilder;
 use test_utils::transaction::{publish_package_with_wallet, upgrade_package_with_wallet};
 
-use crate::{BytecodeSourceVerifier, SourceMode};
+use crate::{
+    BytecodeSourceVerifier, PackageVerification, PreviousVersion, SourceMode, VerificationReport,
+};
 
 #[tokio::test]
 async fn successful_verification() -> anyhow::Result<()> {
@@ -162,9 +164,16 @@ async fn successful_verification_upgrades() -> anyhow::Result<()> {
         upgrade_package(context, sender, b_v1.0, b_cap.0, b_src).await
     };
 
+    // b-v3 defines no types of its own.
+    let b_v3 = {
+        let fixtures = tempfile::tempdir()?;
+        let b_src = copy_published_package(&fixtures, "b-v2", SuiAddress::ZERO).await?;
+        upgrade_package(context, sender, b_v2.0, b_cap.0, b_src).await
+    };
+
     let (b_pkg, e_pkg) = {
         let fixtures = tempfile::tempdir()?;
-        let b_src = copy_upgraded_package(&fixtures, "b-v2", b_v2.0.into(), b_v1.0.into()).await?;
+        let b_src = copy_upgraded_package(&fixtures, "b-v2", b_v3.0.into(), b_v1.0.into()).await?;
         let e_src = copy_published_package(&fixtures, "e", SuiAddress::ZERO).await?;
         (compile_package(b_src), compile_package(e_src))
     };
@@ -172,15 +181,98 @@ async fn successful_verification_upgrades() -> anyhow::Result<()> {
     let client = context.get_client().await?;
     let verifier = BytecodeSourceVerifier::new(client.read_api());
 
-    // Verify the upgraded package b-v2 as the root.
+    // Verify the upgraded package b-v3 as the root, and each previous version of it.
     let verify_deps = false;
-    verifier
+    let report = verifier
         .verify_package(&b_pkg, verify_deps, SourceMode::Verify)
         .await
         .unwrap();
+    assert_eq!(
+        report,
+        VerificationReport {
+            packages: vec![PackageVerification {
+                package: "b".to_string(),
+                runtime_id: b_v1.0.into(),
+                storage_id: b_v3.0.into(),
+                modules: vec!["b".into(), "c".into(), "d".into(), "s".into()],
+                previous_versions: vec![
+                    PreviousVersion {
+                        storage_id: b_v1.0,
+                        version: b_v1.1,
+                        types: vec!["s::S".into()],
+                    },
+                    PreviousVersion {
+                        storage_id: b_v2.0,
+                        version: b_v2.1,
+                        types: vec![],
+                    },
+                ],
+            }],
+        }
+    );
+
+    // Verify the upgraded package b-v3 as a dep of e.
+    let report = verifier.verify_package_deps(&e_pkg).await.unwrap();
+    let b_report = report.packages.iter().find(|p| p.package == "b").unwrap();
+    assert_eq!(b_report.storage_id, AccountAddress::from(b_v3.0));
+    let previous_versions: Vec<_> = b_report
+        .previous_versions
+        .iter()
+        .map(|p| p.storage_id)
+        .collect();
+    assert_eq!(previous_versions, vec![b_v1.0, b_v2.0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fail_verification_linkage_mismatch() -> anyhow::Result<()> {
+    let mut cluster = TestClusterBuilder::new().build().await?;
+    let sender = cluster.get_address_0();
+    let context = &mut cluster.wallet;
+    let mut stable_addrs = HashMap::new();
+
+    let (b_v1, b_cap) = {
+        let fixtures = tempfile::tempdir()?;
+        let b_src = copy_published_package(&fixtures, "b", SuiAddress::ZERO).await?;
+        publish_package(context, sender, b_src).await
+    };
+    stable_addrs.insert(b_v1.0.into(), "<b_v1>");
 
-    // Verify the upgraded package b-v2 as a dep of e.
-    verifier.verify_package_deps(&e_pkg).await.unwrap();
+    let b_v2 = {
+        let fixtures = tempfile::tempdir()?;
+        let b_src = copy_published_package(&fixtures, "b-v2", SuiAddress::ZERO).await?;
+        upgrade_package(context, sender, b_v1.0, b_cap.0, b_src).await
+    };
+    stable_addrs.insert(b_v2.0.into(), "<b_v2>");
+
+    // e is published against b-v2.
+    let e_ref = {
+        let fixtures = tempfile::tempdir()?;
+        copy_upgraded_package(&fixtures, "b-v2", b_v2.0.into(), b_v1.0.into()).await?;
+        let e_src = copy_published_package(&fixtures, "e", SuiAddress::ZERO).await?;
+        publish_package(context, sender, e_src).await.0
+    };
+
+    // Locally, the dependency of e is published at the first version of b.
+    let e_pkg = {
+        let fixtures = tempfile::tempdir()?;
+        copy_upgraded_package(&fixtures, "b-v2", b_v1.0.into(), b_v1.0.into()).await?;
+        let e_src = copy_published_package(&fixtures, "e", SuiAddress::ZERO).await?;
+        compile_package(e_src)
+    };
+
+    let client = context.get_client().await?;
+    let verifier = BytecodeSourceVerifier::new(client.read_api());
+
+    let Err(err) = verifier.verify_package_root_and_deps(&e_pkg, e_ref.0.into()).await else {
+        panic!("Expected verification to fail");
+    };
+
+    let expected = expect![
+        "On-chain package links dependency b at 0x<b_v2>, but it is published at 0x<b_v1> locally"
+    ];
+    expected.assert_eq(&sanitize_id(err.to_string(), &stable_addrs));
 
     Ok(())
 }
@@ -504,6 +596,16 @@ async fn module_bytecode_mismatch() -> anyhow::Result<()> {
     let expected = expect!["Local dependency did not match its on-chain version at <b_id>::b::c"];
     expected.assert_eq(&sanitize_id(err.to_string(), &stable_addrs));
 
+    // The modules that did match are still reported.
+    let b_report = err
+        .report
+        .packages
+        .iter()
+        .find(|p| p.package == "b")
+        .unwrap();
+    assert_eq!(b_report.storage_id, AccountAddress::from(b_ref.0));
+    assert_eq!(b_report.modules, vec!["b", "d", "s"]);
+
     let Err(err) = verifier.verify_package_root(&a_pkg, a_addr.into()).await else {
         panic!("Expected verification to fail");
     };
@@ -0,0 +1,234 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archive of checkpoints on top of an object store. For every checkpoint, the archive holds the
+//! certified summary along with the full contents of the checkpoint: its transactions and their
+//! effects.
+//!
+//! Checkpoints are archived in order, into files of consecutive checkpoints which never span
+//! epochs, laid out as `epoch_<epoch>/<first checkpoint>.chk`. A file starts with a magic number,
+//! the version of the format and the compression of the rest of the file, which holds the BCS
+//! encoding of the checkpoints. The `MANIFEST` file at the root of the archive lists the files
+//! along with their SHA3-256 checksum, and is only updated once a file has been fully written so
+//! that readers never observe partial files. Writers update it every few files rather than after
+//! each one, and resume after its last file when restarted.
+
+use anyhow::{anyhow, Context, Result};
+use bytes::{BufMut, Bytes, BytesMut};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::Arc;
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+
+use crate::compression::FileCompression;
+use crate::object_store::util::put;
+
+pub mod reader;
+pub mod writer;
+
+pub use reader::ArchiveReader;
+pub use writer::ArchiveWriter;
+
+pub const MANIFEST_FILENAME: &str = "MANIFEST";
+pub const CHECKPOINT_FILE_SUFFIX: &str = "chk";
+pub const MANIFEST_FILE_MAGIC: u32 = 0x004D_414E;
+pub const CHECKPOINT_FILE_MAGIC: u32 = 0x0043_4B50;
+pub const ARCHIVE_FORMAT_VERSION: u8 = 1;
+
+/// A checkpoint as stored in the archive. The checkpoint is not verified: readers must verify the
+/// summary against the committee of its epoch, and the contents against the summary.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedCheckpoint {
+    pub summary: CertifiedCheckpointSummary,
+    pub contents: FullCheckpointContents,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub epoch: EpochId,
+    pub checkpoint_seq_range: Range<CheckpointSequenceNumber>,
+    pub sha3_digest: [u8; 32],
+}
+
+impl FileMetadata {
+    pub fn file_path(&self) -> Path {
+        Path::from(format!("epoch_{}", self.epoch)).child(format!(
+            "{}.{CHECKPOINT_FILE_SUFFIX}",
+            self.checkpoint_seq_range.start
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u8,
+    /// The sequence number of the next checkpoint to archive.
+    pub next_checkpoint_seq_num: CheckpointSequenceNumber,
+    /// The files of the archive, in the order of the checkpoints they hold.
+    pub files: Vec<FileMetadata>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            version: ARCHIVE_FORMAT_VERSION,
+            next_checkpoint_seq_num: 0,
+            files: vec![],
+        }
+    }
+}
+
+impl Manifest {
+    /// Returns the file holding the checkpoint with the provided sequence number, if archived.
+    pub fn file_for_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<&FileMetadata> {
+        let index = self
+            .files
+            .partition_point(|file| file.checkpoint_seq_range.end <= sequence_number);
+        self.files
+            .get(index)
+            .filter(|file| file.checkpoint_seq_range.contains(&sequence_number))
+    }
+
+    fn push(&mut self, file: FileMetadata) {
+        assert_eq!(
+            file.checkpoint_seq_range.start, self.next_checkpoint_seq_num,
+            "Files must be archived in the order of checkpoints"
+        );
+        self.next_checkpoint_seq_num = file.checkpoint_seq_range.end;
+        self.files.push(file);
+    }
+}
+
+pub fn encode_checkpoint_file(
+    checkpoints: &[ArchivedCheckpoint],
+    compression: FileCompression,
+) -> Result<Bytes> {
+    let payload = compression.compress(bcs::to_bytes(checkpoints)?)?;
+    let mut bytes = BytesMut::with_capacity(payload.len() + 6);
+    bytes.put_u32(CHECKPOINT_FILE_MAGIC);
+    bytes.put_u8(ARCHIVE_FORMAT_VERSION);
+    bytes.put_u8(compression as u8);
+    bytes.put_slice(&payload);
+    Ok(bytes.freeze())
+}
+
+pub fn decode_checkpoint_file(bytes: &[u8]) -> Result<Vec<ArchivedCheckpoint>> {
+    if bytes.len() < 6 {
+        return Err(anyhow!("Checkpoint file is too short"));
+    }
+    let (header, payload) = bytes.split_at(6);
+    if header[..4] != CHECKPOINT_FILE_MAGIC.to_be_bytes()[..] {
+        return Err(anyhow!("Unexpected magic number in checkpoint file"));
+    }
+    if header[4] != ARCHIVE_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported checkpoint file version {}", header[4]));
+    }
+    let compression = FileCompression::try_from(header[5])?;
+    Ok(bcs::from_bytes(&compression.decompress(payload)?)?)
+}
+
+/// Encodes the manifest, followed by the checksum of the encoding.
+pub fn encode_manifest(manifest: &Manifest) -> Result<Bytes> {
+    let mut bytes = BytesMut::new();
+    bytes.put_u32(MANIFEST_FILE_MAGIC);
+    bytes.put_slice(&bcs::to_bytes(manifest)?);
+    let checksum = Sha3_256::digest(&bytes[..]).digest;
+    bytes.put_slice(&checksum);
+    Ok(bytes.freeze())
+}
+
+pub fn decode_manifest(bytes: &[u8]) -> Result<Manifest> {
+    if bytes.len() < 4 + 32 {
+        return Err(anyhow!("Manifest is too short"));
+    }
+    let (content, checksum) = bytes.split_at(bytes.len() - 32);
+    if checksum != Sha3_256::digest(content).digest.as_slice() {
+        return Err(anyhow!("Manifest checksum does not match its content"));
+    }
+    let (magic, manifest) = content.split_at(4);
+    if magic != MANIFEST_FILE_MAGIC.to_be_bytes().as_slice() {
+        return Err(anyhow!("Unexpected magic number in manifest"));
+    }
+    let manifest: Manifest = bcs::from_bytes(manifest)?;
+    if manifest.version != ARCHIVE_FORMAT_VERSION {
+        return Err(anyhow!("Unsupported manifest version {}", manifest.version));
+    }
+    Ok(manifest)
+}
+
+/// Reads the manifest of the archive, or None if nothing has been archived yet.
+pub async fn read_manifest(store: Arc<DynObjectStore>) -> Result<Option<Manifest>> {
+    match store.get(&Path::from(MANIFEST_FILENAME)).await {
+        Ok(result) => {
+            let bytes = result.bytes().await?;
+            decode_manifest(&bytes).map(Some)
+        }
+        Err(object_store::Error::NotFound { .. }) => Ok(None),
+        Err(err) => Err(err).context("Failed to read archive manifest"),
+    }
+}
+
+pub async fn write_manifest(store: Arc<DynObjectStore>, manifest: &Manifest) -> Result<()> {
+    put(
+        &Path::from(MANIFEST_FILENAME),
+        encode_manifest(manifest)?,
+        store,
+    )
+    .await
+    .context("Failed to write archive manifest")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let mut manifest = Manifest::default();
+        for range in [0..10, 10..15, 15..25] {
+            manifest.push(FileMetadata {
+                epoch: range.start / 10,
+                checkpoint_seq_range: range,
+                sha3_digest: [7; 32],
+            });
+        }
+        assert_eq!(manifest.next_checkpoint_seq_num, 25);
+
+        let bytes = encode_manifest(&manifest).unwrap();
+        assert_eq!(decode_manifest(&bytes).unwrap(), manifest);
+
+        // Any corruption of the manifest is detected
+        let mut corrupted = bytes.to_vec();
+        corrupted[8] ^= 1;
+        assert!(decode_manifest(&corrupted).is_err());
+
+        let range_of = |sequence_number| -> Option<Range<u64>> {
+            manifest
+                .file_for_checkpoint(sequence_number)
+                .map(|file| file.checkpoint_seq_range.clone())
+        };
+        assert_eq!(range_of(0), Some(0..10));
+        assert_eq!(range_of(10), Some(10..15));
+        assert_eq!(range_of(24), Some(15..25));
+        assert_eq!(range_of(25), None);
+    }
+
+    #[test]
+    fn test_checkpoint_file_round_trip() {
+        for compression in [FileCompression::None, FileCompression::Snappy] {
+            let bytes = encode_checkpoint_file(&[], compression).unwrap();
+            assert!(decode_checkpoint_file(&bytes).unwrap().is_empty());
+        }
+        assert!(decode_checkpoint_file(b"CHK").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Context, Result};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use object_store::DynObjectStore;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use super::{decode_checkpoint_file, read_manifest, ArchivedCheckpoint, FileMetadata, Manifest};
+use crate::object_store::ObjectStoreConfig;
+
+/// Reads back the checkpoints of an archive written by the `ArchiveWriter`.
+#[derive(Clone)]
+pub struct ArchiveReader {
+    remote_object_store: Arc<DynObjectStore>,
+    /// How long the manifest is cached before it is read again from the archive.
+    manifest_refresh_interval: Duration,
+    cached_manifest: Arc<Mutex<CachedManifest>>,
+}
+
+#[derive(Default)]
+struct CachedManifest {
+    /// When the manifest was last read, successfully or not.
+    read_at: Option<Instant>,
+    manifest: Option<Arc<Manifest>>,
+}
+
+impl ArchiveReader {
+    pub fn new(
+        remote_object_store_config: &ObjectStoreConfig,
+        manifest_refresh_interval: Duration,
+    ) -> Result<Self> {
+        Ok(ArchiveReader {
+            remote_object_store: remote_object_store_config.make()?,
+            manifest_refresh_interval,
+            cached_manifest: Arc::new(Mutex::new(CachedManifest::default())),
+        })
+    }
+
+    /// Reads the manifest from the archive, and caches it.
+    pub async fn read_manifest(&self) -> Result<Manifest> {
+        let manifest = read_manifest(self.remote_object_store.clone())
+            .await
+            .and_then(|manifest| manifest.ok_or_else(|| anyhow!("Checkpoint archive is empty")));
+        let mut cached_manifest = self.cached_manifest.lock();
+        cached_manifest.read_at = Some(Instant::now());
+        cached_manifest.manifest = manifest.as_ref().ok().cloned().map(Arc::new);
+        manifest
+    }
+
+    /// Returns the cached manifest, which is read again from the archive once the refresh
+    /// interval has elapsed since the last read.
+    async fn manifest(&self) -> Result<Arc<Manifest>> {
+        {
+            let cached_manifest = self.cached_manifest.lock();
+            if self.is_fresh(&cached_manifest) {
+                return cached_manifest
+                    .manifest
+                    .clone()
+                    .ok_or_else(|| anyhow!("Checkpoint archive manifest could not be read"));
+            }
+        }
+        self.read_manifest().await.map(Arc::new)
+    }
+
+    /// Returns false when the archive is known not to hold the checkpoint with the provided
+    /// sequence number, according to the cached manifest. Once the refresh interval has elapsed,
+    /// the archive may hold any checkpoint until the manifest is read again.
+    pub fn may_hold_checkpoint(&self, sequence_number: CheckpointSequenceNumber) -> bool {
+        let cached_manifest = self.cached_manifest.lock();
+        if !self.is_fresh(&cached_manifest) {
+            return true;
+        }
+        cached_manifest.manifest.as_ref().map_or(false, |manifest| {
+            manifest.file_for_checkpoint(sequence_number).is_some()
+        })
+    }
+
+    fn is_fresh(&self, cached_manifest: &CachedManifest) -> bool {
+        cached_manifest.read_at.map_or(false, |read_at| {
+            read_at.elapsed() < self.manifest_refresh_interval
+        })
+    }
+
+    /// Reads the checkpoints of an archived file, after verifying the file against its checksum.
+    pub async fn read_checkpoint_file(
+        &self,
+        file_metadata: &FileMetadata,
+    ) -> Result<Vec<ArchivedCheckpoint>> {
+        let path = file_metadata.file_path();
+        let bytes = match self.remote_object_store.get(&path).await {
+            Ok(result) => result.bytes().await,
+            Err(err) => Err(err),
+        }
+        .with_context(|| format!("Failed to read archived file {path}"))?;
+        if Sha3_256::digest(&bytes).digest != file_metadata.sha3_digest {
+            return Err(anyhow!("Checksum of archived file {path} does not match"));
+        }
+        decode_checkpoint_file(&bytes)
+    }
+
+    /// Reads the archived checkpoints starting from the one with the provided sequence number, up
+    /// to the end of the file holding it.
+    pub async fn read_checkpoints_from(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<Vec<ArchivedCheckpoint>> {
+        let manifest = self.manifest().await?;
+        let file_metadata = manifest
+            .file_for_checkpoint(sequence_number)
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} is not archived"))?;
+        let mut checkpoints = self.read_checkpoint_file(file_metadata).await?;
+        checkpoints.retain(|checkpoint| checkpoint.summary.sequence_number >= sequence_number);
+        Ok(checkpoints)
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use fastcrypto::hash::{HashFunction, Sha3_256};
+use object_store::DynObjectStore;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ReadStore;
+use tokio::sync::oneshot::{self, Sender};
+use tracing::{error, info};
+
+use super::{
+    encode_checkpoint_file, read_manifest, write_manifest, ArchivedCheckpoint, FileMetadata,
+    Manifest,
+};
+use crate::compression::FileCompression;
+use crate::object_store::util::put;
+use crate::object_store::ObjectStoreConfig;
+
+/// Maximum number of files written between two updates of the manifest.
+const MANIFEST_UPDATE_INTERVAL: usize = 16;
+
+/// Continuously archives the checkpoints synced by the node into an object store.
+pub struct ArchiveWriter<S> {
+    store: S,
+    remote_object_store: Arc<DynObjectStore>,
+    /// Maximum number of checkpoints archived in a single file
+    checkpoints_per_file: u64,
+    /// Time interval to check for newly synced checkpoints
+    interval: Duration,
+    /// The manifest of the archive, read from the object store once and then kept up to date in
+    /// memory. It may list files that are not listed in the object store yet.
+    manifest: Option<Manifest>,
+    /// Number of files of `manifest` listed in the object store.
+    listed_files: usize,
+}
+
+impl<S> ArchiveWriter<S>
+where
+    S: ReadStore + Send + Sync + 'static,
+    <S as ReadStore>::Error: std::error::Error + Send + Sync + 'static,
+{
+    pub fn new(
+        store: S,
+        remote_object_store_config: &ObjectStoreConfig,
+        checkpoints_per_file: u64,
+        interval: Duration,
+    ) -> Result<Self> {
+        assert!(checkpoints_per_file > 0);
+        Ok(ArchiveWriter {
+            store,
+            remote_object_store: remote_object_store_config.make()?,
+            checkpoints_per_file,
+            interval,
+            manifest: None,
+            listed_files: 0,
+        })
+    }
+
+    pub fn start(mut self) -> Sender<()> {
+        let (sender, mut recv) = oneshot::channel::<()>();
+        let mut interval = tokio::time::interval(self.interval);
+        tokio::task::spawn(async move {
+            info!("Checkpoint archive writer loop started");
+            loop {
+                tokio::select! {
+                    _now = interval.tick() => {
+                        if let Err(err) = self.archive_synced_checkpoints().await {
+                            error!("Failed to archive checkpoints with err: {:?}", err);
+                        }
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+        });
+        sender
+    }
+
+    /// Archives the checkpoints synced since the last archived one. Files are only written once
+    /// complete, when they hold `checkpoints_per_file` checkpoints or end with the last checkpoint
+    /// of an epoch, so the most recent checkpoints are archived with a delay.
+    pub async fn archive_synced_checkpoints(&mut self) -> Result<()> {
+        let mut manifest = match self.manifest.take() {
+            Some(manifest) => manifest,
+            None => {
+                // Resume after the last file listed in the manifest of the archive
+                let manifest = read_manifest(self.remote_object_store.clone())
+                    .await?
+                    .unwrap_or_default();
+                self.listed_files = manifest.files.len();
+                manifest
+            }
+        };
+        let result = self.archive_files(&mut manifest).await;
+        self.manifest = Some(manifest);
+        result
+    }
+
+    async fn archive_files(&mut self, manifest: &mut Manifest) -> Result<()> {
+        let highest_synced = *self
+            .store
+            .get_highest_synced_checkpoint()?
+            .sequence_number();
+
+        while let Some(checkpoints) =
+            self.next_file_checkpoints(manifest.next_checkpoint_seq_num, highest_synced)?
+        {
+            let file_metadata = self.write_checkpoint_file(&checkpoints).await?;
+            info!(
+                "Archived checkpoints {:?} of epoch {}",
+                file_metadata.checkpoint_seq_range, file_metadata.epoch
+            );
+            manifest.push(file_metadata);
+            if manifest.files.len() - self.listed_files >= MANIFEST_UPDATE_INTERVAL {
+                self.update_manifest(manifest).await?;
+            }
+        }
+        if manifest.files.len() > self.listed_files {
+            self.update_manifest(manifest).await?;
+        }
+        Ok(())
+    }
+
+    /// Lists the files written so far in the manifest of the object store. The manifest is only
+    /// updated once its files are fully written.
+    async fn update_manifest(&mut self, manifest: &Manifest) -> Result<()> {
+        write_manifest(self.remote_object_store.clone(), manifest).await?;
+        self.listed_files = manifest.files.len();
+        Ok(())
+    }
+
+    /// Returns the checkpoints of the file starting at checkpoint `start`, or None if the
+    /// checkpoints of the file have not all been synced yet.
+    fn next_file_checkpoints(
+        &self,
+        start: CheckpointSequenceNumber,
+        highest_synced: CheckpointSequenceNumber,
+    ) -> Result<Option<Vec<ArchivedCheckpoint>>> {
+        let mut checkpoints = vec![];
+        for sequence_number in start..=highest_synced {
+            let summary = self
+                .store
+                .get_checkpoint_by_sequence_number(sequence_number)?
+                .ok_or_else(|| anyhow!("Missing synced checkpoint {sequence_number}"))?;
+            let contents = self
+                .store
+                .get_full_checkpoint_contents(&summary.content_digest)?
+                .ok_or_else(|| {
+                    anyhow!("Missing contents of synced checkpoint {sequence_number}")
+                })?;
+
+            let end_of_epoch = summary.next_epoch_committee().is_some();
+            checkpoints.push(ArchivedCheckpoint {
+                summary: summary.into_inner(),
+                contents,
+            });
+            if end_of_epoch || checkpoints.len() as u64 == self.checkpoints_per_file {
+                return Ok(Some(checkpoints));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn write_checkpoint_file(
+        &self,
+        checkpoints: &[ArchivedCheckpoint],
+    ) -> Result<FileMetadata> {
+        let first = &checkpoints[0].summary;
+        let last = &checkpoints[checkpoints.len() - 1].summary;
+        let bytes = encode_checkpoint_file(checkpoints, FileCompression::Snappy)?;
+        let file_metadata = FileMetadata {
+            epoch: first.epoch,
+            checkpoint_seq_range: *first.sequence_number()..last.sequence_number() + 1,
+            sha3_digest: Sha3_256::digest(&bytes).digest,
+        };
+        put(
+            &file_metadata.file_path(),
+            bytes,
+            self.remote_object_store.clone(),
+        )
+        .await?;
+        Ok(file_metadata)
+    }
+}
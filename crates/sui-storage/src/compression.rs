@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression of the files written to object stores, shared by the checkpoint archive and the
+//! state snapshots. The compression is recorded as one byte in the header of each file.
+
+use anyhow::{anyhow, Result};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FileCompression {
+    None = 0,
+    Snappy = 1,
+}
+
+impl FileCompression {
+    pub fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            FileCompression::None => Ok(bytes),
+            FileCompression::Snappy => Ok(snap::raw::Encoder::new().compress_vec(&bytes)?),
+        }
+    }
+
+    pub fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            FileCompression::None => Ok(bytes.to_vec()),
+            FileCompression::Snappy => Ok(snap::raw::Decoder::new().decompress_vec(bytes)?),
+        }
+    }
+}
+
+impl TryFrom<u8> for FileCompression {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FileCompression::None),
+            1 => Ok(FileCompression::Snappy),
+            _ => Err(anyhow!("Unknown file compression {value}")),
+        }
+    }
+}
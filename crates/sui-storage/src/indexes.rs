@@ -5,26 +5,32 @@
 //! The main user of this data is the explorer.
 
 use std::cmp::{max, min};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 
 use anyhow::anyhow;
+use itertools::Itertools;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use parking_lot::RwLock;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sui_types::temporary_store::TxCoins;
-use tracing::{debug, trace};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, trace};
 
-use sui_json_rpc_types::SuiObjectDataFilter;
+use sui_json_rpc_types::{coin_balance_deltas, SuiObjectDataFilter};
 use sui_types::base_types::{
     ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
 };
-use sui_types::base_types::{ObjectInfo, ObjectRef};
+use sui_types::base_types::{ObjectInfo, ObjectRef, ObjectType};
 use sui_types::digests::{ObjectDigest, TransactionEventsDigest};
 use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::TransactionEvents;
-use sui_types::object::Owner;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::{Object, Owner};
 use sui_types::query::TransactionFilter;
 use typed_store::rocks::{default_db_options, point_lookup_db_options, DBBatch, DBMap, MetricConf};
 use typed_store::rocks::{
@@ -35,13 +41,32 @@ use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store_derive::DBMapUtils;
 
 type OwnerIndexKey = (SuiAddress, ObjectID);
+/// (owner, package, module, struct name, type params, object id)
+type OwnerTypeIndexKey = (SuiAddress, ObjectID, String, String, String, ObjectID);
 type CoinIndexKey = (SuiAddress, String, ObjectID);
+type BalanceChangeKey = (SuiAddress, String, TxSequenceNumber);
 type DynamicFieldKey = (ObjectID, ObjectID);
 type EventId = (TxSequenceNumber, usize);
 type EventIndex = (TransactionEventsDigest, TransactionDigest, u64);
+type TransactionIndexIter<'a> =
+    Box<dyn Iterator<Item = (TxSequenceNumber, TransactionDigest)> + 'a>;
+
+/// Resolves the checkpoint a transaction was finalized in, if any.
+pub type CheckpointResolver<'a> =
+    dyn Fn(&TransactionDigest) -> SuiResult<Option<CheckpointSequenceNumber>> + 'a;
+
+/// Module and function names matching each partial `MoveFunction` filter, listed once per query.
+type PartialMoveFunctions =
+    HashMap<(ObjectID, Option<String>, Option<String>), Vec<(String, String)>>;
 
 pub const MAX_TX_RANGE_SIZE: u64 = 4096;
 
+/// Maximum number of transactions scanned for a filter that has no indexed criterion.
+pub const MAX_UNINDEXED_TX_SCAN: usize = 10_000;
+
+/// Maximum number of events scanned for a page of a combined event filter.
+pub const MAX_COMPOSITE_EVENT_SCAN: usize = 10_000;
+
 pub const MAX_GET_OWNED_OBJECT_SIZE: usize = 256;
 const ENV_VAR_COIN_INDEX_BLOCK_CACHE_SIZE_MB: &str = "COIN_INDEX_BLOCK_CACHE_MB";
 
@@ -61,6 +86,32 @@ pub struct CoinInfo {
     pub previous_transaction: TransactionDigest,
 }
 
+/// Progress of populating `owner_type_index` from `owner_index`, for stores created before the
+/// type index existed.
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub enum OwnerTypeIndexBackfill {
+    /// Backfilling resumes from this `owner_index` key.
+    Resume(OwnerIndexKey),
+    Complete,
+}
+
+/// Progress of indexing the live object set of a store whose transactions were not indexed, i.e.
+/// a new store or one restored from a state snapshot.
+#[derive(Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub enum LiveObjectSetIndexing {
+    NotStarted,
+    /// Indexing resumes after this object.
+    Resume(ObjectID),
+    Complete,
+}
+
+/// Net change made by one transaction to the coins of one type owned by an address.
+#[derive(Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Debug, Default)]
+pub struct BalanceDelta {
+    pub balance: i128,
+    pub coin_object_count: i64,
+}
+
 #[derive(DBMapUtils)]
 pub struct IndexStoreTables {
     /// Index from sui address to transactions initiated by that address.
@@ -106,9 +157,34 @@ pub struct IndexStoreTables {
     #[default_options_override_fn = "owner_index_table_default_config"]
     owner_index: DBMap<OwnerIndexKey, ObjectInfo>,
 
+    /// This is a secondary index of `owner_index`, keyed by the owner and the type of the object,
+    /// so that the objects of a package, module or struct type owned by an address can be listed
+    /// with a range scan instead of filtering every object the address owns.
+    #[default_options_override_fn = "owner_index_table_default_config"]
+    owner_type_index: DBMap<OwnerTypeIndexKey, ObjectInfo>,
+
+    /// Progress of backfilling `owner_type_index`, the index is only queried once complete.
+    #[default_options_override_fn = "index_table_default_config"]
+    owner_type_index_backfill: DBMap<(), OwnerTypeIndexBackfill>,
+
+    /// Progress of indexing the live object set, stores without an entry whose owner index is
+    /// not empty were indexed from their genesis transactions.
+    #[default_options_override_fn = "index_table_default_config"]
+    live_object_set_indexing: DBMap<(), LiveObjectSetIndexing>,
+
     #[default_options_override_fn = "coin_index_table_default_config"]
     coin_index: DBMap<CoinIndexKey, CoinInfo>,
 
+    /// Index from an address and coin type to the balance changes made by each transaction,
+    /// used to answer balance queries as of a past checkpoint.
+    #[default_options_override_fn = "index_table_default_config"]
+    balance_changes: DBMap<BalanceChangeKey, BalanceDelta>,
+
+    /// Sequence number of the first transaction whose balance changes were indexed. Balances
+    /// cannot be rolled back to checkpoints executed before it.
+    #[default_options_override_fn = "index_table_default_config"]
+    balance_changes_start: DBMap<(), TxSequenceNumber>,
+
     /// This is an index of object references to currently existing dynamic field object, indexed by the
     /// composite key of the object ID of their parent and the object ID of the dynamic field object.
     /// This composite index allows an efficient iterator to list all objects currently owned
@@ -131,6 +207,14 @@ pub struct IndexStoreTables {
 pub struct IndexStore {
     next_sequence_number: AtomicU64,
     tables: IndexStoreTables,
+    owner_type_index_complete: AtomicBool,
+    /// Held for writing while backfilling a batch of `owner_type_index` or indexing a batch of the
+    /// live object set, and for reading while indexing changes of the object owners, so that
+    /// backfilled entries are never stale.
+    owner_type_index_lock: RwLock<()>,
+    /// Held for reading while writing changes of `coin_index` and `balance_changes`, and for
+    /// writing while reading both, so that balances are computed from a consistent view.
+    coin_balance_lock: RwLock<()>,
 }
 
 // These functions are used to initialize the DB tables
@@ -190,17 +274,106 @@ impl IndexStore {
             .skip_to_last()
             .next()
             .map(|(seq, _)| seq + 1)
-            .unwrap_or(0)
-            .into();
+            .unwrap_or(0);
+        if tables
+            .balance_changes_start
+            .get(&())
+            .expect("Reading the balance changes start should not fail")
+            .is_none()
+        {
+            tables
+                .balance_changes_start
+                .insert(&(), &next_sequence_number)
+                .expect("Writing the balance changes start should not fail");
+        }
+        let next_sequence_number = next_sequence_number.into();
+
+        // New stores maintain the type index from the start.
+        let owner_type_index_complete = match tables
+            .owner_type_index_backfill
+            .get(&())
+            .expect("Reading the owner type index backfill should not fail")
+        {
+            Some(backfill) => backfill == OwnerTypeIndexBackfill::Complete,
+            None if tables.owner_index.is_empty() => {
+                tables
+                    .owner_type_index_backfill
+                    .insert(&(), &OwnerTypeIndexBackfill::Complete)
+                    .expect("Writing the owner type index backfill should not fail");
+                true
+            }
+            None => false,
+        };
 
         Self {
             tables,
             next_sequence_number,
+            owner_type_index_complete: AtomicBool::new(owner_type_index_complete),
+            owner_type_index_lock: RwLock::new(()),
+            coin_balance_lock: RwLock::new(()),
+        }
+    }
+
+    /// Backfills `owner_type_index` on a blocking thread, see `backfill_owner_type_index`.
+    pub fn spawn_owner_type_index_backfill(self: &Arc<Self>) -> JoinHandle<SuiResult> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.backfill_owner_type_index())
+    }
+
+    /// Populates `owner_type_index` from `owner_index`, for stores created before the type
+    /// index existed. Progress is persisted after every batch so that an interrupted backfill
+    /// resumes where it stopped. Until it completes, objects are listed by type from
+    /// `owner_index` instead.
+    pub fn backfill_owner_type_index(&self) -> SuiResult {
+        const BATCH_SIZE: usize = 10_000;
+        let mut cursor = match self.tables.owner_type_index_backfill.get(&())? {
+            Some(OwnerTypeIndexBackfill::Complete) => return Ok(()),
+            Some(OwnerTypeIndexBackfill::Resume(cursor)) => Some(cursor),
+            None => None,
+        };
+        info!(?cursor, "Backfilling the owner type index");
+        loop {
+            let _guard = self.owner_type_index_lock.write();
+            let mut iter = self.tables.owner_index.iter();
+            if let Some(cursor) = &cursor {
+                iter = iter.skip_to(cursor)?;
+            }
+            let mut entries: Vec<_> = iter.take(BATCH_SIZE + 1).collect();
+            let next = if entries.len() > BATCH_SIZE {
+                entries.pop().map(|(key, _)| key)
+            } else {
+                None
+            };
+
+            let mut batch = self.tables.owner_type_index.batch();
+            batch.insert_batch(
+                &self.tables.owner_type_index,
+                entries.iter().filter_map(|((owner, _), info)| {
+                    owner_type_index_key(*owner, info).map(|key| (key, info))
+                }),
+            )?;
+            let progress = match next {
+                Some(next) => OwnerTypeIndexBackfill::Resume(next),
+                None => OwnerTypeIndexBackfill::Complete,
+            };
+            batch.insert_batch(
+                &self.tables.owner_type_index_backfill,
+                std::iter::once(((), progress)),
+            )?;
+            batch.write()?;
+
+            if next.is_none() {
+                self.owner_type_index_complete.store(true, Ordering::SeqCst);
+                info!("Backfilled the owner type index");
+                return Ok(());
+            }
+            cursor = next;
         }
     }
 
     pub fn index_coin(
         &self,
+        sequence: TxSequenceNumber,
         digest: &TransactionDigest,
         batch: &mut DBBatch,
         object_index_changes: &ObjectIndexChanges,
@@ -242,7 +415,39 @@ impl IndexStore {
 
         batch.delete_batch(&self.tables.coin_index, coin_delete_keys.into_iter())?;
 
-        // 2. Upsert new owner, by looking at `object_index_changes.new_owners`.
+        // 2. Record the net balance change of each owner and coin type, coins are debited from
+        // their owner before the tx and credited to their owner after it.
+        let address_coins =
+            |obj: &Object| match (obj.owner, obj.coin_type_maybe(), obj.as_coin_maybe()) {
+                (Owner::AddressOwner(owner), Some(coin_type), Some(coin)) => {
+                    Some(((owner, coin_type.to_string()), coin.balance.value()))
+                }
+                _ => None,
+            };
+        let balance_deltas = coin_balance_deltas(
+            input_coins.values().filter_map(address_coins),
+            written_coins
+                .values()
+                .filter_map(|(_, obj, _)| address_coins(obj)),
+        );
+        batch.insert_batch(
+            &self.tables.balance_changes,
+            balance_deltas
+                .into_iter()
+                .map(|(key, (balance, coin_object_count))| {
+                    (
+                        key,
+                        BalanceDelta {
+                            balance,
+                            coin_object_count,
+                        },
+                    )
+                })
+                .filter(|(_, delta)| delta != &BalanceDelta::default())
+                .map(|((owner, coin_type), delta)| ((owner, coin_type, sequence), delta)),
+        )?;
+
+        // 3. Upsert new owner, by looking at `object_index_changes.new_owners`.
         // For a object to appear in `new_owners`, it must be owned by `Owner::Address` after the tx.
         // It also must not be deleted, hence appear in written_coins (see `AuthorityState::commit_certificate`)
         // It also must be a coin type (see `AuthorityState::commit_certificate`).
@@ -346,9 +551,35 @@ impl IndexStore {
         )?;
 
         // Coin Index
-        self.index_coin(digest, &mut batch, &object_index_changes, tx_coins)?;
+        let _coin_balance_guard = self.coin_balance_lock.read();
+        self.index_coin(
+            sequence,
+            digest,
+            &mut batch,
+            &object_index_changes,
+            tx_coins,
+        )?;
 
         // Owner index
+        let _guard = self.owner_type_index_lock.read();
+        let deleted_owner_types = self
+            .tables
+            .owner_index
+            .multi_get(&object_index_changes.deleted_owners)?
+            .into_iter()
+            .zip(&object_index_changes.deleted_owners)
+            .filter_map(|(info, (owner, _))| owner_type_index_key(*owner, &info?))
+            .collect::<Vec<_>>();
+        batch.delete_batch(&self.tables.owner_type_index, deleted_owner_types)?;
+        batch.insert_batch(
+            &self.tables.owner_type_index,
+            object_index_changes
+                .new_owners
+                .iter()
+                .filter_map(|((owner, _), info)| {
+                    owner_type_index_key(*owner, info).map(|key| (key, info))
+                }),
+        )?;
         batch.delete_batch(
             &self.tables.owner_index,
             object_index_changes.deleted_owners.into_iter(),
@@ -427,13 +658,32 @@ impl IndexStore {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
 
+    /// `checkpoint_of` resolves the checkpoint a transaction was finalized in, it is used to
+    /// evaluate `Checkpoint` and `CheckpointRange` filters nested in composite filters. Filters
+    /// without any indexed criterion stop scanning after `MAX_UNINDEXED_TX_SCAN` transactions.
     pub fn get_transactions(
         &self,
         filter: Option<TransactionFilter>,
         cursor: Option<TransactionDigest>,
         limit: Option<usize>,
         reverse: bool,
+        checkpoint_of: &CheckpointResolver<'_>,
     ) -> Result<Vec<TransactionDigest>, anyhow::Error> {
+        Ok(self
+            .get_transactions_with_scan_cursor(filter, cursor, limit, reverse, checkpoint_of)?
+            .0)
+    }
+
+    /// Same as `get_transactions`, also returns the last scanned transaction if the scan of a
+    /// filter without any indexed criterion stopped before finding `limit` transactions.
+    pub fn get_transactions_with_scan_cursor(
+        &self,
+        filter: Option<TransactionFilter>,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        reverse: bool,
+        checkpoint_of: &CheckpointResolver<'_>,
+    ) -> Result<(Vec<TransactionDigest>, Option<TransactionDigest>), anyhow::Error> {
         // Lookup TransactionDigest sequence number,
         let cursor = if let Some(cursor) = cursor {
             Some(
@@ -443,7 +693,24 @@ impl IndexStore {
         } else {
             None
         };
-        match filter {
+        if let Some(
+            filter @ (TransactionFilter::FromAndToAddress { .. }
+            | TransactionFilter::CheckpointRange { .. }
+            | TransactionFilter::TimeRange { .. }
+            | TransactionFilter::All(_)
+            | TransactionFilter::Any(_)
+            | TransactionFilter::Not(_)),
+        ) = &filter
+        {
+            return Ok(self.get_transactions_by_composite_filter(
+                filter,
+                cursor,
+                limit,
+                reverse,
+                checkpoint_of,
+            )?);
+        }
+        let digests = match filter {
             Some(TransactionFilter::MoveFunction {
                 package,
                 module,
@@ -492,7 +759,8 @@ impl IndexStore {
                     }
                 }
             }
-        }
+        }?;
+        Ok((digests, None))
     }
 
     /// Returns unix timestamp for a transaction if it exists
@@ -663,6 +931,468 @@ impl IndexStore {
         )
     }
 
+    /// Evaluates a filter that has no dedicated index. The candidates are read from the most
+    /// selective index available for the filter, and the remaining criteria are then checked
+    /// with point lookups into the other index tables. Filters without any indexed criterion
+    /// scan all transactions, up to `MAX_UNINDEXED_TX_SCAN` of them, after which the last
+    /// scanned transaction is returned along with the transactions found so far.
+    fn get_transactions_by_composite_filter(
+        &self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        limit: Option<usize>,
+        reverse: bool,
+        checkpoint_of: &CheckpointResolver<'_>,
+    ) -> SuiResult<(Vec<TransactionDigest>, Option<TransactionDigest>)> {
+        let (candidates, residual, indexed) =
+            self.plan_composite_filter(filter, cursor, reverse)?;
+        let mut move_functions = PartialMoveFunctions::new();
+        let mut results = vec![];
+        let mut last_scanned = None;
+        for (scanned, (seq, digest)) in candidates.enumerate() {
+            if limit.map_or(false, |limit| results.len() >= limit) {
+                break;
+            }
+            if !indexed && scanned >= MAX_UNINDEXED_TX_SCAN {
+                return Ok((results, last_scanned));
+            }
+            let matches = match &residual {
+                Some(residual) => self.transaction_matches(
+                    residual,
+                    seq,
+                    &digest,
+                    checkpoint_of,
+                    &mut move_functions,
+                )?,
+                None => true,
+            };
+            if matches {
+                results.push(digest);
+            }
+            last_scanned = Some(digest);
+        }
+        Ok((results, None))
+    }
+
+    /// Picks the index used to iterate candidates for `filter`, and returns it together with
+    /// the part of the filter that still has to be checked against every candidate, and whether
+    /// the candidates come from an index rather than from all transactions.
+    fn plan_composite_filter<'a>(
+        &'a self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<(TransactionIndexIter<'a>, Option<TransactionFilter>, bool)> {
+        match filter {
+            TransactionFilter::FromAndToAddress { from, to } => Ok((
+                Self::transaction_index_iter(
+                    &self.tables.transactions_from_addr,
+                    *from,
+                    cursor,
+                    reverse,
+                )?,
+                Some(TransactionFilter::ToAddress(*to)),
+                true,
+            )),
+            TransactionFilter::All(filters) => {
+                // Prefer move function criteria as the driving index, since they are the most
+                // expensive ones to check with point lookups, then the other indexed criteria,
+                // then the nested filters that can be evaluated from indexes.
+                let mut order: Vec<_> = (0..filters.len()).collect();
+                order.sort_by_key(|i| match &filters[*i] {
+                    TransactionFilter::MoveFunction { .. } => 0,
+                    f if Self::has_transaction_index(f) => 1,
+                    _ => 2,
+                });
+                for i in order {
+                    let (iter, residual, indexed) =
+                        self.plan_composite_filter(&filters[i], cursor, reverse)?;
+                    if indexed {
+                        let mut rest = filters.clone();
+                        rest.remove(i);
+                        rest.extend(residual);
+                        return Ok((iter, Some(TransactionFilter::All(rest)), true));
+                    }
+                }
+                Ok((
+                    self.transaction_order_iter(cursor, reverse)?,
+                    Some(filter.clone()),
+                    false,
+                ))
+            }
+            TransactionFilter::Any(filters) => {
+                // The candidates are the union of the candidates of every alternative, which
+                // all have to be evaluated from indexes.
+                let mut iters = vec![];
+                let mut exact = true;
+                for f in filters {
+                    let (iter, residual, indexed) =
+                        self.plan_composite_filter(f, cursor, reverse)?;
+                    if !indexed {
+                        return Ok((
+                            self.transaction_order_iter(cursor, reverse)?,
+                            Some(filter.clone()),
+                            false,
+                        ));
+                    }
+                    exact &= residual.is_none();
+                    iters.push(iter);
+                }
+                Ok((
+                    Self::merge_transaction_iters(iters, reverse),
+                    (!exact).then(|| filter.clone()),
+                    true,
+                ))
+            }
+            _ => match self.indexed_transactions_iter(filter, cursor, reverse)? {
+                Some(iter) => Ok((iter, None, true)),
+                None => Ok((
+                    self.transaction_order_iter(cursor, reverse)?,
+                    Some(filter.clone()),
+                    false,
+                )),
+            },
+        }
+    }
+
+    /// Merges iterators that are each in transaction order into a single one in transaction
+    /// order, without duplicates.
+    fn merge_transaction_iters(
+        iters: Vec<TransactionIndexIter<'_>>,
+        reverse: bool,
+    ) -> TransactionIndexIter<'_> {
+        Box::new(
+            iters
+                .into_iter()
+                .kmerge_by(move |(a, _), (b, _)| if reverse { a > b } else { a < b })
+                .dedup_by(|(a, _), (b, _)| a == b),
+        )
+    }
+
+    fn has_transaction_index(filter: &TransactionFilter) -> bool {
+        matches!(
+            filter,
+            TransactionFilter::MoveFunction { .. }
+                | TransactionFilter::InputObject(_)
+                | TransactionFilter::ChangedObject(_)
+                | TransactionFilter::FromAddress(_)
+                | TransactionFilter::ToAddress(_)
+        )
+    }
+
+    /// Returns an iterator over the dedicated index of `filter`, if there is one.
+    fn indexed_transactions_iter(
+        &self,
+        filter: &TransactionFilter,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<Option<TransactionIndexIter<'_>>> {
+        Ok(Some(match filter {
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => self.move_function_index_iter(
+                *package,
+                module.clone(),
+                function.clone(),
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::InputObject(object_id) => Self::transaction_index_iter(
+                &self.tables.transactions_by_input_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::ChangedObject(object_id) => Self::transaction_index_iter(
+                &self.tables.transactions_by_mutated_object_id,
+                *object_id,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::FromAddress(address) => Self::transaction_index_iter(
+                &self.tables.transactions_from_addr,
+                *address,
+                cursor,
+                reverse,
+            )?,
+            TransactionFilter::ToAddress(address) => Self::transaction_index_iter(
+                &self.tables.transactions_to_addr,
+                *address,
+                cursor,
+                reverse,
+            )?,
+            _ => return Ok(None),
+        }))
+    }
+
+    /// First sequence number to visit after the exclusive `cursor`, `None` if there is nothing
+    /// left to visit.
+    fn first_seq_after_cursor(
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> Option<TxSequenceNumber> {
+        match (cursor, reverse) {
+            (None, false) => Some(TxSequenceNumber::MIN),
+            (None, true) => Some(TxSequenceNumber::MAX),
+            (Some(cursor), false) => cursor.checked_add(1),
+            (Some(cursor), true) => cursor.checked_sub(1),
+        }
+    }
+
+    fn transaction_order_iter(
+        &self,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TransactionIndexIter<'_>> {
+        let Some(start) = Self::first_seq_after_cursor(cursor, reverse) else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+        let iter = self.tables.transaction_order.iter();
+        Ok(if reverse {
+            Box::new(iter.skip_prior_to(&start)?.reverse())
+        } else {
+            Box::new(iter.skip_to(&start)?)
+        })
+    }
+
+    fn transaction_index_iter<KeyT>(
+        index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        key: KeyT,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TransactionIndexIter<'_>>
+    where
+        KeyT: Clone + Serialize + DeserializeOwned + PartialEq + 'static,
+    {
+        let Some(start) = Self::first_seq_after_cursor(cursor, reverse) else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+        let iter = index.iter();
+        Ok(if reverse {
+            Box::new(
+                iter.skip_prior_to(&(key.clone(), start))?
+                    .reverse()
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        } else {
+            Box::new(
+                iter.skip_to(&(key.clone(), start))?
+                    .take_while(move |((id, _), _)| *id == key)
+                    .map(|((_, seq), digest)| (seq, digest)),
+            )
+        })
+    }
+
+    fn move_function_index_iter(
+        &self,
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+        cursor: Option<TxSequenceNumber>,
+        reverse: bool,
+    ) -> SuiResult<TransactionIndexIter<'_>> {
+        let (Some(module), Some(function)) = (module.clone(), function.clone()) else {
+            // Entries of a partial key are ordered by module and function before sequence number,
+            // so the entries of every matching function are merged to keep the transaction order.
+            let iters = self
+                .list_move_functions(package, module, function)?
+                .into_iter()
+                .map(|(module, function)| {
+                    self.move_function_index_iter(
+                        package,
+                        Some(module),
+                        Some(function),
+                        cursor,
+                        reverse,
+                    )
+                })
+                .collect::<SuiResult<Vec<_>>>()?;
+            return Ok(Self::merge_transaction_iters(iters, reverse));
+        };
+        let Some(start) = Self::first_seq_after_cursor(cursor, reverse) else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+        let key = (package, module.clone(), function.clone(), start);
+        let iter = self.tables.transactions_by_move_function.iter();
+        Ok(if reverse {
+            Box::new(
+                iter.skip_prior_to(&key)?
+                    .reverse()
+                    .take_while(move |((id, m, f, _), _)| {
+                        *id == package && *m == module && *f == function
+                    })
+                    .map(|((_, _, _, seq), digest)| (seq, digest)),
+            )
+        } else {
+            Box::new(
+                iter.skip_to(&key)?
+                    .take_while(move |((id, m, f, _), _)| {
+                        *id == package && *m == module && *f == function
+                    })
+                    .map(|((_, _, _, seq), digest)| (seq, digest)),
+            )
+        })
+    }
+
+    /// Lists the module and function names of `package` that were called by transactions and
+    /// match the given `module` and `function`, by seeking past the entries of each function.
+    fn list_move_functions(
+        &self,
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+    ) -> SuiResult<Vec<(String, String)>> {
+        let mut functions = vec![];
+        let mut next = (
+            package,
+            module.clone().unwrap_or_default(),
+            String::new(),
+            TxSequenceNumber::MIN,
+        );
+        while let Some(((id, m, f, _), _)) = self
+            .tables
+            .transactions_by_move_function
+            .iter()
+            .skip_to(&next)?
+            .next()
+        {
+            if id != package || module.as_ref().map_or(false, |x| *x != m) {
+                break;
+            }
+            next = (package, m.clone(), f.clone(), TxSequenceNumber::MAX);
+            if function.as_ref().map_or(true, |x| *x == f) {
+                functions.push((m, f));
+            }
+        }
+        Ok(functions)
+    }
+
+    /// Checks whether the transaction at `seq` matches `filter`, using point lookups into the
+    /// index tables. Partial `MoveFunction` filters are looked up for each function they match,
+    /// which are listed into `move_functions` the first time they are checked.
+    fn transaction_matches(
+        &self,
+        filter: &TransactionFilter,
+        seq: TxSequenceNumber,
+        digest: &TransactionDigest,
+        checkpoint_of: &CheckpointResolver<'_>,
+        move_functions: &mut PartialMoveFunctions,
+    ) -> SuiResult<bool> {
+        Ok(match filter {
+            TransactionFilter::MoveFunction {
+                package,
+                module: Some(module),
+                function: Some(function),
+            } => self.tables.transactions_by_move_function.contains_key(&(
+                *package,
+                module.clone(),
+                function.clone(),
+                seq,
+            ))?,
+            TransactionFilter::MoveFunction {
+                package,
+                module,
+                function,
+            } => {
+                let key = (*package, module.clone(), function.clone());
+                if !move_functions.contains_key(&key) {
+                    let functions =
+                        self.list_move_functions(*package, module.clone(), function.clone())?;
+                    move_functions.insert(key.clone(), functions);
+                }
+                for (module, function) in &move_functions[&key] {
+                    if self.tables.transactions_by_move_function.contains_key(&(
+                        *package,
+                        module.clone(),
+                        function.clone(),
+                        seq,
+                    ))? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            TransactionFilter::InputObject(object_id) => self
+                .tables
+                .transactions_by_input_object_id
+                .contains_key(&(*object_id, seq))?,
+            TransactionFilter::ChangedObject(object_id) => self
+                .tables
+                .transactions_by_mutated_object_id
+                .contains_key(&(*object_id, seq))?,
+            TransactionFilter::FromAddress(address) => self
+                .tables
+                .transactions_from_addr
+                .contains_key(&(*address, seq))?,
+            TransactionFilter::ToAddress(address) => self
+                .tables
+                .transactions_to_addr
+                .contains_key(&(*address, seq))?,
+            TransactionFilter::FromAndToAddress { from, to } => {
+                self.tables
+                    .transactions_from_addr
+                    .contains_key(&(*from, seq))?
+                    && self.tables.transactions_to_addr.contains_key(&(*to, seq))?
+            }
+            TransactionFilter::Checkpoint(checkpoint) => {
+                checkpoint_of(digest)? == Some(*checkpoint)
+            }
+            TransactionFilter::CheckpointRange {
+                start_checkpoint,
+                end_checkpoint,
+            } => matches!(
+                checkpoint_of(digest)?,
+                Some(checkpoint) if *start_checkpoint <= checkpoint && checkpoint < *end_checkpoint
+            ),
+            TransactionFilter::TimeRange {
+                start_time,
+                end_time,
+            } => matches!(
+                self.tables.timestamps.get(digest)?,
+                Some(timestamp) if *start_time <= timestamp && timestamp < *end_time
+            ),
+            TransactionFilter::All(filters) => {
+                for filter in filters {
+                    if !self.transaction_matches(
+                        filter,
+                        seq,
+                        digest,
+                        checkpoint_of,
+                        move_functions,
+                    )? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            TransactionFilter::Any(filters) => {
+                for filter in filters {
+                    if self.transaction_matches(
+                        filter,
+                        seq,
+                        digest,
+                        checkpoint_of,
+                        move_functions,
+                    )? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            TransactionFilter::Not(filter) => {
+                !self.transaction_matches(filter, seq, digest, checkpoint_of, move_functions)?
+            }
+            TransactionFilter::TransactionKind(_) => {
+                return Err(SuiError::UnsupportedFeatureError {
+                    error: format!("Unsupported filter: {:?}", filter),
+                })
+            }
+        })
+    }
+
     pub fn get_transaction_seq(
         &self,
         digest: &TransactionDigest,
@@ -824,6 +1554,10 @@ impl IndexStore {
         )
     }
 
+    /// Returns events emitted in the [start_time, end_time) interval, starting from the event
+    /// at (`tx_seq`, `event_seq`) inclusive, like the other event indexes. Events are keyed by
+    /// time first, so the timestamp of the starting transaction is looked up to resume from the
+    /// right position in the index. `end_time` is exclusive, as in `EventFilter::TimeRange`.
     pub fn event_iterator(
         &self,
         start_time: u64,
@@ -833,12 +1567,21 @@ impl IndexStore {
         limit: usize,
         descending: bool,
     ) -> SuiResult<Vec<(TransactionEventsDigest, TransactionDigest, usize, u64)>> {
+        let cursor_time = match self.tables.transaction_order.get(&tx_seq)? {
+            Some(digest) => self.tables.timestamps.get(&digest)?,
+            None => None,
+        };
         Ok(if descending {
+            let start = match cursor_time {
+                Some(time) if time < end_time => (time, (tx_seq, event_seq)),
+                _ => (end_time, (TxSequenceNumber::MIN, usize::MIN)),
+            };
             self.tables
                 .event_by_time
                 .iter()
-                .skip_prior_to(&(end_time, (tx_seq, event_seq)))?
+                .skip_prior_to(&start)?
                 .reverse()
+                .skip_while(|((m, _), _)| m >= &end_time)
                 .take_while(|((m, _), _)| m >= &start_time)
                 .take(limit)
                 .map(|((_, (_, event_seq)), (digest, tx_digest, time))| {
@@ -846,11 +1589,15 @@ impl IndexStore {
                 })
                 .collect()
         } else {
+            let start = match cursor_time {
+                Some(time) if time >= start_time => (time, (tx_seq, event_seq)),
+                _ => (start_time, (TxSequenceNumber::MIN, usize::MIN)),
+            };
             self.tables
                 .event_by_time
                 .iter()
-                .skip_to(&(start_time, (tx_seq, event_seq)))?
-                .take_while(|((m, _), _)| m <= &end_time)
+                .skip_to(&start)?
+                .take_while(|((m, _), _)| m < &end_time)
                 .take(limit)
                 .map(|((_, (_, event_seq)), (digest, tx_digest, time))| {
                     (digest, tx_digest, event_seq, time)
@@ -906,6 +1653,14 @@ impl IndexStore {
         limit: usize,
         filter: Option<SuiObjectDataFilter>,
     ) -> SuiResult<Vec<ObjectInfo>> {
+        if let Some(filter) = filter
+            .as_ref()
+            .filter(|_| self.owner_type_index_complete.load(Ordering::SeqCst))
+        {
+            if let Some(prefix) = OwnerTypePrefix::from_filter(filter) {
+                return self.get_owner_objects_by_type(owner, cursor, limit, &prefix, filter);
+            }
+        }
         let cursor = match cursor {
             Some(cursor) => cursor,
             None => ObjectID::ZERO,
@@ -916,6 +1671,77 @@ impl IndexStore {
             .collect())
     }
 
+    /// Lists the objects of `owner` whose type starts with `prefix` and that match `filter`, in
+    /// object id order. Every struct type under the prefix is scanned separately from the cursor,
+    /// so that pages follow the same order as `get_owner_objects_iterator`.
+    fn get_owner_objects_by_type(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        prefix: &OwnerTypePrefix,
+        filter: &SuiObjectDataFilter,
+    ) -> SuiResult<Vec<ObjectInfo>> {
+        let same_type = |a: &OwnerTypeIndexKey, b: &OwnerTypeIndexKey| {
+            (a.0, a.1, &a.2, &a.3, &a.4) == (b.0, b.1, &b.2, &b.3, &b.4)
+        };
+
+        // Find the distinct struct types owned by `owner` under the prefix, jumping over all the
+        // objects of one type at a time.
+        let mut types: Vec<OwnerTypeIndexKey> = vec![];
+        let mut next = prefix.start_key(owner);
+        loop {
+            let last = types.last();
+            let Some((key, _)) = self
+                .tables
+                .owner_type_index
+                .iter()
+                .skip_to(&next)?
+                .find(|(key, _)| last.map_or(true, |last| !same_type(key, last)))
+            else {
+                break;
+            };
+            if !prefix.contains(owner, &key) {
+                break;
+            }
+            next = (
+                key.0,
+                key.1,
+                key.2.clone(),
+                key.3.clone(),
+                key.4.clone(),
+                ObjectID::MAX,
+            );
+            types.push(key);
+        }
+
+        let mut objects = vec![];
+        for type_key in types {
+            let start = (
+                type_key.0,
+                type_key.1,
+                type_key.2.clone(),
+                type_key.3.clone(),
+                type_key.4.clone(),
+                cursor.unwrap_or(ObjectID::ZERO),
+            );
+            objects.extend(
+                self.tables
+                    .owner_type_index
+                    .iter()
+                    .skip_to(&start)?
+                    .take_while(|(key, _)| same_type(key, &type_key))
+                    .filter(|(key, _)| cursor.map_or(true, |cursor| key.5 > cursor))
+                    .filter(|(_, info)| filter.matches(info))
+                    .take(limit)
+                    .map(|(_, info)| info),
+            );
+        }
+        objects.sort_by_key(|info| info.object_id);
+        objects.truncate(limit);
+        Ok(objects)
+    }
+
     pub fn get_owned_coins_iterator(
         &self,
         owner: SuiAddress,
@@ -941,6 +1767,73 @@ impl IndexStore {
             .map(|((_, coin_type, obj_id), coin)| (coin_type, obj_id, coin)))
     }
 
+    /// Returns the total balance and the number of coins of `coin_type` owned by `owner` as of
+    /// `checkpoint`, whose transactions are `checkpoint_transactions`. The balance changes of the
+    /// transactions that are not part of a checkpoint up to `checkpoint` are rolled back from the
+    /// current balance. Checkpoints executed before balance changes were first indexed are
+    /// rejected.
+    pub fn get_balance_at_checkpoint(
+        &self,
+        owner: SuiAddress,
+        coin_type: String,
+        checkpoint: CheckpointSequenceNumber,
+        checkpoint_transactions: impl IntoIterator<Item = TransactionDigest>,
+        checkpoint_of: &CheckpointResolver<'_>,
+    ) -> SuiResult<(u128, usize)> {
+        let not_indexed = || SuiError::UnsupportedFeatureError {
+            error: format!(
+                "Balance history of {owner} is not indexed as of checkpoint {checkpoint}"
+            ),
+        };
+        let digests: Vec<_> = checkpoint_transactions.into_iter().collect();
+        let sequences = self
+            .tables
+            .transactions_seq
+            .multi_get(&digests)?
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(not_indexed)?;
+        let start = self.tables.balance_changes_start.get(&())?.unwrap_or(0);
+        if sequences.iter().min().map_or(true, |first| *first < start) {
+            return Err(not_indexed());
+        }
+
+        let ((mut balance, mut coin_object_count), changes) = {
+            let _guard = self.coin_balance_lock.write();
+            let current = self
+                .get_owned_coins_iterator(owner, Some(coin_type.clone()))?
+                .fold((0i128, 0i64), |(balance, count), (_, _, coin)| {
+                    (balance + coin.balance as i128, count + 1)
+                });
+            let changes: Vec<_> = self
+                .tables
+                .balance_changes
+                .iter()
+                .skip_to(&(owner, coin_type.clone(), start))?
+                .take_while(|((address, type_, _), _)| *address == owner && *type_ == coin_type)
+                .map(|((_, _, sequence), delta)| (sequence, delta))
+                .collect();
+            (current, changes)
+        };
+
+        let (sequences, deltas): (Vec<_>, Vec<_>) = changes.into_iter().unzip();
+        let digests = self.tables.transaction_order.multi_get(&sequences)?;
+        for (digest, delta) in digests.into_iter().zip(deltas) {
+            let Some(digest) = digest else {
+                continue;
+            };
+            if checkpoint_of(&digest)?.map_or(true, |c| c > checkpoint) {
+                balance -= delta.balance;
+                coin_object_count -= delta.coin_object_count;
+            }
+        }
+
+        match (u128::try_from(balance), usize::try_from(coin_object_count)) {
+            (Ok(balance), Ok(coin_object_count)) => Ok((balance, coin_object_count)),
+            _ => Err(not_indexed()),
+        }
+    }
+
     pub fn get_owned_coins_iterator_with_cursor(
         &self,
         owner: SuiAddress,
@@ -997,8 +1890,39 @@ impl IndexStore {
             .map(|(_, object_info)| object_info))
     }
 
-    pub fn insert_genesis_objects(&self, object_index_changes: ObjectIndexChanges) -> SuiResult {
+    pub fn live_object_set_indexing(&self) -> SuiResult<LiveObjectSetIndexing> {
+        Ok(match self.tables.live_object_set_indexing.get(&())? {
+            Some(progress) => progress,
+            None if self.is_empty() => LiveObjectSetIndexing::NotStarted,
+            None => LiveObjectSetIndexing::Complete,
+        })
+    }
+
+    /// Indexes a batch of the live object set: the owners and dynamic fields of the
+    /// `ObjectIndexChanges`, and the address owned coins among the objects, both returned by
+    /// `index_objects`. Transactions are not indexed while `index_objects` runs and the batch is
+    /// written, so that `index_objects` can leave out the objects they have modified. The batch is
+    /// written along with `progress`, so that an interrupted indexing resumes after the last
+    /// written batch.
+    pub fn index_live_objects(
+        &self,
+        index_objects: impl FnOnce() -> SuiResult<(Vec<Object>, ObjectIndexChanges)>,
+        progress: LiveObjectSetIndexing,
+    ) -> SuiResult {
+        // Locked in the same order as when indexing transactions.
+        let _coin_balance_guard = self.coin_balance_lock.read();
+        let _guard = self.owner_type_index_lock.write();
+        let (objects, object_index_changes) = index_objects()?;
         let mut batch = self.tables.owner_index.batch();
+        batch.insert_batch(
+            &self.tables.owner_type_index,
+            object_index_changes
+                .new_owners
+                .iter()
+                .filter_map(|((owner, _), info)| {
+                    owner_type_index_key(*owner, info).map(|key| (key, info))
+                }),
+        )?;
         batch.insert_batch(
             &self.tables.owner_index,
             object_index_changes.new_owners.into_iter(),
@@ -1007,6 +1931,27 @@ impl IndexStore {
             &self.tables.dynamic_field_index,
             object_index_changes.new_dynamic_fields.into_iter(),
         )?;
+        batch.insert_batch(
+            &self.tables.coin_index,
+            objects.iter().filter_map(|object| {
+                let (Owner::AddressOwner(owner), Some(coin_type), Some(coin)) =
+                    (object.owner, object.coin_type_maybe(), object.as_coin_maybe())
+                else {
+                    return None;
+                };
+                let coin_info = CoinInfo {
+                    version: object.version(),
+                    digest: object.digest(),
+                    balance: coin.balance.value(),
+                    previous_transaction: object.previous_transaction,
+                };
+                Some(((owner, coin_type.to_string(), object.id()), coin_info))
+            }),
+        )?;
+        batch.insert_batch(
+            &self.tables.live_object_set_indexing,
+            std::iter::once(((), progress)),
+        )?;
         batch.write()?;
         Ok(())
     }
@@ -1023,3 +1968,83 @@ impl IndexStore {
             .map_err(SuiError::StorageError)
     }
 }
+
+fn owner_type_index_key(owner: SuiAddress, info: &ObjectInfo) -> Option<OwnerTypeIndexKey> {
+    match &info.type_ {
+        ObjectType::Struct(s) => Some((
+            owner,
+            s.address().into(),
+            s.module().to_string(),
+            s.name().to_string(),
+            type_params_key(&s.type_params()),
+            info.object_id,
+        )),
+        ObjectType::Package => None,
+    }
+}
+
+fn type_params_key(type_params: &[TypeTag]) -> String {
+    type_params
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The leading part of an object type pinned by a `SuiObjectDataFilter`, used to range scan
+/// `owner_type_index`.
+struct OwnerTypePrefix {
+    package: ObjectID,
+    module: Option<String>,
+    name: Option<String>,
+    type_params: Option<String>,
+}
+
+impl OwnerTypePrefix {
+    fn from_filter(filter: &SuiObjectDataFilter) -> Option<Self> {
+        match filter {
+            SuiObjectDataFilter::Package(package) => Some(Self {
+                package: *package,
+                module: None,
+                name: None,
+                type_params: None,
+            }),
+            SuiObjectDataFilter::MoveModule { package, module } => Some(Self {
+                package: *package,
+                module: Some(module.to_string()),
+                name: None,
+                type_params: None,
+            }),
+            // A struct tag without type params matches all instantiations of the struct.
+            SuiObjectDataFilter::StructType(tag) => Some(Self {
+                package: tag.address.into(),
+                module: Some(tag.module.to_string()),
+                name: Some(tag.name.to_string()),
+                type_params: (!tag.type_params.is_empty())
+                    .then(|| type_params_key(&tag.type_params)),
+            }),
+            SuiObjectDataFilter::MatchAll(filters) => filters.iter().find_map(Self::from_filter),
+            _ => None,
+        }
+    }
+
+    fn start_key(&self, owner: SuiAddress) -> OwnerTypeIndexKey {
+        (
+            owner,
+            self.package,
+            self.module.clone().unwrap_or_default(),
+            self.name.clone().unwrap_or_default(),
+            self.type_params.clone().unwrap_or_default(),
+            ObjectID::ZERO,
+        )
+    }
+
+    fn contains(&self, owner: SuiAddress, key: &OwnerTypeIndexKey) -> bool {
+        let (key_owner, package, module, name, type_params, _) = key;
+        *key_owner == owner
+            && *package == self.package
+            && self.module.as_ref().map_or(true, |m| m == module)
+            && self.name.as_ref().map_or(true, |n| n == name)
+            && self.type_params.as_ref().map_or(true, |t| t == type_params)
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod archive;
+pub mod compression;
 pub mod indexes;
 pub use indexes::{IndexStore, IndexStoreTables};
 
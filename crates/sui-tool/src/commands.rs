@@ -4,7 +4,8 @@
 use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     get_object, get_transaction_block, make_clients, restore_from_db_checkpoint,
-    ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
+    restore_from_state_snapshot, write_state_snapshot, ConciseObjectOutput, GroupedObjectOutput,
+    VerboseObjectOutput,
 };
 use anyhow::Result;
 use std::path::PathBuf;
@@ -144,6 +145,38 @@ pub enum ToolCommand {
         #[clap(long = "db-checkpoint-path")]
         db_checkpoint_path: PathBuf,
     },
+
+    /// Write the snapshot of the live object set at the end of an epoch, from the db of a node
+    /// which executed the last checkpoint of the epoch, or from a db checkpoint taken at the end
+    /// of the epoch.
+    #[clap(name = "write-snapshot")]
+    WriteStateSnapshot {
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        #[clap(long = "epoch")]
+        epoch: EpochId,
+        #[clap(long = "snapshot-path")]
+        snapshot_path: PathBuf,
+        #[clap(
+            long = "file-size-mb",
+            default_value = "128",
+            help = "Size of the snapshot files before compression, in MiB"
+        )]
+        file_size_mb: usize,
+    },
+
+    /// Restore the db of a node from the snapshot of an epoch, after verifying the snapshot
+    /// against the checkpoints certified since genesis. Full nodes index the objects of the
+    /// snapshot when first started, the transactions preceding the snapshot are not indexed.
+    #[clap(name = "restore-snapshot")]
+    RestoreFromStateSnapshot {
+        #[clap(long = "config-path")]
+        config_path: PathBuf,
+        #[clap(long = "snapshot-path")]
+        snapshot_path: PathBuf,
+        #[clap(long = "epoch")]
+        epoch: EpochId,
+    },
 }
 
 trait OptionDebug<T> {
@@ -302,6 +335,23 @@ impl ToolCommand {
                 let config = sui_config::NodeConfig::load(config_path)?;
                 restore_from_db_checkpoint(&config, &db_checkpoint_path).await?;
             }
+            ToolCommand::WriteStateSnapshot {
+                db_path,
+                epoch,
+                snapshot_path,
+                file_size_mb,
+            } => {
+                write_state_snapshot(&db_path, epoch, &snapshot_path, file_size_mb * 1024 * 1024)
+                    .await?;
+            }
+            ToolCommand::RestoreFromStateSnapshot {
+                config_path,
+                snapshot_path,
+                epoch,
+            } => {
+                let config = sui_config::NodeConfig::load(config_path)?;
+                restore_from_state_snapshot(&config, &snapshot_path, epoch).await?;
+            }
         };
         Ok(())
     }
@@ -10,8 +10,12 @@ use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 use sui_config::{genesis::Genesis, NodeConfig};
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::state_snapshot::{StateSnapshotReader, StateSnapshotWriter};
 use sui_network::default_mysten_network_config;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_types::multiaddr::Multiaddr;
 use sui_types::object::ObjectFormatOptions;
 use sui_types::{base_types::*, messages::*, object::Owner};
@@ -570,3 +574,40 @@ pub async fn restore_from_db_checkpoint(
     copy_dir_all(db_checkpoint_path, config.db_path(), vec![])?;
     Ok(())
 }
+
+fn local_object_store_config(path: &Path) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(path.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+/// Writes the snapshot of an epoch from the db of a node, or from a db checkpoint taken at the end
+/// of the epoch, into a local directory.
+pub async fn write_state_snapshot(
+    db_path: &Path,
+    epoch: EpochId,
+    snapshot_path: &Path,
+    file_size_bytes: usize,
+) -> Result<(), anyhow::Error> {
+    let perpetual_db = AuthorityPerpetualTables::open(&db_path.join("store"), None);
+    let checkpoint_store = CheckpointStore::new(&db_path.join("checkpoints"));
+    let writer =
+        StateSnapshotWriter::new(&local_object_store_config(snapshot_path), file_size_bytes)?;
+    writer
+        .write(epoch, &perpetual_db, &checkpoint_store)
+        .await?;
+    Ok(())
+}
+
+pub async fn restore_from_state_snapshot(
+    config: &NodeConfig,
+    snapshot_path: &Path,
+    epoch: EpochId,
+) -> Result<(), anyhow::Error> {
+    let reader = StateSnapshotReader::new(&local_object_store_config(snapshot_path))?;
+    reader
+        .restore(epoch, config.genesis()?, &config.db_path())
+        .await
+}
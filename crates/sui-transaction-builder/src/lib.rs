@@ -17,7 +17,9 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 
 use sui_adapter::adapter::{resolve_and_type_check, CheckCallArg};
 use sui_adapter::execution_mode::ExecutionMode;
-use sui_json::{resolve_move_function_args, ResolvedCallArg, SuiJsonValue};
+use sui_json::{
+    resolve_move_function_args, resolve_move_function_partial_args, ResolvedCallArg, SuiJsonValue,
+};
 use sui_json_rpc_types::{
     RPCTransactionRequestParams, SuiData, SuiObjectDataOptions, SuiObjectResponse, SuiRawData,
     SuiTypeTag,
@@ -56,6 +58,15 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// Argument of a move call in a programmable transaction.
+#[derive(Clone, Debug)]
+pub enum MoveCallArg {
+    /// A JSON value resolved against the function signature, see [SuiJsonValue].
+    Json(SuiJsonValue),
+    /// An input or the result of an earlier command.
+    Argument(Argument),
+}
+
 #[derive(Clone)]
 pub struct TransactionBuilder<Mode: ExecutionMode>(
     Arc<dyn DataReader + Sync + Send>,
@@ -334,7 +345,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         &self,
         id: ObjectID,
         objects: &mut BTreeMap<ObjectID, Object>,
-        expected_type: SignatureToken,
+        is_mutable_ref: bool,
     ) -> Result<ObjectArg, anyhow::Error> {
         let response = self
             .0
@@ -351,7 +362,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             } => ObjectArg::SharedObject {
                 id,
                 initial_shared_version,
-                mutable: matches!(expected_type, SignatureToken::MutableReference(_)),
+                mutable: is_mutable_ref,
             },
             Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
                 ObjectArg::ImmOrOwnedObject(obj_ref)
@@ -359,15 +370,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         })
     }
 
-    async fn resolve_and_checks_json_args(
-        &self,
-        builder: &mut ProgrammableTransactionBuilder,
-        package_id: ObjectID,
-        module: &Identifier,
-        function: &Identifier,
-        type_args: &[TypeTag],
-        json_args: Vec<SuiJsonValue>,
-    ) -> Result<Vec<Argument>, anyhow::Error> {
+    async fn get_move_package(&self, package_id: ObjectID) -> anyhow::Result<MovePackage> {
         let object = self
             .0
             .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
@@ -376,15 +379,103 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let Some(SuiRawData::Package(package)) = object.bcs else {
             bail!("Bcs field in object [{}] is missing or not a package.", package_id);
         };
-        let package: MovePackage = MovePackage::new(
+        Ok(MovePackage::new(
             package.id,
             object.version,
             package.module_map,
             ProtocolConfig::get_for_min_version().max_move_package_size(),
             package.type_origin_table,
             package.linkage_table,
+        )?)
+    }
+
+    /// Resolves `object_id` into an object input of the transaction. Shared objects are used
+    /// mutably if `mutable` is set.
+    pub async fn resolve_object_arg(
+        &self,
+        object_id: ObjectID,
+        mutable: bool,
+    ) -> anyhow::Result<ObjectArg> {
+        self.get_object_arg(object_id, &mut BTreeMap::new(), mutable)
+            .await
+    }
+
+    /// Adds a move call to a programmable transaction. JSON args are resolved against the
+    /// function signature: object IDs are resolved into owned, shared or immutable object inputs
+    /// and pure values are serialized into the parameter type. Results of earlier commands are
+    /// passed through as is.
+    pub async fn programmable_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package_id: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<TypeTag>,
+        call_args: Vec<MoveCallArg>,
+    ) -> anyhow::Result<Argument> {
+        let module = Identifier::from_str(module)?;
+        let function = Identifier::from_str(function)?;
+        let package = self.get_move_package(package_id).await?;
+
+        let (json_args, mut arguments): (Vec<_>, Vec<_>) = call_args
+            .into_iter()
+            .map(|arg| match arg {
+                MoveCallArg::Json(json) => (Some(json), None),
+                MoveCallArg::Argument(argument) => (None, Some(argument)),
+            })
+            .unzip();
+        let resolved_args = resolve_move_function_partial_args(
+            &package,
+            module.clone(),
+            function.clone(),
+            &type_args,
+            json_args,
+            Mode::allow_arbitrary_function_calls(),
         )?;
 
+        let mut objects = BTreeMap::new();
+        let mut args = Vec::with_capacity(resolved_args.len());
+        for ((arg, expected_type), argument) in resolved_args.into_iter().zip(&mut arguments) {
+            let is_mutable_ref = matches!(expected_type, SignatureToken::MutableReference(_));
+            args.push(match arg {
+                // Not given as JSON, so this is the result of an earlier command.
+                None => argument
+                    .take()
+                    .expect("Each arg is either JSON or an argument"),
+                Some(ResolvedCallArg::Pure(bytes)) => builder.pure_bytes(bytes, false),
+                Some(ResolvedCallArg::Object(id)) => builder.obj(
+                    self.get_object_arg(id, &mut objects, is_mutable_ref)
+                        .await?,
+                )?,
+                Some(ResolvedCallArg::ObjVec(ids)) => {
+                    let mut object_args = vec![];
+                    for id in ids {
+                        object_args.push(
+                            self.get_object_arg(id, &mut objects, is_mutable_ref)
+                                .await?,
+                        );
+                    }
+                    builder.make_obj_vec(object_args)?
+                }
+            });
+        }
+
+        Ok(builder.command(Command::move_call(
+            package_id, module, function, type_args, args,
+        )))
+    }
+
+    async fn resolve_and_checks_json_args(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package_id: ObjectID,
+        module: &Identifier,
+        function: &Identifier,
+        type_args: &[TypeTag],
+        json_args: Vec<SuiJsonValue>,
+    ) -> Result<Vec<Argument>, anyhow::Error> {
+        let package = self.get_move_package(package_id).await?;
+
         let json_args_and_tokens = resolve_move_function_args(
             &package,
             module.clone(),
@@ -396,16 +487,18 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let mut check_args = Vec::new();
         let mut objects = BTreeMap::new();
         for (arg, expected_type) in json_args_and_tokens {
+            let is_mutable_ref = matches!(expected_type, SignatureToken::MutableReference(_));
             check_args.push(match arg {
                 ResolvedCallArg::Object(id) => CheckCallArg::Object(
-                    self.get_object_arg(id, &mut objects, expected_type).await?,
+                    self.get_object_arg(id, &mut objects, is_mutable_ref)
+                        .await?,
                 ),
                 ResolvedCallArg::Pure(p) => CheckCallArg::Pure(p),
                 ResolvedCallArg::ObjVec(v) => {
                     let mut object_ids = vec![];
                     for id in v {
                         object_ids.push(
-                            self.get_object_arg(id, &mut objects, expected_type.clone())
+                            self.get_object_arg(id, &mut objects, is_mutable_ref)
                                 .await?,
                         );
                     }
@@ -27,7 +27,7 @@ use crate::messages::TransactionEffects;
 use crate::messages::TransactionEffectsAPI;
 use crate::messages::VerifiedTransaction;
 use crate::messages_checkpoint::CheckpointTimestamp;
-use crate::multisig::MultiSigPublicKey;
+use crate::multisig::{MultiSigMember, MultiSigPublicKey};
 use crate::object::{Object, Owner};
 use crate::parse_sui_struct_tag;
 use crate::signature::GenericSignature;
@@ -559,20 +559,30 @@ impl From<MultiSigPublicKey> for SuiAddress {
     /// is defined as the 32-byte Blake2b hash of serializing the flag, the
     /// threshold, concatenation of each participating flag, public keys and
     /// its weight. `flag_MultiSig || threshold || flag_1 || pk_1 || weight_1
-    /// || ... || flag_n || pk_n || weight_n`.
+    /// || ... || flag_n || pk_n || weight_n`. The public key of a nested
+    /// MultiSig member is the bcs bytes of its [struct MultiSigPublicKey].
     fn from(multisig_pk: MultiSigPublicKey) -> Self {
         let mut hasher = DefaultHash::default();
         hasher.update([SignatureScheme::MultiSig.flag()]);
         hasher.update(multisig_pk.threshold().to_le_bytes());
-        multisig_pk.pubkeys().iter().for_each(|(pk, w)| {
-            hasher.update([pk.flag()]);
-            hasher.update(pk.as_ref());
+        multisig_pk.pubkeys().iter().for_each(|(member, w)| {
+            hasher.update([member.flag()]);
+            hasher.update(member.to_bytes());
             hasher.update(w.to_le_bytes());
         });
         SuiAddress(hasher.finalize().digest)
     }
 }
 
+impl From<&MultiSigMember> for SuiAddress {
+    fn from(member: &MultiSigMember) -> Self {
+        match member {
+            MultiSigMember::PublicKey(pk) => pk.into(),
+            MultiSigMember::MultiSig(multisig_pk) => multisig_pk.clone().into(),
+        }
+    }
+}
+
 impl TryFrom<&GenericSignature> for SuiAddress {
     type Error = SuiError;
     /// Derive a SuiAddress from a serialized signature in Sui [GenericSignature].
@@ -38,6 +38,7 @@ use strum::EnumString;
 use crate::base_types::{AuthorityName, SuiAddress};
 use crate::committee::{Committee, EpochId, StakeUnit};
 use crate::error::{SuiError, SuiResult};
+use crate::multisig::MultiSig;
 use crate::sui_serde::{Readable, SuiBitmap};
 pub use enum_dispatch::enum_dispatch;
 use fastcrypto::encoding::{Base64, Encoding, Hex};
@@ -1638,11 +1639,13 @@ impl SignatureScheme {
 }
 
 /// Unlike [enum Signature], [enum CompressedSignature] does not contain public key.
+/// The signature of a nested MultiSig member is the nested [struct MultiSig] itself.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum CompressedSignature {
     Ed25519(Ed25519SignatureAsBytes),
     Secp256k1(Secp256k1SignatureAsBytes),
     Secp256r1(Secp256r1SignatureAsBytes),
+    MultiSig(Box<MultiSig>),
 }
 
 impl FromStr for Signature {
@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::messages::{Transaction, TransactionEffects, TransactionEvents};
+use crate::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+use crate::object::Object;
+use serde::{Deserialize, Serialize};
+
+/// Everything needed to process an executed checkpoint without reading back from a node: the
+/// certified summary and contents, along with every transaction of the checkpoint and the
+/// objects it read and wrote.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointData {
+    pub checkpoint_summary: CertifiedCheckpointSummary,
+    pub checkpoint_contents: CheckpointContents,
+    /// The transactions of the checkpoint, in the order of the checkpoint contents.
+    pub transactions: Vec<CheckpointTransaction>,
+}
+
+impl CheckpointData {
+    pub fn sequence_number(&self) -> CheckpointSequenceNumber {
+        self.checkpoint_summary.sequence_number
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointTransaction {
+    /// The input Transaction
+    pub transaction: Transaction,
+    /// The effects produced by executing this transaction
+    pub effects: TransactionEffects,
+    /// The events, if any, emitted by this transaction during execution
+    pub events: Option<TransactionEvents>,
+    /// The state of the objects modified or read as shared by this transaction, before it
+    /// was executed
+    pub input_objects: Vec<Object>,
+    /// The state of the objects created, mutated or unwrapped by this transaction, after it
+    /// was executed
+    pub output_objects: Vec<Object>,
+}
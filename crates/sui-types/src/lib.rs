@@ -17,6 +17,9 @@ use base_types::ObjectID;
 
 pub use mysten_network::multiaddr;
 
+// Allows `#[derive(MoveStruct)]` to be used within this crate.
+extern crate self as sui_types;
+
 #[macro_use]
 pub mod error;
 
@@ -43,6 +46,7 @@ pub mod message_envelope;
 pub mod messages;
 pub mod messages_checkpoint;
 pub mod move_package;
+pub mod move_struct;
 pub mod multisig;
 pub mod object;
 pub mod programmable_transaction_builder;
@@ -56,6 +60,7 @@ pub mod temporary_store;
 pub mod versioned;
 
 pub mod epoch_data;
+pub mod full_checkpoint_content;
 
 #[cfg(feature = "test-utils")]
 #[path = "./unit_tests/utils.rs"]
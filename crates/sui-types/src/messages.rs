@@ -829,6 +829,15 @@ impl TransactionKind {
         )
     }
 
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ChangeEpoch(_) => "ChangeEpoch",
+            Self::Genesis(_) => "Genesis",
+            Self::ConsensusCommitPrologue(_) => "ConsensusCommitPrologue",
+            Self::ProgrammableTransaction(_) => "ProgrammableTransaction",
+        }
+    }
+
     /// If this is advance epoch transaction, returns (total gas charged, total gas rebated).
     /// TODO: We should use GasCostSummary directly in ChangeEpoch struct, and return that
     /// directly.
@@ -1650,15 +1659,21 @@ impl VersionedProtocolMessage for SenderSignedData {
         self.transaction_data()
             .check_version_supported(protocol_config)?;
 
-        // This code does nothing right now. Its purpose is to cause a compiler error when a
-        // new signature type is added.
+        // This code also causes a compiler error when a new signature type is added.
         //
         // When adding a new signature type, check if current_protocol_version
         // predates support for the new type. If it does, return
         // SuiError::WrongMessageVersion
         for sig in &self.inner().tx_signatures {
             match sig {
-                GenericSignature::MultiSig(_) | GenericSignature::Signature(_) => (),
+                GenericSignature::MultiSig(multisig) => {
+                    if multisig.multisig_pk.is_nested_or_large() {
+                        protocol_config
+                            .check_nested_multisig_supported()
+                            .map_err(|e| SuiError::UnsupportedFeatureError { error: e.0 })?;
+                    }
+                }
+                GenericSignature::Signature(_) => (),
             }
         }
 
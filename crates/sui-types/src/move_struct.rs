@@ -0,0 +1,286 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Traits of Rust types mirroring Move types, implemented for Rust structs by
+//! `#[derive(MoveStruct)]` from `sui-macros`.
+
+use anyhow::{anyhow, ensure};
+use move_core_types::ident_str;
+use move_core_types::u256::U256;
+use move_core_types::value::MoveStruct;
+use serde::de::DeserializeOwned;
+
+// Used by the code generated by `#[derive(MoveStruct)]`.
+pub use move_core_types::account_address::AccountAddress;
+pub use move_core_types::language_storage::{StructTag, TypeTag};
+pub use move_core_types::value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout};
+pub use sui_macros::MoveStruct;
+
+use crate::base_types::{
+    ObjectID, SuiAddress, STD_OPTION_MODULE_NAME, STD_OPTION_STRUCT_NAME, STD_UTF8_MODULE_NAME,
+    STD_UTF8_STRUCT_NAME,
+};
+use crate::id::{ID, UID};
+use crate::{MoveTypeTagTrait, MOVE_STDLIB_ADDRESS};
+
+/// Layout of the Move type a Rust type is decoded from.
+pub trait MoveTypeLayoutTrait {
+    fn get_layout() -> MoveTypeLayout;
+}
+
+/// A Rust struct mirroring a Move struct.
+pub trait MoveStructType {
+    fn type_() -> StructTag;
+
+    fn layout() -> MoveStructLayout;
+
+    /// Decodes the BCS bytes of a Move value of type `type_`. Fails if `type_` is not the type of
+    /// this struct or if the bytes don't match its layout.
+    fn from_bcs_bytes(type_: &StructTag, bytes: &[u8]) -> anyhow::Result<Self>
+    where
+        Self: DeserializeOwned + Sized,
+    {
+        let expected = Self::type_();
+        ensure!(
+            type_ == &expected,
+            "Type mismatch, expected {expected} but found {type_}"
+        );
+        MoveStruct::simple_deserialize(bytes, &Self::layout())
+            .map_err(|e| anyhow!("Cannot decode {type_} with the layout of {expected}: {e}"))?;
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+macro_rules! impl_move_primitive {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl MoveTypeTagTrait for $ty {
+                fn get_type_tag() -> TypeTag {
+                    TypeTag::$variant
+                }
+            }
+
+            impl MoveTypeLayoutTrait for $ty {
+                fn get_layout() -> MoveTypeLayout {
+                    MoveTypeLayout::$variant
+                }
+            }
+        )*
+    };
+}
+
+impl_move_primitive!(
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u128 => U128,
+    U256 => U256,
+);
+
+impl MoveTypeLayoutTrait for u64 {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::U64
+    }
+}
+
+impl MoveTypeLayoutTrait for ObjectID {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Address
+    }
+}
+
+impl MoveTypeLayoutTrait for SuiAddress {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Address
+    }
+}
+
+impl<T: MoveTypeTagTrait> MoveTypeTagTrait for Vec<T> {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Vector(Box::new(T::get_type_tag()))
+    }
+}
+
+impl<T: MoveTypeLayoutTrait> MoveTypeLayoutTrait for Vec<T> {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Vector(Box::new(T::get_layout()))
+    }
+}
+
+/// `String` mirrors `0x1::string::String`, a struct wrapping its UTF-8 bytes which has the same BCS
+/// encoding.
+fn utf8_string_type() -> StructTag {
+    StructTag {
+        address: MOVE_STDLIB_ADDRESS,
+        module: STD_UTF8_MODULE_NAME.to_owned(),
+        name: STD_UTF8_STRUCT_NAME.to_owned(),
+        type_params: vec![],
+    }
+}
+
+impl MoveTypeTagTrait for String {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Struct(Box::new(utf8_string_type()))
+    }
+}
+
+impl MoveTypeLayoutTrait for String {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: utf8_string_type(),
+            fields: vec![MoveFieldLayout::new(
+                ident_str!("bytes").to_owned(),
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            )],
+        })
+    }
+}
+
+/// `Option<T>` mirrors `0x1::option::Option<T>`, a struct wrapping a vector of at most one element,
+/// which has the same BCS encoding.
+fn option_type<T: MoveTypeTagTrait>() -> StructTag {
+    StructTag {
+        address: MOVE_STDLIB_ADDRESS,
+        module: STD_OPTION_MODULE_NAME.to_owned(),
+        name: STD_OPTION_STRUCT_NAME.to_owned(),
+        type_params: vec![T::get_type_tag()],
+    }
+}
+
+impl<T: MoveTypeTagTrait> MoveTypeTagTrait for Option<T> {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Struct(Box::new(option_type::<T>()))
+    }
+}
+
+impl<T: MoveTypeTagTrait + MoveTypeLayoutTrait> MoveTypeLayoutTrait for Option<T> {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: option_type::<T>(),
+            fields: vec![MoveFieldLayout::new(
+                ident_str!("vec").to_owned(),
+                MoveTypeLayout::Vector(Box::new(T::get_layout())),
+            )],
+        })
+    }
+}
+
+impl MoveTypeLayoutTrait for ID {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Struct(ID::layout())
+    }
+}
+
+impl MoveTypeTagTrait for UID {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Struct(Box::new(UID::type_()))
+    }
+}
+
+impl MoveTypeLayoutTrait for UID {
+    fn get_layout() -> MoveTypeLayout {
+        MoveTypeLayout::Struct(UID::layout())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas_coin::GasCoin;
+    use serde::Deserialize;
+    use std::marker::PhantomData;
+
+    #[derive(Deserialize, MoveStruct)]
+    #[move_struct(address = "0x2", module = "sui", name = "SUI")]
+    struct Sui {}
+
+    #[derive(Deserialize, MoveStruct)]
+    #[move_struct(address = "0x2", module = "balance")]
+    struct Balance<T> {
+        value: u64,
+        #[move_struct(skip)]
+        _phantom: PhantomData<T>,
+    }
+
+    #[derive(Deserialize, MoveStruct)]
+    #[move_struct(address = "0x2", module = "coin")]
+    struct Coin<T> {
+        id: UID,
+        balance: Balance<T>,
+    }
+
+    #[derive(Deserialize, MoveStruct)]
+    #[move_struct(address = "0x2", module = "example")]
+    struct Profile {
+        id: UID,
+        name: String,
+        age: Option<u8>,
+        nicknames: Vec<String>,
+    }
+
+    #[test]
+    fn test_derived_type() {
+        assert_eq!(Coin::<Sui>::type_(), GasCoin::type_());
+        assert_eq!(
+            Vec::<Coin<Sui>>::get_type_tag(),
+            TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(GasCoin::type_()))))
+        );
+    }
+
+    #[test]
+    fn test_from_bcs_bytes() {
+        let gas_coin = GasCoin::new(ObjectID::random(), 100);
+        let coin =
+            Coin::<Sui>::from_bcs_bytes(&GasCoin::type_(), &gas_coin.to_bcs_bytes()).unwrap();
+        assert_eq!(coin.id.id.bytes, *gas_coin.id());
+        assert_eq!(coin.balance.value, 100);
+
+        // Wrong type
+        assert!(Coin::<Sui>::from_bcs_bytes(&UID::type_(), &gas_coin.to_bcs_bytes()).is_err());
+        // Bytes not matching the layout
+        assert!(Coin::<Sui>::from_bcs_bytes(&GasCoin::type_(), &[0; 8]).is_err());
+    }
+
+    #[test]
+    fn test_string_and_option() {
+        assert_eq!(
+            Option::<String>::get_type_tag().to_string(),
+            "0x1::option::Option<0x1::string::String>"
+        );
+
+        let id = ObjectID::random();
+        let bytes = bcs::to_bytes(&(
+            UID::new(id),
+            "alice".to_string(),
+            Some(30u8),
+            vec!["al".to_string()],
+        ))
+        .unwrap();
+        let profile = Profile::from_bcs_bytes(&Profile::type_(), &bytes).unwrap();
+        assert_eq!(profile.id.id.bytes, id);
+        assert_eq!(profile.name, "alice");
+        assert_eq!(profile.age, Some(30));
+        assert_eq!(profile.nicknames, vec!["al".to_string()]);
+
+        let bytes = bcs::to_bytes(&(
+            UID::new(id),
+            String::new(),
+            None::<u8>,
+            Vec::<String>::new(),
+        ))
+        .unwrap();
+        let profile = Profile::from_bcs_bytes(&Profile::type_(), &bytes).unwrap();
+        assert_eq!(profile.age, None);
+
+        // An option holding more than one element
+        let bytes = bcs::to_bytes(&(
+            UID::new(id),
+            String::new(),
+            vec![1u8, 2],
+            Vec::<String>::new(),
+        ))
+        .unwrap();
+        assert!(Profile::from_bcs_bytes(&Profile::type_(), &bytes).is_err());
+    }
+}
@@ -3,26 +3,27 @@
 
 use crate::{
     crypto::{CompressedSignature, DefaultHash, SignatureScheme},
-    signature::AuthenticatorTrait,
+    signature::{AuthenticatorTrait, GenericSignature},
     sui_serde::SuiBitmap,
 };
 pub use enum_dispatch::enum_dispatch;
 use fastcrypto::{
     ed25519::Ed25519PublicKey,
-    encoding::Base64,
+    encoding::{Base64, Encoding},
     error::FastCryptoError,
     hash::HashFunction,
     secp256k1::Secp256k1PublicKey,
     secp256r1::Secp256r1PublicKey,
-    traits::{ToFromBytes, VerifyingKey},
+    traits::{EncodeDecodeBase64, ToFromBytes, VerifyingKey},
 };
 use once_cell::sync::OnceCell;
 use roaring::RoaringBitmap;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::serde_as;
 use shared_crypto::intent::IntentMessage;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 use crate::{
     base_types::SuiAddress,
@@ -36,7 +37,14 @@ mod multisig_tests;
 
 pub type WeightUnit = u8;
 pub type ThresholdUnit = u16;
+/// The maximum number of members of a MultiSig, unless nested MultiSigs are supported by the
+/// protocol.
 pub const MAX_SIGNER_IN_MULTISIG: usize = 10;
+/// The maximum number of public keys of a MultiSig, counting the public keys of its nested
+/// MultiSigs.
+pub const MAX_SIGNER_IN_NESTED_MULTISIG: usize = 100;
+/// The maximum nesting depth of a MultiSig, a MultiSig without MultiSig members has depth 1.
+pub const MAX_MULTISIG_DEPTH: usize = 2;
 
 /// This initialize the underlying bytes representation of MultiSig. It encodes
 /// [struct MultiSig] as the MultiSig flag (0x03) concat with the bcs bytes
@@ -100,7 +108,9 @@ impl AuthenticatorTrait for MultiSig {
     where
         T: Serialize,
     {
-        if self.multisig_pk.pk_map.len() > MAX_SIGNER_IN_MULTISIG {
+        if self.multisig_pk.signer_count() > MAX_SIGNER_IN_NESTED_MULTISIG
+            || self.multisig_pk.depth() > MAX_MULTISIG_DEPTH
+        {
             return Err(SuiError::InvalidSignature {
                 error: "Invalid number of public keys".to_string(),
             });
@@ -111,68 +121,153 @@ impl AuthenticatorTrait for MultiSig {
                 error: "Invalid address".to_string(),
             });
         }
-        let mut weight_sum: u16 = 0;
         let message = bcs::to_bytes(&value).expect("Message serialization should not fail");
         let mut hasher = DefaultHash::default();
         hasher.update(message);
         let digest = hasher.finalize().digest;
+        self.verify_digest(&digest)
+    }
+}
+
+impl MultiSig {
+    /// This combines a list of [enum Signature] `flag || signature || pk` to a MultiSig.
+    pub fn combine(
+        full_sigs: Vec<Signature>,
+        multisig_pk: MultiSigPublicKey,
+    ) -> Result<Self, SuiError> {
+        Self::combine_generic(
+            full_sigs
+                .into_iter()
+                .map(GenericSignature::Signature)
+                .collect(),
+            multisig_pk,
+        )
+    }
+
+    /// This combines a list of [enum GenericSignature] to a MultiSig, a plain signature for each
+    /// signing public key member and a MultiSig for each signing MultiSig member.
+    pub fn combine_generic(
+        full_sigs: Vec<GenericSignature>,
+        multisig_pk: MultiSigPublicKey,
+    ) -> Result<Self, SuiError> {
+        if full_sigs.len() > multisig_pk.pk_map.len()
+            || multisig_pk.signer_count() > MAX_SIGNER_IN_NESTED_MULTISIG
+            || full_sigs.is_empty()
+            || multisig_pk.pk_map.is_empty()
+        {
+            return Err(SuiError::InvalidSignature {
+                error: "Invalid number of signatures".to_string(),
+            });
+        }
+        let mut bitmap = RoaringBitmap::new();
+        let mut sigs = Vec::with_capacity(full_sigs.len());
+        for s in full_sigs {
+            let (member, sig) = match s {
+                GenericSignature::Signature(s) => (
+                    MultiSigMember::PublicKey(s.to_public_key()?),
+                    s.to_compressed()?,
+                ),
+                GenericSignature::MultiSig(s) => (
+                    MultiSigMember::MultiSig(s.multisig_pk.clone()),
+                    CompressedSignature::MultiSig(Box::new(s)),
+                ),
+            };
+            bitmap.insert(multisig_pk.get_member_index(&member).ok_or(
+                SuiError::IncorrectSigner {
+                    error: "pk does not exist".to_string(),
+                },
+            )?);
+            sigs.push(sig);
+        }
+        Ok(MultiSig {
+            sigs,
+            bitmap,
+            multisig_pk,
+            bytes: OnceCell::new(),
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), FastCryptoError> {
+        if self.sigs.len() > self.multisig_pk.pk_map.len() || self.sigs.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        self.multisig_pk.validate()?;
+        for sig in &self.sigs {
+            if let CompressedSignature::MultiSig(multisig) = sig {
+                multisig.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies the signatures against the digest of the intent message, the signatures of nested
+    /// MultiSigs being verified against the same digest.
+    fn verify_digest(&self, digest: &[u8; 32]) -> Result<(), SuiError> {
+        let mut weight_sum: u16 = 0;
 
         // Verify each signature against its corresponding signature scheme and public key.
         // TODO: further optimization can be done because multiple Ed25519 signatures can be batch verified.
         for (sig, i) in self.sigs.iter().zip(&self.bitmap) {
-            let (pk, weight) =
+            let (member, weight) =
                 self.multisig_pk
                     .pk_map
                     .get(i as usize)
                     .ok_or(SuiError::InvalidSignature {
                         error: "Invalid public keys index".to_string(),
                     })?;
-            let res = match sig {
-                CompressedSignature::Ed25519(s) => {
+            let res = match (sig, member) {
+                (CompressedSignature::Ed25519(s), MultiSigMember::PublicKey(pk)) => {
                     let pk = Ed25519PublicKey::from_bytes(pk.as_ref()).map_err(|_| {
                         SuiError::InvalidSignature {
                             error: "Invalid public key".to_string(),
                         }
                     })?;
                     pk.verify(
-                        &digest,
+                        digest,
                         &s.try_into().map_err(|_| SuiError::InvalidSignature {
                             error: "Fail to verify single sig".to_string(),
                         })?,
                     )
                 }
-                CompressedSignature::Secp256k1(s) => {
+                (CompressedSignature::Secp256k1(s), MultiSigMember::PublicKey(pk)) => {
                     let pk = Secp256k1PublicKey::from_bytes(pk.as_ref()).map_err(|_| {
                         SuiError::InvalidSignature {
                             error: "Invalid public key".to_string(),
                         }
                     })?;
                     pk.verify(
-                        &digest,
+                        digest,
                         &s.try_into().map_err(|_| SuiError::InvalidSignature {
                             error: "Fail to verify single sig".to_string(),
                         })?,
                     )
                 }
-                CompressedSignature::Secp256r1(s) => {
+                (CompressedSignature::Secp256r1(s), MultiSigMember::PublicKey(pk)) => {
                     let pk = Secp256r1PublicKey::from_bytes(pk.as_ref()).map_err(|_| {
                         SuiError::InvalidSignature {
                             error: "Invalid public key".to_string(),
                         }
                     })?;
                     pk.verify(
-                        &digest,
+                        digest,
                         &s.try_into().map_err(|_| SuiError::InvalidSignature {
                             error: "Fail to verify single sig".to_string(),
                         })?,
                     )
                 }
+                (
+                    CompressedSignature::MultiSig(multisig),
+                    MultiSigMember::MultiSig(multisig_pk),
+                ) if &multisig.multisig_pk == multisig_pk => multisig
+                    .verify_digest(digest)
+                    .map_err(|_| FastCryptoError::InvalidSignature),
+                _ => Err(FastCryptoError::InvalidSignature),
             };
             if res.is_ok() {
                 weight_sum += *weight as u16;
             } else {
                 return Err(SuiError::InvalidSignature {
-                    error: format!("Invalid signature for pk={:?}", pk),
+                    error: format!("Invalid signature for pk={:?}", member),
                 });
             }
         }
@@ -187,53 +282,102 @@ impl AuthenticatorTrait for MultiSig {
     }
 }
 
-impl MultiSig {
-    /// This combines a list of [enum Signature] `flag || signature || pk` to a MultiSig.
-    pub fn combine(
-        full_sigs: Vec<Signature>,
-        multisig_pk: MultiSigPublicKey,
-    ) -> Result<Self, SuiError> {
-        if full_sigs.len() > multisig_pk.pk_map.len()
-            || multisig_pk.pk_map.len() > MAX_SIGNER_IN_MULTISIG
-            || full_sigs.is_empty()
-            || multisig_pk.pk_map.is_empty()
-        {
-            return Err(SuiError::InvalidSignature {
-                error: "Invalid number of signatures".to_string(),
-            });
+/// A member of a MultiSig, either a public key or another MultiSig. A member is encoded as
+/// `flag || bytes`, where a MultiSig member is the MultiSig flag (0x03) concat with the bcs bytes
+/// of its [struct MultiSigPublicKey], so that public key members are encoded as [enum PublicKey].
+#[derive(Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub enum MultiSigMember {
+    #[schemars(with = "Base64")]
+    PublicKey(PublicKey),
+    #[schemars(with = "Base64")]
+    MultiSig(MultiSigPublicKey),
+}
+
+impl MultiSigMember {
+    pub fn flag(&self) -> u8 {
+        match self {
+            MultiSigMember::PublicKey(pk) => pk.flag(),
+            MultiSigMember::MultiSig(_) => SignatureScheme::MultiSig.flag(),
         }
-        let mut bitmap = RoaringBitmap::new();
-        let mut sigs = Vec::with_capacity(full_sigs.len());
-        for s in full_sigs {
-            bitmap.insert(multisig_pk.get_index(s.to_public_key()?).ok_or(
-                SuiError::IncorrectSigner {
-                    error: "pk does not exist".to_string(),
-                },
-            )?);
-            sigs.push(s.to_compressed()?);
+    }
+
+    /// The member bytes without the flag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            MultiSigMember::PublicKey(pk) => pk.as_ref().to_vec(),
+            MultiSigMember::MultiSig(multisig_pk) => {
+                bcs::to_bytes(multisig_pk).expect("BCS serialization should not fail")
+            }
         }
-        Ok(MultiSig {
-            sigs,
-            bitmap,
-            multisig_pk,
-            bytes: OnceCell::new(),
-        })
     }
+}
 
-    pub fn validate(&self) -> Result<(), FastCryptoError> {
-        if self.sigs.len() > self.multisig_pk.pk_map.len() || self.sigs.is_empty() {
-            return Err(FastCryptoError::InvalidInput);
+impl From<PublicKey> for MultiSigMember {
+    fn from(pk: PublicKey) -> Self {
+        MultiSigMember::PublicKey(pk)
+    }
+}
+
+impl From<MultiSigPublicKey> for MultiSigMember {
+    fn from(multisig_pk: MultiSigPublicKey) -> Self {
+        MultiSigMember::MultiSig(multisig_pk)
+    }
+}
+
+impl EncodeDecodeBase64 for MultiSigMember {
+    fn encode_base64(&self) -> String {
+        let mut bytes = vec![self.flag()];
+        bytes.extend_from_slice(&self.to_bytes());
+        Base64::encode(&bytes[..])
+    }
+
+    fn decode_base64(value: &str) -> Result<Self, eyre::Report> {
+        let bytes = Base64::decode(value).map_err(|e| eyre::eyre!("{}", e.to_string()))?;
+        match bytes.first() {
+            Some(x) if x == &SignatureScheme::MultiSig.flag() => {
+                let multisig_pk: MultiSigPublicKey = bcs::from_bytes(&bytes[1..])?;
+                multisig_pk
+                    .validate()
+                    .map_err(|_| eyre::eyre!("Invalid MultiSig public key"))?;
+                Ok(MultiSigMember::MultiSig(multisig_pk))
+            }
+            _ => Ok(MultiSigMember::PublicKey(PublicKey::decode_base64(value)?)),
         }
-        self.multisig_pk.validate()?;
-        Ok(())
+    }
+}
+
+impl Serialize for MultiSigMember {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.encode_base64())
+    }
+}
+
+impl<'de> Deserialize<'de> for MultiSigMember {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let s = String::deserialize(deserializer)?;
+        Self::decode_base64(&s).map_err(|e| Error::custom(e.to_string()))
+    }
+}
+
+impl FromStr for MultiSigMember {
+    type Err = eyre::Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_base64(s).map_err(|e| eyre::eyre!("Fail to decode base64 {}", e.to_string()))
     }
 }
 
 /// The struct that contains the public key used for authenticating a MultiSig.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct MultiSigPublicKey {
-    /// A list of public key and its corresponding weight.
-    pk_map: Vec<(PublicKey, WeightUnit)>,
+    /// A list of members, public keys or nested MultiSigs, and their corresponding weight.
+    pk_map: Vec<(MultiSigMember, WeightUnit)>,
     /// If the total weight of the public keys corresponding to verified signatures is larger than threshold, the MultiSig is verified.
     threshold: ThresholdUnit,
 }
@@ -244,44 +388,90 @@ impl MultiSigPublicKey {
         weights: Vec<WeightUnit>,
         threshold: ThresholdUnit,
     ) -> Result<Self, SuiError> {
-        if pks.is_empty()
-            || weights.is_empty()
-            || threshold == 0
-            || pks.len() != weights.len()
-            || pks.len() > MAX_SIGNER_IN_MULTISIG
-            || weights.iter().any(|w| *w == 0)
-            || weights
-                .iter()
-                .map(|w| *w as ThresholdUnit)
-                .sum::<ThresholdUnit>()
-                < threshold
-        {
+        Self::new_with_members(
+            pks.into_iter().map(MultiSigMember::PublicKey).collect(),
+            weights,
+            threshold,
+        )
+    }
+
+    /// Creates a MultiSig public key whose members may be public keys or nested MultiSigs.
+    pub fn new_with_members(
+        members: Vec<MultiSigMember>,
+        weights: Vec<WeightUnit>,
+        threshold: ThresholdUnit,
+    ) -> Result<Self, SuiError> {
+        if members.len() != weights.len() {
             return Err(SuiError::InvalidSignature {
                 error: "Invalid number of public keys".to_string(),
             });
         }
-        Ok(MultiSigPublicKey {
-            pk_map: pks.into_iter().zip(weights.into_iter()).collect(),
+        let multisig_pk = MultiSigPublicKey {
+            pk_map: members.into_iter().zip(weights.into_iter()).collect(),
             threshold,
-        })
+        };
+        multisig_pk
+            .validate()
+            .map_err(|_| SuiError::InvalidSignature {
+                error: "Invalid number of public keys".to_string(),
+            })?;
+        Ok(multisig_pk)
     }
 
     pub fn get_index(&self, pk: PublicKey) -> Option<u32> {
-        self.pk_map.iter().position(|x| x.0 == pk).map(|x| x as u32)
+        self.get_member_index(&MultiSigMember::PublicKey(pk))
+    }
+
+    pub fn get_member_index(&self, member: &MultiSigMember) -> Option<u32> {
+        self.pk_map
+            .iter()
+            .position(|x| &x.0 == member)
+            .map(|x| x as u32)
     }
 
     pub fn threshold(&self) -> &ThresholdUnit {
         &self.threshold
     }
 
-    pub fn pubkeys(&self) -> &Vec<(PublicKey, WeightUnit)> {
+    pub fn pubkeys(&self) -> &Vec<(MultiSigMember, WeightUnit)> {
         &self.pk_map
     }
 
+    /// The number of public keys of the MultiSig, counting the public keys of nested MultiSigs.
+    pub fn signer_count(&self) -> usize {
+        self.pk_map
+            .iter()
+            .map(|(member, _)| match member {
+                MultiSigMember::PublicKey(_) => 1,
+                MultiSigMember::MultiSig(multisig_pk) => multisig_pk.signer_count(),
+            })
+            .sum()
+    }
+
+    /// The nesting depth of the MultiSig, 1 if none of its members is a MultiSig.
+    pub fn depth(&self) -> usize {
+        1 + self
+            .pk_map
+            .iter()
+            .map(|(member, _)| match member {
+                MultiSigMember::PublicKey(_) => 0,
+                MultiSigMember::MultiSig(multisig_pk) => multisig_pk.depth(),
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the MultiSig has MultiSig members or more than [MAX_SIGNER_IN_MULTISIG] members,
+    /// which must be supported by the protocol.
+    pub fn is_nested_or_large(&self) -> bool {
+        self.pk_map.len() > MAX_SIGNER_IN_MULTISIG || self.depth() > 1
+    }
+
     pub fn validate(&self) -> Result<(), FastCryptoError> {
         if self.threshold == 0
             || self.pubkeys().is_empty()
-            || self.pubkeys().len() > MAX_SIGNER_IN_MULTISIG
+            || self.signer_count() > MAX_SIGNER_IN_NESTED_MULTISIG
+            || self.depth() > MAX_MULTISIG_DEPTH
             || self.pubkeys().iter().any(|(_pk, weight)| *weight == 0)
             || self
                 .pubkeys()
@@ -292,6 +482,11 @@ impl MultiSigPublicKey {
         {
             return Err(FastCryptoError::InvalidInput);
         }
+        for (member, _) in self.pubkeys() {
+            if let MultiSigMember::MultiSig(multisig_pk) = member {
+                multisig_pk.validate()?;
+            }
+        }
         Ok(())
     }
 }
@@ -38,4 +38,60 @@ pub enum TransactionFilter {
     FromAndToAddress { from: SuiAddress, to: SuiAddress },
     /// Query by transaction kind
     TransactionKind(String),
+    /// Query by checkpoint range, in [start_checkpoint, end_checkpoint) interval
+    #[serde(rename_all = "camelCase")]
+    CheckpointRange {
+        /// left endpoint of checkpoint interval, inclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        start_checkpoint: CheckpointSequenceNumber,
+        /// right endpoint of checkpoint interval, exclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        end_checkpoint: CheckpointSequenceNumber,
+    },
+    /// Query by the time the transaction was indexed, in [start_time, end_time) interval
+    #[serde(rename_all = "camelCase")]
+    TimeRange {
+        /// left endpoint of time interval, milliseconds since epoch, inclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        start_time: u64,
+        /// right endpoint of time interval, milliseconds since epoch, exclusive
+        #[schemars(with = "BigInt<u64>")]
+        #[serde_as(as = "Readable<BigInt<u64>, _>")]
+        end_time: u64,
+    },
+    /// Query transactions matching all the given filters.
+    All(Vec<TransactionFilter>),
+    /// Query transactions matching any of the given filters.
+    Any(Vec<TransactionFilter>),
+    /// Query transactions not matching the given filter.
+    Not(Box<TransactionFilter>),
+}
+
+impl TransactionFilter {
+    pub fn and(self, other_filter: TransactionFilter) -> Self {
+        Self::All(vec![self, other_filter])
+    }
+
+    pub fn or(self, other_filter: TransactionFilter) -> Self {
+        Self::Any(vec![self, other_filter])
+    }
+
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    /// Returns true if the filter can be evaluated against a transaction as soon as it is
+    /// executed, i.e. it does not depend on the checkpoint or on the index timestamp of the
+    /// transaction.
+    pub fn is_streamable(&self) -> bool {
+        match self {
+            Self::Checkpoint(_) | Self::CheckpointRange { .. } | Self::TimeRange { .. } => false,
+            Self::All(filters) | Self::Any(filters) => filters.iter().all(Self::is_streamable),
+            Self::Not(filter) => filter.is_streamable(),
+            _ => true,
+        }
+    }
 }
@@ -15,6 +15,7 @@ use schemars::JsonSchema;
 use serde::Serialize;
 use shared_crypto::intent::IntentMessage;
 use std::hash::Hash;
+use std::str::FromStr;
 
 /// A lightweight trait that all members of [enum GenericSignature] implement.
 #[enum_dispatch]
@@ -81,6 +82,13 @@ impl AsRef<[u8]> for GenericSignature {
 // A macro to implement [trait Serialize] and [trait Deserialize] for [enum GenericSignature] using its bytes representation.
 serde_to_from_bytes!(GenericSignature);
 
+impl FromStr for GenericSignature {
+    type Err = eyre::Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_base64(s).map_err(|e| eyre::eyre!("Fail to decode base64 {}", e.to_string()))
+    }
+}
+
 /// This ports the wrapper trait to the verify_secure defined on [enum Signature].
 impl AuthenticatorTrait for Signature {
     fn verify_secure_generic<T>(
@@ -8,17 +8,21 @@ use once_cell::sync::OnceCell;
 use rand::{rngs::StdRng, SeedableRng};
 use roaring::RoaringBitmap;
 
-use super::{MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use super::{MultiSigMember, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use crate::{
-    base_types::SuiAddress,
+    base_types::{random_object_ref, SuiAddress},
     crypto::{
-        get_key_pair, get_key_pair_from_rng, Ed25519SuiSignature, Signature, SuiKeyPair,
-        SuiSignatureInner,
+        get_key_pair, get_key_pair_from_rng, Ed25519SuiSignature, EncodeDecodeBase64, Signature,
+        SuiKeyPair, SuiSignatureInner,
+    },
+    messages::{Transaction, TransactionData, VersionedProtocolMessage},
+    multisig::{
+        MultiSig, MAX_MULTISIG_DEPTH, MAX_SIGNER_IN_MULTISIG, MAX_SIGNER_IN_NESTED_MULTISIG,
     },
-    multisig::{MultiSig, MAX_SIGNER_IN_MULTISIG},
     signature::{AuthenticatorTrait, GenericSignature},
 };
 use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 
 pub fn keys() -> Vec<SuiKeyPair> {
     let mut seed = StdRng::from_seed([0; 32]);
@@ -193,7 +197,7 @@ fn test_serde_roundtrip() {
 
     // Malformed multisig cannot be deserialized
     let multisig_pk = MultiSigPublicKey {
-        pk_map: vec![(keys()[0].public(), 1)],
+        pk_map: vec![(keys()[0].public().into(), 1)],
         threshold: 1,
     };
     let multisig = MultiSig {
@@ -324,9 +328,9 @@ fn test_max_sig() {
 
     // multisig_pk with larger that max number of pks fails.
     assert!(MultiSigPublicKey::new(
-        vec![keys[0].public(); MAX_SIGNER_IN_MULTISIG + 1],
-        vec![WeightUnit::MAX; MAX_SIGNER_IN_MULTISIG + 1],
-        ThresholdUnit::MAX
+        vec![keys[0].public(); MAX_SIGNER_IN_NESTED_MULTISIG + 1],
+        vec![1; MAX_SIGNER_IN_NESTED_MULTISIG + 1],
+        1
     )
     .is_err());
 
@@ -364,3 +368,145 @@ fn test_max_sig() {
     let multisig = MultiSig::combine(vec![sig; 1], low_threshold_pk).unwrap();
     assert!(multisig.verify_secure_generic(&msg, address).is_ok());
 }
+
+#[test]
+fn test_nested_multisig() {
+    let msg = IntentMessage::new(
+        Intent::sui_transaction(),
+        PersonalMessage {
+            message: "Hello".as_bytes().to_vec(),
+        },
+    );
+    let keys = keys();
+    let pks: Vec<_> = keys.iter().map(|kp| kp.public()).collect();
+    let sigs: Vec<_> = keys
+        .iter()
+        .map(|kp| Signature::new_secure(&msg, kp))
+        .collect();
+
+    // 2 of 3 teams, each team being a 2 of 3 MultiSig of the keys.
+    let team_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 2).unwrap();
+    let other_team_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 3).unwrap();
+    let multisig_pk = MultiSigPublicKey::new_with_members(
+        vec![
+            team_pk.clone().into(),
+            other_team_pk.clone().into(),
+            pks[0].clone().into(),
+        ],
+        vec![1, 1, 1],
+        2,
+    )
+    .unwrap();
+    assert_eq!(multisig_pk.depth(), 2);
+    assert_eq!(multisig_pk.signer_count(), 7);
+    assert!(multisig_pk.is_nested_or_large());
+    assert!(!team_pk.is_nested_or_large());
+    let addr = SuiAddress::from(multisig_pk.clone());
+
+    let team_sig = MultiSig::combine(vec![sigs[0].clone(), sigs[2].clone()], team_pk).unwrap();
+    let multisig = MultiSig::combine_generic(
+        vec![team_sig.clone().into(), sigs[0].clone().into()],
+        multisig_pk.clone(),
+    )
+    .unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_ok());
+
+    // The nested MultiSig roundtrips through its bytes representation.
+    let generic_sig = GenericSignature::MultiSig(multisig);
+    let generic_sig_roundtrip = GenericSignature::from_bytes(generic_sig.as_bytes()).unwrap();
+    assert_eq!(generic_sig, generic_sig_roundtrip);
+
+    // A nested MultiSig below its own threshold fails.
+    let other_team_sig =
+        MultiSig::combine(vec![sigs[0].clone(), sigs[1].clone()], other_team_pk).unwrap();
+    let multisig = MultiSig::combine_generic(
+        vec![team_sig.into(), other_team_sig.into()],
+        multisig_pk.clone(),
+    )
+    .unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_err());
+
+    // A single signed member is below the threshold.
+    let multisig =
+        MultiSig::combine_generic(vec![sigs[0].clone().into()], multisig_pk.clone()).unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_err());
+
+    // Public key members are encoded as public keys, nested members roundtrip.
+    let member = MultiSigMember::from(pks[1].clone());
+    assert_eq!(member.encode_base64(), pks[1].encode_base64());
+    let member = MultiSigMember::from(multisig_pk.clone());
+    assert_eq!(
+        MultiSigMember::decode_base64(&member.encode_base64()).unwrap(),
+        member
+    );
+
+    // MultiSigs cannot be nested deeper than the max depth.
+    assert_eq!(multisig_pk.depth(), MAX_MULTISIG_DEPTH);
+    assert!(MultiSigPublicKey::new_with_members(vec![multisig_pk.into()], vec![1], 1).is_err());
+}
+
+#[test]
+fn test_large_multisig() {
+    let msg = IntentMessage::new(
+        Intent::sui_transaction(),
+        PersonalMessage {
+            message: "Hello".as_bytes().to_vec(),
+        },
+    );
+    let mut seed = StdRng::from_seed([0; 32]);
+    let keys: Vec<SuiKeyPair> = (0..MAX_SIGNER_IN_MULTISIG * 2)
+        .map(|_| SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut seed).1))
+        .collect();
+    let multisig_pk = MultiSigPublicKey::new(
+        keys.iter().map(|kp| kp.public()).collect(),
+        vec![1; keys.len()],
+        MAX_SIGNER_IN_MULTISIG as ThresholdUnit + 1,
+    )
+    .unwrap();
+    assert!(multisig_pk.is_nested_or_large());
+    let addr = SuiAddress::from(multisig_pk.clone());
+
+    let sigs: Vec<_> = keys
+        .iter()
+        .take(MAX_SIGNER_IN_MULTISIG + 1)
+        .map(|kp| Signature::new_secure(&msg, kp))
+        .collect();
+    let multisig = MultiSig::combine(sigs.clone(), multisig_pk.clone()).unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_ok());
+
+    let multisig = MultiSig::combine(sigs[..MAX_SIGNER_IN_MULTISIG].to_vec(), multisig_pk).unwrap();
+    assert!(multisig.verify_secure_generic(&msg, addr).is_err());
+}
+
+#[test]
+fn test_nested_multisig_protocol_support() {
+    let keys = keys();
+    let pks: Vec<_> = keys.iter().map(|kp| kp.public()).collect();
+    let team_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 1).unwrap();
+    let multisig_pk =
+        MultiSigPublicKey::new_with_members(vec![team_pk.clone().into()], vec![1], 1).unwrap();
+    let sender = SuiAddress::from(multisig_pk.clone());
+    let tx_data = TransactionData::new_transfer_sui(
+        SuiAddress::random_for_testing_only(),
+        sender,
+        None,
+        random_object_ref(),
+        1_000_000,
+        1,
+    );
+    let msg = IntentMessage::new(Intent::sui_transaction(), tx_data.clone());
+    let team_sig = MultiSig::combine(vec![Signature::new_secure(&msg, &keys[0])], team_pk).unwrap();
+    let multisig = MultiSig::combine_generic(vec![team_sig.into()], multisig_pk).unwrap();
+    let tx = Transaction::from_generic_sig_data(
+        tx_data,
+        Intent::sui_transaction(),
+        vec![multisig.into()],
+    );
+    assert!(tx.verify_signature().is_ok());
+
+    // Nested MultiSigs are only supported from the protocol version enabling them.
+    let mut config = ProtocolConfig::get_for_version(ProtocolVersion::MAX);
+    assert!(tx.check_version_supported(&config).is_ok());
+    config.set_nested_multisig_for_testing(false);
+    assert!(tx.check_version_supported(&config).is_err());
+}
@@ -4,13 +4,16 @@
 use core::fmt;
 use std::sync::Arc;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter, Write},
     path::{Path, PathBuf},
     time::Instant,
 };
 
+use crate::client_ptb::{add_ptb_commands, PtbCommand};
 use crate::config::{Config, PersistedConfig, SuiClientConfig, SuiEnv};
+use crate::multisig_request::MultiSigRequest;
+use crate::replay::{replay_transaction, ReplayResult};
 use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
@@ -27,7 +30,7 @@ use serde::Serialize;
 use serde_json::{json, Value};
 use sui_framework::build_move_package;
 use sui_move::build::resolve_lock_file_path;
-use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
+use sui_source_validation::{BytecodeSourceVerifier, SourceMode, VerificationReport};
 use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiError;
 
@@ -38,12 +41,14 @@ use sui_framework_build::compiled_package::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectDataFilter, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectDataFilter,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
-use sui_keys::keystore::AccountKeystore;
+use sui_keys::key_metadata::{validate_alias, KeyIdentity};
+use sui_keys::keystore::{AccountKeystore, Keystore, RemoteKeystore};
+use sui_keys::remote_signer::RemoteSignerEndpoint;
 use sui_sdk::SuiClient;
 use sui_types::crypto::SignatureScheme;
 use sui_types::dynamic_field::DynamicFieldType;
@@ -52,7 +57,7 @@ use sui_types::signature::GenericSignature;
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress},
     gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    messages::{Transaction, TransactionData, VerifiedTransaction},
     object::Owner,
     parse_sui_type_tag,
 };
@@ -65,10 +70,10 @@ pub enum SuiClientCommands {
     /// Switch active address and network(e.g., devnet, local rpc server)
     #[clap(name = "switch")]
     Switch {
-        /// An Sui address to be used as the active address for subsequent
-        /// commands.
+        /// An Sui address, or the alias of a key of the keystore, to be used as the active
+        /// address for subsequent commands.
         #[clap(long)]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
         /// The RPC server URL (e.g., local rpc server, devnet rpc server, etc) to be
         /// used for subsequent commands.
         #[clap(long)]
@@ -87,6 +92,22 @@ pub enum SuiClientCommands {
     /// List all Sui environments
     Envs,
 
+    /// Sign with the keys of a remote signer in place of the keystore of the client config, and
+    /// make one of its addresses the active address.
+    #[clap(name = "use-remote-signer")]
+    UseRemoteSigner {
+        /// Endpoint of the signer, `unix:<path>` or `tcp:<loopback ip>:<port>`.
+        endpoint: RemoteSignerEndpoint,
+    },
+
+    /// Sign with the keys of a keystore file in place of the keystore of the client config, e.g.
+    /// to stop signing with a remote signer, and make one of its addresses the active address.
+    #[clap(name = "use-keystore")]
+    UseKeystore {
+        /// Path of the keystore file, prompts for the passphrase if it is encrypted.
+        path: PathBuf,
+    },
+
     /// Default address used for commands when none specified
     #[clap(name = "active-address")]
     ActiveAddress,
@@ -149,9 +170,8 @@ pub enum SuiClientCommands {
         #[clap(long)]
         with_unpublished_dependencies: bool,
 
-        /// Do not Sign transaction, output Base64-encoded Serialized Output
-        #[clap(long)]
-        serialize_output: bool,
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Upgrade Move modules
@@ -191,6 +211,9 @@ pub enum SuiClientCommands {
         /// Also publish transitive dependencies that have not already been published.
         #[clap(long)]
         with_unpublished_dependencies: bool,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Verify local Move packages against on-chain packages, and optionally their dependencies.
@@ -255,14 +278,39 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
+    },
+
+    /// Run a programmable transaction block of several commands, e.g.
+    /// `--cmd "split-coins gas [1000]" --cmd "transfer-objects [@0] 0x.."`.
+    /// Commands are split-coins, merge-coins, transfer-objects, make-move-vec,
+    /// move-call, publish and upgrade, `@<i>` and `@<i>.<j>` refer to the results
+    /// of the i-th command.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Commands of the transaction block, in order
+        #[clap(long = "cmd", required = true)]
+        commands: Vec<PtbCommand>,
+        /// Gas budget of the transaction block, estimated with a dry run if not provided
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Build options of the packages published or upgraded by the block
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Transfer object
     #[clap(name = "transfer")]
     Transfer {
-        /// Recipient address
+        /// Recipient address, or alias of a key of the keystore
         #[clap(long)]
-        to: SuiAddress,
+        to: KeyIdentity,
 
         /// Object to transfer, in 20 bytes Hex string
         #[clap(long)]
@@ -276,15 +324,18 @@ pub enum SuiClientCommands {
         /// Gas budget for this transfer
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
     /// Transfer SUI, and pay gas with the same SUI coin object.
     /// If amount is specified, only the amount is transferred; otherwise the entire object
     /// is transferred.
     #[clap(name = "transfer-sui")]
     TransferSui {
-        /// Recipient address
+        /// Recipient address, or alias of a key of the keystore
         #[clap(long)]
-        to: SuiAddress,
+        to: KeyIdentity,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
@@ -297,6 +348,9 @@ pub enum SuiClientCommands {
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
         amount: Option<u64>,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
     /// Pay coins to recipients following specified amounts, with input coins.
     /// Length of recipients must be the same as that of amounts.
@@ -306,9 +360,10 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts
+        /// The recipient addresses, or aliases of keys of the keystore, must be of same length
+        /// as amounts
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<KeyIdentity>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
@@ -322,6 +377,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Pay SUI coins to recipients following following specified amounts, with input coins.
@@ -333,9 +391,10 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts.
+        /// The recipient addresses, or aliases of keys of the keystore, must be of same length
+        /// as amounts.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<KeyIdentity>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
@@ -344,6 +403,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Pay all residual SUI coins to the recipient with input coins, after deducting the gas cost.
@@ -354,13 +416,16 @@ pub enum SuiClientCommands {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient address.
+        /// The recipient address, or alias of a key of the keystore.
         #[clap(long, multiple_occurrences = false)]
-        recipient: SuiAddress,
+        recipient: KeyIdentity,
 
         /// Gas budget for this transaction
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Obtain the Addresses managed by the client.
@@ -376,23 +441,26 @@ pub enum SuiClientCommands {
         key_scheme: SignatureScheme,
         word_length: Option<String>,
         derivation_path: Option<DerivationPath>,
+        /// Alias of the new address, usable instead of the address in commands.
+        #[clap(long)]
+        alias: Option<String>,
     },
 
     /// Obtain all objects owned by the address
     #[clap(name = "objects")]
     Objects {
-        /// Address owning the objects
+        /// Address, or alias of a key of the keystore, owning the objects
         /// Shows all objects owned by `sui client active-address` if no argument is passed
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
     },
 
     /// Obtain all gas objects owned by the address.
     #[clap(name = "gas")]
     Gas {
-        /// Address owning the objects
+        /// Address, or alias of a key of the keystore, owning the objects
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
     },
 
     /// Query a dynamic field by its address.
@@ -428,6 +496,9 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Merge two coin objects into one coin
@@ -445,14 +516,17 @@ pub enum SuiClientCommands {
         /// Gas budget for this call
         #[clap(long)]
         gas_budget: u64,
+
+        #[clap(flatten)]
+        opts: Opts,
     },
 
     /// Serialize a transfer that can be signed. This is useful when user prefers to take the data to sign elsewhere.
     #[clap(name = "serialize-transfer-sui")]
     SerializeTransferSui {
-        /// Recipient address
+        /// Recipient address, or alias of a key of the keystore
         #[clap(long)]
-        to: SuiAddress,
+        to: KeyIdentity,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
@@ -477,6 +551,61 @@ pub enum SuiClientCommands {
         #[clap(long)]
         signatures: Vec<String>,
     },
+
+    /// Combine the signatures of a MultiSig request file, see `sui keytool multi-sig-request-create`,
+    /// and execute its transaction once their weight meets the threshold.
+    ExecuteMultiSigRequest {
+        /// Path of the request file.
+        #[clap(long)]
+        file: PathBuf,
+    },
+
+    /// Re-execute a transaction locally against the objects it was executed against on-chain, and
+    /// compare the resulting effects with the on-chain effects.
+    #[clap(name = "replay")]
+    Replay {
+        /// Digest of the transaction to replay.
+        #[clap(name = "digest")]
+        digest: TransactionDigest,
+
+        /// Protocol version to execute the transaction with, defaults to the protocol version of
+        /// the epoch the transaction was executed in.
+        #[clap(long)]
+        protocol_version: Option<u64>,
+
+        /// Show the loaded objects, a trace of the commands of the local execution, the local
+        /// effects and the debug output of the Move code.
+        #[clap(long)]
+        verbose: bool,
+
+        /// Path to a local Move package to build and execute in place of the on-chain package
+        /// `--package-id`, e.g. to test a fix against the transaction.
+        #[clap(long, parse(from_os_str), requires = "package_id")]
+        local_package: Option<PathBuf>,
+
+        /// ID of the on-chain package replaced by `--local-package`.
+        #[clap(long, requires = "local_package")]
+        package_id: Option<ObjectID>,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+    },
+}
+
+/// Options of the commands sending a transaction, to process it in another way than signing and
+/// executing it.
+#[derive(Args, Debug, Default, Clone, Copy)]
+pub struct Opts {
+    /// Dry run the transaction instead of executing it, showing its effects, gas cost and balance
+    /// changes
+    #[clap(long, conflicts_with = "serialize_unsigned")]
+    pub dry_run: bool,
+    /// Do not sign or execute the transaction, output its Base64 encoded unsigned transaction data
+    /// instead, to be signed elsewhere and executed with `execute-signed-tx`. `--serialize-output`
+    /// is a deprecated alias of this flag.
+    #[clap(long, alias = "serialize-output")]
+    pub serialize_unsigned: bool,
 }
 
 impl SuiClientCommands {
@@ -493,6 +622,7 @@ impl SuiClientCommands {
                 gas_budget,
                 skip_dependency_verification,
                 with_unpublished_dependencies,
+                opts,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -518,28 +648,7 @@ impl SuiClientCommands {
                     ),
                 })?;
 
-                let resp = context
-                    .get_client()
-                    .await?
-                    .read_api()
-                    .get_object_with_options(
-                        upgrade_capability,
-                        SuiObjectDataOptions::default().with_bcs().with_owner(),
-                    )
-                    .await?;
-
-                let Some(data) = resp.data else {
-                    return Err(anyhow!("Could not find upgrade capability at {upgrade_capability}"))
-                };
-
-                let upgrade_cap: UpgradeCap = data
-                    .bcs
-                    .ok_or_else(|| {
-                        anyhow!("Fetch upgrade capability object but no data was returned")
-                    })?
-                    .try_as_move()
-                    .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
-                    .deserialize()?;
+                let upgrade_cap = get_upgrade_cap(&client, upgrade_capability).await?;
                 // We keep the existing policy -- no fancy policies or changing the upgrade
                 // policy at the moment. To change the policy you can call a Move function in the
                 // `package` module to change this policy.
@@ -561,6 +670,9 @@ impl SuiClientCommands {
                         gas_budget,
                     )
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature = context.config.keystore.sign_secure(
                     &sender,
                     &data,
@@ -582,7 +694,7 @@ impl SuiClientCommands {
                 gas_budget,
                 skip_dependency_verification,
                 with_unpublished_dependencies,
-                serialize_output,
+                opts,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
@@ -607,12 +719,9 @@ impl SuiClientCommands {
                         gas_budget,
                     )
                     .await?;
-                if serialize_output {
-                    return Ok(SuiClientCommandResult::SerializePublish(Base64::encode(
-                        bcs::to_bytes(&data).unwrap(),
-                    )));
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
                 }
-
                 let signature = context.config.keystore.sign_secure(
                     &sender,
                     &data,
@@ -675,12 +784,43 @@ impl SuiClientCommands {
                 gas,
                 gas_budget,
                 args,
+                opts,
             } => {
-                let response = call_move(
-                    package, &module, &function, type_args, gas, gas_budget, args, context,
+                call_move(
+                    package, &module, &function, type_args, gas, gas_budget, args, opts, context,
                 )
-                .await?;
-                SuiClientCommandResult::Call(response)
+                .await?
+            }
+
+            SuiClientCommands::Ptb {
+                commands,
+                gas_budget,
+                build_config,
+                opts,
+            } => {
+                let sender = context.active_address()?;
+                let client = context.get_client().await?;
+                let mut builder = client.ptb_builder(sender);
+                if let Some(gas_budget) = gas_budget {
+                    builder.gas_budget(gas_budget);
+                }
+                add_ptb_commands(&client, &mut builder, commands, build_config).await?;
+                let data = builder.build().await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
+                let signature = context.config.keystore.sign_secure(
+                    &sender,
+                    &data,
+                    Intent::sui_transaction(),
+                )?;
+                let response = context
+                    .execute_transaction_block(
+                        Transaction::from_data(data, Intent::sui_transaction(), vec![signature])
+                            .verify()?,
+                    )
+                    .await?;
+                SuiClientCommandResult::Ptb(response)
             }
 
             SuiClientCommands::Transfer {
@@ -688,7 +828,9 @@ impl SuiClientCommands {
                 object_id,
                 gas,
                 gas_budget,
+                opts,
             } => {
+                let to = context.config.keystore.get_address_by_identity(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let time_start = Instant::now();
 
@@ -697,6 +839,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature =
                     context
                         .config
@@ -726,7 +871,9 @@ impl SuiClientCommands {
                 sui_coin_object_id: object_id,
                 gas_budget,
                 amount,
+                opts,
             } => {
+                let to = context.config.keystore.get_address_by_identity(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
 
                 let client = context.get_client().await?;
@@ -734,6 +881,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .transfer_sui(from, object_id, gas_budget, to, amount)
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature =
                     context
                         .config
@@ -760,6 +910,7 @@ impl SuiClientCommands {
                 amounts,
                 gas,
                 gas_budget,
+                opts,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -777,12 +928,19 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = recipients
+                    .iter()
+                    .map(|identity| context.config.keystore.get_address_by_identity(identity))
+                    .collect::<Result<Vec<_>, _>>()?;
                 let from = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
                     .transaction_builder()
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature =
                     context
                         .config
@@ -811,6 +969,7 @@ impl SuiClientCommands {
                 recipients,
                 amounts,
                 gas_budget,
+                opts,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
@@ -828,12 +987,19 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = recipients
+                    .iter()
+                    .map(|identity| context.config.keystore.get_address_by_identity(identity))
+                    .collect::<Result<Vec<_>, _>>()?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
                     .transaction_builder()
                     .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -861,11 +1027,16 @@ impl SuiClientCommands {
                 input_coins,
                 recipient,
                 gas_budget,
+                opts,
             } => {
                 ensure!(
                     !input_coins.is_empty(),
                     "PayAllSui transaction requires a non-empty list of input coins"
                 );
+                let recipient = context
+                    .config
+                    .keystore
+                    .get_address_by_identity(&recipient)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -873,6 +1044,9 @@ impl SuiClientCommands {
                     .pay_all_sui(signer, input_coins, recipient, gas_budget)
                     .await?;
 
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -896,13 +1070,20 @@ impl SuiClientCommands {
                 SuiClientCommandResult::PayAllSui(response)
             }
 
-            SuiClientCommands::Addresses => SuiClientCommandResult::Addresses(
-                context.config.keystore.addresses(),
-                context.active_address().ok(),
-            ),
+            SuiClientCommands::Addresses => {
+                let keystore = &context.config.keystore;
+                let addresses = keystore.try_addresses()?;
+                let aliases = addresses
+                    .iter()
+                    .filter_map(|address| {
+                        Some((*address, keystore.get_alias(address)?.to_string()))
+                    })
+                    .collect();
+                SuiClientCommandResult::Addresses(addresses, context.active_address().ok(), aliases)
+            }
 
             SuiClientCommands::Objects { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = context.get_identity_address(address)?;
                 let client = context.get_client().await?;
                 let mut objects: Vec<SuiObjectResponse> = Vec::new();
                 let mut cursor = None;
@@ -933,16 +1114,26 @@ impl SuiClientCommands {
                 key_scheme,
                 derivation_path,
                 word_length,
+                alias,
             } => {
-                let (address, phrase, scheme) = context.config.keystore.generate_and_add_new_key(
-                    key_scheme,
-                    derivation_path,
-                    word_length,
-                )?;
+                let keystore = &mut context.config.keystore;
+                if let Some(alias) = &alias {
+                    validate_alias(alias)?;
+                    let identity = KeyIdentity::Alias(alias.clone());
+                    ensure!(
+                        keystore.get_address_by_identity(&identity).is_err(),
+                        "Alias [{alias}] is already used"
+                    );
+                }
+                let (address, phrase, scheme) =
+                    keystore.generate_and_add_new_key(key_scheme, derivation_path, word_length)?;
+                if alias.is_some() {
+                    keystore.set_alias(&address, alias)?;
+                }
                 SuiClientCommandResult::NewAddress((address, phrase, scheme))
             }
             SuiClientCommands::Gas { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = context.get_identity_address(address)?;
                 let coins = context
                     .gas_objects(address)
                     .await?
@@ -958,6 +1149,7 @@ impl SuiClientCommands {
                 count,
                 gas,
                 gas_budget,
+                opts,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
                 let client = context.get_client().await?;
@@ -981,6 +1173,9 @@ impl SuiClientCommands {
                         return Err(anyhow!("Exactly one of `count` and `amounts` must be present for split-coin command."));
                     }
                 };
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -999,6 +1194,7 @@ impl SuiClientCommands {
                 coin_to_merge,
                 gas,
                 gas_budget,
+                opts,
             } => {
                 let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
@@ -1006,6 +1202,9 @@ impl SuiClientCommands {
                     .transaction_builder()
                     .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
                     .await?;
+                if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+                    return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+                }
                 let signature = context.config.keystore.sign_secure(
                     &signer,
                     &data,
@@ -1021,12 +1220,15 @@ impl SuiClientCommands {
                 SuiClientCommandResult::MergeCoin(response)
             }
             SuiClientCommands::Switch { address, env } => {
+                let address = address
+                    .map(|identity| context.config.keystore.get_address_by_identity(&identity))
+                    .transpose()?;
                 match (address, &env) {
                     (None, Some(env)) => {
                         Self::switch_env(&mut context.config, env)?;
                     }
                     (Some(addr), None) => {
-                        if !context.config.keystore.addresses().contains(&addr) {
+                        if !context.config.keystore.try_addresses()?.contains(&addr) {
                             return Err(anyhow!("Address {} not managed by wallet", addr));
                         }
                         context.config.active_address = Some(addr);
@@ -1046,6 +1248,7 @@ impl SuiClientCommands {
                 gas_budget,
                 amount,
             } => {
+                let to = context.config.keystore.get_address_by_identity(&to)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -1087,6 +1290,47 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_block(verified).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
+            SuiClientCommands::ExecuteMultiSigRequest { file } => {
+                let (data, multisig) = MultiSigRequest::read(&file)?.combine()?;
+                let verified = Transaction::from_generic_sig_data(
+                    data,
+                    Intent::sui_transaction(),
+                    vec![multisig],
+                )
+                .verify()?;
+                let response = context.execute_transaction_block(verified).await?;
+                SuiClientCommandResult::ExecuteSignedTx(response)
+            }
+            SuiClientCommands::Replay {
+                digest,
+                protocol_version,
+                verbose,
+                local_package,
+                package_id,
+                build_config,
+            } => {
+                let local_package = match (local_package, package_id) {
+                    (Some(package_path), Some(package_id)) => {
+                        let build_config =
+                            resolve_lock_file_path(build_config, Some(package_path.clone()))?;
+                        let compiled_package = build_move_package(
+                            &package_path,
+                            BuildConfig {
+                                config: build_config,
+                                run_bytecode_verifier: true,
+                                print_diags_to_stderr: true,
+                            },
+                        )?;
+                        Some((package_id, compiled_package))
+                    }
+                    _ => None,
+                };
+                let client = context.get_client().await?;
+                SuiClientCommandResult::Replay(
+                    replay_transaction(&client, digest, protocol_version, local_package, verbose)
+                        .await?,
+                )
+            }
             SuiClientCommands::NewEnv { alias, rpc, ws } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
@@ -1101,6 +1345,47 @@ impl SuiClientCommands {
                 context.config.save()?;
                 SuiClientCommandResult::NewEnv(env)
             }
+            SuiClientCommands::UseRemoteSigner { endpoint } => {
+                let keystore = RemoteKeystore::new(endpoint);
+                let addresses: Vec<SuiAddress> = keystore
+                    .fetch_keys()?
+                    .iter()
+                    .map(SuiAddress::from)
+                    .collect();
+                ensure!(
+                    !addresses.is_empty(),
+                    "Remote signer {} has no keys",
+                    keystore.endpoint()
+                );
+                let active_address = context.config.active_address;
+                if !active_address.map_or(false, |address| addresses.contains(&address)) {
+                    context.config.active_address = Some(addresses[0]);
+                }
+                let previous_keystore = context.config.keystore.path().map(Path::to_path_buf);
+                context.config.keystore = Keystore::from(keystore);
+                context.config.save()?;
+                SuiClientCommandResult::UseRemoteSigner(
+                    addresses,
+                    context.config.active_address,
+                    previous_keystore,
+                )
+            }
+            SuiClientCommands::UseKeystore { path } => {
+                ensure!(
+                    path.exists(),
+                    "Keystore file {} does not exist",
+                    path.display()
+                );
+                let keystore = Keystore::from_path(&path)?;
+                let addresses = keystore.addresses();
+                let active_address = context.config.active_address;
+                if !active_address.map_or(false, |address| addresses.contains(&address)) {
+                    context.config.active_address = addresses.first().copied();
+                }
+                context.config.keystore = keystore;
+                context.config.save()?;
+                SuiClientCommandResult::UseKeystore(path, addresses, context.config.active_address)
+            }
             SuiClientCommands::ActiveEnv => {
                 SuiClientCommandResult::ActiveEnv(context.config.active_env.clone())
             }
@@ -1135,7 +1420,7 @@ impl SuiClientCommands {
 
                 let client = context.get_client().await?;
 
-                BytecodeSourceVerifier::new(client.read_api())
+                let report = match BytecodeSourceVerifier::new(client.read_api())
                     .verify_package(
                         &compiled_package,
                         verify_deps,
@@ -1145,9 +1430,17 @@ impl SuiClientCommands {
                             (false, Some(addr)) => SourceMode::VerifyAt(addr.into()),
                         },
                     )
-                    .await?;
+                    .await
+                {
+                    Ok(report) => report,
+                    Err(err) => {
+                        // Still show what did verify, next to what did not.
+                        eprint!("{}", err.report);
+                        return Err(err.into());
+                    }
+                };
 
-                SuiClientCommandResult::VerifySource
+                SuiClientCommandResult::VerifySource(report)
             }
         });
         ret
@@ -1161,7 +1454,31 @@ impl SuiClientCommands {
     }
 }
 
-async fn compile_package(
+pub(crate) async fn get_upgrade_cap(
+    client: &SuiClient,
+    upgrade_capability: ObjectID,
+) -> Result<UpgradeCap, anyhow::Error> {
+    let resp = client
+        .read_api()
+        .get_object_with_options(
+            upgrade_capability,
+            SuiObjectDataOptions::default().with_bcs().with_owner(),
+        )
+        .await?;
+
+    let Some(data) = resp.data else {
+        return Err(anyhow!("Could not find upgrade capability at {upgrade_capability}"))
+    };
+
+    Ok(data
+        .bcs
+        .ok_or_else(|| anyhow!("Fetch upgrade capability object but no data was returned"))?
+        .try_as_move()
+        .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
+        .deserialize()?)
+}
+
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1276,21 +1593,26 @@ impl WalletContext {
     }
 
     pub fn active_address(&mut self) -> Result<SuiAddress, anyhow::Error> {
-        if self.config.keystore.addresses().is_empty() {
+        let addresses = self.config.keystore.try_addresses()?;
+        let Some(first) = addresses.first() else {
             return Err(anyhow!(
                 "No managed addresses. Create new address with `new-address` command."
             ));
-        }
+        };
 
-        // Ok to unwrap because we checked that config addresses not empty
         // Set it if not exists
-        self.config.active_address = Some(
-            self.config
-                .active_address
-                .unwrap_or(*self.config.keystore.addresses().get(0).unwrap()),
-        );
+        Ok(*self.config.active_address.get_or_insert(*first))
+    }
 
-        Ok(self.config.active_address.unwrap())
+    /// Returns the address of the given address or alias, or the active address if None.
+    pub fn get_identity_address(
+        &mut self,
+        input: Option<KeyIdentity>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        match input {
+            Some(identity) => self.config.keystore.get_address_by_identity(&identity),
+            None => self.active_address(),
+        }
     }
 
     /// Get the latest object reference given a object id
@@ -1397,6 +1719,29 @@ impl WalletContext {
         Ok(gas_price)
     }
 
+    /// Dry runs or serializes the transaction if asked to by `opts`, returns `None` if it is to be
+    /// signed and executed.
+    pub async fn dry_run_or_serialize(
+        &self,
+        data: &TransactionData,
+        opts: Opts,
+    ) -> anyhow::Result<Option<UnexecutedTransaction>> {
+        if opts.serialize_unsigned {
+            return Ok(Some(UnexecutedTransaction::SerializedUnsigned(
+                Base64::encode(bcs::to_bytes(data)?),
+            )));
+        }
+        if opts.dry_run {
+            let client = self.get_client().await?;
+            let response = client
+                .read_api()
+                .dry_run_transaction_block(data.clone())
+                .await?;
+            return Ok(Some(UnexecutedTransaction::DryRun(response)));
+        }
+        Ok(None)
+    }
+
     pub async fn execute_transaction_block(
         &self,
         tx: VerifiedTransaction,
@@ -1455,7 +1800,7 @@ impl Display for SuiClientCommandResult {
                 };
                 writeln!(writer, "{}", raw_object)?;
             }
-            SuiClientCommandResult::Call(response) => {
+            SuiClientCommandResult::Call(response) | SuiClientCommandResult::Ptb(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
             SuiClientCommandResult::Transfer(time_elapsed, response) => {
@@ -1474,13 +1819,17 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::PayAllSui(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
-            SuiClientCommandResult::Addresses(addresses, active_address) => {
+            SuiClientCommandResult::Addresses(addresses, active_address, aliases) => {
                 writeln!(writer, "Showing {} results.", addresses.len())?;
                 for address in addresses {
+                    let alias = aliases
+                        .get(address)
+                        .map(|alias| format!(" ({alias})"))
+                        .unwrap_or_default();
                     if *active_address == Some(*address) {
-                        writeln!(writer, "{} <=", address)?;
+                        writeln!(writer, "{address}{alias} <=")?;
                     } else {
-                        writeln!(writer, "{}", address)?;
+                        writeln!(writer, "{address}{alias}")?;
                     }
                 }
             }
@@ -1591,8 +1940,11 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::SerializeTransferSui(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
-            SuiClientCommandResult::SerializePublish(data) => {
-                writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
+            SuiClientCommandResult::Replay(result) => {
+                write!(writer, "{}", result)?;
+            }
+            SuiClientCommandResult::Unexecuted(unexecuted) => {
+                write!(writer, "{}", unexecuted)?;
             }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
@@ -1600,6 +1952,30 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::NewEnv(env) => {
                 writeln!(writer, "Added new Sui env [{}] to config.", env.alias)?;
             }
+            SuiClientCommandResult::UseRemoteSigner(addresses, active_address, previous) => {
+                writeln!(
+                    writer,
+                    "Signing with the {} keys of the remote signer.",
+                    addresses.len()
+                )?;
+                write_addresses(&mut writer, addresses, active_address)?;
+                if let Some(previous) = previous {
+                    writeln!(
+                        writer,
+                        "Run `sui client use-keystore {}` to sign with the previous keystore again.",
+                        previous.display()
+                    )?;
+                }
+            }
+            SuiClientCommandResult::UseKeystore(path, addresses, active_address) => {
+                writeln!(
+                    writer,
+                    "Signing with the {} keys of the keystore {}.",
+                    addresses.len(),
+                    path.display()
+                )?;
+                write_addresses(&mut writer, addresses, active_address)?;
+            }
             SuiClientCommandResult::Envs(envs, active) => {
                 for env in envs {
                     write!(writer, "{} => {}", env.alias, env.rpc)?;
@@ -1609,7 +1985,8 @@ impl Display for SuiClientCommandResult {
                     writeln!(writer)?;
                 }
             }
-            SuiClientCommandResult::VerifySource => {
+            SuiClientCommandResult::VerifySource(report) => {
+                write!(writer, "{report}")?;
                 writeln!(writer, "Source verification succeeded!")?;
             }
         }
@@ -1625,8 +2002,9 @@ pub async fn call_move(
     gas: Option<ObjectID>,
     gas_budget: u64,
     args: Vec<SuiJsonValue>,
+    opts: Opts,
     context: &mut WalletContext,
-) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
+) -> Result<SuiClientCommandResult, anyhow::Error> {
     // Convert all numeric input to String, this will allow number input from the CLI without failing SuiJSON's checks.
     let args = args
         .into_iter()
@@ -1653,6 +2031,9 @@ pub async fn call_move(
             gas_budget,
         )
         .await?;
+    if let Some(unexecuted) = context.dry_run_or_serialize(&data, opts).await? {
+        return Ok(SuiClientCommandResult::Unexecuted(unexecuted));
+    }
     let signature =
         context
             .config
@@ -1669,7 +2050,7 @@ pub async fn call_move(
     if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
         return Err(anyhow!("Error calling module: {:#?}", effects.status()));
     }
-    Ok(response)
+    Ok(SuiClientCommandResult::Call(response))
 }
 
 fn convert_number_to_string(value: Value) -> Value {
@@ -1736,6 +2117,22 @@ impl Debug for SuiClientCommandResult {
     }
 }
 
+/// Writes one address per line, marking the active address.
+fn write_addresses(
+    writer: &mut String,
+    addresses: &[SuiAddress],
+    active_address: &Option<SuiAddress>,
+) -> std::fmt::Result {
+    for address in addresses {
+        if *active_address == Some(*address) {
+            writeln!(writer, "{address} <=")?;
+        } else {
+            writeln!(writer, "{address}")?;
+        }
+    }
+    Ok(())
+}
+
 fn unwrap_err_to_string<T: Display, F: FnOnce() -> Result<T, anyhow::Error>>(func: F) -> String {
     match func() {
         Ok(s) => format!("{s}"),
@@ -1764,11 +2161,12 @@ impl SuiClientCommandResult {
 pub enum SuiClientCommandResult {
     Upgrade(SuiTransactionBlockResponse),
     Publish(SuiTransactionBlockResponse),
-    VerifySource,
+    VerifySource(VerificationReport),
     Object(SuiObjectResponse),
     RawObject(SuiObjectResponse),
     TransactionBlock(SuiTransactionBlockResponse),
     Call(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
     Transfer(
         // Skipping serialisation for elapsed time.
         #[serde(skip)] u128,
@@ -1778,7 +2176,12 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionBlockResponse),
     PaySui(SuiTransactionBlockResponse),
     PayAllSui(SuiTransactionBlockResponse),
-    Addresses(Vec<SuiAddress>, Option<SuiAddress>),
+    /// Addresses, the active address and the aliases of the addresses having one.
+    Addresses(
+        Vec<SuiAddress>,
+        Option<SuiAddress>,
+        BTreeMap<SuiAddress, String>,
+    ),
     Objects(Vec<SuiObjectResponse>),
     DynamicFieldQuery(DynamicFieldPage),
     SyncClientState,
@@ -1791,9 +2194,45 @@ pub enum SuiClientCommandResult {
     ActiveEnv(Option<String>),
     Envs(Vec<SuiEnv>, Option<String>),
     SerializeTransferSui(String),
-    SerializePublish(String),
+    Unexecuted(UnexecutedTransaction),
     ExecuteSignedTx(SuiTransactionBlockResponse),
+    Replay(ReplayResult),
     NewEnv(SuiEnv),
+    /// Addresses of the keys of the remote signer, the active address, and the path of the
+    /// keystore file that was replaced.
+    UseRemoteSigner(Vec<SuiAddress>, Option<SuiAddress>, Option<PathBuf>),
+    /// Path of the keystore file, addresses of its keys and the active address.
+    UseKeystore(PathBuf, Vec<SuiAddress>, Option<SuiAddress>),
+}
+
+/// A transaction that was dry run or serialized instead of being executed, see [Opts].
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum UnexecutedTransaction {
+    DryRun(DryRunTransactionBlockResponse),
+    SerializedUnsigned(String),
+}
+
+impl Display for UnexecutedTransaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        match self {
+            UnexecutedTransaction::DryRun(response) => {
+                writeln!(writer, "{}", "----- Dry Run Effects ----".bold())?;
+                writeln!(writer, "{}", response.effects)?;
+                let gas_cost = response.effects.gas_cost_summary();
+                writeln!(writer, "{}", "----- Gas Cost ----".bold())?;
+                writeln!(writer, "{}", gas_cost)?;
+                writeln!(writer, "Net gas usage: {}", gas_cost.net_gas_usage())?;
+                writeln!(writer, "{}", "----- Balance Changes ----".bold())?;
+                writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+            }
+            UnexecutedTransaction::SerializedUnsigned(data) => {
+                writeln!(writer, "Raw tx_bytes to sign: {}", data)?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -0,0 +1,513 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands of the programmable transaction blocks built by `sui client ptb`.
+//!
+//! Each command is given as one string, e.g. `split-coins gas [1000,2000]`. Arguments are
+//! separated by spaces and are one of:
+//! - `gas`: the gas coin.
+//! - `@<i>` or `@<i>.<j>`: the result, or the j-th result, of the i-th command of the block.
+//! - `0x..`: an object ID or an address.
+//! - a number, `true`, `false` or a double quoted string.
+//! - `[<arg>,..]`: a vector of arguments.
+//!
+//! `publish <path>` and `upgrade <path> <cap>` build the package at `path`, with the build options
+//! of the block. The upgrade capability returned by `publish` must be used by a later command,
+//! e.g. transferred. `upgrade` commits the upgrade itself and has no result, nor have
+//! `merge-coins` and `transfer-objects`.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::TypeTag;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde_json::Value;
+use sui_framework_build::compiled_package::PublishedAtError;
+use sui_json::SuiJsonValue;
+use sui_sdk::ptb_builder::{MoveCallArg, PtbBuilder};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{Argument, Command, ProgrammableMoveCall};
+use sui_types::move_package::PACKAGE_MODULE_NAME;
+use sui_types::{parse_sui_type_tag, SUI_FRAMEWORK_OBJECT_ID};
+
+use crate::client_commands::{compile_package, get_upgrade_cap};
+
+#[cfg(test)]
+#[path = "unit_tests/client_ptb_tests.rs"]
+mod client_ptb_tests;
+
+/// Argument of a [PtbCommand].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtbArg {
+    Gas,
+    Result(u16),
+    NestedResult(u16, u16),
+    Id(ObjectID),
+    Number(String),
+    Bool(bool),
+    String(String),
+    Vector(Vec<PtbArg>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtbCommand {
+    SplitCoins {
+        coin: PtbArg,
+        amounts: Vec<PtbArg>,
+    },
+    MergeCoins {
+        coin: PtbArg,
+        coins: Vec<PtbArg>,
+    },
+    TransferObjects {
+        objects: Vec<PtbArg>,
+        recipient: PtbArg,
+    },
+    MakeMoveVec {
+        type_: Option<TypeTag>,
+        elements: Vec<PtbArg>,
+    },
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbArg>,
+    },
+    Publish {
+        package_path: PathBuf,
+    },
+    Upgrade {
+        package_path: PathBuf,
+        upgrade_capability: ObjectID,
+    },
+}
+
+impl FromStr for PtbArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "gas" {
+            return Ok(Self::Gas);
+        }
+        if s == "true" || s == "false" {
+            return Ok(Self::Bool(s == "true"));
+        }
+        if let Some(result) = s.strip_prefix('@') {
+            return Ok(match result.split_once('.') {
+                Some((i, j)) => Self::NestedResult(parse_index(i)?, parse_index(j)?),
+                None => Self::Result(parse_index(result)?),
+            });
+        }
+        if let Some(elements) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return Ok(Self::Vector(
+                split_top_level(elements, ',')?
+                    .iter()
+                    .map(|element| element.parse())
+                    .collect::<Result<_, _>>()?,
+            ));
+        }
+        if let Some(string) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+            return Ok(Self::String(string.to_string()));
+        }
+        if s.starts_with("0x") {
+            return Ok(Self::Id(
+                ObjectID::from_str(s).map_err(|e| anyhow!("Invalid ID {s}: {e}"))?,
+            ));
+        }
+        if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(Self::Number(s.to_string()));
+        }
+        bail!("Invalid argument [{s}]")
+    }
+}
+
+impl Display for PtbArg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gas => write!(f, "gas"),
+            Self::Result(i) => write!(f, "@{i}"),
+            Self::NestedResult(i, j) => write!(f, "@{i}.{j}"),
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::String(s) => write!(f, "\"{s}\""),
+            Self::Vector(elements) => {
+                let elements: Vec<_> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(","))
+            }
+        }
+    }
+}
+
+impl FromStr for PtbCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = split_top_level(s, ' ')?;
+        let Some((name, args)) = tokens.split_first() else {
+            bail!("Empty command");
+        };
+        let arity = |expected: usize| {
+            ensure!(
+                args.len() == expected,
+                "Command {name} takes {expected} arguments, {} given",
+                args.len()
+            );
+            Ok(())
+        };
+        Ok(match name.as_str() {
+            "split-coins" => {
+                arity(2)?;
+                Self::SplitCoins {
+                    coin: args[0].parse()?,
+                    amounts: parse_vector(&args[1])?,
+                }
+            }
+            "merge-coins" => {
+                arity(2)?;
+                Self::MergeCoins {
+                    coin: args[0].parse()?,
+                    coins: parse_vector(&args[1])?,
+                }
+            }
+            "transfer-objects" => {
+                arity(2)?;
+                Self::TransferObjects {
+                    objects: parse_vector(&args[0])?,
+                    recipient: args[1].parse()?,
+                }
+            }
+            "make-move-vec" => match args {
+                [elements] => Self::MakeMoveVec {
+                    type_: None,
+                    elements: parse_vector(elements)?,
+                },
+                [type_, elements] => Self::MakeMoveVec {
+                    type_: Some(
+                        parse_type_args(type_)?
+                            .pop()
+                            .ok_or_else(|| anyhow!("Missing type"))?,
+                    ),
+                    elements: parse_vector(elements)?,
+                },
+                _ => bail!("Command {name} takes a type and a vector of elements"),
+            },
+            "move-call" => {
+                let Some((target, args)) = args.split_first() else {
+                    bail!("Command {name} takes a <package>::<module>::<function> target");
+                };
+                let [package, module, function] = target.split("::").collect::<Vec<_>>()[..] else {
+                    bail!("Invalid move call target {target}, expected <package>::<module>::<function>");
+                };
+                let (type_args, args) = match args.split_first() {
+                    Some((type_args, args)) if type_args.starts_with('<') => {
+                        (parse_type_args(type_args)?, args)
+                    }
+                    _ => (vec![], args),
+                };
+                Self::MoveCall {
+                    package: ObjectID::from_str(package)
+                        .map_err(|e| anyhow!("Invalid package ID {package}: {e}"))?,
+                    module: module.to_string(),
+                    function: function.to_string(),
+                    type_args,
+                    args: args
+                        .iter()
+                        .map(|arg| arg.parse())
+                        .collect::<Result<_, _>>()?,
+                }
+            }
+            "publish" => {
+                arity(1)?;
+                Self::Publish {
+                    package_path: PathBuf::from(&args[0]),
+                }
+            }
+            "upgrade" => {
+                arity(2)?;
+                let PtbArg::Id(upgrade_capability) = args[1].parse()? else {
+                    bail!("Invalid upgrade capability ID {}", args[1]);
+                };
+                Self::Upgrade {
+                    package_path: PathBuf::from(&args[0]),
+                    upgrade_capability,
+                }
+            }
+            _ => bail!(
+                "Unknown command {name}, expected one of split-coins, merge-coins, \
+                transfer-objects, make-move-vec, move-call, publish or upgrade"
+            ),
+        })
+    }
+}
+
+/// Adds the commands to the builder, resolving object IDs through the RPC.
+pub async fn add_ptb_commands(
+    client: &SuiClient,
+    builder: &mut PtbBuilder<'_>,
+    commands: Vec<PtbCommand>,
+    build_config: MoveBuildConfig,
+) -> Result<(), anyhow::Error> {
+    // Commands may add several commands to the block, results are referred to by their position
+    // in the list of commands given by the user.
+    let mut results = vec![];
+    for (i, command) in commands.into_iter().enumerate() {
+        let result = add_ptb_command(client, builder, &results, command, &build_config)
+            .await
+            .map_err(|e| anyhow!("Command {i} failed: {e}"))?;
+        results.push(result);
+    }
+    Ok(())
+}
+
+/// Adds the commands implementing `command` to the block, returning its result if it has one.
+async fn add_ptb_command(
+    client: &SuiClient,
+    builder: &mut PtbBuilder<'_>,
+    results: &[Option<Argument>],
+    command: PtbCommand,
+    build_config: &MoveBuildConfig,
+) -> Result<Option<Argument>, anyhow::Error> {
+    Ok(Some(match command {
+        PtbCommand::SplitCoins { coin, amounts } => {
+            let coin = to_object_argument(builder, results, coin).await?;
+            let amounts = amounts.iter().map(to_u64).collect::<Result<Vec<_>, _>>()?;
+            builder.split_coins(coin, amounts)?
+        }
+        PtbCommand::MergeCoins { coin, coins } => {
+            let coin = to_object_argument(builder, results, coin).await?;
+            let mut arguments = vec![];
+            for coin in coins {
+                arguments.push(to_object_argument(builder, results, coin).await?);
+            }
+            builder.merge_coins(coin, arguments);
+            return Ok(None);
+        }
+        PtbCommand::TransferObjects { objects, recipient } => {
+            let PtbArg::Id(recipient) = recipient else {
+                bail!("Invalid recipient {recipient}, expected an address");
+            };
+            let mut arguments = vec![];
+            for object in objects {
+                arguments.push(to_object_argument(builder, results, object).await?);
+            }
+            builder.transfer_objects(arguments, SuiAddress::from(recipient))?;
+            return Ok(None);
+        }
+        PtbCommand::MakeMoveVec { type_, elements } => {
+            let mut arguments = vec![];
+            for element in elements {
+                arguments.push(match element {
+                    PtbArg::Number(_) => builder.pure(to_u64(&element)?)?,
+                    PtbArg::Bool(b) => builder.pure(b)?,
+                    element => to_object_argument(builder, results, element).await?,
+                });
+            }
+            builder.make_move_vec(type_, arguments)
+        }
+        PtbCommand::MoveCall {
+            package,
+            module,
+            function,
+            type_args,
+            args,
+        } => {
+            let args = args
+                .into_iter()
+                .map(|arg| to_move_call_arg(results, arg))
+                .collect::<Result<_, _>>()?;
+            builder
+                .move_call(package, &module, &function, type_args, args)
+                .await?
+        }
+        PtbCommand::Publish { package_path } => {
+            let (dependencies, modules, _, _) =
+                compile_package(client, build_config.clone(), package_path, false, false).await?;
+            builder.command(Command::Publish(
+                modules,
+                dependencies.published.into_values().collect(),
+            ))
+        }
+        PtbCommand::Upgrade {
+            package_path,
+            upgrade_capability,
+        } => {
+            let (dependencies, modules, compiled_package, package_id) =
+                compile_package(client, build_config.clone(), package_path, false, false).await?;
+            let package_id = package_id.map_err(|e| match e {
+                PublishedAtError::NotPresent => {
+                    anyhow!("No 'published-at' field in manifest for package to be upgraded.")
+                }
+                PublishedAtError::Invalid(v) => {
+                    anyhow!(
+                        "Invalid 'published-at' field in manifest of package to be upgraded: {v:?}"
+                    )
+                }
+            })?;
+            let policy = get_upgrade_cap(client, upgrade_capability).await?.policy;
+            let digest = compiled_package.get_package_digest(false).to_vec();
+
+            let cap = builder.object(upgrade_capability).await?;
+            let policy = builder.pure(policy)?;
+            let digest = builder.pure(digest)?;
+            let ticket = builder.command(package_move_call(
+                ident_str!("authorize_upgrade"),
+                vec![cap, policy, digest],
+            ));
+            let receipt = builder.command(Command::Upgrade(
+                modules,
+                dependencies.published.into_values().collect(),
+                package_id,
+                ticket,
+            ));
+            builder.command(package_move_call(
+                ident_str!("commit_upgrade"),
+                vec![cap, receipt],
+            ));
+            return Ok(None);
+        }
+    }))
+}
+
+fn package_move_call(function: &IdentStr, arguments: Vec<Argument>) -> Command {
+    Command::MoveCall(Box::new(ProgrammableMoveCall {
+        package: SUI_FRAMEWORK_OBJECT_ID,
+        module: PACKAGE_MODULE_NAME.to_owned(),
+        function: function.to_owned(),
+        type_arguments: vec![],
+        arguments,
+    }))
+}
+
+fn to_result_argument(
+    results: &[Option<Argument>],
+    arg: &PtbArg,
+) -> Result<Option<Argument>, anyhow::Error> {
+    let result = |i: u16| {
+        results
+            .get(i as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Result @{i} refers to a command that comes later"))?
+            .ok_or_else(|| anyhow!("Command {i} has no result"))
+    };
+    Ok(match arg {
+        PtbArg::Gas => Some(Argument::GasCoin),
+        PtbArg::Result(i) => Some(result(*i)?),
+        PtbArg::NestedResult(i, j) => match result(*i)? {
+            Argument::Result(command) => Some(Argument::NestedResult(command, *j)),
+            _ => bail!("Command {i} has no result @{i}.{j}"),
+        },
+        _ => None,
+    })
+}
+
+async fn to_object_argument(
+    builder: &mut PtbBuilder<'_>,
+    results: &[Option<Argument>],
+    arg: PtbArg,
+) -> Result<Argument, anyhow::Error> {
+    if let Some(argument) = to_result_argument(results, &arg)? {
+        return Ok(argument);
+    }
+    match arg {
+        PtbArg::Id(id) => builder.object(id).await,
+        arg => bail!("Invalid argument {arg}, expected an object"),
+    }
+}
+
+fn to_move_call_arg(
+    results: &[Option<Argument>],
+    arg: PtbArg,
+) -> Result<MoveCallArg, anyhow::Error> {
+    if let Some(argument) = to_result_argument(results, &arg)? {
+        return Ok(MoveCallArg::Argument(argument));
+    }
+    Ok(MoveCallArg::Json(SuiJsonValue::new(to_json(arg)?)?))
+}
+
+fn to_json(arg: PtbArg) -> Result<Value, anyhow::Error> {
+    Ok(match arg {
+        // Numbers are passed as strings to not fail SuiJSON's checks, as in `sui client call`.
+        PtbArg::Number(n) => Value::from(n),
+        PtbArg::Bool(b) => Value::from(b),
+        PtbArg::String(s) => Value::from(s),
+        PtbArg::Id(id) => Value::from(id.to_hex_literal()),
+        PtbArg::Vector(elements) => Value::Array(
+            elements
+                .into_iter()
+                .map(to_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        arg => bail!("Results cannot be nested in vectors, found {arg}"),
+    })
+}
+
+fn to_u64(arg: &PtbArg) -> Result<u64, anyhow::Error> {
+    match arg {
+        PtbArg::Number(n) => n.parse().map_err(|e| anyhow!("Invalid u64 {n}: {e}")),
+        arg => bail!("Invalid argument {arg}, expected a number"),
+    }
+}
+
+fn parse_index(s: &str) -> Result<u16, anyhow::Error> {
+    s.parse()
+        .map_err(|e| anyhow!("Invalid result index {s}: {e}"))
+}
+
+fn parse_vector(s: &str) -> Result<Vec<PtbArg>, anyhow::Error> {
+    match s.parse()? {
+        PtbArg::Vector(elements) => Ok(elements),
+        arg => bail!("Invalid argument {arg}, expected a vector"),
+    }
+}
+
+fn parse_type_args(s: &str) -> Result<Vec<TypeTag>, anyhow::Error> {
+    let type_args = s
+        .strip_prefix('<')
+        .and_then(|s| s.strip_suffix('>'))
+        .ok_or_else(|| anyhow!("Invalid type arguments {s}, expected <type,..>"))?;
+    split_top_level(type_args, ',')?
+        .iter()
+        .map(|type_| parse_sui_type_tag(type_))
+        .collect()
+}
+
+/// Splits `s` on `separator`, except within brackets, type arguments and strings. Empty parts are
+/// dropped.
+fn split_top_level(s: &str, separator: char) -> Result<Vec<String>, anyhow::Error> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    for c in s.chars() {
+        match c {
+            '"' => in_string = !in_string,
+            '[' | '<' if !in_string => depth += 1,
+            ']' | '>' if !in_string => depth -= 1,
+            c if c == separator && depth == 0 && !in_string => {
+                if !current.trim().is_empty() {
+                    parts.push(current.trim().to_string());
+                }
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        ensure!(depth >= 0, "Unbalanced brackets in {s}");
+        current.push(c);
+    }
+    ensure!(
+        depth == 0 && !in_string,
+        "Unbalanced brackets or quotes in {s}"
+    );
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    Ok(parts)
+}
@@ -1,26 +1,33 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
-use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding};
+use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding, Hex};
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::KeyPair;
 use shared_crypto::intent::{Intent, IntentMessage};
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use sui_keys::encryption::{
+    read_new_passphrase, SUI_KEYSTORE_NEW_PASSPHRASE_ENV, SUI_KEYSTORE_PASSPHRASE_ENV,
+};
 use sui_keys::key_derive::generate_new_key;
+use sui_keys::key_metadata::KeyIdentity;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{AccountKeystore, EncryptedFileKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
-use sui_types::crypto::{DefaultHash, PublicKey, Signature};
-use sui_types::messages::TransactionData;
-use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use sui_types::crypto::{DefaultHash, PublicKey, Signature, SuiSignature, ToFromBytes};
+use sui_types::messages::{Transaction, TransactionData, TransactionDataAPI};
+use sui_types::multisig::{MultiSig, MultiSigMember, MultiSigPublicKey, ThresholdUnit, WeightUnit};
 use sui_types::signature::GenericSignature;
+
+use crate::multisig_request::{is_signed, signed_weight, MultiSigRequest};
 use tracing::info;
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
@@ -56,9 +63,17 @@ pub enum KeyToolCommand {
     Unpack {
         keypair: SuiKeyPair,
     },
-    /// List all keys by its Sui address, Base64 encoded public key, key scheme name in
+    /// List all keys by its Sui address, Base64 encoded public key, key scheme name and alias in
     /// sui.keystore.
     List,
+
+    /// Set the alias of a key in sui.keystore, given its address or current alias. The alias can
+    /// be used instead of the address in `sui client` commands. Removes the alias if no new alias
+    /// is given.
+    UpdateAlias {
+        address: KeyIdentity,
+        new_alias: Option<String>,
+    },
     /// Create signature using the private key for for the given address in sui keystore.
     /// Any signature commits to a [struct IntentMessage] consisting of the Base64 encoded
     /// of the BCS serialized transaction bytes itself (the result of
@@ -73,6 +88,29 @@ pub enum KeyToolCommand {
         #[clap(long)]
         intent: Option<Intent>,
     },
+    /// Print the payload of a transaction for a signer outside of sui.keystore, e.g. an
+    /// air-gapped hardware wallet: the addresses required to sign, the hex encoded intent
+    /// message to display or encode as a QR code, and the digest of the intent message to sign.
+    /// Pass the Base64 encoded transaction bytes, e.g. from `--serialize-unsigned`.
+    SigningPayload {
+        #[clap(long)]
+        tx_bytes: String,
+    },
+
+    /// Verify the detached signatures of a signer outside of sui.keystore against the transaction
+    /// and the public keys they were made with, and print the transaction and its signatures
+    /// ready for `sui client execute-signed-tx`. Each signature is the raw signature of the
+    /// digest printed by `signing-payload`, hex or Base64 encoded, and is paired with the public
+    /// key `flag || pk` in Base64 at the same position.
+    AssembleSignedTx {
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        signatures: Vec<String>,
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        public_keys: Vec<PublicKey>,
+    },
+
     /// Add a new key to sui.key based on the input mnemonic phrase, the key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// and an optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or m/54'/784'/0'/0/0 for secp256k1
     /// or m/74'/784'/0'/0/0 for secp256r1. Supports mnemonic phrase of word length 12, 15, 18`, 21, 24.
@@ -94,12 +132,13 @@ pub enum KeyToolCommand {
     },
 
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
-    /// See `keytool list` for example public keys.
+    /// See `keytool list` for example public keys. A member can also be another MultiSig,
+    /// passed as the MultiSig public key printed by this command.
     MultiSigAddress {
         #[clap(long)]
         threshold: ThresholdUnit,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        pks: Vec<PublicKey>,
+        pks: Vec<MultiSigMember>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         weights: Vec<WeightUnit>,
     },
@@ -107,16 +146,79 @@ pub enum KeyToolCommand {
     /// Provides a list of signatures (`flag || sig || pk` encoded in Base64), threshold, a list of public keys.
     /// Returns a valid MultiSig and its sender address. The result can be used as signature field for `sui client execute-signed-tx`.
     /// The number of sigs must be greater than the threshold. The number of sigs must be smaller than the number of pks.
+    /// The signature of a nested MultiSig member is the serialized MultiSig combined for that member.
     MultiSigCombinePartialSig {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        sigs: Vec<Signature>,
+        sigs: Vec<GenericSignature>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
-        pks: Vec<PublicKey>,
+        pks: Vec<MultiSigMember>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         weights: Vec<WeightUnit>,
         #[clap(long)]
         threshold: ThresholdUnit,
     },
+
+    /// Create a file collecting the signatures of a MultiSig transaction from its signers, given
+    /// the Base64 encoded unsigned transaction bytes, e.g. from `--serialize-unsigned`, and the
+    /// public keys, weights and threshold of the MultiSig sending the transaction. The signers of
+    /// nested MultiSig members sign the same request file.
+    MultiSigRequestCreate {
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        pks: Vec<MultiSigMember>,
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        /// Path of the request file to create.
+        #[clap(long)]
+        file: PathBuf,
+    },
+
+    /// Sign the transaction of a MultiSig request file with the key of the given address or alias
+    /// in sui.keystore, and add the signature to the file.
+    MultiSigRequestSign {
+        #[clap(long)]
+        file: PathBuf,
+        #[clap(long)]
+        address: KeyIdentity,
+    },
+
+    /// Show the signers of a MultiSig request file and the weight of the signatures collected so
+    /// far.
+    MultiSigRequestStatus {
+        #[clap(long)]
+        file: PathBuf,
+    },
+
+    /// Combine the signatures of a MultiSig request file once their weight meets the threshold.
+    /// The result can be used with `sui client execute-signed-tx`, or the request file submitted
+    /// with `sui client execute-multi-sig-request`.
+    MultiSigRequestCombine {
+        #[clap(long)]
+        file: PathBuf,
+    },
+
+    /// Encrypt the plaintext sui.keystore in place under a passphrase, read from
+    /// `SUI_KEYSTORE_PASSPHRASE` or prompted for. The keystore then prompts for the
+    /// passphrase whenever it is opened.
+    Encrypt,
+
+    /// Re-encrypt the encrypted sui.keystore under a new passphrase, read from
+    /// `SUI_KEYSTORE_NEW_PASSPHRASE` or prompted for.
+    ChangePassphrase,
+
+    /// Print the keypair of the given address in sui.keystore as Base64 encoded
+    /// `flag || privkey`, e.g. to import it into a wallet. Asks for confirmation first
+    /// unless `--yes` is passed.
+    Export {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// Print the private key without asking for confirmation.
+        #[clap(short = 'y', long = "yes")]
+        yes: bool,
+    },
 }
 
 impl KeyToolCommand {
@@ -175,19 +277,29 @@ impl KeyToolCommand {
             }
             KeyToolCommand::List => {
                 println!(
-                    " {0: ^42} | {1: ^45} | {2: ^6}",
-                    "Sui Address", "Public Key (Base64)", "Scheme"
+                    " {0: ^66} | {1: ^45} | {2: ^9} | {3: ^15}",
+                    "Sui Address", "Public Key (Base64)", "Scheme", "Alias"
                 );
-                println!("{}", ["-"; 100].join(""));
+                println!("{}", ["-"; 144].join(""));
                 for pub_key in keystore.keys() {
+                    let address = Into::<SuiAddress>::into(&pub_key);
                     println!(
-                        " {0: ^42} | {1: ^45} | {2: ^6}",
-                        Into::<SuiAddress>::into(&pub_key),
+                        " {0: ^66} | {1: ^45} | {2: ^9} | {3: ^15}",
+                        address,
                         pub_key.encode_base64(),
-                        pub_key.scheme().to_string()
+                        pub_key.scheme().to_string(),
+                        keystore.get_alias(&address).unwrap_or_default()
                     );
                 }
             }
+            KeyToolCommand::UpdateAlias { address, new_alias } => {
+                let address = keystore.get_address_by_identity(&address)?;
+                keystore.set_alias(&address, new_alias.clone())?;
+                match new_alias {
+                    Some(alias) => println!("Alias of [{address}] set to [{alias}]"),
+                    None => println!("Alias of [{address}] removed"),
+                }
+            }
             KeyToolCommand::Sign {
                 address,
                 data,
@@ -257,14 +369,51 @@ impl KeyToolCommand {
                     }
                 }
             }
+            KeyToolCommand::SigningPayload { tx_bytes } => {
+                let intent_msg = IntentMessage::new(
+                    Intent::sui_transaction(),
+                    decode_transaction_data(&tx_bytes)?,
+                );
+                for signer in intent_msg.value.signers() {
+                    println!("Signer address: {signer}");
+                }
+                println!("Intent: {:?}", intent_msg.intent);
+                let intent_msg_bytes = bcs::to_bytes(&intent_msg)?;
+                println!("Intent message (hex): {}", Hex::encode(&intent_msg_bytes));
+                let mut hasher = DefaultHash::default();
+                hasher.update(&intent_msg_bytes);
+                let digest = hasher.finalize().digest;
+                println!("Digest to sign (hex): {}", Hex::encode(digest));
+                println!("Digest to sign (Base64): {}", Base64::encode(digest));
+            }
+            KeyToolCommand::AssembleSignedTx {
+                tx_bytes,
+                signatures,
+                public_keys,
+            } => {
+                let transaction =
+                    assemble_signed_transaction(&tx_bytes, &signatures, &public_keys)?;
+                println!("Raw tx_bytes to execute: {tx_bytes}");
+                for signature in &transaction.data().inner().tx_signatures {
+                    println!(
+                        "Serialized signature (`flag || sig || pk` in Base64): {}",
+                        signature.encode_base64()
+                    );
+                }
+            }
             KeyToolCommand::MultiSigAddress {
                 threshold,
                 pks,
                 weights,
             } => {
-                let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
-                let address: SuiAddress = multisig_pk.into();
+                let multisig_pk =
+                    MultiSigPublicKey::new_with_members(pks.clone(), weights.clone(), threshold)?;
+                let address: SuiAddress = multisig_pk.clone().into();
                 println!("MultiSig address: {address}");
+                println!(
+                    "MultiSig public key: {}",
+                    MultiSigMember::from(multisig_pk).encode_base64()
+                );
 
                 println!("Participating parties:");
                 println!(
@@ -275,7 +424,7 @@ impl KeyToolCommand {
                 for (pk, w) in pks.into_iter().zip(weights.into_iter()) {
                     println!(
                         " {0: ^42} | {1: ^45} | {2: ^6}",
-                        Into::<SuiAddress>::into(&pk),
+                        SuiAddress::from(&pk),
                         pk.encode_base64(),
                         w
                     );
@@ -287,14 +436,98 @@ impl KeyToolCommand {
                 weights,
                 threshold,
             } => {
-                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let multisig_pk = MultiSigPublicKey::new_with_members(pks, weights, threshold)?;
                 let address: SuiAddress = multisig_pk.clone().into();
-                let multisig = MultiSig::combine(sigs, multisig_pk)?;
+                let multisig = MultiSig::combine_generic(sigs, multisig_pk)?;
                 let generic_sig: GenericSignature = multisig.into();
                 println!("MultiSig address: {address}");
                 println!("MultiSig parsed: {:?}", generic_sig);
                 println!("MultiSig serialized: {:?}", generic_sig.encode_base64());
             }
+
+            KeyToolCommand::MultiSigRequestCreate {
+                tx_bytes,
+                pks,
+                weights,
+                threshold,
+                file,
+            } => {
+                ensure!(!file.exists(), "File {:?} already exists", file);
+                let multisig_pk = MultiSigPublicKey::new_with_members(pks, weights, threshold)?;
+                let request = MultiSigRequest::new(tx_bytes, multisig_pk)?;
+                request.write(&file)?;
+                println!("MultiSig request written to {:?}", file);
+                print_multisig_request_status(&request)?;
+            }
+            KeyToolCommand::MultiSigRequestSign { file, address } => {
+                let address = keystore.get_address_by_identity(&address)?;
+                let mut request = MultiSigRequest::read(&file)?;
+                request.sign(&*keystore, &address)?;
+                request.write(&file)?;
+                println!("Signature of {address} added to {:?}", file);
+                print_multisig_request_status(&request)?;
+            }
+            KeyToolCommand::MultiSigRequestStatus { file } => {
+                print_multisig_request_status(&MultiSigRequest::read(&file)?)?;
+            }
+            KeyToolCommand::MultiSigRequestCombine { file } => {
+                let request = MultiSigRequest::read(&file)?;
+                let (data, multisig) = request.combine()?;
+                println!("MultiSig address: {}", request.address());
+                println!(
+                    "Transaction bytes: {}",
+                    Base64::encode(bcs::to_bytes(&data)?)
+                );
+                println!("MultiSig serialized: {}", multisig.encode_base64());
+            }
+
+            KeyToolCommand::Encrypt => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only plaintext file keystores can be encrypted"));
+                };
+                let path = file
+                    .path()
+                    .ok_or_else(|| anyhow!("Keystore has no file path"))?
+                    .to_path_buf();
+                let passphrase = read_new_passphrase(SUI_KEYSTORE_PASSPHRASE_ENV)?;
+                *keystore = Keystore::from(EncryptedFileKeystore::encrypt_file_keystore(
+                    &path,
+                    &passphrase,
+                )?);
+                println!("Keystore {:?} encrypted", path);
+            }
+
+            KeyToolCommand::ChangePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!(
+                        "Keystore is not encrypted, use `sui keytool encrypt` to encrypt it"
+                    ));
+                };
+                encrypted
+                    .change_passphrase(&read_new_passphrase(SUI_KEYSTORE_NEW_PASSPHRASE_ENV)?)?;
+                println!("Keystore passphrase changed");
+            }
+
+            KeyToolCommand::Export { address, yes } => {
+                let keypair = keystore.get_key(&address)?;
+                if !yes {
+                    print!(
+                        "This prints the private key of {address} in plain text, anyone who sees it \
+                        controls the account. Continue [y/N]? "
+                    );
+                    let _ = io::stdout().flush();
+                    let mut line = String::new();
+                    io::stdin().read_line(&mut line)?;
+                    if line.trim().to_lowercase() != "y" {
+                        println!("Export cancelled");
+                        return Ok(());
+                    }
+                }
+                println!(
+                    "Keypair (`flag || privkey` in Base64): {}",
+                    keypair.encode_base64()
+                );
+            }
         }
 
         Ok(())
@@ -314,3 +547,98 @@ fn store_and_print_keypair(address: SuiAddress, keypair: SuiKeyPair) {
         path.to_str().unwrap()
     );
 }
+
+fn print_multisig_request_status(request: &MultiSigRequest) -> Result<(), anyhow::Error> {
+    let signers = request.signers()?;
+    println!("MultiSig address: {}", request.address());
+    println!(
+        " {0: ^66} | {1: ^45} | {2: ^6} | {3: ^6}",
+        "Sui Address", "Public Key (Base64)", "Weight", "Signed"
+    );
+    println!("{}", ["-"; 133].join(""));
+    print_multisig_members(&request.multisig_pk, &signers);
+    let weight = request.weight()?;
+    let threshold = request.multisig_pk.threshold();
+    println!("Signatures weight: {weight} / threshold: {threshold}");
+    if weight >= *threshold {
+        println!("The threshold is met, the signatures can be combined.");
+    }
+    Ok(())
+}
+
+/// Prints a row for each member of the MultiSig, followed by the rows of its members for a nested
+/// MultiSig member.
+fn print_multisig_members(multisig_pk: &MultiSigPublicKey, signers: &[PublicKey]) {
+    for (member, weight) in multisig_pk.pubkeys() {
+        let pk = match member {
+            MultiSigMember::PublicKey(pk) => pk.encode_base64(),
+            MultiSigMember::MultiSig(nested) => format!(
+                "MultiSig, weight {} / threshold {}",
+                signed_weight(nested, signers),
+                nested.threshold()
+            ),
+        };
+        println!(
+            " {0: ^66} | {1: ^45} | {2: ^6} | {3: ^6}",
+            SuiAddress::from(member),
+            pk,
+            weight,
+            if is_signed(member, signers) {
+                "yes"
+            } else {
+                "no"
+            }
+        );
+        if let MultiSigMember::MultiSig(nested) = member {
+            print_multisig_members(nested, signers);
+        }
+    }
+}
+
+fn decode_transaction_data(tx_bytes: &str) -> Result<TransactionData, anyhow::Error> {
+    let bytes = Base64::decode(tx_bytes)
+        .map_err(|e| anyhow!("Cannot deserialize data as TransactionData {:?}", e))?;
+    Ok(bcs::from_bytes(&bytes)?)
+}
+
+/// Decodes a detached signature, hex encoded with an optional `0x` prefix or Base64 encoded.
+fn decode_detached_signature(signature: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Hex::decode(signature.trim_start_matches("0x"))
+        .or_else(|_| Base64::decode(signature))
+        .map_err(|e| anyhow!("Signature {signature} is neither hex nor Base64: {e}"))
+}
+
+/// Assembles the transaction signed by the detached signatures of a signer outside of
+/// sui.keystore, each signature being verified against the public key at the same position.
+pub fn assemble_signed_transaction(
+    tx_bytes: &str,
+    signatures: &[String],
+    public_keys: &[PublicKey],
+) -> Result<Transaction, anyhow::Error> {
+    ensure!(
+        signatures.len() == public_keys.len(),
+        "Expected a public key for each of the {} signatures, got {}",
+        signatures.len(),
+        public_keys.len()
+    );
+    let intent_msg = IntentMessage::new(
+        Intent::sui_transaction(),
+        decode_transaction_data(tx_bytes)?,
+    );
+    let mut sigs = Vec::with_capacity(signatures.len());
+    for (signature, pk) in signatures.iter().zip(public_keys) {
+        let mut bytes = vec![pk.flag()];
+        bytes.extend_from_slice(&decode_detached_signature(signature)?);
+        bytes.extend_from_slice(pk.as_ref());
+        let signature = Signature::from_bytes(&bytes)
+            .map_err(|e| anyhow!("Invalid {:?} signature: {e}", pk.scheme()))?;
+        let address = SuiAddress::from(pk);
+        signature
+            .verify_secure(&intent_msg, address)
+            .map_err(|e| anyhow!("Signature does not verify for {address}: {e}"))?;
+        sigs.push(signature);
+    }
+    let transaction = Transaction::from_data(intent_msg.value, intent_msg.intent, sigs);
+    transaction.verify_signature()?;
+    Ok(transaction)
+}
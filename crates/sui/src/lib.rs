@@ -3,10 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod client_commands;
+pub mod client_ptb;
 pub mod config;
 pub mod console;
 pub mod fire_drill;
 pub mod keytool;
+pub mod multisig_request;
+pub mod replay;
 pub mod shell;
 pub mod sui_commands;
 pub mod validator_commands;
@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Files collecting the signatures of a MultiSig transaction from its signers. A request is
+//! created from the unsigned transaction bytes and the MultiSig public key, passed to each signer
+//! to add their signature with `sui keytool multi-sig-request-sign`, and combined into a MultiSig
+//! once the weight of the signatures meets the threshold. The signers of nested MultiSig members
+//! sign the same request, a nested MultiSig counting towards the threshold once the weight of its
+//! own signatures meets its threshold.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, ensure};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_keys::keystore::{write_atomically, AccountKeystore};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiSignature};
+use sui_types::messages::{TransactionData, TransactionDataAPI};
+use sui_types::multisig::{MultiSig, MultiSigMember, MultiSigPublicKey, ThresholdUnit};
+use sui_types::signature::GenericSignature;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigRequest {
+    /// Base64 encoded BCS bytes of the unsigned `TransactionData`.
+    pub tx_bytes: String,
+    pub multisig_pk: MultiSigPublicKey,
+    /// Base64 encoded `flag || sig || pk` signatures collected so far.
+    pub signatures: Vec<String>,
+}
+
+impl MultiSigRequest {
+    /// Creates a request without signatures, the transaction must be sent by the MultiSig address.
+    pub fn new(tx_bytes: String, multisig_pk: MultiSigPublicKey) -> Result<Self, anyhow::Error> {
+        let request = Self {
+            tx_bytes,
+            multisig_pk,
+            signatures: vec![],
+        };
+        let sender = request.transaction_data()?.sender();
+        ensure!(
+            sender == request.address(),
+            "Transaction sender {sender} is not the MultiSig address {}",
+            request.address()
+        );
+        Ok(request)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        serde_json::from_slice(&fs::read(path)?)
+            .map_err(|e| anyhow!("Can't deserialize MultiSig request from {:?}: {e}", path))
+    }
+
+    /// Writes the request through a temporary file, so signers updating the same request never
+    /// leave it truncated.
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        write_atomically(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    /// The MultiSig address sending the transaction.
+    pub fn address(&self) -> SuiAddress {
+        self.multisig_pk.clone().into()
+    }
+
+    pub fn transaction_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let bytes = Base64::decode(&self.tx_bytes)
+            .map_err(|e| anyhow!("Invalid transaction bytes: {e}"))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    pub fn signatures(&self) -> Result<Vec<Signature>, anyhow::Error> {
+        self.signatures
+            .iter()
+            .map(|s| Signature::decode_base64(s).map_err(|e| anyhow!("Invalid signature: {e}")))
+            .collect()
+    }
+
+    /// Public keys of the signers that signed the transaction.
+    pub fn signers(&self) -> Result<Vec<PublicKey>, anyhow::Error> {
+        self.signatures()?
+            .iter()
+            .map(|s| Ok(s.to_public_key()?))
+            .collect()
+    }
+
+    /// Adds the signature of one of the MultiSig public keys, or of the public keys of its nested
+    /// MultiSigs, after checking it signs the transaction.
+    pub fn add_signature(&mut self, signature: Signature) -> Result<(), anyhow::Error> {
+        let pk = signature.to_public_key()?;
+        ensure!(
+            is_member(&self.multisig_pk, &pk),
+            "Public key {} is not part of the MultiSig",
+            pk.encode_base64()
+        );
+        ensure!(
+            !self.signers()?.contains(&pk),
+            "Transaction is already signed by {}",
+            pk.encode_base64()
+        );
+        let intent_msg = IntentMessage::new(Intent::sui_transaction(), self.transaction_data()?);
+        signature
+            .verify_secure(&intent_msg, (&pk).into())
+            .map_err(|e| anyhow!("Invalid signature: {e}"))?;
+        self.signatures.push(signature.encode_base64());
+        Ok(())
+    }
+
+    /// Signs the transaction with the key of `address` in the keystore.
+    pub fn sign(
+        &mut self,
+        keystore: &impl AccountKeystore,
+        address: &SuiAddress,
+    ) -> Result<(), anyhow::Error> {
+        let signature = keystore.sign_secure(
+            address,
+            &self.transaction_data()?,
+            Intent::sui_transaction(),
+        )?;
+        self.add_signature(signature)
+    }
+
+    /// Total weight of the signatures collected so far.
+    pub fn weight(&self) -> Result<ThresholdUnit, anyhow::Error> {
+        Ok(signed_weight(&self.multisig_pk, &self.signers()?))
+    }
+
+    /// Combines the signatures into a MultiSig, once their weight meets the threshold.
+    pub fn combine(&self) -> Result<(TransactionData, GenericSignature), anyhow::Error> {
+        let weight = self.weight()?;
+        let threshold = *self.multisig_pk.threshold();
+        ensure!(
+            weight >= threshold,
+            "Signatures weight {weight} is below the threshold {threshold}"
+        );
+        let multisig = combine_signatures(&self.multisig_pk, &self.signatures()?)?;
+        Ok((self.transaction_data()?, multisig.into()))
+    }
+}
+
+/// Whether `pk` is a member of the MultiSig or of one of its nested MultiSigs.
+fn is_member(multisig_pk: &MultiSigPublicKey, pk: &PublicKey) -> bool {
+    multisig_pk
+        .pubkeys()
+        .iter()
+        .any(|(member, _)| match member {
+            MultiSigMember::PublicKey(member) => member == pk,
+            MultiSigMember::MultiSig(nested) => is_member(nested, pk),
+        })
+}
+
+/// Whether the member is signed, a nested MultiSig being signed once the weight of its signatures
+/// meets its threshold.
+pub fn is_signed(member: &MultiSigMember, signers: &[PublicKey]) -> bool {
+    match member {
+        MultiSigMember::PublicKey(pk) => signers.contains(pk),
+        MultiSigMember::MultiSig(nested) => signed_weight(nested, signers) >= *nested.threshold(),
+    }
+}
+
+/// Total weight of the signed members of the MultiSig.
+pub fn signed_weight(multisig_pk: &MultiSigPublicKey, signers: &[PublicKey]) -> ThresholdUnit {
+    multisig_pk
+        .pubkeys()
+        .iter()
+        .filter(|(member, _)| is_signed(member, signers))
+        .map(|(_, weight)| *weight as ThresholdUnit)
+        .sum()
+}
+
+/// Combines the signatures of the signed members into a MultiSig, nested MultiSigs being combined
+/// from the signatures of their own members.
+fn combine_signatures(
+    multisig_pk: &MultiSigPublicKey,
+    signatures: &[Signature],
+) -> Result<MultiSig, anyhow::Error> {
+    let signers = signatures
+        .iter()
+        .map(|s| s.to_public_key())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut sigs = vec![];
+    for (member, _) in multisig_pk.pubkeys() {
+        match member {
+            MultiSigMember::PublicKey(pk) => {
+                if let Some(i) = signers.iter().position(|signer| signer == pk) {
+                    sigs.push(signatures[i].clone().into());
+                }
+            }
+            MultiSigMember::MultiSig(nested) => {
+                if is_signed(member, &signers) {
+                    sigs.push(combine_signatures(nested, signatures)?.into());
+                }
+            }
+        }
+    }
+    Ok(MultiSig::combine_generic(sigs, multisig_pk.clone())?)
+}
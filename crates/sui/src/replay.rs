@@ -0,0 +1,865 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local re-execution of historical transactions. The transaction, the versions of its input
+//! objects it was executed against and the packages it depends on are fetched from a full node,
+//! the transaction is executed again by the local execution engine and the resulting effects are
+//! compared with the effects certified on-chain.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter, Write};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, ensure};
+use colored::Colorize;
+use fastcrypto::encoding::{Encoding, Hex};
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use serde::Serialize;
+use sui_adapter::{adapter, execution_engine, execution_mode};
+use sui_core::transaction_input_checker::check_objects;
+use sui_framework_build::compiled_package::CompiledPackage;
+use sui_json_rpc_types::{
+    SuiEvent, SuiObjectDataOptions, SuiPastObjectResponse, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockEvents, SuiTransactionBlockResponseOptions,
+};
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, VersionNumber};
+use sui_types::committee::EpochId;
+use sui_types::digests::TransactionDigest;
+use sui_types::epoch_data::EpochData;
+use sui_types::error::{SuiError, SuiObjectResponseError, SuiResult};
+use sui_types::gas::SuiGasStatus;
+use sui_types::in_memory_storage::InMemoryStorage;
+use sui_types::messages::{
+    GenesisObject, InputObjectKind, SenderSignedData, TransactionDataAPI, TransactionKind,
+};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::move_package::MovePackage;
+use sui_types::object::{Data, Object, OBJECT_START_VERSION};
+use sui_types::storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync};
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use sui_types::temporary_store::TemporaryStore;
+use sui_types::{MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_PACKAGE_ID};
+
+/// Result of the local re-execution of a transaction.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub digest: TransactionDigest,
+    pub protocol_version: u64,
+    /// Objects and packages loaded in the local store before execution.
+    pub objects: Vec<ObjectRef>,
+    pub on_chain_effects: SuiTransactionBlockEffects,
+    pub local_effects: SuiTransactionBlockEffects,
+    /// Trace of the commands of the local execution, empty if the execution failed or the
+    /// transaction is not programmable.
+    pub trace: Vec<CommandTrace>,
+    /// Events emitted by the local execution.
+    pub local_events: Vec<SuiEvent>,
+    /// Error of the local execution, if it failed.
+    pub execution_error: Option<String>,
+    /// Fields of the local effects that differ from the on-chain effects.
+    pub diffs: Vec<EffectsDiff>,
+    #[serde(skip)]
+    pub verbose: bool,
+}
+
+/// A command of the local execution, with the mutable references it updated and the values it
+/// returned.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTrace {
+    pub command: String,
+    /// The arguments borrowed mutably by the command, with their values once it returned.
+    pub mutable_reference_outputs: Vec<(String, TracedValue)>,
+    pub return_values: Vec<TracedValue>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TracedValue {
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Hex encoded BCS bytes of the value.
+    pub bcs: String,
+}
+
+impl TracedValue {
+    fn new(bytes: &[u8], type_tag: &TypeTag) -> Self {
+        Self {
+            type_: type_tag.to_string(),
+            bcs: Hex::encode(bytes),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectsDiff {
+    pub field: String,
+    pub on_chain: String,
+    pub local: String,
+}
+
+impl Display for ReplayResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            "Replayed transaction {} with protocol version {}",
+            self.digest, self.protocol_version
+        )?;
+        if self.verbose {
+            writeln!(writer, "{}", "----- Loaded Objects ----".bold())?;
+            for (id, version, digest) in &self.objects {
+                writeln!(writer, "{id} version {} digest {digest}", version.value())?;
+            }
+            writeln!(writer, "{}", "----- Execution Trace ----".bold())?;
+            for (i, command) in self.trace.iter().enumerate() {
+                writeln!(writer, "Command {i}: {}", command.command)?;
+                for (argument, value) in &command.mutable_reference_outputs {
+                    writeln!(
+                        writer,
+                        "  updated {argument}: {} = 0x{}",
+                        value.type_, value.bcs
+                    )?;
+                }
+                for (j, value) in command.return_values.iter().enumerate() {
+                    writeln!(
+                        writer,
+                        "  returned Result({i},{j}): {} = 0x{}",
+                        value.type_, value.bcs
+                    )?;
+                }
+            }
+            for event in &self.local_events {
+                writeln!(
+                    writer,
+                    "Event {} emitted by {}::{}: {}",
+                    event.type_, event.package_id, event.transaction_module, event.parsed_json
+                )?;
+            }
+            writeln!(writer, "{}", "----- Local Effects ----".bold())?;
+            writeln!(writer, "{}", self.local_effects)?;
+        }
+        if let Some(error) = &self.execution_error {
+            writeln!(writer, "Local execution error: {error}")?;
+        }
+        if self.diffs.is_empty() {
+            writeln!(
+                writer,
+                "{}",
+                "Local effects match the on-chain effects.".green()
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "{}",
+                "Local effects differ from the on-chain effects:".red()
+            )?;
+            for diff in &self.diffs {
+                writeln!(writer, "{}", diff.field.bold())?;
+                writeln!(writer, "  on-chain: {}", diff.on_chain)?;
+                writeln!(writer, "  local:    {}", diff.local)?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+/// Fetches the transaction `digest` and the objects it was executed against, executes it locally
+/// and compares the resulting effects with the on-chain effects. `local_package` replaces the
+/// modules of the on-chain package with the given ID by the modules of a locally built package,
+/// keeping its version and linkage, to test a fix against a historical transaction.
+pub async fn replay_transaction(
+    client: &SuiClient,
+    digest: TransactionDigest,
+    protocol_version: Option<u64>,
+    local_package: Option<(ObjectID, CompiledPackage)>,
+    verbose: bool,
+) -> Result<ReplayResult, anyhow::Error> {
+    let response = client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionBlockResponseOptions::new()
+                .with_raw_input()
+                .with_effects(),
+        )
+        .await?;
+    let on_chain_effects = response
+        .effects
+        .ok_or_else(|| anyhow!("Effects of transaction {digest} are not available"))?;
+    let checkpoint = response
+        .checkpoint
+        .ok_or_else(|| anyhow!("Transaction {digest} is not part of a checkpoint yet"))?;
+    ensure!(
+        !response.raw_transaction.is_empty(),
+        "Raw data of transaction {digest} is not available"
+    );
+    let sender_signed_data: SenderSignedData = bcs::from_bytes(&response.raw_transaction)?;
+    let tx_data = sender_signed_data.transaction_data().clone();
+
+    let epoch = on_chain_effects.executed_epoch();
+    let (epoch_protocol_version, epoch_start_timestamp_ms) =
+        fetch_epoch_start(client, epoch, checkpoint).await?;
+    let protocol_version = protocol_version.unwrap_or(epoch_protocol_version);
+    let protocol_config = ProtocolConfig::get_for_version(ProtocolVersion::new(protocol_version));
+    let epoch_data = EpochData::new(epoch, epoch_start_timestamp_ms, Default::default());
+
+    // Input objects, at the versions they were executed against.
+    let shared_versions: BTreeMap<_, _> = on_chain_effects
+        .shared_objects()
+        .iter()
+        .map(|o| (o.object_id, o.version))
+        .collect();
+    let input_object_kinds = tx_data.input_objects()?;
+    let mut to_fetch = vec![];
+    let mut packages = BTreeSet::new();
+    for kind in &input_object_kinds {
+        match kind {
+            InputObjectKind::MovePackage(id) => {
+                packages.insert(*id);
+            }
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                to_fetch.push((*id, *version));
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => {
+                let version = shared_versions.get(id).ok_or_else(|| {
+                    anyhow!("Version of shared object {id} is missing from the effects")
+                })?;
+                to_fetch.push((*id, *version));
+            }
+        }
+    }
+    // Child objects accessed by the transaction are not part of its inputs, but the ones it
+    // modified are recorded in the effects with the version they were modified at.
+    let inputs: BTreeSet<_> = to_fetch.iter().map(|(id, _)| *id).collect();
+    to_fetch.extend(
+        on_chain_effects
+            .modified_at_versions()
+            .into_iter()
+            .filter(|(id, _)| !inputs.contains(id)),
+    );
+
+    let mut objects = BTreeMap::new();
+    for (id, version) in to_fetch {
+        let object = fetch_past_object(client, id, version).await?;
+        if let Some(tag) = object.struct_tag() {
+            add_struct_tag_packages(&mut packages, &tag);
+        }
+        objects.insert(id, object);
+    }
+
+    // System packages are upgraded in place at epoch boundaries, the versions in effect during the
+    // epoch of the transaction are used.
+    let mut fetched_packages = BTreeSet::new();
+    for id in [
+        MOVE_STDLIB_OBJECT_ID,
+        SUI_FRAMEWORK_OBJECT_ID,
+        SUI_SYSTEM_PACKAGE_ID,
+    ] {
+        objects.insert(id, fetch_system_package(client, id, epoch).await?);
+        fetched_packages.insert(id);
+    }
+    fetch_packages(client, packages, &mut fetched_packages, &mut objects).await?;
+
+    if let Some((package_id, compiled_package)) = local_package {
+        let on_chain = objects
+            .get(&package_id)
+            .ok_or_else(|| anyhow!("Package {package_id} is not used by transaction {digest}"))?;
+        objects.insert(
+            package_id,
+            with_local_modules(on_chain, &compiled_package, &protocol_config)?,
+        );
+    }
+
+    // Debug output of the Move code is only printed in verbose mode.
+    let natives = sui_framework::natives::all_natives(/* silent */ !verbose);
+    let move_vm = Arc::new(adapter::new_move_vm(natives, &protocol_config, false)?);
+
+    // Child objects the transaction only read are not recorded anywhere, they are loaded as the
+    // execution reads them and the transaction is executed again until it reads no missing child.
+    // The children the transaction created did not exist before it.
+    let mut absent_children: BTreeSet<_> = on_chain_effects
+        .created()
+        .iter()
+        .map(|o| o.reference.object_id)
+        .collect();
+    let (store, inner_temporary_store, local_effects, execution_result) = loop {
+        let input_objects = input_object_kinds
+            .iter()
+            .map(|kind| {
+                objects
+                    .get(&kind.object_id())
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Input object {} was not fetched", kind.object_id()))
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let input_objects = check_objects(&tx_data, input_object_kinds.clone(), input_objects)?;
+        let shared_object_refs = input_objects.filter_shared_objects();
+        let transaction_dependencies = input_objects.transaction_dependencies();
+        let store = Arc::new(ReplayStore::new(objects.values().cloned().collect()));
+        let temporary_store =
+            TemporaryStore::new(store.clone(), input_objects, digest, &protocol_config);
+
+        let gas_status = if tx_data.kind().is_system_tx() {
+            SuiGasStatus::new_unmetered(&protocol_config)
+        } else {
+            SuiGasStatus::new_with_budget(
+                tx_data.gas_budget(),
+                tx_data.gas_price(),
+                &protocol_config,
+            )
+        };
+        let (kind, signer, gas) = tx_data.execution_parts();
+        let (inner_temporary_store, local_effects, execution_result) =
+            execution_engine::execute_transaction_to_effects::<execution_mode::Trace, _>(
+                shared_object_refs,
+                temporary_store,
+                kind,
+                signer,
+                &gas,
+                digest,
+                transaction_dependencies,
+                &move_vm,
+                gas_status,
+                &epoch_data,
+                &protocol_config,
+                false, // enable_expensive_checks
+            );
+
+        let missing_children: Vec<_> = store
+            .missing_children()
+            .into_iter()
+            .filter(|id| !absent_children.contains(id))
+            .collect();
+        if missing_children.is_empty() {
+            break (
+                store,
+                inner_temporary_store,
+                local_effects,
+                execution_result,
+            );
+        }
+        let mut packages = BTreeSet::new();
+        for id in missing_children {
+            match fetch_read_child_object(client, id, digest, checkpoint).await? {
+                Some(object) => {
+                    if let Some(tag) = object.struct_tag() {
+                        add_struct_tag_packages(&mut packages, &tag);
+                    }
+                    objects.insert(id, object);
+                }
+                None => {
+                    absent_children.insert(id);
+                }
+            }
+        }
+        fetch_packages(client, packages, &mut fetched_packages, &mut objects).await?;
+    };
+    let local_effects = SuiTransactionBlockEffects::try_from(local_effects)?;
+    let local_events = SuiTransactionBlockEvents::try_from(
+        inner_temporary_store.events,
+        digest,
+        None,
+        store.as_ref(),
+    )?
+    .data;
+    let commands = match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(pt) => pt.commands.as_slice(),
+        _ => &[],
+    };
+    let trace = match &execution_result {
+        Ok(results) => commands
+            .iter()
+            .zip(results)
+            .map(
+                |(command, (mutable_reference_outputs, return_values))| CommandTrace {
+                    command: command.to_string(),
+                    mutable_reference_outputs: mutable_reference_outputs
+                        .iter()
+                        .map(|(argument, bytes, type_tag)| {
+                            (argument.to_string(), TracedValue::new(bytes, type_tag))
+                        })
+                        .collect(),
+                    return_values: return_values
+                        .iter()
+                        .map(|(bytes, type_tag)| TracedValue::new(bytes, type_tag))
+                        .collect(),
+                },
+            )
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    Ok(ReplayResult {
+        digest,
+        protocol_version,
+        objects: objects
+            .values()
+            .map(|o| o.compute_object_reference())
+            .collect(),
+        diffs: diff_effects(&on_chain_effects, &local_effects),
+        on_chain_effects,
+        local_effects,
+        trace,
+        local_events,
+        execution_error: execution_result.err().map(|e| format!("{e:?}")),
+        verbose,
+    })
+}
+
+/// Fields of `local` that differ from `on_chain`.
+pub fn diff_effects(
+    on_chain: &SuiTransactionBlockEffects,
+    local: &SuiTransactionBlockEffects,
+) -> Vec<EffectsDiff> {
+    let mut diffs = vec![];
+    let mut diff = |field: &str, on_chain: String, local: String| {
+        if on_chain != local {
+            diffs.push(EffectsDiff {
+                field: field.to_string(),
+                on_chain,
+                local,
+            })
+        }
+    };
+    diff(
+        "status",
+        format!("{:?}", on_chain.status()),
+        format!("{:?}", local.status()),
+    );
+    diff(
+        "gasUsed",
+        format!("{:?}", on_chain.gas_cost_summary()),
+        format!("{:?}", local.gas_cost_summary()),
+    );
+    diff(
+        "sharedObjects",
+        format!("{:?}", on_chain.shared_objects()),
+        format!("{:?}", local.shared_objects()),
+    );
+    diff(
+        "created",
+        format!("{:?}", on_chain.created()),
+        format!("{:?}", local.created()),
+    );
+    diff(
+        "mutated",
+        format!("{:?}", on_chain.mutated()),
+        format!("{:?}", local.mutated()),
+    );
+    diff(
+        "unwrapped",
+        format!("{:?}", on_chain.unwrapped()),
+        format!("{:?}", local.unwrapped()),
+    );
+    diff(
+        "deleted",
+        format!("{:?}", on_chain.deleted()),
+        format!("{:?}", local.deleted()),
+    );
+    diff(
+        "unwrappedThenDeleted",
+        format!("{:?}", on_chain.unwrapped_then_deleted()),
+        format!("{:?}", local.unwrapped_then_deleted()),
+    );
+    diff(
+        "wrapped",
+        format!("{:?}", on_chain.wrapped()),
+        format!("{:?}", local.wrapped()),
+    );
+    diff(
+        "gasObject",
+        format!("{:?}", on_chain.gas_object()),
+        format!("{:?}", local.gas_object()),
+    );
+    diff(
+        "eventsDigest",
+        format!("{:?}", on_chain.events_digest()),
+        format!("{:?}", local.events_digest()),
+    );
+    diff(
+        "dependencies",
+        format!("{:?}", on_chain.dependencies()),
+        format!("{:?}", local.dependencies()),
+    );
+    diffs
+}
+
+async fn fetch_past_object(
+    client: &SuiClient,
+    id: ObjectID,
+    version: SequenceNumber,
+) -> Result<Object, anyhow::Error> {
+    match client
+        .read_api()
+        .try_get_parsed_past_object(id, version, SuiObjectDataOptions::bcs_lossless())
+        .await?
+    {
+        SuiPastObjectResponse::VersionFound(data) => data.try_into(),
+        response => Err(anyhow!(
+            "Can't fetch object {id} at version {}: {:?}",
+            version.value(),
+            response
+        )),
+    }
+}
+
+async fn fetch_object(client: &SuiClient, id: ObjectID) -> Result<Object, anyhow::Error> {
+    client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_object()
+        .map_err(|e| anyhow!("Can't fetch package {id}: {e}"))?
+        .try_into()
+}
+
+/// Fetches `packages` that are not fetched yet, along with their transitive dependencies.
+async fn fetch_packages(
+    client: &SuiClient,
+    mut packages: BTreeSet<ObjectID>,
+    fetched_packages: &mut BTreeSet<ObjectID>,
+    objects: &mut BTreeMap<ObjectID, Object>,
+) -> Result<(), anyhow::Error> {
+    while let Some(id) = packages
+        .iter()
+        .find(|id| !fetched_packages.contains(*id))
+        .copied()
+    {
+        fetched_packages.insert(id);
+        let object = fetch_object(client, id).await?;
+        let package = object
+            .data
+            .try_as_package()
+            .ok_or_else(|| anyhow!("Object {id} is not a package"))?;
+        packages.extend(
+            package
+                .linkage_table()
+                .values()
+                .map(|info| info.upgraded_id),
+        );
+        objects.insert(id, object);
+    }
+    Ok(())
+}
+
+/// Protocol version and start timestamp of `epoch`, given `checkpoint` of that epoch. They are
+/// read from the system state for the current epoch, from the genesis transaction for the first
+/// epoch and from the transaction that started the epoch otherwise.
+async fn fetch_epoch_start(
+    client: &SuiClient,
+    epoch: EpochId,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<(u64, u64), anyhow::Error> {
+    let system_state = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?;
+    if system_state.epoch == epoch {
+        return Ok((
+            system_state.protocol_version,
+            system_state.epoch_start_timestamp_ms,
+        ));
+    }
+
+    if epoch == 0 {
+        let genesis_checkpoint = client.read_api().get_checkpoint(0.into()).await?;
+        for digest in genesis_checkpoint.transactions {
+            if let TransactionKind::Genesis(genesis) =
+                fetch_transaction_kind(client, digest).await?
+            {
+                let store = InMemoryStorage::new(
+                    genesis
+                        .objects
+                        .into_iter()
+                        .map(|GenesisObject::RawObject { data, owner }| Object {
+                            data,
+                            owner,
+                            previous_transaction: digest,
+                            storage_rebate: 0,
+                        })
+                        .collect(),
+                );
+                let system_state = get_sui_system_state(&store)?;
+                return Ok((
+                    system_state.protocol_version(),
+                    system_state.epoch_start_timestamp_ms(),
+                ));
+            }
+        }
+        return Err(anyhow!("Genesis transaction not found in checkpoint 0"));
+    }
+
+    // Binary search of the first checkpoint of the epoch, the transaction that started the epoch
+    // is part of the checkpoint before it.
+    let (mut low, mut high) = (0, checkpoint);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if client.read_api().get_checkpoint(middle.into()).await?.epoch < epoch {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    ensure!(low > 0, "No checkpoint found before epoch {epoch}");
+    let last_checkpoint = client.read_api().get_checkpoint((low - 1).into()).await?;
+    for digest in last_checkpoint.transactions.into_iter().rev() {
+        if let TransactionKind::ChangeEpoch(change_epoch) =
+            fetch_transaction_kind(client, digest).await?
+        {
+            ensure!(
+                change_epoch.epoch == epoch,
+                "Checkpoint {} changes to epoch {} instead of epoch {epoch}",
+                last_checkpoint.sequence_number,
+                change_epoch.epoch
+            );
+            return Ok((
+                change_epoch.protocol_version.as_u64(),
+                change_epoch.epoch_start_timestamp_ms,
+            ));
+        }
+    }
+    Err(anyhow!(
+        "Transaction starting epoch {epoch} not found in checkpoint {}",
+        last_checkpoint.sequence_number
+    ))
+}
+
+async fn fetch_transaction_kind(
+    client: &SuiClient,
+    digest: TransactionDigest,
+) -> Result<TransactionKind, anyhow::Error> {
+    let response = client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionBlockResponseOptions::new().with_raw_input(),
+        )
+        .await?;
+    let sender_signed_data: SenderSignedData = bcs::from_bytes(&response.raw_transaction)?;
+    Ok(sender_signed_data.transaction_data().kind().clone())
+}
+
+/// The version of system package `id` in effect during `epoch`. System packages are upgraded by
+/// the transaction that changes epoch, which writes the next version of the package at the end of
+/// the epoch preceding the upgrade.
+async fn fetch_system_package(
+    client: &SuiClient,
+    id: ObjectID,
+    epoch: EpochId,
+) -> Result<Object, anyhow::Error> {
+    let mut package = fetch_object(client, id).await?;
+    while package.version() > OBJECT_START_VERSION {
+        let written_in = fetch_executed_epoch(client, package.previous_transaction).await?;
+        if written_in < epoch {
+            break;
+        }
+        let version = SequenceNumber::from_u64(package.version().value() - 1);
+        package = fetch_past_object(client, id, version).await?;
+    }
+    Ok(package)
+}
+
+async fn fetch_executed_epoch(
+    client: &SuiClient,
+    digest: TransactionDigest,
+) -> Result<EpochId, anyhow::Error> {
+    Ok(client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionBlockResponseOptions::new().with_effects(),
+        )
+        .await?
+        .effects
+        .ok_or_else(|| anyhow!("Effects of transaction {digest} are not available"))?
+        .executed_epoch())
+}
+
+/// The version of child object `id` read by transaction `digest` of `checkpoint`, or `None` if the
+/// child did not exist then. Versions read without being modified are not recorded, so the
+/// versions of the child are walked back from its current one, through the transactions that
+/// modified it, to the last one written before the transaction.
+async fn fetch_read_child_object(
+    client: &SuiClient,
+    id: ObjectID,
+    digest: TransactionDigest,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<Option<Object>, anyhow::Error> {
+    let mut object: Object = match client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+        .await?
+        .into_object()
+    {
+        Ok(data) => data.try_into()?,
+        Err(SuiObjectResponseError::NotExists { .. }) => return Ok(None),
+        Err(e) => {
+            return Err(anyhow!(
+                "Can't load child object {id} read by the transaction: {e}"
+            ))
+        }
+    };
+    let mut checkpoint_transactions = None;
+    loop {
+        let written_by = object.previous_transaction;
+        let response = client
+            .read_api()
+            .get_transaction_with_options(
+                written_by,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+            )
+            .await?;
+        let written_at = response
+            .checkpoint
+            .ok_or_else(|| anyhow!("Transaction {written_by} is not part of a checkpoint yet"))?;
+        let written_before = if written_at == checkpoint {
+            // Transactions of the same checkpoint are executed in the order of the checkpoint.
+            if checkpoint_transactions.is_none() {
+                let checkpoint = client.read_api().get_checkpoint(checkpoint.into()).await?;
+                checkpoint_transactions = Some(checkpoint.transactions);
+            }
+            let transactions = checkpoint_transactions.as_deref().unwrap_or_default();
+            let position = |tx| transactions.iter().position(|d| *d == tx);
+            position(written_by) < position(digest)
+        } else {
+            written_at < checkpoint
+        };
+        if written_before {
+            return Ok(Some(object));
+        }
+
+        let effects = response
+            .effects
+            .ok_or_else(|| anyhow!("Effects of transaction {written_by} are not available"))?;
+        // The child was created (or unwrapped) after the transaction.
+        let Some((_, version)) = effects
+            .modified_at_versions()
+            .into_iter()
+            .find(|(modified, _)| *modified == id)
+        else {
+            return Ok(None);
+        };
+        object = fetch_past_object(client, id, version).await?;
+    }
+}
+
+/// The on-chain package object with its modules replaced by the root modules of `package`.
+fn with_local_modules(
+    on_chain: &Object,
+    package: &CompiledPackage,
+    protocol_config: &ProtocolConfig,
+) -> Result<Object, anyhow::Error> {
+    let on_chain_package = on_chain
+        .data
+        .try_as_package()
+        .ok_or_else(|| anyhow!("Object {} is not a package", on_chain.id()))?;
+    let module_map = package
+        .get_modules()
+        .map(|module| {
+            let mut bytes = vec![];
+            module.serialize(&mut bytes)?;
+            Ok((module.self_id().name().to_string(), bytes))
+        })
+        .collect::<Result<_, anyhow::Error>>()?;
+    let package = MovePackage::new(
+        on_chain_package.id(),
+        on_chain_package.version(),
+        module_map,
+        protocol_config.max_move_package_size(),
+        on_chain_package.type_origin_table().clone(),
+        on_chain_package.linkage_table().clone(),
+    )?;
+    Ok(Object {
+        data: Data::Package(package),
+        owner: on_chain.owner,
+        previous_transaction: on_chain.previous_transaction,
+        storage_rebate: on_chain.storage_rebate,
+    })
+}
+
+fn add_struct_tag_packages(packages: &mut BTreeSet<ObjectID>, tag: &StructTag) {
+    packages.insert(tag.address.into());
+    for type_param in &tag.type_params {
+        add_type_tag_packages(packages, type_param);
+    }
+}
+
+fn add_type_tag_packages(packages: &mut BTreeSet<ObjectID>, tag: &TypeTag) {
+    match tag {
+        TypeTag::Struct(tag) => add_struct_tag_packages(packages, tag),
+        TypeTag::Vector(tag) => add_type_tag_packages(packages, tag),
+        _ => (),
+    }
+}
+
+/// Objects loaded for the replay. Child objects are read by the execution without being declared
+/// as inputs, the ones that are not loaded are recorded to be fetched.
+struct ReplayStore {
+    objects: InMemoryStorage,
+    missing_children: Mutex<BTreeSet<ObjectID>>,
+}
+
+impl ReplayStore {
+    fn new(objects: Vec<Object>) -> Self {
+        Self {
+            objects: InMemoryStorage::new(objects),
+            missing_children: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    fn missing_children(&self) -> BTreeSet<ObjectID> {
+        self.missing_children.lock().unwrap().clone()
+    }
+}
+
+impl BackingPackageStore for ReplayStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.objects.get_package_object(package_id)
+    }
+}
+
+impl ChildObjectResolver for ReplayStore {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let object = self.objects.read_child_object(parent, child)?;
+        if object.is_none() {
+            self.missing_children.lock().unwrap().insert(*child);
+        }
+        Ok(object)
+    }
+}
+
+impl ParentSync for ReplayStore {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        self.objects.get_latest_parent_entry_ref(object_id)
+    }
+}
+
+impl ObjectStore for ReplayStore {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        ObjectStore::get_object(&self.objects, object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        self.objects.get_object_by_key(object_id, version)
+    }
+}
+
+impl GetModule for ReplayStore {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        self.objects.get_module_by_id(id)
+    }
+}
@@ -23,7 +23,8 @@ use sui_config::{
     sui_config_dir, Config, PersistedConfig, FULL_NODE_DB_PATH, SUI_CLIENT_CONFIG,
     SUI_FULLNODE_CONFIG, SUI_NETWORK_CONFIG,
 };
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::key_metadata::metadata_path;
+use sui_keys::keystore::{is_encrypted_keystore, AccountKeystore, FileBasedKeystore, Keystore};
 use sui_swarm::memory::Swarm;
 use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 
@@ -250,8 +251,19 @@ impl SuiCommand {
             SuiCommand::KeyTool { keystore_path, cmd } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
-                cmd.execute(&mut keystore)
+                // The client config refers to the keystore by type, so it has to be updated when
+                // the keystore gets encrypted. Read it while the keystore is still in plaintext.
+                let client_config = match cmd {
+                    KeyToolCommand::Encrypt => client_config_using_keystore(&keystore_path),
+                    _ => None,
+                };
+                let mut keystore = Keystore::from_path(&keystore_path)?;
+                cmd.execute(&mut keystore)?;
+                if let Some(mut client_config) = client_config {
+                    client_config.keystore = keystore;
+                    client_config.save()?;
+                }
+                Ok(())
             }
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
@@ -343,13 +355,17 @@ async fn genesis(
     if write_config.is_none() && !files.is_empty() {
         if force {
             // check old keystore and client.yaml is compatible
-            let is_compatible = FileBasedKeystore::new(&keystore_path).is_ok()
+            let is_compatible = (FileBasedKeystore::new(&keystore_path).is_ok()
+                || is_encrypted_keystore(&keystore_path))
                 && PersistedConfig::<SuiClientConfig>::read(&client_path).is_ok();
             // Keep keystore and client.yaml if they are compatible
             if is_compatible {
                 for file in files {
                     let path = file.path();
-                    if path != client_path && path != keystore_path {
+                    if path != client_path
+                        && path != keystore_path
+                        && path != metadata_path(&keystore_path)
+                    {
                         if path.is_file() {
                             fs::remove_file(path)
                         } else {
@@ -588,6 +604,15 @@ async fn prompt_if_no_config(
     Ok(())
 }
 
+/// Returns the default client config if it uses the plaintext keystore at `keystore_path`.
+fn client_config_using_keystore(keystore_path: &Path) -> Option<PersistedConfig<SuiClientConfig>> {
+    let config_path = sui_config_dir().ok()?.join(SUI_CLIENT_CONFIG);
+    let config = PersistedConfig::<SuiClientConfig>::read(&config_path).ok()?;
+    let uses_keystore =
+        matches!(&config.keystore, Keystore::File(file) if file.path() == Some(keystore_path));
+    uses_keystore.then(|| config.persisted(&config_path))
+}
+
 fn read_line() -> Result<String, anyhow::Error> {
     let mut s = String::new();
     let _ = stdout().flush();
@@ -5,10 +5,13 @@ use std::io::Read;
 use std::os::unix::prelude::FileExt;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
+use clap::Parser;
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use serde_json::json;
+use shared_crypto::intent::Intent;
 use sui_types::messages::{
-    TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+    TransactionData, TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
     TEST_ONLY_GAS_UNIT_FOR_PUBLISH, TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
     TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
@@ -17,7 +20,9 @@ use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
+    client_commands::{
+        Opts, SuiClientCommandResult, SuiClientCommands, UnexecutedTransaction, WalletContext,
+    },
     config::SuiClientConfig,
     sui_commands::SuiCommand,
 };
@@ -35,11 +40,13 @@ use sui_json_rpc_types::{
     OwnedObjectRef, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
 };
+use sui_keys::key_metadata::KeyIdentity;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_macros::sim_test;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
-    Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    Ed25519SuiSignature, EncodeDecodeBase64, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair,
+    SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
@@ -209,7 +216,7 @@ async fn test_objects_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -241,7 +248,7 @@ async fn test_regression_6546() -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
 
     let SuiClientCommandResult::Objects(coins) = SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
         .execute(context)
         .await? else{
@@ -291,7 +298,7 @@ async fn test_custom_genesis() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -373,7 +380,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
     let object_to_send = object_refs.data.get(1).unwrap().object().unwrap().object_id;
 
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -383,17 +390,18 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Send an object
     SuiClientCommands::Transfer {
-        to: SuiAddress::random_for_testing_only(),
+        to: SuiAddress::random_for_testing_only().into(),
         object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
 
     // Fetch gas again
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -436,7 +444,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -467,7 +475,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address1`
     SuiClientCommands::Objects {
-        address: Some(address1),
+        address: Some(address1.into()),
     }
     .execute(context)
     .await?
@@ -516,6 +524,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args,
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -553,6 +562,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -577,6 +587,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -603,6 +614,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         args: args.to_vec(),
         gas: Some(gas),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -648,7 +660,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -716,7 +728,7 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -783,7 +795,7 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -828,7 +840,7 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -882,7 +894,7 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -923,7 +935,7 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await;
@@ -978,7 +990,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
-        serialize_output: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1049,6 +1061,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1106,9 +1119,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: Some(gas_obj_id),
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1209,9 +1223,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: None,
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1303,7 +1318,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
 
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -1329,6 +1344,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
         key_scheme: SignatureScheme::ED25519,
         derivation_path: None,
         word_length: None,
+        alias: None,
     }
     .execute(context)
     .await?;
@@ -1341,7 +1357,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     // Check that we can switch to this address
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(new_addr),
+        address: Some(new_addr.into()),
         env: None,
     }
     .execute(context)
@@ -1381,6 +1397,7 @@ async fn test_new_address_command_by_flag() -> Result<(), anyhow::Error> {
         key_scheme: SignatureScheme::Secp256k1,
         derivation_path: None,
         word_length: None,
+        alias: None,
     }
     .execute(context)
     .await?;
@@ -1420,7 +1437,7 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
 
     let addr2 = context.config.keystore.addresses().get(1).cloned().unwrap();
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -1499,6 +1516,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: Some(gas),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1552,6 +1570,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         coin_to_merge,
         gas: None,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1616,6 +1635,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1682,6 +1702,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: None,
         count: Some(3),
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1751,6 +1772,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -1787,6 +1809,72 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb_split_and_transfer() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    // Split the gas coin and transfer both new coins in the same transaction block.
+    let resp = SuiClientCommands::Ptb {
+        commands: vec![
+            "split-coins gas [1000,10]".parse()?,
+            format!("transfer-objects [@0.0,@0.1] {recipient}").parse()?,
+        ],
+        gas_budget: None,
+        build_config: BuildConfig::new_for_testing().config,
+        opts: Opts::default(),
+    }
+    .execute(context)
+    .await?;
+
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        unreachable!("Invalid response");
+    };
+    assert!(
+        response.status_ok().unwrap(),
+        "Command failed: {response:?}"
+    );
+    let created = response.effects.unwrap().created().to_vec();
+    assert_eq!(created.len(), 2);
+    let mut values = vec![];
+    for obj_ref in created {
+        assert_eq!(obj_ref.owner, Owner::AddressOwner(recipient));
+        let object = get_parsed_object_assert_existence(obj_ref.reference.object_id, context).await;
+        values.push(get_gas_value(&object));
+    }
+    values.sort();
+    assert_eq!(values, vec![10, 1000]);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb_reference_to_command_without_result() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    // Transferring objects has no result that later commands could use.
+    let err = SuiClientCommands::Ptb {
+        commands: vec![
+            "split-coins gas [1000]".parse()?,
+            format!("transfer-objects [@0] {recipient}").parse()?,
+            format!("transfer-objects [@1] {recipient}").parse()?,
+        ],
+        gas_budget: None,
+        build_config: BuildConfig::new_for_testing().config,
+        opts: Opts::default(),
+    }
+    .execute(context)
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Command 1 has no result"),
+        "Unexpected error: {err}"
+    );
+    Ok(())
+}
+
 #[sim_test]
 async fn test_signature_flag() -> Result<(), anyhow::Error> {
     let res = SignatureScheme::from_flag("0");
@@ -1849,7 +1937,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let coin = object_refs.get(1).unwrap().object().unwrap().object_id;
 
     SuiClientCommands::SerializeTransferSui {
-        to: address1,
+        to: address1.into(),
         sui_coin_object_id: coin,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         amount: Some(1),
@@ -1859,6 +1947,281 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_transfer_to_alias() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    context
+        .config
+        .keystore
+        .set_alias(&address1, Some("alice".to_string()))?;
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+
+    // Recipients can be given by the alias of their key.
+    let resp = SuiClientCommands::TransferSui {
+        to: KeyIdentity::Alias("alice".to_string()),
+        sui_coin_object_id: coin,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: Some(1),
+        opts: Opts::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::TransferSui(response) = resp else {
+        panic!("Command failed, unexpected response {resp:?}")
+    };
+    let created = response.effects.unwrap().created().to_vec();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].owner.get_owner_address()?, address1);
+
+    // Unknown aliases are rejected.
+    assert!(SuiClientCommands::TransferSui {
+        to: KeyIdentity::Alias("bob".to_string()),
+        sui_coin_object_id: coin,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: Some(1),
+        opts: Opts::default(),
+    }
+    .execute(context)
+    .await
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_serialize_output_alias() {
+    // `--serialize-output` is still accepted for `--serialize-unsigned`.
+    let cmd = SuiClientCommands::parse_from([
+        "client",
+        "publish",
+        "--gas-budget",
+        "1",
+        "--serialize-output",
+    ]);
+    let SuiClientCommands::Publish { opts, .. } = cmd else {
+        panic!("Expected a publish command");
+    };
+    assert!(opts.serialize_unsigned);
+    assert!(!opts.dry_run);
+}
+
+#[sim_test]
+async fn test_use_unreachable_remote_signer() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+    let addresses = context.config.keystore.addresses();
+    let active_address = context.config.active_address;
+    let dir = tempfile::tempdir()?;
+
+    // The signer is connected to before switching, a missing signer keeps the keystore.
+    let endpoint = format!("unix:{}", dir.path().join("signer.sock").display());
+    assert!(
+        SuiClientCommands::parse_from(["client", "use-remote-signer", &endpoint])
+            .execute(context)
+            .await
+            .is_err()
+    );
+    assert_eq!(context.config.keystore.addresses(), addresses);
+    assert_eq!(context.config.active_address, active_address);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_use_keystore() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let context = &mut test_cluster.wallet;
+    let addresses = context.config.keystore.addresses();
+    let keystore_path = context.config.keystore.path().unwrap().to_path_buf();
+    let dir = tempfile::tempdir()?;
+
+    // Switching to another keystore makes one of its addresses the active address.
+    let other_path = dir.path().join("other.keystore");
+    let mut other = FileBasedKeystore::new(&other_path)?;
+    let (address, _, _) = other.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    SuiClientCommands::UseKeystore { path: other_path }
+        .execute(context)
+        .await?;
+    assert_eq!(context.config.keystore.addresses(), vec![address]);
+    assert_eq!(context.config.active_address, Some(address));
+
+    // And switching back restores the keys of the original keystore.
+    SuiClientCommands::UseKeystore {
+        path: keystore_path,
+    }
+    .execute(context)
+    .await?;
+    assert_eq!(context.config.keystore.addresses(), addresses);
+    assert!(addresses.contains(&context.active_address()?));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_dry_run_and_serialize_unsigned() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+    let transfer_sui = |opts| SuiClientCommands::TransferSui {
+        to: address1.into(),
+        sui_coin_object_id: coin,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: Some(1),
+        opts,
+    };
+
+    // Dry run the transfer, nothing is executed.
+    let resp = transfer_sui(Opts {
+        dry_run: true,
+        serialize_unsigned: false,
+    })
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Unexecuted(UnexecutedTransaction::DryRun(response)) = resp else {
+        unreachable!("Invalid response");
+    };
+    assert!(response.effects.status().is_ok());
+    assert!(!response.balance_changes.is_empty());
+    let object = get_object(coin, context).await.unwrap();
+    assert_eq!(object.owner, Some(Owner::AddressOwner(address)));
+
+    // Serialize the transfer, sign it and execute it.
+    let resp = transfer_sui(Opts {
+        dry_run: false,
+        serialize_unsigned: true,
+    })
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Unexecuted(UnexecutedTransaction::SerializedUnsigned(tx_bytes)) =
+        resp else {
+        unreachable!("Invalid response");
+    };
+    let data: TransactionData = bcs::from_bytes(&Base64::decode(&tx_bytes).unwrap())?;
+    let signature =
+        context
+            .config
+            .keystore
+            .sign_secure(&address, &data, Intent::sui_transaction())?;
+    let resp = SuiClientCommands::ExecuteSignedTx {
+        tx_bytes,
+        signatures: vec![signature.encode_base64()],
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExecuteSignedTx(response) = resp else {
+        unreachable!("Invalid response");
+    };
+    assert!(response.status_ok().unwrap());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_replay_transaction() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .with_epoch_duration_ms(10000)
+        .build()
+        .await?;
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let resp = SuiClientCommands::Ptb {
+        commands: vec![
+            "split-coins gas [1000]".parse()?,
+            format!("transfer-objects [@0.0] {recipient}").parse()?,
+        ],
+        gas_budget: None,
+        build_config: BuildConfig::new_for_testing().config,
+        opts: Opts::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        unreachable!("Invalid response");
+    };
+
+    // Replaying the transaction locally results in the on-chain effects.
+    let resp = SuiClientCommands::Replay {
+        digest: response.digest,
+        protocol_version: None,
+        verbose: true,
+        local_package: None,
+        package_id: None,
+        build_config: Default::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Replay(result) = resp else {
+        unreachable!("Invalid response");
+    };
+    assert_eq!(result.digest, response.digest);
+    assert!(result.execution_error.is_none());
+    assert!(
+        result.diffs.is_empty(),
+        "Effects differ: {:?}",
+        result.diffs
+    );
+    assert_eq!(result.local_effects, result.on_chain_effects);
+    // The trace records the coin returned by the split and nothing for the transfer.
+    assert_eq!(result.trace.len(), 2);
+    assert_eq!(result.trace[0].return_values.len(), 1);
+    assert!(result.trace[1].return_values.is_empty());
+
+    // The transaction still replays with the configuration of its epoch once the epoch is over.
+    test_cluster.wait_for_epoch(Some(1)).await;
+    let context = &mut test_cluster.wallet;
+    let resp = SuiClientCommands::Replay {
+        digest: response.digest,
+        protocol_version: None,
+        verbose: false,
+        local_package: None,
+        package_id: None,
+        build_config: Default::default(),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Replay(past_epoch_result) = resp else {
+        unreachable!("Invalid response");
+    };
+    assert_eq!(past_epoch_result.protocol_version, result.protocol_version);
+    assert!(
+        past_epoch_result.diffs.is_empty(),
+        "Effects differ: {:?}",
+        past_epoch_result.diffs
+    );
+
+    // Swapping in a package the transaction does not use fails.
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let resp = SuiClientCommands::Replay {
+        digest: response.digest,
+        protocol_version: None,
+        verbose: false,
+        local_package: Some(package_path),
+        package_id: Some(ObjectID::random()),
+        build_config: Default::default(),
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
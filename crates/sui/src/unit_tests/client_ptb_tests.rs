@@ -0,0 +1,140 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::{to_result_argument, PtbArg, PtbCommand};
+use sui_types::base_types::ObjectID;
+use sui_types::messages::Argument;
+use sui_types::parse_sui_type_tag;
+
+#[test]
+fn test_parse_ptb_args() {
+    assert_eq!(PtbArg::from_str("gas").unwrap(), PtbArg::Gas);
+    assert_eq!(PtbArg::from_str("@2").unwrap(), PtbArg::Result(2));
+    assert_eq!(
+        PtbArg::from_str("@2.1").unwrap(),
+        PtbArg::NestedResult(2, 1)
+    );
+    assert_eq!(
+        PtbArg::from_str("0x2").unwrap(),
+        PtbArg::Id(ObjectID::from_single_byte(2))
+    );
+    assert_eq!(
+        PtbArg::from_str("1000").unwrap(),
+        PtbArg::Number("1000".to_string())
+    );
+    assert_eq!(PtbArg::from_str("true").unwrap(), PtbArg::Bool(true));
+    assert_eq!(
+        PtbArg::from_str("\"a b, c\"").unwrap(),
+        PtbArg::String("a b, c".to_string())
+    );
+    assert_eq!(
+        PtbArg::from_str("[1,[@0, gas]]").unwrap(),
+        PtbArg::Vector(vec![
+            PtbArg::Number("1".to_string()),
+            PtbArg::Vector(vec![PtbArg::Result(0), PtbArg::Gas]),
+        ])
+    );
+    assert_eq!(PtbArg::from_str("[]").unwrap(), PtbArg::Vector(vec![]));
+
+    assert!(PtbArg::from_str("@").is_err());
+    assert!(PtbArg::from_str("@a.1").is_err());
+    assert!(PtbArg::from_str("0xzz").is_err());
+    assert!(PtbArg::from_str("[1,2").is_err());
+    assert!(PtbArg::from_str("-1").is_err());
+}
+
+#[test]
+fn test_parse_ptb_commands() {
+    assert_eq!(
+        PtbCommand::from_str("split-coins gas [1000, 2000]").unwrap(),
+        PtbCommand::SplitCoins {
+            coin: PtbArg::Gas,
+            amounts: vec![
+                PtbArg::Number("1000".to_string()),
+                PtbArg::Number("2000".to_string())
+            ],
+        }
+    );
+    assert_eq!(
+        PtbCommand::from_str("transfer-objects [@0.0,@0.1] 0x5").unwrap(),
+        PtbCommand::TransferObjects {
+            objects: vec![PtbArg::NestedResult(0, 0), PtbArg::NestedResult(0, 1)],
+            recipient: PtbArg::Id(ObjectID::from_single_byte(5)),
+        }
+    );
+    assert_eq!(
+        PtbCommand::from_str("make-move-vec <0x2::coin::Coin<0x2::sui::SUI>> [@0,@1]").unwrap(),
+        PtbCommand::MakeMoveVec {
+            type_: Some(parse_sui_type_tag("0x2::coin::Coin<0x2::sui::SUI>").unwrap()),
+            elements: vec![PtbArg::Result(0), PtbArg::Result(1)],
+        }
+    );
+    assert_eq!(
+        PtbCommand::from_str("move-call 0x2::pay::split_vec <0x2::sui::SUI> @0 [1,2]").unwrap(),
+        PtbCommand::MoveCall {
+            package: ObjectID::from_single_byte(2),
+            module: "pay".to_string(),
+            function: "split_vec".to_string(),
+            type_args: vec![parse_sui_type_tag("0x2::sui::SUI").unwrap()],
+            args: vec![
+                PtbArg::Result(0),
+                PtbArg::Vector(vec![
+                    PtbArg::Number("1".to_string()),
+                    PtbArg::Number("2".to_string())
+                ]),
+            ],
+        }
+    );
+    assert_eq!(
+        PtbCommand::from_str("move-call 0x2::devnet_nft::mint \"nft\" \"an nft\" \"url\"").unwrap(),
+        PtbCommand::MoveCall {
+            package: ObjectID::from_single_byte(2),
+            module: "devnet_nft".to_string(),
+            function: "mint".to_string(),
+            type_args: vec![],
+            args: vec![
+                PtbArg::String("nft".to_string()),
+                PtbArg::String("an nft".to_string()),
+                PtbArg::String("url".to_string()),
+            ],
+        }
+    );
+    assert_eq!(
+        PtbCommand::from_str("upgrade ./my_package 0x6").unwrap(),
+        PtbCommand::Upgrade {
+            package_path: PathBuf::from("./my_package"),
+            upgrade_capability: ObjectID::from_single_byte(6),
+        }
+    );
+
+    assert!(PtbCommand::from_str("").is_err());
+    assert!(PtbCommand::from_str("split gas [1]").is_err());
+    assert!(PtbCommand::from_str("split-coins gas").is_err());
+    assert!(PtbCommand::from_str("split-coins gas 1").is_err());
+    assert!(PtbCommand::from_str("move-call 0x2::coin").is_err());
+    assert!(PtbCommand::from_str("upgrade ./my_package 10").is_err());
+}
+
+#[test]
+fn test_ptb_result_arguments() {
+    // The second command, e.g. an upgrade, has no result.
+    let results = [Some(Argument::Result(0)), None];
+    assert_eq!(
+        to_result_argument(&results, &PtbArg::Result(0)).unwrap(),
+        Some(Argument::Result(0))
+    );
+    assert_eq!(
+        to_result_argument(&results, &PtbArg::NestedResult(0, 1)).unwrap(),
+        Some(Argument::NestedResult(0, 1))
+    );
+    assert_eq!(
+        to_result_argument(&results, &PtbArg::Gas).unwrap(),
+        Some(Argument::GasCoin)
+    );
+    assert!(to_result_argument(&results, &PtbArg::Result(1)).is_err());
+    assert!(to_result_argument(&results, &PtbArg::NestedResult(1, 0)).is_err());
+    assert!(to_result_argument(&results, &PtbArg::Result(2)).is_err());
+}
@@ -6,14 +6,20 @@ use std::str::FromStr;
 use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
 
+use super::assemble_signed_transaction;
 use super::write_keypair_to_file;
 use super::KeyToolCommand;
+use crate::multisig_request::MultiSigRequest;
 use fastcrypto::encoding::Base64;
 use fastcrypto::encoding::Encoding;
+use fastcrypto::encoding::Hex;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use shared_crypto::intent::Intent;
+use shared_crypto::intent::IntentMessage;
 use shared_crypto::intent::IntentScope;
+use shared_crypto::intent::PersonalMessage;
+use sui_keys::key_metadata::KeyIdentity;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
 use sui_types::base_types::ObjectID;
@@ -29,9 +35,12 @@ use sui_types::crypto::Secp256r1SuiSignature;
 use sui_types::crypto::Signature;
 use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
+use sui_types::crypto::SuiSignature;
 use sui_types::crypto::SuiSignatureInner;
+use sui_types::messages::Transaction;
 use sui_types::messages::TransactionData;
 use sui_types::messages::TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
+use sui_types::multisig::MultiSigPublicKey;
 use tempfile::TempDir;
 
 const TEST_MNEMONIC: &str = "result crisp session latin must fruit genuine question prevent start coconut brave speak student dismiss";
@@ -51,6 +60,54 @@ fn test_addresses_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test]
+fn test_update_alias() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(2));
+    let addresses = keystore.addresses();
+    let treasury = KeyIdentity::Alias("treasury".to_string());
+
+    KeyToolCommand::UpdateAlias {
+        address: addresses[0].into(),
+        new_alias: Some("treasury".to_string()),
+    }
+    .execute(&mut keystore)?;
+    assert_eq!(keystore.get_address_by_identity(&treasury)?, addresses[0]);
+
+    // Aliases are unique.
+    assert!(KeyToolCommand::UpdateAlias {
+        address: addresses[1].into(),
+        new_alias: Some("treasury".to_string()),
+    }
+    .execute(&mut keystore)
+    .is_err());
+
+    // Keys are found by alias.
+    KeyToolCommand::UpdateAlias {
+        address: treasury.clone(),
+        new_alias: None,
+    }
+    .execute(&mut keystore)?;
+    assert_eq!(keystore.get_alias(&addresses[0]), None);
+    assert!(keystore.get_address_by_identity(&treasury).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_export() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(1));
+    let address = keystore.addresses()[0];
+    KeyToolCommand::Export { address, yes: true }.execute(&mut keystore)?;
+
+    // Only keys of the keystore can be exported.
+    assert!(KeyToolCommand::Export {
+        address: SuiAddress::random_for_testing_only(),
+        yes: true,
+    }
+    .execute(&mut keystore)
+    .is_err());
+    Ok(())
+}
+
 #[test]
 fn test_flag_in_signature_and_keypair() -> Result<(), anyhow::Error> {
     let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(0));
@@ -384,3 +441,211 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
     .execute(&mut keystore)?;
     Ok(())
 }
+
+#[test]
+fn test_multisig_request() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(3));
+    let addresses = keystore.addresses();
+    let multisig_pk = MultiSigPublicKey::new(keystore.keys(), vec![1, 1, 1], 2)?;
+    let sender: SuiAddress = multisig_pk.clone().into();
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let gas_price = 1;
+    let pay_sui = |sender| {
+        TransactionData::new_pay_sui(
+            sender,
+            vec![gas],
+            vec![SuiAddress::random_for_testing_only()],
+            vec![10000],
+            gas,
+            gas_price * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            gas_price,
+        )
+        .unwrap()
+    };
+    let tx_data = pay_sui(sender);
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("request.json");
+    KeyToolCommand::MultiSigRequestCreate {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        pks: keystore.keys().into_iter().map(Into::into).collect(),
+        weights: vec![1, 1, 1],
+        threshold: 2,
+        file: file.clone(),
+    }
+    .execute(&mut keystore)?;
+
+    // The threshold is not met with a single signature, which cannot be added twice.
+    let sign = |address: SuiAddress| KeyToolCommand::MultiSigRequestSign {
+        file: file.clone(),
+        address: address.into(),
+    };
+    sign(addresses[0]).execute(&mut keystore)?;
+    assert!(sign(addresses[0]).execute(&mut keystore).is_err());
+    assert_eq!(MultiSigRequest::read(&file)?.weight()?, 1);
+    assert!(
+        KeyToolCommand::MultiSigRequestCombine { file: file.clone() }
+            .execute(&mut keystore)
+            .is_err()
+    );
+
+    sign(addresses[2]).execute(&mut keystore)?;
+    let request = MultiSigRequest::read(&file)?;
+    assert_eq!(request.weight()?, 2);
+    let (data, multisig) = request.combine()?;
+    assert_eq!(data, tx_data);
+    Transaction::from_generic_sig_data(data, Intent::sui_transaction(), vec![multisig]).verify()?;
+
+    // Transactions of another sender cannot be signed through the MultiSig.
+    let other_tx_data = pay_sui(addresses[0]);
+    assert!(
+        MultiSigRequest::new(Base64::encode(bcs::to_bytes(&other_tx_data)?), multisig_pk).is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nested_multisig_request() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(4));
+    let addresses = keystore.addresses();
+    let pks = keystore.keys();
+
+    // 2 of 2 of a key and a 2 of 3 team of the other keys.
+    let team_pk = MultiSigPublicKey::new(pks[1..].to_vec(), vec![1, 1, 1], 2)?;
+    let members = vec![pks[0].clone().into(), team_pk.into()];
+    let multisig_pk = MultiSigPublicKey::new_with_members(members.clone(), vec![1, 1], 2)?;
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui(
+        multisig_pk.into(),
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1,
+    )?;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("request.json");
+    KeyToolCommand::MultiSigRequestCreate {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        pks: members,
+        weights: vec![1, 1],
+        threshold: 2,
+        file: file.clone(),
+    }
+    .execute(&mut keystore)?;
+
+    // The team counts once 2 of its keys signed.
+    for address in [addresses[0], addresses[1]] {
+        KeyToolCommand::MultiSigRequestSign {
+            file: file.clone(),
+            address: address.into(),
+        }
+        .execute(&mut keystore)?;
+    }
+    let request = MultiSigRequest::read(&file)?;
+    assert_eq!(request.weight()?, 1);
+    assert!(request.combine().is_err());
+
+    KeyToolCommand::MultiSigRequestSign {
+        file: file.clone(),
+        address: addresses[3].into(),
+    }
+    .execute(&mut keystore)?;
+    let request = MultiSigRequest::read(&file)?;
+    assert_eq!(request.weight()?, 2);
+    let (data, multisig) = request.combine()?;
+    Transaction::from_generic_sig_data(data, Intent::sui_transaction(), vec![multisig]).verify()?;
+    Ok(())
+}
+
+#[test]
+fn test_assemble_signed_tx() -> Result<(), anyhow::Error> {
+    // The keys of the external signers are not in any keystore.
+    let keys = [
+        SuiKeyPair::Ed25519(get_key_pair().1),
+        SuiKeyPair::Secp256k1(get_key_pair().1),
+    ];
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    for key in keys {
+        let tx_data = TransactionData::new_pay_sui(
+            (&key.public()).into(),
+            vec![gas],
+            vec![SuiAddress::random_for_testing_only()],
+            vec![10000],
+            gas,
+            TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            1,
+        )?;
+        let tx_bytes = Base64::encode(bcs::to_bytes(&tx_data)?);
+        KeyToolCommand::SigningPayload {
+            tx_bytes: tx_bytes.clone(),
+        }
+        .execute(&mut Keystore::from(InMemKeystore::new_insecure_for_tests(
+            0,
+        )))?;
+
+        let signature = Signature::new_secure(
+            &IntentMessage::new(Intent::sui_transaction(), tx_data),
+            &key,
+        );
+        let hex_signature = Hex::encode(signature.signature_bytes());
+        let transaction =
+            assemble_signed_transaction(&tx_bytes, &[hex_signature.clone()], &[key.public()])?;
+        assert_eq!(
+            transaction.data().inner().tx_signatures,
+            vec![signature.clone().into()]
+        );
+
+        // Base64 encoded and 0x prefixed hex signatures are accepted too.
+        let base64_signature = Base64::encode(signature.signature_bytes());
+        assert!(
+            assemble_signed_transaction(&tx_bytes, &[base64_signature], &[key.public()]).is_ok()
+        );
+        assert!(assemble_signed_transaction(
+            &tx_bytes,
+            &[format!("0x{hex_signature}")],
+            &[key.public()]
+        )
+        .is_ok());
+
+        // The signature must verify against the public key, which must be the sender's.
+        let other_key = SuiKeyPair::Ed25519(get_key_pair().1);
+        assert!(assemble_signed_transaction(
+            &tx_bytes,
+            &[hex_signature.clone()],
+            &[other_key.public()]
+        )
+        .is_err());
+        let other_signature = Signature::new_secure(
+            &IntentMessage::new(
+                Intent::sui_transaction(),
+                PersonalMessage { message: vec![0] },
+            ),
+            &key,
+        );
+        assert!(assemble_signed_transaction(
+            &tx_bytes,
+            &[Hex::encode(other_signature.signature_bytes())],
+            &[key.public()]
+        )
+        .is_err());
+        assert!(assemble_signed_transaction(&tx_bytes, &[hex_signature], &[]).is_err());
+    }
+    Ok(())
+}
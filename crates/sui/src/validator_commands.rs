@@ -24,7 +24,7 @@ use sui_types::{
 };
 use tap::tap::TapOptional;
 
-use crate::client_commands::WalletContext;
+use crate::client_commands::{Opts, UnexecutedTransaction, WalletContext};
 use crate::fire_drill::get_gas_obj_ref;
 use clap::*;
 use colored::Colorize;
@@ -38,9 +38,10 @@ use shared_crypto::intent::{Intent, IntentMessage, IntentScope};
 use sui_json_rpc_types::{
     SuiObjectDataOptions, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
-use sui_keys::keystore::AccountKeystore;
+use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_keys::{
     key_derive::generate_new_key,
+    key_metadata::KeyIdentity,
     keypair_file::{
         read_authority_keypair_from_file, read_keypair_from_file, read_network_keypair_from_file,
         write_authority_keypair_to_file, write_keypair_to_file,
@@ -77,23 +78,31 @@ pub enum SuiValidatorCommand {
         file: PathBuf,
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     #[clap(name = "join-committee")]
     JoinCommittee {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     #[clap(name = "leave-committee")]
     LeaveCommittee {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     #[clap(name = "display-metadata")]
     DisplayMetadata {
+        /// The Sui address of the validator, or the alias of its key in the keystore. Defaults to
+        /// the active address.
         #[clap(name = "validator-address")]
-        validator_address: Option<SuiAddress>,
+        validator_address: Option<KeyIdentity>,
         #[clap(name = "json", long)]
         json: Option<bool>,
     },
@@ -104,6 +113,8 @@ pub enum SuiValidatorCommand {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     /// Update gas price that is used to calculate Reference Gas Price
     #[clap(name = "update-gas-price")]
@@ -118,6 +129,8 @@ pub enum SuiValidatorCommand {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     /// Report or un-report a validator.
     #[clap(name = "report-validator")]
@@ -127,23 +140,27 @@ pub enum SuiValidatorCommand {
         /// Validator's OperationCap ID can be found by using the `display-metadata` subcommand.
         #[clap(name = "operation-cap-id", long)]
         operation_cap_id: Option<ObjectID>,
-        /// The Sui Address of the validator is being reported or un-reported
+        /// The Sui Address of the validator is being reported or un-reported, or the alias of its
+        /// key in the keystore
         #[clap(name = "reportee-address")]
-        reportee_address: SuiAddress,
+        reportee_address: KeyIdentity,
         /// If true, undo an existing report.
         #[clap(name = "undo-report", long)]
         undo_report: Option<bool>,
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        #[clap(flatten)]
+        opts: Opts,
     },
     /// Serialize the payload that is used to generate Proof of Possession.
     /// This is useful to take the payload offline for an Authority protocol keypair to sign.
     #[clap(name = "serialize-payload-pop")]
     SerializePayloadForPoP {
-        /// Authority account address encoded in hex with 0x prefix.
+        /// Authority account address encoded in hex with 0x prefix, or the alias of its key in
+        /// the keystore.
         #[clap(name = "account-address", long)]
-        account_address: SuiAddress,
+        account_address: KeyIdentity,
         /// Authority protocol public key encoded in hex.
         #[clap(name = "protocol-public-key", long)]
         protocol_public_key: AuthorityPublicKeyBytes,
@@ -162,6 +179,7 @@ pub enum SuiValidatorCommandResponse {
     UpdateGasPrice(SuiTransactionBlockResponse),
     ReportValidator(SuiTransactionBlockResponse),
     SerializedPayload(String),
+    Unexecuted(UnexecutedTransaction),
 }
 
 fn make_key_files(
@@ -212,6 +230,13 @@ impl SuiValidatorCommand {
                 host_name,
                 gas_price,
             } => {
+                if let Keystore::Remote(keystore) = &context.config.keystore {
+                    bail!(
+                        "make-validator-info is not supported with a remote signer, it writes the \
+                         account key of [{sui_address}] held by the signer at {} to a file",
+                        keystore.endpoint()
+                    );
+                }
                 let dir = std::env::current_dir()?;
                 let protocol_key_file_name = dir.join("protocol.key");
                 let account_key = match context.config.keystore.get_key(&sui_address)? {
@@ -275,7 +300,11 @@ impl SuiValidatorCommand {
                 );
                 SuiValidatorCommandResponse::MakeValidatorInfo
             }
-            SuiValidatorCommand::BecomeCandidate { file, gas_budget } => {
+            SuiValidatorCommand::BecomeCandidate {
+                file,
+                gas_budget,
+                opts,
+            } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let validator_info_bytes = fs::read(file)?;
                 // Note: we should probably rename the struct or evolve it accordingly.
@@ -319,33 +348,51 @@ impl SuiValidatorCommand {
                     CallArg::Pure(bcs::to_bytes(&validator.gas_price()).unwrap()),
                     CallArg::Pure(bcs::to_bytes(&validator.commission_rate()).unwrap()),
                 ];
-                let response =
-                    call_0x5(context, "request_add_validator_candidate", args, gas_budget).await?;
-                SuiValidatorCommandResponse::BecomeCandidate(response)
+                call_0x5(
+                    context,
+                    "request_add_validator_candidate",
+                    args,
+                    gas_budget,
+                    opts,
+                    SuiValidatorCommandResponse::BecomeCandidate,
+                )
+                .await?
             }
 
-            SuiValidatorCommand::JoinCommittee { gas_budget } => {
+            SuiValidatorCommand::JoinCommittee { gas_budget, opts } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
-                let response =
-                    call_0x5(context, "request_add_validator", vec![], gas_budget).await?;
-                SuiValidatorCommandResponse::JoinCommittee(response)
+                call_0x5(
+                    context,
+                    "request_add_validator",
+                    vec![],
+                    gas_budget,
+                    opts,
+                    SuiValidatorCommandResponse::JoinCommittee,
+                )
+                .await?
             }
 
-            SuiValidatorCommand::LeaveCommittee { gas_budget } => {
+            SuiValidatorCommand::LeaveCommittee { gas_budget, opts } => {
                 // Only an active validator can leave committee.
                 let _status =
                     check_status(context, HashSet::from([ValidatorStatus::Active])).await?;
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
-                let response =
-                    call_0x5(context, "request_remove_validator", vec![], gas_budget).await?;
-                SuiValidatorCommandResponse::LeaveCommittee(response)
+                call_0x5(
+                    context,
+                    "request_remove_validator",
+                    vec![],
+                    gas_budget,
+                    opts,
+                    SuiValidatorCommandResponse::LeaveCommittee,
+                )
+                .await?
             }
 
             SuiValidatorCommand::DisplayMetadata {
                 validator_address,
                 json,
             } => {
-                let validator_address = validator_address.unwrap_or(context.active_address()?);
+                let validator_address = context.get_identity_address(validator_address)?;
                 // Default display with json serialization for better UX.
                 let sui_client = context.get_client().await?;
                 display_metadata(&sui_client, validator_address, json.unwrap_or(true)).await?;
@@ -355,21 +402,20 @@ impl SuiValidatorCommand {
             SuiValidatorCommand::UpdateMetadata {
                 metadata,
                 gas_budget,
+                opts,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
-                let resp = update_metadata(context, metadata, gas_budget).await?;
-                SuiValidatorCommandResponse::UpdateMetadata(resp)
+                update_metadata(context, metadata, gas_budget, opts).await?
             }
 
             SuiValidatorCommand::UpdateGasPrice {
                 operation_cap_id,
                 gas_price,
                 gas_budget,
+                opts,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
-                let resp =
-                    update_gas_price(context, operation_cap_id, gas_price, gas_budget).await?;
-                SuiValidatorCommandResponse::UpdateGasPrice(resp)
+                update_gas_price(context, operation_cap_id, gas_price, gas_budget, opts).await?
             }
 
             SuiValidatorCommand::ReportValidator {
@@ -377,24 +423,33 @@ impl SuiValidatorCommand {
                 reportee_address,
                 undo_report,
                 gas_budget,
+                opts,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
                 let undo_report = undo_report.unwrap_or(false);
-                let resp = report_validator(
+                let reportee_address = context
+                    .config
+                    .keystore
+                    .get_address_by_identity(&reportee_address)?;
+                report_validator(
                     context,
                     reportee_address,
                     operation_cap_id,
                     undo_report,
                     gas_budget,
+                    opts,
                 )
-                .await?;
-                SuiValidatorCommandResponse::ReportValidator(resp)
+                .await?
             }
 
             SuiValidatorCommand::SerializePayloadForPoP {
                 account_address,
                 protocol_public_key,
             } => {
+                let account_address = context
+                    .config
+                    .keystore
+                    .get_address_by_identity(&account_address)?;
                 let mut msg: Vec<u8> = Vec::new();
                 msg.extend_from_slice(protocol_public_key.as_bytes());
                 msg.extend_from_slice(account_address.as_ref());
@@ -469,7 +524,8 @@ async fn update_gas_price(
     operation_cap_id: Option<ObjectID>,
     gas_price: u64,
     gas_budget: u64,
-) -> Result<SuiTransactionBlockResponse> {
+    opts: Opts,
+) -> Result<SuiValidatorCommandResponse> {
     let (_status, _summary, cap_obj_ref) = get_cap_object_ref(context, operation_cap_id).await?;
 
     // TODO: Only active/pending validators can set gas price.
@@ -478,7 +534,15 @@ async fn update_gas_price(
         CallArg::Object(ObjectArg::ImmOrOwnedObject(cap_obj_ref)),
         CallArg::Pure(bcs::to_bytes(&gas_price).unwrap()),
     ];
-    call_0x5(context, "request_set_gas_price", args, gas_budget).await
+    call_0x5(
+        context,
+        "request_set_gas_price",
+        args,
+        gas_budget,
+        opts,
+        SuiValidatorCommandResponse::UpdateGasPrice,
+    )
+    .await
 }
 
 async fn report_validator(
@@ -487,7 +551,8 @@ async fn report_validator(
     operation_cap_id: Option<ObjectID>,
     undo_report: bool,
     gas_budget: u64,
-) -> Result<SuiTransactionBlockResponse> {
+    opts: Opts,
+) -> Result<SuiValidatorCommandResponse> {
     let (status, summary, cap_obj_ref) = get_cap_object_ref(context, operation_cap_id).await?;
 
     let validator_address = summary.sui_address;
@@ -508,7 +573,15 @@ async fn report_validator(
     } else {
         "report_validator"
     };
-    call_0x5(context, function_name, args, gas_budget).await
+    call_0x5(
+        context,
+        function_name,
+        args,
+        gas_budget,
+        opts,
+        SuiValidatorCommandResponse::ReportValidator,
+    )
+    .await
 }
 
 async fn get_validator_summary_from_cap_id(
@@ -551,7 +624,9 @@ async fn call_0x5(
     function: &'static str,
     call_args: Vec<CallArg>,
     gas_budget: u64,
-) -> anyhow::Result<SuiTransactionBlockResponse> {
+    opts: Opts,
+    response: fn(SuiTransactionBlockResponse) -> SuiValidatorCommandResponse,
+) -> anyhow::Result<SuiValidatorCommandResponse> {
     let sender = context.active_address()?;
     let sui_client = context.get_client().await?;
     let mut args = vec![SUI_SYSTEM_OBJ_CALL_ARG];
@@ -574,6 +649,9 @@ async fn call_0x5(
         rgp,
     )
     .unwrap();
+    if let Some(unexecuted) = context.dry_run_or_serialize(&tx_data, opts).await? {
+        return Ok(SuiValidatorCommandResponse::Unexecuted(unexecuted));
+    }
     let signature =
         context
             .config
@@ -591,6 +669,7 @@ async fn call_0x5(
             Some(sui_types::messages::ExecuteTransactionRequestType::WaitForLocalExecution),
         )
         .await
+        .map(response)
         .map_err(|err| anyhow::anyhow!(err.to_string()))
 }
 
@@ -621,6 +700,9 @@ impl Display for SuiValidatorCommandResponse {
             SuiValidatorCommandResponse::SerializedPayload(response) => {
                 write!(writer, "Serialized payload: {}", response)?;
             }
+            SuiValidatorCommandResponse::Unexecuted(unexecuted) => {
+                write!(writer, "{}", unexecuted)?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
@@ -827,30 +909,63 @@ async fn update_metadata(
     context: &mut WalletContext,
     metadata: MetadataUpdate,
     gas_budget: u64,
-) -> anyhow::Result<SuiTransactionBlockResponse> {
+    opts: Opts,
+) -> anyhow::Result<SuiValidatorCommandResponse> {
     use ValidatorStatus::*;
     match metadata {
         MetadataUpdate::Name { name } => {
             let args = vec![CallArg::Pure(bcs::to_bytes(&name.into_bytes()).unwrap())];
-            call_0x5(context, "update_validator_name", args, gas_budget).await
+            call_0x5(
+                context,
+                "update_validator_name",
+                args,
+                gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
+            )
+            .await
         }
         MetadataUpdate::Description { description } => {
             let args = vec![CallArg::Pure(
                 bcs::to_bytes(&description.into_bytes()).unwrap(),
             )];
-            call_0x5(context, "update_validator_description", args, gas_budget).await
+            call_0x5(
+                context,
+                "update_validator_description",
+                args,
+                gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
+            )
+            .await
         }
         MetadataUpdate::ImageUrl { image_url } => {
             let args = vec![CallArg::Pure(
                 bcs::to_bytes(&image_url.into_bytes()).unwrap(),
             )];
-            call_0x5(context, "update_validator_image_url", args, gas_budget).await
+            call_0x5(
+                context,
+                "update_validator_image_url",
+                args,
+                gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
+            )
+            .await
         }
         MetadataUpdate::ProjectUrl { project_url } => {
             let args = vec![CallArg::Pure(
                 bcs::to_bytes(&project_url.into_bytes()).unwrap(),
             )];
-            call_0x5(context, "update_validator_project_url", args, gas_budget).await
+            call_0x5(
+                context,
+                "update_validator_project_url",
+                args,
+                gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
+            )
+            .await
         }
         MetadataUpdate::NetworkAddress { network_address } => {
             let _status = check_status(context, HashSet::from([Pending, Active])).await?;
@@ -860,6 +975,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_network_address",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -871,6 +988,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_primary_address",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -883,6 +1002,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_worker_address",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -894,6 +1015,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_p2p_address",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -909,6 +1032,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_network_pubkey",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -924,6 +1049,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_worker_pubkey",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
@@ -947,6 +1074,8 @@ async fn update_metadata(
                 "update_validator_next_epoch_protocol_pubkey",
                 args,
                 gas_budget,
+                opts,
+                SuiValidatorCommandResponse::UpdateMetadata,
             )
             .await
         }
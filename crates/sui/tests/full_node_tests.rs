@@ -12,11 +12,11 @@ use move_core_types::value::MoveStructLayout;
 use mysten_metrics::RegistryService;
 use prometheus::Registry;
 use serde_json::json;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
+use sui::client_commands::{Opts, SuiClientCommandResult, SuiClientCommands, WalletContext};
 use sui_json_rpc_types::EventFilter;
 use sui_json_rpc_types::{
-    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffectsAPI,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::*;
@@ -211,6 +211,63 @@ async fn test_full_node_move_function_index() -> Result<(), anyhow::Error> {
     assert_eq!(txes.len(), 2);
     assert_eq!(txes[1], digest);
 
+    let increment = TransactionFilter::MoveFunction {
+        package: package_ref.0,
+        module: Some("counter".to_string()),
+        function: Some("increment".to_string()),
+    };
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::FromAddress(sender).and(increment.clone())),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+
+    let txes = node.state().get_transactions(
+        Some(TransactionFilter::All(vec![
+            TransactionFilter::MoveFunction {
+                package: package_ref.0,
+                module: None,
+                function: None,
+            },
+            increment.not(),
+        ])),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes.len(), 1);
+    assert_ne!(txes[0], digest);
+
+    // The checkpoint of a transaction is recorded once its checkpoint is executed.
+    let checkpoint = timeout(Duration::from_secs(60), async {
+        loop {
+            if let Some((_, checkpoint)) = node
+                .state()
+                .get_transaction_checkpoint_sequence(&digest)
+                .unwrap()
+            {
+                break checkpoint;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("Transaction checkpoint not executed in time");
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(sender).and(TransactionFilter::CheckpointRange {
+                start_checkpoint: checkpoint,
+                end_checkpoint: checkpoint + 1,
+            }),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes, vec![digest]);
+
     Ok(())
 }
 
@@ -285,6 +342,20 @@ async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     )?;
     assert_eq!(txes.len(), 0);
 
+    // Alternatives are read from the union of their indexes, in transaction order.
+    let txes = node.state().get_transactions(
+        Some(
+            TransactionFilter::FromAddress(receiver)
+                .or(TransactionFilter::InputObject(transferred_object))
+                .or(TransactionFilter::ChangedObject(transferred_object)),
+        ),
+        None,
+        None,
+        false,
+    )?;
+    assert_eq!(txes.len(), 2);
+    assert_eq!(txes[1], digest);
+
     // timestamp is recorded
     let ts = node.state().get_timestamp_ms(&digest).await?;
     assert!(ts.is_some());
@@ -498,6 +569,7 @@ async fn test_full_node_sync_flood() -> Result<(), anyhow::Error> {
                         coin_id: object_to_split.0,
                         gas: Some(gas_object_id),
                         gas_budget: 50000,
+                        opts: Opts::default(),
                     }
                     .execute(context)
                     .await
@@ -646,6 +718,71 @@ async fn test_full_node_sub_and_query_move_event_ok() -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_sub_to_transaction_ok() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+
+    // Start a new fullnode that is not on the write path
+    let fullnode = start_fullnode_from_config(
+        test_cluster
+            .fullnode_config_builder()
+            .with_event_store()
+            .build()
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let node = fullnode.sui_node;
+    let ws_client = fullnode.ws_client;
+
+    let context = &mut test_cluster.wallet;
+    let package_id = publish_nfts_package(context).await.0;
+
+    let mut sub: Subscription<SuiTransactionBlockEffects> = ws_client
+        .subscribe(
+            "suix_subscribeTransaction",
+            rpc_params![TransactionFilter::MoveFunction {
+                package: package_id,
+                module: Some("devnet_nft".to_string()),
+                function: None,
+            }],
+            "suix_unsubscribeTransaction",
+        )
+        .await
+        .unwrap();
+
+    let (_, _, digest) = create_devnet_nft(context, package_id).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // Wait for streaming
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Ok(Some(Ok(effects))) => {
+            assert_eq!(effects.transaction_digest(), &digest);
+            assert!(effects.status().is_ok());
+        }
+        other => panic!("Failed to get SuiTransactionBlockEffects, but {:?}", other),
+    };
+
+    // A transfer does not call the nft package and should not be streamed.
+    let (_, _, _, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // No more
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because no new transactions are coming in. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
 // Test fullnode has event read jsonrpc endpoints working
 #[sim_test]
 async fn test_full_node_event_read_api_ok() {
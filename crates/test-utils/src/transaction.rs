@@ -7,7 +7,7 @@ use shared_crypto::intent::Intent;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use sui::client_commands::WalletContext;
-use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
+use sui::client_commands::{Opts, SuiClientCommandResult, SuiClientCommands};
 use sui_core::authority_client::AuthorityAPI;
 pub use sui_core::test_utils::{
     compile_basics_package, compile_nfts_package, wait_for_all_txes, wait_for_tx,
@@ -411,6 +411,7 @@ pub async fn create_devnet_nft(
         args,
         gas: Some(*gas_object),
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_GENERIC * gas_price,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -454,10 +455,11 @@ pub async fn transfer_sui(
         .unwrap();
 
     let res = SuiClientCommands::TransferSui {
-        to: receiver,
+        to: receiver.into(),
         amount: None,
         sui_coin_object_id: gas_ref.0,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -525,10 +527,11 @@ pub async fn transfer_coin(
         object_to_send, sender, receiver
     );
     let res = SuiClientCommands::Transfer {
-        to: receiver,
+        to: receiver.into(),
         object_id: object_to_send,
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        opts: Opts::default(),
     }
     .execute(context)
     .await?;
@@ -570,6 +573,7 @@ pub async fn split_coin_with_wallet_context(context: &mut WalletContext, coin_id
         count: Some(2),
         gas: None,
         gas_budget: TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN * gas_price,
+        opts: Opts::default(),
     }
     .execute(context)
     .await
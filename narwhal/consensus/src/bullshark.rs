@@ -1,21 +1,19 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::leader_schedule::LeaderSchedule;
 use crate::metrics::ConsensusMetrics;
 use crate::{
-    consensus::{ConsensusProtocol, ConsensusState, Dag},
+    consensus::{ConsensusProtocol, ConsensusState},
     utils, ConsensusError, Outcome,
 };
-use config::{AuthorityIdentifier, Committee, Stake};
+use config::{Committee, Stake};
 use fastcrypto::hash::Hash;
 use std::sync::Arc;
 use storage::ConsensusStore;
 use tokio::time::Instant;
 use tracing::{debug, error_span};
-use types::{
-    Certificate, CertificateAPI, CertificateDigest, CommittedSubDag, HeaderAPI, ReputationScores,
-    Round,
-};
+use types::{Certificate, CertificateAPI, CommittedSubDag, HeaderAPI, ReputationScores, Round};
 
 #[cfg(test)]
 #[path = "tests/bullshark_tests.rs"]
@@ -56,6 +54,8 @@ pub struct Bullshark {
     /// The number of committed subdags that will trigger the schedule change and reputation
     /// score reset.
     pub num_sub_dags_per_schedule: u64,
+    /// The leader election schedule, updated with the final reputation scores of every schedule.
+    pub leader_schedule: LeaderSchedule,
 }
 
 impl ConsensusProtocol for Bullshark {
@@ -92,6 +92,98 @@ impl ConsensusProtocol for Bullshark {
 
         self.max_inserted_certificate_round = self.max_inserted_certificate_round.max(round);
 
+        // Try to commit a leader. When the leader schedule changes while committing, the leaders
+        // of the rounds that are not committed yet might be different under the new schedule, so
+        // we elect them again.
+        let mut committed_sub_dags = Vec::new();
+        let outcome = loop {
+            let (outcome, committed) = self.commit_leader(state, round)?;
+            committed_sub_dags.extend(committed);
+
+            if outcome != Outcome::ScheduleChanged {
+                break outcome;
+            }
+        };
+
+        // If nothing has been committed we simply return the outcome.
+        if committed_sub_dags.is_empty() {
+            return Ok((outcome, committed_sub_dags));
+        }
+
+        let total_committed_certificates: usize =
+            committed_sub_dags.iter().map(|sub_dag| sub_dag.len()).sum();
+
+        // record the last time we got a successful leader election
+        let elapsed = self.last_successful_leader_election_timestamp.elapsed();
+
+        self.metrics
+            .commit_rounds_latency
+            .observe(elapsed.as_secs_f64());
+
+        self.last_successful_leader_election_timestamp = Instant::now();
+
+        self.metrics
+            .leader_election
+            .with_label_values(&["elected"])
+            .inc();
+
+        // The total leader_commits are expected to grow the same amount on validators,
+        // but strong vs weak counts are not expected to be the same across validators.
+        self.metrics
+            .leader_commits
+            .with_label_values(&["strong"])
+            .inc();
+        self.metrics
+            .leader_commits
+            .with_label_values(&["weak"])
+            .inc_by(committed_sub_dags.len() as u64 - 1);
+
+        // Log the latest committed round of every authority (for debug).
+        // Performance note: if tracing at the debug log level is disabled, this is cheap, see
+        // https://github.com/tokio-rs/tracing/pull/326
+        for (name, round) in &state.last_committed {
+            debug!("Latest commit of {}: Round {}", name, round);
+        }
+
+        self.metrics
+            .committed_certificates
+            .report(total_committed_certificates as u64);
+
+        Ok((Outcome::Commit, committed_sub_dags))
+    }
+}
+
+impl Bullshark {
+    /// Create a new Bullshark consensus instance. The leader schedule is restored from the
+    /// reputation scores of the latest schedule found in the store.
+    pub fn new(
+        committee: Committee,
+        store: Arc<ConsensusStore>,
+        metrics: Arc<ConsensusMetrics>,
+        num_sub_dags_per_schedule: u64,
+    ) -> Self {
+        let leader_schedule = LeaderSchedule::from_store(committee.clone(), store.clone());
+
+        Self {
+            committee,
+            store,
+            last_successful_leader_election_timestamp: Instant::now(),
+            last_leader_election: LastRound::default(),
+            max_inserted_certificate_round: 0,
+            metrics,
+            num_sub_dags_per_schedule,
+            leader_schedule,
+        }
+    }
+
+    /// Commits the leader of the round below the provided `round` (if any), along with all the
+    /// preceding leaders linked to it that are not committed yet. Returns `Outcome::ScheduleChanged`
+    /// when the leader schedule has been updated before all the leaders got committed.
+    fn commit_leader(
+        &mut self,
+        state: &mut ConsensusState,
+        round: Round,
+    ) -> Result<(Outcome, Vec<CommittedSubDag>), ConsensusError> {
         // Try to order the dag to commit. Start from the highest round for which we have at least
         // f+1 certificates. This is because we need them to provide
         // enough support to the leader.
@@ -108,7 +200,9 @@ impl ConsensusProtocol for Bullshark {
         if leader_round <= state.last_round.committed_round {
             return Ok((Outcome::LeaderBelowCommitRound, Vec::new()));
         }
-        let (leader_digest, leader) = match Self::leader(&self.committee, leader_round, &state.dag)
+        let (leader_digest, leader) = match self
+            .leader_schedule
+            .leader_certificate(leader_round, &state.dag)
         {
             Some(x) => x,
             None => {
@@ -149,13 +243,13 @@ impl ConsensusProtocol for Bullshark {
         // Get an ordered list of past leaders that are linked to the current leader.
         debug!("Leader {:?} has enough support", leader);
         let mut committed_sub_dags = Vec::new();
-        let mut total_committed_certificates = 0;
 
         // TODO: duplicated in tusk.rs
-        for leader in utils::order_leaders(&self.committee, leader, state, Self::leader)
-            .iter()
-            .rev()
-        {
+        let leaders_to_commit =
+            utils::order_leaders(&self.committee, leader, state, |_, round, dag| {
+                self.leader_schedule.leader_certificate(round, dag)
+            });
+        for leader in leaders_to_commit.iter().rev() {
             let sub_dag_index = state.next_sub_dag_index();
             let _span = error_span!("bullshark_process_sub_dag", sub_dag_index);
 
@@ -177,8 +271,6 @@ impl ConsensusProtocol for Bullshark {
             }
             debug!(min_round, "Subdag has {} certificates", sequence.len());
 
-            total_committed_certificates += sequence.len();
-
             // We resolve the reputation score that should be stored alongside with this sub dag.
             let reputation_score = self.resolve_reputation_score(state, &sequence, sub_dag_index);
 
@@ -197,110 +289,21 @@ impl ConsensusProtocol for Bullshark {
             // Update the last sub dag
             state.last_committed_sub_dag = Some(sub_dag.clone());
 
-            committed_sub_dags.push(sub_dag);
-        }
-
-        // record the last time we got a successful leader election
-        let elapsed = self.last_successful_leader_election_timestamp.elapsed();
-
-        self.metrics
-            .commit_rounds_latency
-            .observe(elapsed.as_secs_f64());
-
-        self.last_successful_leader_election_timestamp = Instant::now();
-
-        self.metrics
-            .leader_election
-            .with_label_values(&["elected"])
-            .inc();
-
-        // The total leader_commits are expected to grow the same amount on validators,
-        // but strong vs weak counts are not expected to be the same across validators.
-        self.metrics
-            .leader_commits
-            .with_label_values(&["strong"])
-            .inc();
-        self.metrics
-            .leader_commits
-            .with_label_values(&["weak"])
-            .inc_by(committed_sub_dags.len() as u64 - 1);
-
-        // Log the latest committed round of every authority (for debug).
-        // Performance note: if tracing at the debug log level is disabled, this is cheap, see
-        // https://github.com/tokio-rs/tracing/pull/326
-        for (name, round) in &state.last_committed {
-            debug!("Latest commit of {}: Round {}", name, round);
-        }
-
-        self.metrics
-            .committed_certificates
-            .report(total_committed_certificates as u64);
-
-        Ok((Outcome::Commit, committed_sub_dags))
-    }
-}
+            // The final scores of the schedule update the leader schedule. The leaders of the
+            // rounds above this one have been elected under the previous schedule, so if any of
+            // them is left to commit we stop here and elect them again.
+            let schedule_changed = self
+                .leader_schedule
+                .update_leader_swap_table(leader.round(), &sub_dag.reputation_score);
 
-impl Bullshark {
-    /// Create a new Bullshark consensus instance.
-    pub fn new(
-        committee: Committee,
-        store: Arc<ConsensusStore>,
-        metrics: Arc<ConsensusMetrics>,
-        num_sub_dags_per_schedule: u64,
-    ) -> Self {
-        Self {
-            committee,
-            store,
-            last_successful_leader_election_timestamp: Instant::now(),
-            last_leader_election: LastRound::default(),
-            max_inserted_certificate_round: 0,
-            metrics,
-            num_sub_dags_per_schedule,
-        }
-    }
-
-    // Returns the PublicKey of the authority which is the leader for the provided `round`.
-    // Pay attention that this method will return always the first authority as the leader
-    // when used under a test environment.
-    pub fn leader_authority(committee: &Committee, round: Round) -> AuthorityIdentifier {
-        assert_eq!(
-            round % 2,
-            0,
-            "We should never attempt to do a leader election for odd rounds"
-        );
+            committed_sub_dags.push(sub_dag);
 
-        cfg_if::cfg_if! {
-            if #[cfg(test)] {
-                // We apply round robin in leader election. Since we expect round to be an even number,
-                // 2, 4, 6, 8... it can't work well for leader election as we'll omit leaders. Thus
-                // we can always divide by 2 to get a monotonically incremented sequence,
-                // 2/2 = 1, 4/2 = 2, 6/2 = 3, 8/2 = 4  etc, and then do minus 1 so we can always
-                // start with base zero 0.
-                let next_leader = (round/2 - 1) as usize % committee.size();
-                let authorities = committee.authorities().collect::<Vec<_>>();
-
-                authorities.get(next_leader).unwrap().id()
-            } else {
-                // Elect the leader in a stake-weighted choice seeded by the round
-                committee.leader(round).id()
+            if schedule_changed && leader != &leaders_to_commit[0] {
+                return Ok((Outcome::ScheduleChanged, committed_sub_dags));
             }
         }
-    }
 
-    // TODO: duplicated in tusk.rs
-    /// Returns the certificate (and the certificate's digest) originated by the leader of the
-    /// specified round (if any).
-    fn leader<'a>(
-        committee: &Committee,
-        round: Round,
-        dag: &'a Dag,
-    ) -> Option<&'a (CertificateDigest, Certificate)> {
-        // Note: this function is often called with even rounds only. While we do not aim at random selection
-        // yet (see issue #10), repeated calls to this function should still pick from the whole roster of leaders.
-        let leader = Self::leader_authority(committee, round);
-
-        // Return its certificate and the certificate's digest.
-        dag.get(&round).and_then(|x| x.get(&leader))
+        Ok((Outcome::Commit, committed_sub_dags))
     }
 
     /// Calculates the reputation score for the current commit by taking into account the reputation
@@ -313,8 +316,6 @@ impl Bullshark {
     ) -> ReputationScores {
         // we reset the scores for every schedule change window, or initialise when it's the first
         // sub dag we are going to create.
-        // TODO: when schedule change is implemented we should probably change a little bit
-        // this logic here.
         let mut reputation_score =
             if sub_dag_index == 1 || sub_dag_index % self.num_sub_dags_per_schedule == 0 {
                 ReputationScores::new(&self.committee)
@@ -0,0 +1,244 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus::Dag;
+use config::{Authority, AuthorityIdentifier, Committee, Stake};
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use storage::ConsensusStore;
+use tracing::debug;
+use types::{Certificate, CertificateDigest, ReputationScores, Round};
+
+#[cfg(test)]
+#[path = "tests/leader_schedule_tests.rs"]
+pub mod leader_schedule_tests;
+
+/// The percentage of the total stake up to which the worst (and best) scoring authorities are
+/// considered bad (and good) nodes when the leader swap table is built. It must be the same on
+/// every node, otherwise they will elect different leaders.
+pub const BAD_NODES_STAKE_THRESHOLD: u64 = 20;
+
+/// Swaps the leader slots of the worst scoring authorities (the bad nodes) of a schedule with the
+/// best scoring ones (the good nodes) for the next schedule.
+#[derive(Clone, Debug, Default)]
+pub struct LeaderSwapTable {
+    /// The round of the commit whose final reputation scores built the table.
+    round: Round,
+    /// The worst scoring authorities, whose leader slots are swapped.
+    bad_nodes: HashMap<AuthorityIdentifier, Authority>,
+    /// The best scoring authorities, which take over the leader slots of the bad nodes.
+    good_nodes: Vec<Authority>,
+}
+
+impl LeaderSwapTable {
+    /// Builds the table out of the final reputation scores of a schedule, as committed with the
+    /// leader of the provided `round`.
+    pub fn new(committee: &Committee, round: Round, reputation_scores: &ReputationScores) -> Self {
+        assert!(
+            reputation_scores.final_of_schedule,
+            "Only the final reputation scores of a schedule should be used"
+        );
+
+        // Nobody has scored, so there is no way to tell the good nodes from the bad ones.
+        if reputation_scores.all_zero() {
+            return Self {
+                round,
+                ..Default::default()
+            };
+        }
+
+        let authorities = reputation_scores.authorities_by_score_desc();
+
+        let good_nodes =
+            retrieve_first_nodes(committee, authorities.iter(), BAD_NODES_STAKE_THRESHOLD);
+        // The best scoring authority alone holds more than the threshold, so there is nobody to
+        // hand the slots of the bad nodes to.
+        if good_nodes.is_empty() {
+            debug!(
+                round,
+                "No good node below the stake threshold, no leader is swapped"
+            );
+            return Self {
+                round,
+                ..Default::default()
+            };
+        }
+        let bad_nodes = retrieve_first_nodes(
+            committee,
+            authorities.iter().rev(),
+            BAD_NODES_STAKE_THRESHOLD,
+        )
+        .into_iter()
+        .map(|authority| (authority.id(), authority))
+        .collect();
+
+        debug!(round, ?good_nodes, ?bad_nodes, "Leader swap table updated");
+
+        Self {
+            round,
+            bad_nodes,
+            good_nodes,
+        }
+    }
+
+    /// The round of the commit whose final reputation scores built the table.
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
+    pub fn bad_nodes(&self) -> &HashMap<AuthorityIdentifier, Authority> {
+        &self.bad_nodes
+    }
+
+    pub fn good_nodes(&self) -> &[Authority] {
+        &self.good_nodes
+    }
+
+    /// Returns the good node that takes over the slot of the `leader` on `leader_round` when the
+    /// leader is a bad node and there is a good node to swap it with, otherwise None. The good
+    /// node is picked by a choice seeded by the round, so every node picks the same one.
+    pub fn swap(&self, leader: &AuthorityIdentifier, leader_round: Round) -> Option<Authority> {
+        if !self.bad_nodes.contains_key(leader) {
+            return None;
+        }
+
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes[32 - 8..].copy_from_slice(&leader_round.to_le_bytes());
+        let mut rng = StdRng::from_seed(seed_bytes);
+
+        self.good_nodes.choose(&mut rng).cloned()
+    }
+}
+
+/// Returns the first authorities of the provided iterator as long as their accumulated stake does
+/// not exceed the `stake_threshold` percentage of the total stake.
+fn retrieve_first_nodes<'a>(
+    committee: &Committee,
+    authorities: impl Iterator<Item = &'a (AuthorityIdentifier, u64)>,
+    stake_threshold: u64,
+) -> Vec<Authority> {
+    let max_stake: Stake = stake_threshold * committee.total_stake() / 100;
+
+    let mut filtered_authorities = Vec::new();
+    let mut stake = 0;
+    for (authority_id, _score) in authorities {
+        // Scores might have been calculated for authorities of a different committee.
+        let authority = match committee.authority(authority_id) {
+            Some(authority) => authority,
+            None => continue,
+        };
+
+        stake += authority.stake();
+        if stake > max_stake {
+            break;
+        }
+
+        filtered_authorities.push(authority.clone());
+    }
+    filtered_authorities
+}
+
+/// Elects the leader of every round. Leaders are chosen by a stake-weighted choice seeded by the
+/// round, and then swapped by the leader swap table of the latest completed schedule, so the slots
+/// of the worst scoring authorities are handed to the best scoring ones.
+#[derive(Clone, Debug)]
+pub struct LeaderSchedule {
+    committee: Committee,
+    leader_swap_table: LeaderSwapTable,
+}
+
+impl LeaderSchedule {
+    pub fn new(committee: Committee, leader_swap_table: LeaderSwapTable) -> Self {
+        Self {
+            committee,
+            leader_swap_table,
+        }
+    }
+
+    /// Restores the schedule from the final reputation scores of the latest schedule that has
+    /// been committed, so a node recovering from a crash elects the same leaders as the others.
+    pub fn from_store(committee: Committee, store: Arc<ConsensusStore>) -> Self {
+        let leader_swap_table = store
+            .read_latest_commit_with_final_reputation_scores()
+            .map(|commit| {
+                LeaderSwapTable::new(
+                    &committee,
+                    commit.leader_round(),
+                    &commit.reputation_score(),
+                )
+            })
+            .unwrap_or_default();
+
+        Self::new(committee, leader_swap_table)
+    }
+
+    pub fn leader_swap_table(&self) -> &LeaderSwapTable {
+        &self.leader_swap_table
+    }
+
+    /// Updates the leader swap table with the final reputation scores of a schedule, committed
+    /// with the leader of the provided `round`. The scores are ignored if they are not final.
+    /// Returns true when the table has been updated.
+    pub fn update_leader_swap_table(
+        &mut self,
+        round: Round,
+        reputation_scores: &ReputationScores,
+    ) -> bool {
+        if !reputation_scores.final_of_schedule {
+            return false;
+        }
+
+        self.leader_swap_table = LeaderSwapTable::new(&self.committee, round, reputation_scores);
+        true
+    }
+
+    // Returns the authority which is the leader for the provided `round`.
+    // Pay attention that this method will elect the authorities in a round robin fashion
+    // when used under a test environment.
+    pub fn leader(&self, round: Round) -> Authority {
+        assert_eq!(
+            round % 2,
+            0,
+            "We should never attempt to do a leader election for odd rounds"
+        );
+
+        cfg_if::cfg_if! {
+            if #[cfg(test)] {
+                // We apply round robin in leader election. Since we expect round to be an even number,
+                // 2, 4, 6, 8... it can't work well for leader election as we'll omit leaders. Thus
+                // we can always divide by 2 to get a monotonically incremented sequence,
+                // 2/2 = 1, 4/2 = 2, 6/2 = 3, 8/2 = 4  etc, and then do minus 1 so we can always
+                // start with base zero 0.
+                let next_leader = (round/2 - 1) as usize % self.committee.size();
+                let authorities = self.committee.authorities().collect::<Vec<_>>();
+
+                let leader: Authority = (*authorities.get(next_leader).unwrap()).clone();
+            } else {
+                // Elect the leader in a stake-weighted choice seeded by the round
+                let leader = self.committee.leader(round);
+            }
+        }
+
+        self.leader_swap_table
+            .swap(&leader.id(), round)
+            .unwrap_or(leader)
+    }
+
+    /// Returns the certificate (and the certificate's digest) originated by the leader of the
+    /// specified round (if any).
+    pub fn leader_certificate<'a>(
+        &self,
+        round: Round,
+        dag: &'a Dag,
+    ) -> Option<&'a (CertificateDigest, Certificate)> {
+        // Note: this function is often called with even rounds only. While we do not aim at random selection
+        // yet (see issue #10), repeated calls to this function should still pick from the whole roster of leaders.
+        let leader = self.leader(round);
+
+        // Return its certificate and the certificate's digest.
+        dag.get(&round).and_then(|x| x.get(&leader.id()))
+    }
+}
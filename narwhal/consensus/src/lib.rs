@@ -13,6 +13,7 @@ pub mod consensus;
 #[path = "tests/consensus_utils.rs"]
 pub mod consensus_utils;
 pub mod dag;
+pub mod leader_schedule;
 pub mod metrics;
 pub mod tusk;
 pub mod utils;
@@ -63,4 +64,8 @@ pub enum Outcome {
 
     // Processed Certificate triggered a commit.
     Commit,
+
+    // The leader schedule has been changed by the final reputation scores of a commit while there
+    // are still leaders to commit, so the leaders need to be elected again under the new schedule.
+    ScheduleChanged,
 }
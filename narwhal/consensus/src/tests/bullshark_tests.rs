@@ -9,11 +9,13 @@ use crate::consensus::ConsensusRound;
 use crate::consensus_utils::NUM_SUB_DAGS_PER_SCHEDULE;
 use crate::consensus_utils::*;
 use crate::{metrics::ConsensusMetrics, Consensus, NUM_SHUTDOWN_RECEIVERS};
+use config::AuthorityIdentifier;
 #[allow(unused_imports)]
 use fastcrypto::traits::KeyPair;
 use prometheus::Registry;
 #[cfg(test)]
 use std::collections::{BTreeSet, VecDeque};
+use std::num::NonZeroUsize;
 use test_utils::CommitteeFixture;
 #[allow(unused_imports)]
 use tokio::sync::mpsc::channel;
@@ -721,6 +723,74 @@ async fn reset_consensus_scores_on_every_schedule_change() {
     }
 }
 
+/// Advance the DAG for 30 rounds with a committee of 5, changing schedule every 5 subdag commits.
+/// Every authority scores the same, so the ties are broken by the authority identifier and the
+/// leader slots of the first authority should go to the last one from the first schedule change.
+#[tokio::test]
+async fn swap_leaders_on_schedule_change() {
+    const NUM_SUB_DAGS_PER_SCHEDULE: u64 = 5;
+
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .build();
+    let committee = fixture.committee();
+    let ids: Vec<AuthorityIdentifier> = committee.authorities().map(|a| a.id()).collect();
+    let epoch = committee.epoch();
+    let gc_depth = 50;
+
+    // Make certificates for rounds 1 to 30.
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let metrics = Arc::new(ConsensusMetrics::new(&Registry::new()));
+    let (certificates, _) =
+        test_utils::make_certificates_with_epoch(&committee, 1..=30, epoch, &genesis, &ids);
+
+    let store = make_consensus_store(&test_utils::temp_dir());
+    let mut state = ConsensusState::new(metrics.clone(), gc_depth);
+    let mut bullshark = Bullshark::new(
+        committee.clone(),
+        store.clone(),
+        metrics.clone(),
+        NUM_SUB_DAGS_PER_SCHEDULE,
+    );
+
+    let mut all_subdags = Vec::new();
+    for certificate in certificates {
+        let (_, committed_subdags) = bullshark
+            .process_certificate(&mut state, certificate)
+            .unwrap();
+        all_subdags.extend(committed_subdags);
+    }
+    assert_eq!(all_subdags.len(), 14);
+
+    for sub_dag in &all_subdags {
+        let leader = sub_dag.leader.origin();
+
+        if sub_dag.sub_dag_index < NUM_SUB_DAGS_PER_SCHEDULE {
+            // Before the first schedule change leaders are elected in a round robin fashion.
+            let expected = (sub_dag.leader_round() / 2 - 1) as usize % committee.size();
+            assert_eq!(leader, ids[expected]);
+        } else {
+            // Afterwards the first authority never leads, its slots are taken by the last one.
+            assert_ne!(leader, ids[0]);
+            if (sub_dag.leader_round() / 2 - 1) as usize % committee.size() == 0 {
+                assert_eq!(leader, ids[4]);
+            }
+        }
+    }
+
+    // AND a restarted instance restores the schedule from the store
+    let bullshark = Bullshark::new(committee, store, metrics, NUM_SUB_DAGS_PER_SCHEDULE);
+    assert!(bullshark
+        .leader_schedule
+        .leader_swap_table()
+        .bad_nodes()
+        .contains_key(&ids[0]));
+    assert_eq!(bullshark.leader_schedule.leader(32).id(), ids[4]);
+}
+
 // Run for 4 dag rounds in ideal conditions (all nodes reference all other nodes). We should commit
 // the leader of round 2. Then shutdown consensus and restart in a new epoch.
 #[tokio::test]
@@ -15,6 +15,7 @@ use tokio::sync::watch;
 use crate::bullshark::Bullshark;
 use crate::consensus::ConsensusRound;
 use crate::consensus_utils::NUM_SUB_DAGS_PER_SCHEDULE;
+use crate::leader_schedule::{LeaderSchedule, LeaderSwapTable};
 use crate::metrics::ConsensusMetrics;
 use crate::Consensus;
 use crate::NUM_SHUTDOWN_RECEIVERS;
@@ -131,12 +132,13 @@ async fn test_consensus_recovery_with_bullshark() {
 
     // AND the last committed store should be updated correctly
     let last_committed = consensus_store.read_last_committed();
+    let leader_schedule = LeaderSchedule::new(committee.clone(), LeaderSwapTable::default());
 
     for id in ids.clone() {
         let last_round = *last_committed.get(&id).unwrap();
 
         // For the leader of round 6 we expect to have last committed round of 6.
-        if id == Bullshark::leader_authority(&committee, 6) {
+        if id == leader_schedule.leader(6).id() {
             assert_eq!(last_round, 6);
         } else {
             // For the others should be 5.
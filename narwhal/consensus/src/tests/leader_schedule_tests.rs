@@ -0,0 +1,199 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::consensus_utils::make_consensus_store;
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use test_utils::CommitteeFixture;
+use types::CommittedSubDag;
+
+/// Reputation scores where every authority scores as much as its identifier.
+fn scores_by_id(committee: &Committee) -> ReputationScores {
+    let mut scores = ReputationScores::new(committee);
+    for authority in committee.authorities() {
+        scores.add_score(authority.id(), authority.id().0 as u64);
+    }
+    scores.final_of_schedule = true;
+    scores
+}
+
+#[test]
+fn test_leader_swap_table() {
+    // GIVEN a committee of 5 authorities of equal stake, so 20% of the stake is one authority
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .build();
+    let committee = fixture.committee();
+
+    // WHEN building the table
+    let table = LeaderSwapTable::new(&committee, 10, &scores_by_id(&committee));
+
+    // THEN the worst scoring authority is the only bad node
+    assert_eq!(table.round(), 10);
+    assert_eq!(table.bad_nodes().len(), 1);
+    assert!(table.bad_nodes().contains_key(&AuthorityIdentifier(0)));
+
+    // AND the best scoring authority is the only good node
+    assert_eq!(table.good_nodes().len(), 1);
+    assert_eq!(table.good_nodes()[0].id(), AuthorityIdentifier(4));
+
+    // AND only the bad node gets swapped, for every round
+    for round in (2..20).step_by(2) {
+        let swapped = table.swap(&AuthorityIdentifier(0), round).unwrap();
+        assert_eq!(swapped.id(), AuthorityIdentifier(4));
+
+        for id in 1..5 {
+            assert!(table.swap(&AuthorityIdentifier(id), round).is_none());
+        }
+    }
+}
+
+#[test]
+fn test_leader_swap_table_with_equal_scores() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .build();
+    let committee = fixture.committee();
+
+    // GIVEN all authorities scoring the same
+    let mut scores = ReputationScores::new(&committee);
+    for authority in committee.authorities() {
+        scores.add_score(authority.id(), 3);
+    }
+    scores.final_of_schedule = true;
+
+    // THEN ties are broken by the authority identifier, so every node builds the same table
+    let table = LeaderSwapTable::new(&committee, 10, &scores);
+    assert!(table.bad_nodes().contains_key(&AuthorityIdentifier(0)));
+    assert_eq!(table.good_nodes()[0].id(), AuthorityIdentifier(4));
+
+    // AND nothing is swapped when nobody has scored
+    let table = LeaderSwapTable::new(
+        &committee,
+        10,
+        &ReputationScores {
+            final_of_schedule: true,
+            ..ReputationScores::new(&committee)
+        },
+    );
+    assert!(table.bad_nodes().is_empty());
+    assert!(table.good_nodes().is_empty());
+}
+
+#[test]
+fn test_leader_swap_table_below_stake_threshold() {
+    // GIVEN a committee of 4 authorities of equal stake, where a single authority is above 20% of
+    // the stake
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+
+    // THEN no authority is swapped
+    let table = LeaderSwapTable::new(&committee, 10, &scores_by_id(&committee));
+    assert!(table.bad_nodes().is_empty());
+    assert!(table.good_nodes().is_empty());
+}
+
+#[test]
+fn test_leader_swap_table_with_skewed_stake() {
+    // GIVEN a committee where the best scoring authority alone holds more than 20% of the stake,
+    // while the two worst scoring ones together hold 20% of it
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .stake_distribution(VecDeque::from(vec![1, 1, 1, 1, 6]))
+        .build();
+    let committee = fixture.committee();
+
+    // WHEN building the table
+    let table = LeaderSwapTable::new(&committee, 10, &scores_by_id(&committee));
+
+    // THEN there is no good node to swap with, so nobody is swapped
+    assert!(table.good_nodes().is_empty());
+    assert!(table.bad_nodes().is_empty());
+    for round in (2..20).step_by(2) {
+        for id in 0..5 {
+            assert!(table.swap(&AuthorityIdentifier(id), round).is_none());
+        }
+    }
+
+    // AND the schedule keeps electing the leaders in a round robin fashion
+    let mut schedule = LeaderSchedule::new(committee.clone(), LeaderSwapTable::default());
+    assert!(schedule.update_leader_swap_table(10, &scores_by_id(&committee)));
+    assert_eq!(schedule.leader(12).id(), AuthorityIdentifier(0));
+    assert_eq!(schedule.leader(14).id(), AuthorityIdentifier(1));
+}
+
+#[test]
+fn test_leader_schedule() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .build();
+    let committee = fixture.committee();
+
+    // GIVEN a schedule without any swaps, leaders are elected in a round robin fashion
+    let mut schedule = LeaderSchedule::new(committee.clone(), LeaderSwapTable::default());
+    assert_eq!(schedule.leader(2).id(), AuthorityIdentifier(0));
+    assert_eq!(schedule.leader(4).id(), AuthorityIdentifier(1));
+
+    // WHEN updating the schedule with scores that are not final
+    let mut scores = scores_by_id(&committee);
+    scores.final_of_schedule = false;
+
+    // THEN the schedule is not changed
+    assert!(!schedule.update_leader_swap_table(10, &scores));
+    assert_eq!(schedule.leader(12).id(), AuthorityIdentifier(0));
+
+    // WHEN updating the schedule with the final scores
+    scores.final_of_schedule = true;
+    assert!(schedule.update_leader_swap_table(10, &scores));
+
+    // THEN the slots of the worst scoring authority are taken by the best scoring one
+    assert_eq!(schedule.leader_swap_table().round(), 10);
+    assert_eq!(schedule.leader(12).id(), AuthorityIdentifier(4));
+    assert_eq!(schedule.leader(14).id(), AuthorityIdentifier(1));
+}
+
+#[tokio::test]
+async fn test_leader_schedule_from_store() {
+    let fixture = CommitteeFixture::builder()
+        .committee_size(NonZeroUsize::new(5).unwrap())
+        .build();
+    let committee = fixture.committee();
+    let store = make_consensus_store(&test_utils::temp_dir());
+
+    // GIVEN an empty store, the schedule has no swaps
+    let schedule = LeaderSchedule::from_store(committee.clone(), store.clone());
+    assert!(schedule.leader_swap_table().bad_nodes().is_empty());
+    assert_eq!(schedule.leader(2).id(), AuthorityIdentifier(0));
+
+    // WHEN a commit with the final scores of a schedule is persisted, followed by a commit of
+    // the next schedule
+    let sub_dag = CommittedSubDag::new(
+        vec![],
+        Certificate::default(),
+        1,
+        scores_by_id(&committee),
+        None,
+    );
+    store
+        .write_consensus_state(&HashMap::new(), &sub_dag)
+        .unwrap();
+    let sub_dag = CommittedSubDag::new(
+        vec![],
+        Certificate::default(),
+        2,
+        ReputationScores::new(&committee),
+        None,
+    );
+    store
+        .write_consensus_state(&HashMap::new(), &sub_dag)
+        .unwrap();
+
+    // THEN the schedule is restored from the final scores
+    let schedule = LeaderSchedule::from_store(committee, store);
+    assert!(schedule
+        .leader_swap_table()
+        .bad_nodes()
+        .contains_key(&AuthorityIdentifier(0)));
+    assert_eq!(schedule.leader(2).id(), AuthorityIdentifier(4));
+}
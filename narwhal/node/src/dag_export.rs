@@ -0,0 +1,325 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of the DAG persisted by a node, to inspect it when consensus stalls. The certificates,
+//! the headers that have not been certified and the commits of a range of rounds are read from
+//! the stores of a stopped node, and exported as JSON or as a Graphviz DOT graph.
+
+use config::{AuthorityIdentifier, Committee};
+use consensus::leader_schedule::{LeaderSchedule, LeaderSwapTable};
+use fastcrypto::hash::Hash;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use storage::{NodeStorage, StoreResult};
+use types::{
+    Certificate, CertificateAPI, CertificateDigest, HeaderAPI, HeaderDigest, Round, SequenceNumber,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DagExportFormat {
+    Json,
+    Dot,
+}
+
+impl FromStr for DagExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!(
+                "Unknown DAG export format {s}, expected json or dot"
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DagExport {
+    pub epoch: u64,
+    pub from_round: Round,
+    pub to_round: Round,
+    pub rounds: Vec<RoundExport>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundExport {
+    pub round: Round,
+    /// The authority elected as leader of the round, only set for even rounds.
+    pub leader: Option<AuthorityIdentifier>,
+    pub certificates: Vec<CertificateExport>,
+    /// The headers of the round which have not been certified.
+    pub uncertified_headers: Vec<HeaderExport>,
+    /// The authorities without a certificate in the round.
+    pub missing: Vec<AuthorityIdentifier>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateExport {
+    pub digest: String,
+    pub author: AuthorityIdentifier,
+    pub parents: Vec<String>,
+    pub num_batches: usize,
+    /// The index of the sub dag which committed the certificate, if committed.
+    pub committed_sub_dag_index: Option<SequenceNumber>,
+    /// Whether the certificate is the leader of a committed sub dag.
+    pub committed_leader: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderExport {
+    pub digest: String,
+    pub author: AuthorityIdentifier,
+    pub parents: Vec<String>,
+}
+
+/// Reads the DAG of the provided `rounds` from the stores of the node. The `committee` must be
+/// the committee of the epoch the stores have been written in.
+pub fn export_dag(
+    store: &NodeStorage,
+    committee: &Committee,
+    rounds: RangeInclusive<Round>,
+) -> StoreResult<DagExport> {
+    // The sub dag index of every committed certificate, and the leaders of the sub dags.
+    let commits = store.consensus_store.read_committed_sub_dags_from(&0)?;
+    let mut committed = HashMap::new();
+    let mut committed_leaders = HashSet::new();
+    for commit in &commits {
+        for digest in commit.certificates() {
+            committed.insert(digest, commit.sub_dag_index());
+        }
+        committed_leaders.insert(commit.leader());
+    }
+
+    // The leader schedules in use over the epoch. The final reputation scores of a schedule
+    // change the leaders of the rounds above the round of the commit they have been committed with.
+    let mut schedules = vec![(
+        0,
+        LeaderSchedule::new(committee.clone(), LeaderSwapTable::default()),
+    )];
+    for commit in &commits {
+        let reputation_scores = commit.reputation_score();
+        if reputation_scores.final_of_schedule {
+            let leader_swap_table =
+                LeaderSwapTable::new(committee, commit.leader_round(), &reputation_scores);
+            schedules.push((
+                commit.leader_round(),
+                LeaderSchedule::new(committee.clone(), leader_swap_table),
+            ));
+        }
+    }
+
+    let mut certificates_by_round: BTreeMap<Round, Vec<Certificate>> = BTreeMap::new();
+    for certificate in store.certificate_store.after_round(*rounds.start())? {
+        if rounds.contains(&certificate.round()) {
+            certificates_by_round
+                .entry(certificate.round())
+                .or_default()
+                .push(certificate);
+        }
+    }
+
+    let mut certified_headers = HashSet::new();
+    let mut rounds_export = Vec::new();
+    for round in rounds.clone() {
+        let certificates = certificates_by_round.remove(&round).unwrap_or_default();
+
+        let leader = (round % 2 == 0 && round > 0).then(|| {
+            let (_, schedule) = schedules
+                .iter()
+                .rev()
+                .find(|(schedule_round, _)| *schedule_round < round)
+                .expect("The initial schedule applies to every round");
+            schedule.leader(round).id()
+        });
+
+        let authors: HashSet<_> = certificates.iter().map(|c| c.origin()).collect();
+        let missing = committee
+            .authorities()
+            .map(|authority| authority.id())
+            .filter(|id| !authors.contains(id))
+            .collect();
+
+        let certificates = certificates
+            .iter()
+            .map(|certificate| {
+                let digest = certificate.digest();
+                certified_headers.insert(certificate.header().digest());
+
+                CertificateExport {
+                    digest: format!("{digest:?}"),
+                    author: certificate.origin(),
+                    parents: digests(certificate.header().parents()),
+                    num_batches: certificate.header().payload().len(),
+                    committed_sub_dag_index: committed.get(&digest).copied(),
+                    committed_leader: committed_leaders.contains(&digest),
+                }
+            })
+            .collect();
+
+        rounds_export.push(RoundExport {
+            round,
+            leader,
+            certificates,
+            uncertified_headers: vec![],
+            missing,
+        });
+    }
+
+    for header in store.header_store.read_all_in_rounds(rounds.clone())? {
+        let digest: HeaderDigest = header.digest();
+        if certified_headers.contains(&digest) {
+            continue;
+        }
+        let index = (header.round() - rounds.start()) as usize;
+        rounds_export[index].uncertified_headers.push(HeaderExport {
+            digest: format!("{digest:?}"),
+            author: header.author(),
+            parents: digests(header.parents()),
+        });
+    }
+
+    Ok(DagExport {
+        epoch: committee.epoch(),
+        from_round: *rounds.start(),
+        to_round: *rounds.end(),
+        rounds: rounds_export,
+    })
+}
+
+fn digests<'a>(digests: impl IntoIterator<Item = &'a CertificateDigest>) -> Vec<String> {
+    digests
+        .into_iter()
+        .map(|digest| format!("{digest:?}"))
+        .collect()
+}
+
+impl DagExport {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Renders the DAG as a Graphviz graph, with an edge from every certificate to each of its
+    /// parents within the exported rounds. The elected leaders are drawn in bold, committed
+    /// certificates are filled, and missing certificates are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let exported: HashSet<&String> = self
+            .rounds
+            .iter()
+            .flat_map(|round| round.certificates.iter().map(|c| &c.digest))
+            .collect();
+
+        let mut dot = String::new();
+        writeln!(dot, "digraph dag {{").unwrap();
+        writeln!(dot, "  rankdir=BT;").unwrap();
+        writeln!(dot, "  node [shape=box, fontname=monospace];").unwrap();
+
+        for round in &self.rounds {
+            writeln!(dot, "  subgraph rank_{} {{", round.round).unwrap();
+            writeln!(dot, "    rank=same;").unwrap();
+            writeln!(
+                dot,
+                "    \"round_{0}\" [shape=plaintext, label=\"Round {0}\"];",
+                round.round
+            )
+            .unwrap();
+
+            for certificate in &round.certificates {
+                let mut label = format!(
+                    "{}\\nauthority {}",
+                    &certificate.digest[..16],
+                    certificate.author.0
+                );
+                if let Some(index) = certificate.committed_sub_dag_index {
+                    write!(label, "\\nsub dag {index}").unwrap();
+                }
+
+                let mut style = vec![];
+                if certificate.committed_sub_dag_index.is_some() {
+                    style.push("filled");
+                }
+                if round.leader == Some(certificate.author) {
+                    style.push("bold");
+                }
+                let color = if certificate.committed_leader {
+                    "gold"
+                } else {
+                    "lightblue"
+                };
+
+                writeln!(
+                    dot,
+                    "    \"{}\" [label=\"{label}\", style=\"{}\", fillcolor={color}];",
+                    certificate.digest,
+                    style.join(","),
+                )
+                .unwrap();
+            }
+
+            for header in &round.uncertified_headers {
+                writeln!(
+                    dot,
+                    "    \"{}\" [label=\"{}\\nauthority {}\\nuncertified header\", style=dotted];",
+                    header.digest,
+                    &header.digest[..16],
+                    header.author.0
+                )
+                .unwrap();
+            }
+
+            for authority in &round.missing {
+                let style = if round.leader == Some(*authority) {
+                    "\"dashed,bold\""
+                } else {
+                    "dashed"
+                };
+                writeln!(
+                    dot,
+                    "    \"missing_{0}_{1}\" [label=\"authority {1}\\nmissing\", style={style}, color=gray];",
+                    round.round, authority.0
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        // Keep the round labels in order.
+        for rounds in self.rounds.windows(2) {
+            writeln!(
+                dot,
+                "  \"round_{}\" -> \"round_{}\" [style=invis];",
+                rounds[1].round, rounds[0].round
+            )
+            .unwrap();
+        }
+
+        for round in &self.rounds {
+            let children = round
+                .certificates
+                .iter()
+                .map(|c| (&c.digest, &c.parents))
+                .chain(
+                    round
+                        .uncertified_headers
+                        .iter()
+                        .map(|h| (&h.digest, &h.parents)),
+                );
+            for (digest, parents) in children {
+                for parent in parents.iter().filter(|p| exported.contains(p)) {
+                    writeln!(dot, "  \"{digest}\" -> \"{parent}\";").unwrap();
+                }
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
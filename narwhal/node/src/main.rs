@@ -19,6 +19,7 @@ use narwhal_node::primary_node::PrimaryNode;
 use narwhal_node::worker_node::WorkerNode;
 use network::client::NetworkClient;
 use node::{
+    dag_export::{export_dag, DagExportFormat},
     execution_state::SimpleExecutionState,
     metrics::{primary_metrics_registry, start_prometheus_server, worker_metrics_registry},
 };
@@ -37,6 +38,7 @@ use tracing::subscriber::set_global_default;
 use tracing::{info, warn};
 #[cfg(feature = "benchmark")]
 use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use types::Round;
 use worker::TrivialTransactionValidator;
 
 #[tokio::main]
@@ -60,6 +62,16 @@ async fn main() -> Result<(), eyre::Report> {
                 .about("Get the public key from a keypair file")
                 .args_from_usage("--filename=<FILE> 'The file where the keypair is stored'"),
         )
+        .subcommand(
+            SubCommand::with_name("export_dag")
+                .about("Export a range of rounds of the DAG persisted by a stopped node, as JSON or Graphviz DOT")
+                .args_from_usage("--committee=<FILE> 'The file containing the committee information of the store's epoch'")
+                .args_from_usage("--store=<PATH> 'The path of the node's data store'")
+                .args_from_usage("--from-round=<INT> 'The first round to export'")
+                .args_from_usage("--to-round=[INT] 'The last round to export, defaults to the highest round in the store'")
+                .args_from_usage("--format=[FORMAT] 'The export format, either json (default) or dot'")
+                .args_from_usage("--output=[FILE] 'The file where to write the export, defaults to stdout'"),
+        )
         .subcommand(
             SubCommand::with_name("run")
                 .about("Run a node")
@@ -133,6 +145,47 @@ async fn main() -> Result<(), eyre::Report> {
                 }
             }
         }
+        ("export_dag", Some(sub_matches)) => {
+            let _guard = setup_telemetry(tracing_level, network_tracing_level, None);
+
+            let committee_file = sub_matches.value_of("committee").unwrap();
+            let mut committee = Committee::import(committee_file)
+                .context("Failed to load the committee information")?;
+            committee.load();
+
+            let store = NodeStorage::reopen(sub_matches.value_of("store").unwrap(), None);
+
+            let from_round = sub_matches
+                .value_of("from-round")
+                .unwrap()
+                .parse::<Round>()
+                .context("The round must be a positive integer")?;
+            let to_round = match sub_matches.value_of("to-round") {
+                Some(round) => round
+                    .parse::<Round>()
+                    .context("The round must be a positive integer")?,
+                None => store.certificate_store.highest_round_number(),
+            };
+            let format = sub_matches
+                .value_of("format")
+                .unwrap_or("json")
+                .parse::<DagExportFormat>()
+                .map_err(|e| eyre::eyre!(e))?;
+
+            let export = export_dag(&store, &committee, from_round..=to_round)
+                .context("Failed to read the DAG from the store")?;
+            let output = match format {
+                DagExportFormat::Json => export.to_json()?,
+                DagExportFormat::Dot => export.to_dot(),
+            };
+
+            match sub_matches.value_of("output") {
+                Some(file) => {
+                    std::fs::write(file, output).context("Failed to write the DAG export")?
+                }
+                None => println!("{output}"),
+            }
+        }
         ("run", Some(sub_matches)) => {
             let primary_key_file = sub_matches.value_of("primary-keys").unwrap();
             let primary_keypair = read_authority_keypair_from_file(primary_key_file)
@@ -0,0 +1,115 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use fastcrypto::hash::Hash;
+use narwhal_node::dag_export::export_dag;
+use std::collections::{BTreeSet, HashMap};
+use storage::NodeStorage;
+use test_utils::{make_optimal_certificates, temp_dir, CommitteeFixture};
+use types::{Certificate, CertificateAPI, CommittedSubDag, ReputationScores};
+
+#[test]
+fn export_dag_rounds() {
+    let fixture = CommitteeFixture::builder().build();
+    let committee = fixture.committee();
+    let ids: Vec<_> = committee.authorities().map(|a| a.id()).collect();
+    let store = NodeStorage::reopen(temp_dir(), None);
+
+    // GIVEN fully connected rounds 1 and 2, and a round 3 where the last authority is missing
+    let genesis = Certificate::genesis(&committee)
+        .iter()
+        .map(|x| x.digest())
+        .collect::<BTreeSet<_>>();
+    let (mut certificates, parents) = make_optimal_certificates(&committee, 1..=2, &genesis, &ids);
+    let (round_3, _) = make_optimal_certificates(&committee, 3..=3, &parents, &ids[..3]);
+    certificates.extend(round_3);
+    store
+        .certificate_store
+        .write_all(certificates.clone())
+        .unwrap();
+
+    // AND the header of the last authority for round 3 which has not been certified
+    let header = fixture
+        .authorities()
+        .find(|a| a.id() == ids[3])
+        .unwrap()
+        .header_with_round(&committee, 3);
+    store.header_store.write(&header).unwrap();
+
+    // AND the leader of round 2 committed along with round 1
+    let leader_id = committee.leader(2).id();
+    let leader = certificates
+        .iter()
+        .find(|c| c.round() == 2 && c.origin() == leader_id)
+        .unwrap()
+        .clone();
+    let mut sequence: Vec<_> = certificates
+        .iter()
+        .filter(|c| c.round() == 1)
+        .cloned()
+        .collect();
+    sequence.push(leader.clone());
+    let sub_dag = CommittedSubDag::new(
+        sequence,
+        leader.clone(),
+        1,
+        ReputationScores::new(&committee),
+        None,
+    );
+    store
+        .consensus_store
+        .write_consensus_state(&HashMap::new(), &sub_dag)
+        .unwrap();
+
+    // WHEN exporting rounds 1 to 3
+    let export = export_dag(&store, &committee, 1..=3).unwrap();
+
+    // THEN all the rounds are exported
+    assert_eq!(export.from_round, 1);
+    assert_eq!(export.to_round, 3);
+    assert_eq!(export.rounds.len(), 3);
+
+    // AND round 1 is fully committed, without a leader since it is odd
+    let round_1 = &export.rounds[0];
+    assert_eq!(round_1.leader, None);
+    assert_eq!(round_1.certificates.len(), 4);
+    assert!(round_1
+        .certificates
+        .iter()
+        .all(|c| c.committed_sub_dag_index == Some(1) && !c.committed_leader));
+    assert!(round_1.missing.is_empty());
+
+    // AND only the leader of round 2 is committed
+    let round_2 = &export.rounds[1];
+    assert_eq!(round_2.leader, Some(leader_id));
+    for certificate in &round_2.certificates {
+        assert_eq!(certificate.parents.len(), 4);
+        if certificate.author == leader_id {
+            assert!(certificate.committed_leader);
+            assert_eq!(certificate.committed_sub_dag_index, Some(1));
+        } else {
+            assert!(!certificate.committed_leader);
+            assert_eq!(certificate.committed_sub_dag_index, None);
+        }
+    }
+
+    // AND round 3 misses the certificate of the last authority, which only has a header
+    let round_3 = &export.rounds[2];
+    assert_eq!(round_3.certificates.len(), 3);
+    assert_eq!(round_3.missing, vec![ids[3]]);
+    assert_eq!(round_3.uncertified_headers.len(), 1);
+    assert_eq!(round_3.uncertified_headers[0].author, ids[3]);
+
+    // AND the JSON export contains the same information
+    let json: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+    assert_eq!(json["rounds"][1]["leader"], leader_id.0);
+    assert_eq!(json["rounds"][2]["missing"][0], ids[3].0);
+
+    // AND the DOT export links the certificates to their parents
+    let dot = export.to_dot();
+    assert!(dot.starts_with("digraph dag {"));
+    let leader_digest = format!("{:?}", leader.digest());
+    let parent_digest = format!("{:?}", leader.header().parents().iter().next().unwrap());
+    assert!(dot.contains(&format!("\"{leader_digest}\" -> \"{parent_digest}\";")));
+    assert!(dot.contains(&format!("\"missing_3_{}\"", ids[3].0)));
+}
@@ -128,6 +128,17 @@ impl ConsensusStore {
             .map(|(_, sub_dag)| ConsensusCommit::V1(sub_dag))
     }
 
+    /// Returns the latest commit whose reputation scores are the final ones of their schedule. If
+    /// no schedule has been completed yet, then None is returned instead.
+    pub fn read_latest_commit_with_final_reputation_scores(&self) -> Option<ConsensusCommit> {
+        self.committed_sub_dags_by_index_v2
+            .iter()
+            .skip_to_last()
+            .reverse()
+            .map(|(_, commit)| commit)
+            .find(|commit| commit.reputation_score().final_of_schedule)
+    }
+
     /// Load all the sub dags committed with sequence number of at least `from`.
     pub fn read_committed_sub_dags_from(
         &self,
@@ -158,7 +169,9 @@ impl ConsensusStore {
 mod test {
     use crate::ConsensusStore;
     use store::Map;
-    use types::{CommittedSubDagShell, ConsensusCommit, ConsensusCommitV2, TimestampMs};
+    use types::{
+        CommittedSubDagShell, ConsensusCommit, ConsensusCommitV2, ReputationScores, TimestampMs,
+    };
 
     #[tokio::test]
     async fn test_v1_v2_backwards_compatibility() {
@@ -219,4 +232,41 @@ mod test {
         let index = store.get_latest_sub_dag_index();
         assert_eq!(index, 5);
     }
+
+    #[tokio::test]
+    async fn test_read_latest_commit_with_final_reputation_scores() {
+        let store = ConsensusStore::new_for_tests();
+
+        // No commit has been written yet
+        assert!(store
+            .read_latest_commit_with_final_reputation_scores()
+            .is_none());
+
+        // Mark the scores of every third commit as the final ones of their schedule
+        for i in 0..8 {
+            let s = ConsensusCommitV2 {
+                certificates: vec![],
+                leader: Default::default(),
+                leader_round: 2 * i,
+                sub_dag_index: i,
+                reputation_score: ReputationScores {
+                    final_of_schedule: (i + 1) % 3 == 0,
+                    ..Default::default()
+                },
+                commit_timestamp: i,
+            };
+
+            store
+                .committed_sub_dags_by_index_v2
+                .insert(&s.sub_dag_index.clone(), &ConsensusCommit::V2(s))
+                .unwrap();
+        }
+
+        // The commit with index 5 is the latest one with final scores
+        let commit = store
+            .read_latest_commit_with_final_reputation_scores()
+            .unwrap();
+        assert_eq!(commit.sub_dag_index(), 5);
+        assert_eq!(commit.leader_round(), 10);
+    }
 }
@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::NodeStorage;
+use std::ops::RangeInclusive;
 use store::rocks::ReadWriteOptions;
 use store::rocks::{open_cf, DBMap, MetricConf};
 use store::{reopen, Map, TypedStoreError};
 use sui_macros::fail_point;
-use types::{Header, HeaderDigest};
+use types::{Header, HeaderAPI, HeaderDigest, Round};
 
 #[derive(Clone)]
 pub struct HeaderStore {
@@ -36,6 +37,22 @@ impl HeaderStore {
         self.store.get(id)
     }
 
+    /// Retrieves all the headers of the provided rounds. Headers are not indexed by round, so the
+    /// whole store is scanned.
+    pub fn read_all_in_rounds(
+        &self,
+        rounds: RangeInclusive<Round>,
+    ) -> Result<Vec<Header>, TypedStoreError> {
+        let mut headers = Vec::new();
+        for header in self.store.values() {
+            let header = header?;
+            if rounds.contains(&header.round()) {
+                headers.push(header);
+            }
+        }
+        Ok(headers)
+    }
+
     #[allow(clippy::let_and_return)]
     pub fn write(&self, header: &Header) -> Result<(), TypedStoreError> {
         fail_point!("narwhal-store-before-write");
@@ -158,6 +158,21 @@ impl ReputationScores {
     pub fn all_zero(&self) -> bool {
         !self.scores_per_authority.values().any(|e| *e > 0)
     }
+
+    /// Returns the authorities and their scores sorted by score in descending order. Authorities
+    /// with equal scores are sorted by their identifier in descending order, so the ordering is
+    /// deterministic across nodes.
+    pub fn authorities_by_score_desc(&self) -> Vec<(AuthorityIdentifier, u64)> {
+        let mut authorities: Vec<_> = self
+            .scores_per_authority
+            .iter()
+            .map(|(authority, score)| (*authority, *score))
+            .collect();
+
+        authorities.sort_by(|a1, a2| a2.1.cmp(&a1.1).then(a2.0.cmp(&a1.0)));
+
+        authorities
+    }
 }
 
 #[enum_dispatch(ConsensusCommitAPI)]